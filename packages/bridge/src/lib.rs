@@ -180,19 +180,18 @@ mod tests {
     #[test]
     fn test_no_unsafe_in_kit() {
         // Verify that ContextfyKit can be created without unsafe code
-        let kit = ContextfyKit::new();
+        let _kit = ContextfyKit::new();
         // The struct should only contain the store Arc
         // This test ensures we eliminated the unsafe transmute
-        drop(kit); // Explicitly drop to verify clean destruction
+        // `_kit` is dropped at the end of the scope to verify clean destruction
     }
 
     #[test]
     fn test_store_arc_management() {
         // Test that the store Arc is properly managed
-        let kit = ContextfyKit::new();
+        let _kit = ContextfyKit::new();
         // The kit internally manages the store Arc
         // Test passes if the kit can be created and dropped without panic
-        drop(kit);
     }
 }
 
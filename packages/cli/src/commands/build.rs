@@ -1,10 +1,15 @@
 use anyhow::Result;
-use contextfy_core::{parse_markdown, SearchEngine};
+use contextfy_core::{
+    chunk_source_file, code_node_type, extract_code_blocks, is_document_path, is_source_path,
+    parse_document, AstChunk, ChunkSize, CodeBlock, DocMetadata, GraphNode, LinkGraph, ParsedDoc,
+    SearchEngine, SliceConfig, SlicedSection, NODE_TYPE_PROSE,
+};
 use serde::Deserialize;
 use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::fs;
 use std::hash::{Hash, Hasher};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 /// 默认文档目录路径
 const DEFAULT_DOCS_PATH: &str = "docs/examples";
@@ -16,160 +21,666 @@ struct Config {
     /// 文档目录路径
     #[serde(default = "default_docs_path")]
     docs_path: String,
-    _name: Option<String>,
+    /// 项目名称，作为知识包（pack）名写入每个切片
+    name: Option<String>,
+    /// 切片配置
+    #[serde(default)]
+    slicing: SlicingConfig,
+    /// 源代码目录或文件（Rust/TypeScript/Python/Java/Go），按定义切片入库
+    #[serde(default)]
+    source_paths: Vec<String>,
     _version: Option<String>,
     _description: Option<String>,
 }
 
+/// 切片配置（contextfy.json 中的 `slicing` 字段）
+///
+/// 未设置的字段使用 [`SliceConfig::default`] 的值；`max_tokens` 优先于 `max_chars`。
+#[derive(Debug, Default, Deserialize)]
+struct SlicingConfig {
+    /// 最深切分层级（2..=4）
+    max_level: Option<u8>,
+    /// 单个切片最大字符数
+    max_chars: Option<usize>,
+    /// 单个切片最大 token 数（估算值）
+    max_tokens: Option<usize>,
+    /// 子切片重叠量（与大小上限同一单位）
+    overlap: Option<usize>,
+    /// 是否保留第一个标题之前的前言
+    keep_preamble: Option<bool>,
+}
+
+impl SlicingConfig {
+    fn to_slice_config(&self) -> SliceConfig {
+        let mut config = SliceConfig::default();
+        if let Some(level) = self.max_level {
+            config = config.with_max_level(level);
+        }
+        if let Some(tokens) = self.max_tokens {
+            config = config.with_max_size(ChunkSize::Tokens(tokens));
+        } else if let Some(chars) = self.max_chars {
+            config = config.with_max_size(ChunkSize::Chars(chars));
+        }
+        if let Some(overlap) = self.overlap {
+            config = config.with_overlap(overlap);
+        }
+        if let Some(keep) = self.keep_preamble {
+            config = config.with_preamble(keep);
+        }
+        config
+    }
+}
+
 fn default_docs_path() -> String {
     DEFAULT_DOCS_PATH.to_string()
 }
 
-/// 构建知识库
-///
-/// 从 contextfy.json 读取配置，扫描指定文档目录，解析 Markdown 文档并存储到知识库中。
-/// 每个文档会被切片并存储为独立的可检索单元。
-///
-/// # Errors
-///
-/// 如果配置文件格式错误、文档目录不存在或文档解析失败，返回错误
-///
-/// # Examples
-///
-/// ```no_run
-/// # use contextfy_cli::commands::build;
-/// # #[tokio::main]
-/// # async fn main() -> anyhow::Result<()> {
-/// build().await?;
-/// # Ok(())
-/// # }
-/// ```
-pub async fn build() -> Result<()> {
-    // 初始化 SearchEngine
-    let engine = SearchEngine::new(
-        Some(std::path::Path::new(".contextfy/data/bm25_index")),
-        ".contextfy/data/lancedb",
-        "knowledge",
-    )
-    .await?;
+/// 扫描源代码时跳过的目录（依赖、构建产物和版本控制目录）
+const SKIPPED_SOURCE_DIRS: &[&str] = &[
+    "target",
+    "node_modules",
+    "vendor",
+    "dist",
+    "build",
+    "__pycache__",
+];
+
+/// 扫描源代码时是否跳过该目录（隐藏目录和 [`SKIPPED_SOURCE_DIRS`]）
+pub(crate) fn is_skipped_source_dir(name: &str) -> bool {
+    name.starts_with('.') || SKIPPED_SOURCE_DIRS.contains(&name)
+}
+
+/// 递归收集目录下支持切片的源文件（跳过隐藏目录和 [`SKIPPED_SOURCE_DIRS`]），按路径排序
+fn collect_source_files(root: &Path) -> Result<Vec<PathBuf>> {
+    if root.is_file() {
+        return Ok(if is_source_path(root) {
+            vec![root.to_path_buf()]
+        } else {
+            Vec::new()
+        });
+    }
+
+    let mut files = Vec::new();
+    let mut dirs = vec![root.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        for entry in fs::read_dir(&dir)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                let name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+                if !is_skipped_source_dir(name) {
+                    dirs.push(path);
+                }
+            } else if is_source_path(&path) {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// 解析后的构建配置（来自 contextfy.json，文件不存在时使用默认值）
+pub(crate) struct BuildConfig {
+    /// 文档目录路径
+    pub(crate) docs_path: String,
+    /// 知识包名
+    pub(crate) pack: Option<String>,
+    /// 切片配置
+    pub(crate) slice_config: SliceConfig,
+    /// 源代码目录或文件
+    pub(crate) source_paths: Vec<String>,
+}
+
+impl BuildConfig {
+    /// 读取当前目录下的 contextfy.json
+    pub(crate) fn load() -> Result<Self> {
+        let config_path = Path::new("contextfy.json");
+        if !config_path.exists() {
+            return Ok(Self {
+                docs_path: default_docs_path(),
+                pack: None,
+                slice_config: SliceConfig::default(),
+                source_paths: Vec::new(),
+            });
+        }
 
-    // 读取配置文件
-    let config_path = Path::new("contextfy.json");
-    let docs_path = if config_path.exists() {
         let config_content = fs::read_to_string(config_path)?;
         let config: Config = serde_json::from_str(&config_content)
             .map_err(|e| anyhow::anyhow!("Failed to parse contextfy.json: {}", e))?;
-        config.docs_path
+        Ok(Self {
+            docs_path: config.docs_path,
+            pack: config.name,
+            slice_config: config.slicing.to_slice_config(),
+            source_paths: config.source_paths,
+        })
+    }
+
+    /// 文件路径是否属于构建范围：文档目录下的文档，或 `source_paths` 中的源文件
+    pub(crate) fn in_scope(&self, file_path: &str) -> bool {
+        let path = Path::new(file_path);
+        if path.parent() == Some(Path::new(&self.docs_path)) && is_document_path(path) {
+            return true;
+        }
+        is_source_path(path) && self.source_paths.iter().any(|root| path.starts_with(root))
+    }
+}
+
+/// 打开项目的知识库（`.contextfy/data/`）
+pub(crate) async fn open_engine() -> Result<SearchEngine> {
+    SearchEngine::new(
+        Some(Path::new(".contextfy/data/bm25_index")),
+        ".contextfy/data/lancedb",
+        "knowledge",
+    )
+    .await
+}
+
+/// 单个文件入库后的记录：切片 ID 到内容指纹的映射，以及链接图节点
+#[derive(Debug, Default)]
+struct IndexedFile {
+    chunks: BTreeMap<String, u64>,
+    graph_nodes: Vec<GraphNode>,
+}
+
+/// 重新解析一个文件后切片的变化（切片 ID 列表）
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct ChunkChanges {
+    /// 新增的切片
+    pub(crate) added: Vec<String>,
+    /// 内容发生变化的切片
+    pub(crate) updated: Vec<String>,
+    /// 已不存在、需要从两个存储中删除的切片
+    pub(crate) removed: Vec<String>,
+}
+
+impl ChunkChanges {
+    /// 文件的切片是否没有任何变化
+    pub(crate) fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.updated.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// 已入库文件的索引，按构建时使用的文件路径组织
+///
+/// 监听模式用它计算单个文件重新解析后需要写入和删除的切片，并在不重新解析其他文件的情况下重建链接图。
+#[derive(Debug, Default)]
+pub(crate) struct IndexedFiles {
+    files: BTreeMap<String, IndexedFile>,
+}
+
+impl IndexedFiles {
+    /// 记录文件当前的切片和链接图节点，替换之前的记录
+    pub(crate) fn record(
+        &mut self,
+        file_path: &str,
+        chunks: &[AstChunk],
+        graph_nodes: Vec<GraphNode>,
+    ) {
+        let chunks = chunks
+            .iter()
+            .map(|chunk| (chunk.id.clone(), fingerprint(chunk)))
+            .collect();
+        self.files.insert(
+            file_path.to_string(),
+            IndexedFile {
+                chunks,
+                graph_nodes,
+            },
+        );
+    }
+
+    /// 移除文件的记录，返回它的全部切片 ID
+    pub(crate) fn remove(&mut self, file_path: &str) -> Vec<String> {
+        self.files
+            .remove(file_path)
+            .map(|file| file.chunks.into_keys().collect())
+            .unwrap_or_default()
+    }
+
+    /// 文件是否已经入库
+    pub(crate) fn contains(&self, file_path: &str) -> bool {
+        self.files.contains_key(file_path)
+    }
+
+    /// 比较文件的新切片与已记录的切片
+    pub(crate) fn diff(&self, file_path: &str, chunks: &[AstChunk]) -> ChunkChanges {
+        let empty = BTreeMap::new();
+        let previous = self
+            .files
+            .get(file_path)
+            .map(|file| &file.chunks)
+            .unwrap_or(&empty);
+
+        let mut changes = ChunkChanges::default();
+        for chunk in chunks {
+            match previous.get(&chunk.id) {
+                None => changes.added.push(chunk.id.clone()),
+                Some(&old) if old != fingerprint(chunk) => changes.updated.push(chunk.id.clone()),
+                Some(_) => {}
+            }
+        }
+        changes.removed = previous
+            .keys()
+            .filter(|id| !chunks.iter().any(|chunk| &chunk.id == *id))
+            .cloned()
+            .collect();
+        changes
+    }
+
+    /// 已入库、属于构建范围但本次构建没有生成的切片 ID（文件变短或已删除），按 ID 排序
+    ///
+    /// `stored` 是存储中切片 ID 到文件路径的映射；`failed` 中的文件解析或写入失败，保留原有切片。
+    pub(crate) fn stale_ids(
+        &self,
+        stored: &HashMap<String, String>,
+        failed: &BTreeSet<String>,
+        in_scope: impl Fn(&str) -> bool,
+    ) -> Vec<String> {
+        let produced: HashSet<&str> = self
+            .files
+            .values()
+            .flat_map(|file| file.chunks.keys().map(String::as_str))
+            .collect();
+        let mut stale: Vec<String> = stored
+            .iter()
+            .filter(|(id, path)| {
+                !produced.contains(id.as_str()) && !failed.contains(*path) && in_scope(path)
+            })
+            .map(|(id, _)| id.clone())
+            .collect();
+        stale.sort();
+        stale
+    }
+
+    /// 由所有已入库文档的节点构建链接图
+    pub(crate) fn link_graph(&self) -> LinkGraph {
+        let nodes: Vec<GraphNode> = self
+            .files
+            .values()
+            .flat_map(|file| file.graph_nodes.iter().cloned())
+            .collect();
+        LinkGraph::build(&nodes)
+    }
+}
+
+/// 切片内容指纹：序列化后的全部字段的哈希
+fn fingerprint(chunk: &AstChunk) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    serde_json::to_string(chunk)
+        .unwrap_or_default()
+        .hash(&mut hasher);
+    hasher.finish()
+}
+
+/// 一个文档解析后得到的切片
+pub(crate) struct DocumentChunks {
+    /// 文档标题
+    pub(crate) title: String,
+    /// 正文切片和代码切片（已写入知识包名和 front-matter 元数据）
+    pub(crate) chunks: Vec<AstChunk>,
+    /// 链接图节点
+    pub(crate) graph_nodes: Vec<GraphNode>,
+}
+
+/// 解析一个文档并转换为切片
+///
+/// ID = 文件路径哈希 + 文件内切片序号，重复构建时 ID 保持不变
+pub(crate) fn document_chunks(file_path: &str, config: &BuildConfig) -> Result<DocumentChunks> {
+    let doc = parse_document(file_path, &config.slice_config)?;
+
+    let mut hasher = DefaultHasher::new();
+    file_path.hash(&mut hasher);
+    let path_hash = hasher.finish();
+
+    let chunks: Vec<AstChunk> = if doc.sections.is_empty() {
+        // No sections, add the whole document as one entry
+        let mut chunks = vec![to_chunk(path_hash, 0, file_path, &doc.title, &doc.content)];
+        chunks.extend(code_chunks(
+            path_hash,
+            0,
+            file_path,
+            &doc.title,
+            &[],
+            &extract_code_blocks(&doc.content),
+        ));
+        chunks
     } else {
-        default_docs_path()
+        // Add each section as a separate entry, followed by its code blocks
+        doc.sections
+            .iter()
+            .enumerate()
+            .flat_map(|(index, section)| section_chunks(path_hash, index, file_path, section))
+            .collect()
     };
+    let chunks = chunks
+        .into_iter()
+        .map(|chunk| with_doc_metadata(chunk, config.pack.as_deref(), &doc.metadata))
+        .collect();
+
+    Ok(DocumentChunks {
+        graph_nodes: to_graph_nodes(path_hash, file_path, &doc),
+        title: doc.title,
+        chunks,
+    })
+}
+
+/// 切片一个源文件并写入知识包名
+pub(crate) fn source_chunks(file_path: &str, pack: Option<&str>) -> Result<Vec<AstChunk>> {
+    let chunks = chunk_source_file(file_path)?;
+    Ok(match pack {
+        Some(pack) => chunks.into_iter().map(|c| c.with_pack(pack)).collect(),
+        None => chunks,
+    })
+}
+
+/// 从两个存储中删除切片，任一存储删除失败时返回错误
+pub(crate) async fn delete_chunks(engine: &SearchEngine, ids: &[String]) -> Result<()> {
+    for id in ids {
+        let result = engine.delete(id).await;
+        if let (Err(e), _) | (_, Err(e)) = (&result.vector_deleted, &result.bm25_deleted) {
+            anyhow::bail!("Failed to delete {}: {}", id, e);
+        }
+    }
+    Ok(())
+}
+
+/// 一次完整构建的结果
+pub(crate) struct BuildOutcome {
+    /// 已入库的文件
+    pub(crate) files: IndexedFiles,
+    /// 成功入库的文档数
+    pub(crate) documents_count: usize,
+    /// 文档切片数
+    pub(crate) sections_count: usize,
+    /// 源代码定义数
+    pub(crate) code_chunk_count: usize,
+    /// 解析或写入失败的文件数
+    pub(crate) parse_errors: usize,
+    /// 链接图统计：链接数、未解析链接数、孤立文档数
+    pub(crate) link_stats: (usize, usize, usize),
+}
 
-    let examples_dir = Path::new(&docs_path);
+/// 扫描文档目录和源代码目录，解析全部文件并写入知识库，最后重建链接图
+///
+/// 单个文件解析或写入失败时打印错误并继续，失败数记录在 [`BuildOutcome::parse_errors`] 中。
+pub(crate) async fn build_all(engine: &SearchEngine, config: &BuildConfig) -> Result<BuildOutcome> {
+    let examples_dir = Path::new(&config.docs_path);
     if !examples_dir.exists() {
         anyhow::bail!(
             "docs directory '{}' not found. Please check contextfy.json or create the directory.",
-            docs_path
+            config.docs_path
         );
     }
 
+    // 构建前已入库的切片，用于删除本次构建不再生成的切片
+    let stored = engine.chunk_paths().await?;
+    let mut files = IndexedFiles::default();
+    let mut failed = BTreeSet::new();
     let mut documents_count = 0;
     let mut sections_count = 0;
     let mut parse_errors = 0;
-    let mut global_id_counter = 0u64;
 
     for entry in fs::read_dir(examples_dir)? {
         let entry = entry?;
         let path = entry.path();
 
-        if path.extension().and_then(|s| s.to_str()) == Some("md") {
+        if is_document_path(&path) {
             let file_path = path.to_string_lossy();
             println!("Processing: {}", file_path);
 
-            match parse_markdown(&file_path) {
+            match document_chunks(&file_path, config) {
                 Ok(doc) => {
-                    // Generate unique ID from file path hash + counter
-                    let mut hasher = DefaultHasher::new();
-                    file_path.hash(&mut hasher);
-                    let path_hash = hasher.finish();
-
-                    // Add document sections to SearchEngine
-                    if doc.sections.is_empty() {
-                        // No sections, add the whole document as one entry
-                        let id = format!("{}-{}", path_hash, global_id_counter);
-                        global_id_counter += 1;
-
-                        // Safe character boundary truncation
-                        let summary_truncated = doc.summary.chars().take(200).collect::<String>();
-
-                        if let Err(e) = engine
-                            .add(&id, &doc.title, &summary_truncated, &doc.content, None)
-                            .await
-                        {
-                            eprintln!("  ✗ Failed to add {}: {}", id, e);
-                            parse_errors += 1;
-                        } else {
-                            println!("  → Stored: {} (ID: {})", doc.title, id);
-                            documents_count += 1;
-                            sections_count += 1;
-                        }
+                    let chunk_count = doc.chunks.len();
+                    if let Err(e) = engine.upsert_batch(doc.chunks.clone()).await {
+                        eprintln!("  ✗ Failed to store {}: {}", file_path, e);
+                        parse_errors += 1;
+                        failed.insert(file_path.to_string());
                     } else {
-                        // Add each section as a separate entry
-                        for section in doc.sections.iter() {
-                            let id = format!("{}-{}", path_hash, global_id_counter);
-                            global_id_counter += 1;
-
-                            // Safe character boundary truncation
-                            let summary_truncated =
-                                section.summary.chars().take(200).collect::<String>();
-
-                            if let Err(e) = engine
-                                .add(
-                                    &id,
-                                    &section.section_title,
-                                    &summary_truncated,
-                                    &section.content,
-                                    None,
-                                )
-                                .await
-                            {
-                                eprintln!("  ✗ Failed to add section {}: {}", id, e);
-                                parse_errors += 1;
-                            } else {
-                                documents_count += 1;
-                            }
-                        }
-                        sections_count += doc.sections.len();
-                        println!("  → Stored: {} ({} slices)", doc.title, doc.sections.len());
+                        files.record(&file_path, &doc.chunks, doc.graph_nodes);
+                        documents_count += 1;
+                        sections_count += chunk_count;
+                        println!("  → Stored: {} ({} slices)", doc.title, chunk_count);
                     }
                 }
                 Err(e) => {
                     eprintln!("  ✗ Failed to parse {}: {}", file_path, e);
                     parse_errors += 1;
+                    failed.insert(file_path.to_string());
+                }
+            }
+        }
+    }
+
+    let mut code_chunk_count = 0;
+    for source_path in &config.source_paths {
+        let root = Path::new(source_path);
+        if !root.exists() {
+            anyhow::bail!(
+                "source path '{}' not found. Please check source_paths in contextfy.json.",
+                source_path
+            );
+        }
+
+        for path in collect_source_files(root)? {
+            let file_path = path.to_string_lossy();
+            let chunks = match source_chunks(&file_path, config.pack.as_deref()) {
+                Ok(chunks) => chunks,
+                Err(e) => {
+                    eprintln!("  ✗ Failed to parse {}: {}", file_path, e);
+                    parse_errors += 1;
+                    failed.insert(file_path.to_string());
+                    continue;
                 }
+            };
+            if chunks.is_empty() {
+                continue;
+            }
+
+            let chunk_count = chunks.len();
+            if let Err(e) = engine.upsert_batch(chunks.clone()).await {
+                eprintln!("  ✗ Failed to store {}: {}", file_path, e);
+                parse_errors += 1;
+                failed.insert(file_path.to_string());
+            } else {
+                files.record(&file_path, &chunks, Vec::new());
+                code_chunk_count += chunk_count;
+                println!("Processing: {} ({} definitions)", file_path, chunk_count);
             }
         }
     }
 
+    // 删除构建范围内不再生成的切片（文档变短或文件已删除），重复构建才是幂等的
+    let stale = files.stale_ids(&stored, &failed, |path| config.in_scope(path));
+    if !stale.is_empty() {
+        delete_chunks(engine, &stale).await?;
+        println!("Removed {} stale sections", stale.len());
+    }
+
+    // 所有文档入库后再解析链接，跨文档链接才能找到目标切片
+    let link_graph = files.link_graph();
+    let link_stats = (
+        link_graph.edges().len(),
+        link_graph.unresolved().len(),
+        link_graph.orphans().len(),
+    );
+    engine.set_link_graph(link_graph)?;
+
+    Ok(BuildOutcome {
+        files,
+        documents_count,
+        sections_count,
+        code_chunk_count,
+        parse_errors,
+        link_stats,
+    })
+}
+
+/// 构建知识库
+///
+/// 从 contextfy.json 读取配置，扫描指定文档目录，解析 Markdown/MDX 文档并存储到知识库中。
+/// 每个文档会被切片并存储为独立的可检索单元。`source_paths` 中的源代码按函数、类型、
+/// impl 块和方法切片后一并入库。
+///
+/// # Errors
+///
+/// 如果配置文件格式错误、文档目录不存在或文档解析失败，返回错误
+///
+/// # Examples
+///
+/// ```no_run
+/// # use contextfy_cli::commands::build;
+/// # #[tokio::main]
+/// # async fn main() -> anyhow::Result<()> {
+/// build().await?;
+/// # Ok(())
+/// # }
+/// ```
+pub async fn build() -> Result<()> {
+    let engine = open_engine().await?;
+    let config = BuildConfig::load()?;
+    let outcome = build_all(&engine, &config).await?;
+
     // 如果有解析错误，返回错误
-    if parse_errors > 0 {
+    if outcome.parse_errors > 0 {
         anyhow::bail!(
             "Build failed: {} out of {} documents had parsing errors",
-            parse_errors,
-            documents_count + parse_errors
+            outcome.parse_errors,
+            outcome.documents_count + outcome.parse_errors
         );
     }
 
     println!("\n✓ Build complete!");
     println!(
         "Found {} documents, {} sections",
-        documents_count, sections_count
+        outcome.documents_count, outcome.sections_count
+    );
+    if !config.source_paths.is_empty() {
+        println!(
+            "Indexed {} source code definitions",
+            outcome.code_chunk_count
+        );
+    }
+    let (link_count, unresolved_count, orphan_count) = outcome.link_stats;
+    println!(
+        "Link graph: {} links, {} unresolved, {} orphan documents",
+        link_count, unresolved_count, orphan_count
     );
     Ok(())
 }
 
+/// 将文档切片转换为 AST chunk
+///
+/// 标题存入 `symbol_name`，源文件路径存入 `file_path`（便于按路径前缀批量删除），
+/// 类型固定为 `prose`。
+fn to_chunk(path_hash: u64, index: usize, file_path: &str, title: &str, content: &str) -> AstChunk {
+    AstChunk::without_dependencies(
+        format!("{}-{}", path_hash, index),
+        file_path,
+        title,
+        NODE_TYPE_PROSE,
+        content,
+    )
+}
+
+/// 将一个切片转换为正文 chunk 及其代码 chunk
+///
+/// 切片带页码范围（PDF）时，正文和代码 chunk 都记录该范围，检索结果据此给出引用页码。
+fn section_chunks(
+    path_hash: u64,
+    index: usize,
+    file_path: &str,
+    section: &SlicedSection,
+) -> Vec<AstChunk> {
+    let prose = to_chunk(
+        path_hash,
+        index,
+        file_path,
+        &section.section_title,
+        &section.content,
+    )
+    .with_heading_path(section.heading_path.clone());
+    let chunks = std::iter::once(prose).chain(code_chunks(
+        path_hash,
+        index,
+        file_path,
+        &section.section_title,
+        &section.heading_path,
+        &section.code_blocks,
+    ));
+    match section.pages {
+        Some(pages) => chunks.map(|chunk| chunk.with_pages(pages)).collect(),
+        None => chunks.collect(),
+    }
+}
+
+/// 将切片中的围栏代码块转换为独立的代码 chunk
+///
+/// ID 为 `{文件哈希}-{切片序号}-code-{代码块序号}`，类型为 `code:<语言>`（未标注语言时为 `code`），
+/// 代码中提取的标识符存入 `dependencies`，所属切片的标题和标题路径保持不变。
+fn code_chunks(
+    path_hash: u64,
+    index: usize,
+    file_path: &str,
+    title: &str,
+    heading_path: &[String],
+    code_blocks: &[CodeBlock],
+) -> Vec<AstChunk> {
+    code_blocks
+        .iter()
+        .enumerate()
+        .map(|(k, block)| {
+            AstChunk::new(
+                format!("{}-{}-code-{}", path_hash, index, k),
+                file_path,
+                title,
+                code_node_type(block.language.as_deref()),
+                block.content.clone(),
+                block.symbols.clone(),
+            )
+            .with_heading_path(heading_path.to_vec())
+        })
+        .collect()
+}
+
+/// 把文档的每个切片（与 `to_chunk` 的 ID 一致）转换为链接图节点
+///
+/// 代码 chunk 不参与链接图：链接属于所在的正文切片。
+fn to_graph_nodes(path_hash: u64, file_path: &str, doc: &ParsedDoc) -> Vec<GraphNode> {
+    let node = |index: usize, title: &str, links| GraphNode {
+        id: format!("{}-{}", path_hash, index),
+        file_path: file_path.to_string(),
+        title: title.to_string(),
+        doc_title: doc.title.clone(),
+        links,
+    };
+
+    if doc.sections.is_empty() {
+        vec![node(0, &doc.title, doc.links.clone())]
+    } else {
+        doc.sections
+            .iter()
+            .enumerate()
+            .map(|(index, section)| node(index, &section.section_title, section.links.clone()))
+            .collect()
+    }
+}
+
+/// 把知识包名和文档 front-matter 中的标签、版本写入切片
+fn with_doc_metadata(chunk: AstChunk, pack: Option<&str>, metadata: &DocMetadata) -> AstChunk {
+    let mut chunk = chunk.with_tags(metadata.tags.clone());
+    if let Some(pack) = pack {
+        chunk = chunk.with_pack(pack);
+    }
+    if let Some(version) = &metadata.version {
+        chunk = chunk.with_version(version);
+    }
+    chunk
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use contextfy_core::PageRange;
     use std::fs::File;
     use std::io::Write;
     use tempfile::TempDir;
@@ -224,6 +735,248 @@ mod tests {
         assert!(err_msg.contains("Failed to parse contextfy.json"));
     }
 
+    /// 测试：slicing 配置映射到 SliceConfig，max_tokens 优先于 max_chars
+    #[test]
+    fn test_slicing_config() {
+        let config: Config = serde_json::from_str(
+            r#"{"slicing": {"max_level": 4, "max_chars": 800, "max_tokens": 300, "overlap": 20, "keep_preamble": false}}"#,
+        )
+        .unwrap();
+        let slice_config = config.slicing.to_slice_config();
+        assert_eq!(slice_config.max_level, 4);
+        assert_eq!(slice_config.max_size, Some(ChunkSize::Tokens(300)));
+        assert_eq!(slice_config.overlap, 20);
+        assert!(!slice_config.keep_preamble);
+
+        let config: Config = serde_json::from_str(r#"{"name": "test"}"#).unwrap();
+        assert_eq!(config.slicing.to_slice_config(), SliceConfig::default());
+    }
+
+    /// 测试：只处理支持的文档格式
+    #[test]
+    fn test_is_document_path() {
+        assert!(is_document_path(Path::new("docs/a.md")));
+        assert!(is_document_path(Path::new("docs/A.MDX")));
+        assert!(is_document_path(Path::new("docs/index.rst")));
+        assert!(is_document_path(Path::new("docs/guide.adoc")));
+        assert!(is_document_path(Path::new("docs/api.html")));
+        assert!(is_document_path(Path::new("docs/notes.txt")));
+        assert!(!is_document_path(Path::new("docs/logo.png")));
+        assert!(!is_document_path(Path::new("docs/mdx")));
+    }
+
+    #[test]
+    fn test_collect_source_files_skips_build_dirs() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        for dir in ["src/nested", "target/debug", "node_modules/pkg", ".git"] {
+            fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        for file in [
+            "src/lib.rs",
+            "src/nested/app.ts",
+            "src/README.md",
+            "target/debug/gen.rs",
+            "node_modules/pkg/index.ts",
+            ".git/hook.py",
+        ] {
+            fs::write(root.join(file), "").unwrap();
+        }
+
+        let files: Vec<PathBuf> = collect_source_files(root)
+            .unwrap()
+            .into_iter()
+            .map(|p| p.strip_prefix(root).unwrap().to_path_buf())
+            .collect();
+        assert_eq!(
+            files,
+            vec![PathBuf::from("src/lib.rs"), PathBuf::from("src/nested/app.ts")]
+        );
+        // 单个文件路径直接返回
+        assert_eq!(collect_source_files(&root.join("src/lib.rs")).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_config_source_paths() {
+        let config: Config = serde_json::from_str(r#"{"source_paths": ["src", "lib/app.py"]}"#).unwrap();
+        assert_eq!(config.source_paths, vec!["src", "lib/app.py"]);
+
+        let config: Config = serde_json::from_str("{}").unwrap();
+        assert!(config.source_paths.is_empty());
+    }
+
+    /// 测试：front-matter 标签和版本写入切片
+    #[test]
+    fn test_with_doc_metadata() {
+        let metadata = DocMetadata {
+            tags: vec!["events".to_string()],
+            version: Some("1.20".to_string()),
+            ..Default::default()
+        };
+        let chunk = with_doc_metadata(to_chunk(1, 0, "a.md", "A", "c"), Some("guide"), &metadata);
+        assert_eq!(chunk.pack.as_deref(), Some("guide"));
+        assert_eq!(chunk.tags, vec!["events"]);
+        assert_eq!(chunk.version.as_deref(), Some("1.20"));
+
+        let chunk = with_doc_metadata(to_chunk(1, 0, "a.md", "A", "c"), None, &DocMetadata::default());
+        assert!(chunk.pack.is_none() && chunk.tags.is_empty() && chunk.version.is_none());
+    }
+
+    /// 测试：切片 ID 只由文件哈希和切片序号决定，重复构建时保持稳定
+    #[test]
+    fn test_to_chunk_ids_are_stable() {
+        let first = to_chunk(42, 3, "docs/a.md", "Title", "Content");
+        let second = to_chunk(42, 3, "docs/a.md", "Title", "Content");
+        assert_eq!(first.id, "42-3");
+        assert_eq!(first.id, second.id);
+        assert_eq!(first.symbol_name, "Title");
+        assert_eq!(first.file_path, "docs/a.md");
+        assert_eq!(first.node_type, "prose");
+    }
+
+    /// 测试：代码块转换为带语言类型的代码 chunk
+    #[test]
+    fn test_code_chunks() {
+        let blocks = extract_code_blocks(
+            "Intro\n\n```rs\nfn spawn_entity() {}\n```\n\n```\nplain text\n```\n",
+        );
+        let heading_path = vec!["Doc".to_string(), "Spawning".to_string()];
+        let chunks = code_chunks(42, 3, "docs/a.md", "Spawning", &heading_path, &blocks);
+
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[0].id, "42-3-code-0");
+        assert_eq!(chunks[0].node_type, "code:rust");
+        assert!(chunks[0].content.contains("fn spawn_entity()"));
+        assert!(chunks[0].dependencies.contains(&"spawn_entity".to_string()));
+        assert_eq!(chunks[0].symbol_name, "Spawning");
+        assert_eq!(chunks[0].heading_path, heading_path);
+        assert_eq!(chunks[1].id, "42-3-code-1");
+        assert_eq!(chunks[1].node_type, "code");
+    }
+
+    /// 测试：PDF 切片的页码范围写入正文和代码 chunk，没有页码的切片不写
+    #[test]
+    fn test_section_chunks_carry_page_range() {
+        let pages = PageRange { start: 3, end: 4 };
+        let mut section = SlicedSection {
+            section_title: "Setup".to_string(),
+            content: "Install it\n\n```rs\nfn install() {}\n```\n".to_string(),
+            parent_doc_title: "Manual".to_string(),
+            summary: String::new(),
+            heading_path: vec!["Manual".to_string(), "Setup".to_string()],
+            code_blocks: extract_code_blocks("```rs\nfn install() {}\n```\n"),
+            links: Vec::new(),
+            pages: Some(pages),
+        };
+
+        let chunks = section_chunks(42, 1, "docs/manual.pdf", &section);
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[0].id, "42-1");
+        assert_eq!(chunks[1].id, "42-1-code-0");
+        assert!(chunks.iter().all(|chunk| chunk.pages == Some(pages)));
+
+        section.pages = None;
+        let chunks = section_chunks(42, 1, "docs/manual.pdf", &section);
+        assert!(chunks.iter().all(|chunk| chunk.pages.is_none()));
+    }
+
+    /// 测试：链接图节点 ID 与切片 ID 一致，跨文档链接可以解析
+    #[test]
+    fn test_graph_nodes_resolve_cross_document_links() {
+        let temp_dir = TempDir::new().unwrap();
+        let guide = temp_dir.path().join("guide.md");
+        let events = temp_dir.path().join("events.md");
+        fs::write(&guide, "# Guide\n\n## Setup\n\nRead [events](events.md#usage).\n").unwrap();
+        fs::write(&events, "# Events\n\n## Intro\n\nText.\n\n## Usage\n\nSee [[Guide]].\n").unwrap();
+
+        let guide_path = guide.to_string_lossy().to_string();
+        let events_path = events.to_string_lossy().to_string();
+        let parse = |path: &str| parse_document(path, &SliceConfig::default()).unwrap();
+        let mut nodes = to_graph_nodes(1, &guide_path, &parse(&guide_path));
+        nodes.extend(to_graph_nodes(2, &events_path, &parse(&events_path)));
+
+        let graph = LinkGraph::build(&nodes);
+        assert_eq!(graph.outbound("1-0"), vec!["2-1"]);
+        assert_eq!(graph.outbound("2-1"), vec!["1-0"]);
+        assert!(graph.orphans().is_empty());
+    }
+
+    /// 测试：文档变短或被删除后重新构建，不再生成的切片被找出；解析失败的文件和范围外的切片保留
+    #[test]
+    fn test_rebuild_finds_stale_sections() {
+        let temp_dir = TempDir::new().unwrap();
+        let docs = temp_dir.path().join("docs");
+        fs::create_dir_all(&docs).unwrap();
+        let config = BuildConfig {
+            docs_path: docs.to_string_lossy().to_string(),
+            pack: None,
+            slice_config: SliceConfig::default(),
+            source_paths: Vec::new(),
+        };
+        let guide = docs.join("guide.md").to_string_lossy().to_string();
+        let old = docs.join("old.md").to_string_lossy().to_string();
+        fs::write(
+            &guide,
+            "# Guide\n\n## One\n\nA.\n\n## Two\n\nB.\n\n## Three\n\nC.\n",
+        )
+        .unwrap();
+        fs::write(&old, "# Old\n\nText.\n").unwrap();
+
+        // 第一次构建后存储中的切片，外加一个不在构建范围内的文档
+        let mut stored = HashMap::new();
+        for path in [&guide, &old] {
+            for chunk in document_chunks(path, &config).unwrap().chunks {
+                stored.insert(chunk.id, chunk.file_path);
+            }
+        }
+        stored.insert("api-doc".to_string(), "notes/api.md".to_string());
+
+        // guide.md 变短、old.md 被删除后重新构建
+        fs::write(&guide, "# Guide\n\n## One\n\nA.\n").unwrap();
+        fs::remove_file(&old).unwrap();
+        let doc = document_chunks(&guide, &config).unwrap();
+        let mut files = IndexedFiles::default();
+        files.record(&guide, &doc.chunks, doc.graph_nodes);
+
+        let stale = files.stale_ids(&stored, &BTreeSet::new(), |path| config.in_scope(path));
+        let stale_paths: Vec<&str> = stale.iter().map(|id| stored[id].as_str()).collect();
+        assert_eq!(stale_paths.iter().filter(|p| **p == guide).count(), 2);
+        assert_eq!(stale_paths.iter().filter(|p| **p == old).count(), 1);
+        assert!(doc.chunks.iter().all(|chunk| !stale.contains(&chunk.id)));
+        assert!(!stale.contains(&"api-doc".to_string()));
+
+        // 解析失败的文件保留原有切片
+        let failed = BTreeSet::from([old.clone()]);
+        let stale = files.stale_ids(&stored, &failed, |path| config.in_scope(path));
+        assert!(stale.iter().all(|id| stored[id] == guide));
+        assert_eq!(stale.len(), 2);
+    }
+
+    /// 测试：构建范围包括文档目录下的文档和 source_paths 中的源文件
+    #[test]
+    fn test_build_config_in_scope() {
+        let config = BuildConfig {
+            docs_path: "docs/examples".to_string(),
+            pack: None,
+            slice_config: SliceConfig::default(),
+            source_paths: vec!["src".to_string(), "lib/app.py".to_string()],
+        };
+        assert!(config.in_scope("docs/examples/a.md"));
+        assert!(!config.in_scope("docs/examples/nested/a.md"));
+        assert!(!config.in_scope("docs/examples/logo.png"));
+        assert!(config.in_scope("src/nested/app.ts"));
+        assert!(config.in_scope("lib/app.py"));
+        assert!(!config.in_scope("lib/other.py"));
+        assert!(!config.in_scope("notes/api.md"));
+    }
+
+    /// 测试：contextfy.json 中的 name 作为知识包名读取
+    #[test]
+    fn test_config_name_is_pack() {
+        let config: Config = serde_json::from_str(r#"{"name": "guide"}"#).unwrap();
+        assert_eq!(config.name.as_deref(), Some("guide"));
+    }
+
     /// 测试：完整的 Config 结构体可以正确反序列化
     #[test]
     fn test_full_config_deserialization() {
@@ -0,0 +1,149 @@
+use anyhow::Result;
+use clap::ValueEnum;
+use colored::Colorize;
+use contextfy_core::{ConsistencyReport, RepairStrategy, SearchEngine};
+
+/// 单个问题列表最多打印的 ID 数量
+const MAX_LISTED_IDS: usize = 10;
+
+/// 修复模式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum RepairMode {
+    /// 以 BM25 为准，在两个存储之间重新同步文档
+    Resync,
+    /// 删除只存在于单个存储中的孤儿文档
+    DeleteOrphans,
+}
+
+impl From<RepairMode> for RepairStrategy {
+    fn from(mode: RepairMode) -> Self {
+        match mode {
+            RepairMode::Resync => RepairStrategy::Resync,
+            RepairMode::DeleteOrphans => RepairStrategy::DeleteOrphans,
+        }
+    }
+}
+
+/// 检查知识库一致性
+///
+/// 枚举 Tantivy (BM25) 与 LanceDB (Vector) 中的全部文档，报告只存在于一侧的 ID、
+/// 重复 ID 以及内容不一致的文档。指定 `repair` 时按对应策略修复。
+///
+/// # Arguments
+///
+/// * `repair` - 可选的修复模式；为 `None` 时只检查不修改
+///
+/// # Errors
+///
+/// 如果知识库打开失败、检查或修复失败，或者未修复时发现不一致，返回错误
+///
+/// # Examples
+///
+/// ```no_run
+/// # use contextfy_cli::commands::doctor;
+/// # #[tokio::main]
+/// # async fn main() -> anyhow::Result<()> {
+/// doctor(None).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub async fn doctor(repair: Option<RepairMode>) -> Result<()> {
+    let engine = SearchEngine::new(
+        Some(std::path::Path::new(".contextfy/data/bm25_index")),
+        ".contextfy/data/lancedb",
+        "knowledge",
+    )
+    .await?;
+
+    println!("Checking index consistency...");
+    let report = engine.check_consistency().await?;
+    print_report(&report);
+
+    if report.is_consistent() {
+        println!("\n{}", "✓ BM25 and vector stores are consistent".green().bold());
+        return Ok(());
+    }
+
+    let Some(mode) = repair else {
+        anyhow::bail!(
+            "Found {} issue(s). Run `contextfy doctor --repair resync` or `--repair delete-orphans` to fix them.",
+            report.issue_count()
+        );
+    };
+
+    println!("\nRepairing with strategy: {:?}", mode);
+    let summary = engine.repair(mode.into()).await?;
+    println!(
+        "  BM25:   {} written, {} deleted",
+        summary.bm25_written, summary.bm25_deleted
+    );
+    println!(
+        "  Vector: {} written, {} deleted",
+        summary.vector_written, summary.vector_deleted
+    );
+
+    let after = engine.check_consistency().await?;
+    if after.is_consistent() {
+        println!("\n{}", "✓ Repair complete, stores are consistent".green().bold());
+    } else {
+        println!(
+            "\n{}",
+            format!("! {} issue(s) remain after repair", after.issue_count()).yellow()
+        );
+        print_report(&after);
+    }
+
+    Ok(())
+}
+
+/// 打印一致性报告
+fn print_report(report: &ConsistencyReport) {
+    println!("  BM25 documents:   {}", report.bm25_count);
+    println!("  Vector documents: {}", report.vector_count);
+
+    print_ids("Missing in vector store", &report.missing_in_vector);
+    print_ids("Missing in BM25 store", &report.missing_in_bm25);
+    print_ids("Duplicated in BM25 store", &report.duplicates_bm25);
+    print_ids("Duplicated in vector store", &report.duplicates_vector);
+    print_ids("Content mismatches", &report.content_mismatches);
+}
+
+/// 打印一类问题的 ID 列表（超过上限时截断）
+fn print_ids(label: &str, ids: &[String]) {
+    if ids.is_empty() {
+        return;
+    }
+
+    println!("\n  {} ({}):", label.red().bold(), ids.len());
+    for id in ids.iter().take(MAX_LISTED_IDS) {
+        println!("    - {}", id);
+    }
+    if ids.len() > MAX_LISTED_IDS {
+        println!("    ... and {} more", ids.len() - MAX_LISTED_IDS);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 测试：修复模式映射到核心策略
+    #[test]
+    fn test_repair_mode_into_strategy() {
+        assert_eq!(RepairStrategy::from(RepairMode::Resync), RepairStrategy::Resync);
+        assert_eq!(
+            RepairStrategy::from(RepairMode::DeleteOrphans),
+            RepairStrategy::DeleteOrphans
+        );
+    }
+
+    /// 测试：命令行取值为 kebab-case
+    #[test]
+    fn test_repair_mode_cli_values() {
+        assert_eq!(
+            RepairMode::from_str("delete-orphans", false).unwrap(),
+            RepairMode::DeleteOrphans
+        );
+        assert_eq!(RepairMode::from_str("resync", false).unwrap(), RepairMode::Resync);
+    }
+}
@@ -76,6 +76,7 @@ pub async fn migrate(
     println!("   - Successful: {}", stats.successful);
     println!("   - Failed: {}", stats.failed);
     println!("   - Skipped: {}", stats.skipped);
+    println!("   - Updated: {}", stats.updated);
     println!("   - Success rate: {:.1}%", stats.success_rate() * 100.0);
 
     Ok(())
@@ -1,11 +1,15 @@
 pub mod build;
+pub mod doctor;
 pub mod init;
 pub mod migrate;
 pub mod scout;
 pub mod serve;
+pub mod watch;
 
 pub use build::build;
+pub use doctor::{doctor, RepairMode};
 pub use init::init;
 pub use migrate::migrate;
 pub use scout::scout;
 pub use serve::serve;
+pub use watch::build_watch;
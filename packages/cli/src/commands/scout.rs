@@ -68,6 +68,9 @@ pub async fn scout(query: String) -> Result<()> {
                             if let Some(Some(doc)) = docs.get(i) {
                                 println!("    Symbol Name: {}", doc.symbol_name);
                                 println!("    File Path: {}", doc.file_path);
+                                if doc.pages.is_some() {
+                                    println!("    Citation: {}", doc.citation());
+                                }
                             } else {
                                 println!("    (Document details not available)");
                             }
@@ -0,0 +1,441 @@
+use anyhow::Result;
+use contextfy_core::{is_document_path, is_source_path, AstChunk, SearchEngine};
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+use std::collections::BTreeSet;
+use std::path::{Component, Path, PathBuf};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc::{self, UnboundedReceiver};
+
+use super::build::{
+    build_all, delete_chunks, document_chunks, is_skipped_source_dir, open_engine, source_chunks,
+    BuildConfig, ChunkChanges, IndexedFiles,
+};
+
+/// 文件系统事件的防抖间隔：最后一个事件之后静默这么久才开始重建索引
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// 被监听文件的类型，决定使用文档解析还是源代码切片
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum FileKind {
+    Document,
+    Source,
+}
+
+/// 需要重新索引的文件（路径与完整构建时一致，因此切片 ID 相同）
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct WatchedFile {
+    path: String,
+    kind: FileKind,
+}
+
+/// 监听根的类型
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum RootKind {
+    /// 文档目录（不递归，与构建一致）
+    Docs,
+    /// 源代码目录（递归）
+    SourceDir,
+    /// 单个源文件
+    SourceFile,
+}
+
+/// 一个被监听的目录或文件
+///
+/// 事件中的路径以 `canonical` 为前缀，映射回配置中的 `configured` 路径。
+#[derive(Debug)]
+struct WatchRoot {
+    kind: RootKind,
+    canonical: PathBuf,
+    configured: PathBuf,
+}
+
+impl WatchRoot {
+    fn new(kind: RootKind, configured: &str) -> Result<Self> {
+        let canonical = Path::new(configured)
+            .canonicalize()
+            .map_err(|e| anyhow::anyhow!("Failed to watch '{}': {}", configured, e))?;
+        Ok(Self {
+            kind,
+            canonical,
+            configured: PathBuf::from(configured),
+        })
+    }
+
+    /// 注册到监听器的路径；单个文件监听其所在目录，编辑器以重命名方式保存时也不会丢失监听
+    fn watch_path(&self) -> &Path {
+        match self.kind {
+            RootKind::SourceFile => self.canonical.parent().unwrap_or(&self.canonical),
+            RootKind::Docs | RootKind::SourceDir => &self.canonical,
+        }
+    }
+
+    fn mode(&self) -> RecursiveMode {
+        match self.kind {
+            RootKind::SourceDir => RecursiveMode::Recursive,
+            RootKind::Docs | RootKind::SourceFile => RecursiveMode::NonRecursive,
+        }
+    }
+
+    /// 把事件路径映射为需要重新索引的文件，不属于该根或构建时不会处理的文件返回 `None`
+    fn resolve(&self, event_path: &Path) -> Option<WatchedFile> {
+        let (path, kind) = match self.kind {
+            RootKind::Docs => {
+                if event_path.parent() != Some(self.canonical.as_path())
+                    || !is_document_path(event_path)
+                {
+                    return None;
+                }
+                (
+                    self.configured.join(event_path.file_name()?),
+                    FileKind::Document,
+                )
+            }
+            RootKind::SourceDir => {
+                let relative = event_path.strip_prefix(&self.canonical).ok()?;
+                let mut dirs = relative.parent()?.components();
+                let skipped = dirs.any(|c| match c {
+                    Component::Normal(name) => is_skipped_source_dir(&name.to_string_lossy()),
+                    _ => true,
+                });
+                if skipped || !is_source_path(relative) {
+                    return None;
+                }
+                (self.configured.join(relative), FileKind::Source)
+            }
+            RootKind::SourceFile => {
+                if event_path != self.canonical {
+                    return None;
+                }
+                (self.configured.clone(), FileKind::Source)
+            }
+        };
+        Some(WatchedFile {
+            path: path.to_string_lossy().to_string(),
+            kind,
+        })
+    }
+}
+
+/// 由构建配置得到所有监听根
+fn watch_roots(config: &BuildConfig) -> Result<Vec<WatchRoot>> {
+    let mut roots = vec![WatchRoot::new(RootKind::Docs, &config.docs_path)?];
+    for source_path in &config.source_paths {
+        let kind = if Path::new(source_path).is_file() {
+            RootKind::SourceFile
+        } else {
+            RootKind::SourceDir
+        };
+        roots.push(WatchRoot::new(kind, source_path)?);
+    }
+    Ok(roots)
+}
+
+/// 等待下一批事件路径：收到第一个事件后，直到 [`DEBOUNCE`] 内不再有新事件才返回
+///
+/// 通道关闭且没有待处理事件时返回 `None`。
+async fn next_batch(rx: &mut UnboundedReceiver<Vec<PathBuf>>) -> Option<Vec<PathBuf>> {
+    let mut paths = rx.recv().await?;
+    while let Ok(more) = tokio::time::timeout(DEBOUNCE, rx.recv()).await {
+        match more {
+            Some(more) => paths.extend(more),
+            None => break,
+        }
+    }
+    Some(paths)
+}
+
+/// 单个文件重新索引的结果
+#[derive(Debug, PartialEq)]
+enum FileChange {
+    /// 新文件入库
+    Added(usize),
+    /// 已入库文件的切片发生变化
+    Updated(ChunkChanges),
+    /// 文件已删除，其切片从两个存储中移除
+    Removed(usize),
+}
+
+impl FileChange {
+    /// 单行变更摘要
+    fn summary(&self, path: &str) -> String {
+        match self {
+            FileChange::Added(count) => format!("  + {}: {} sections", path, count),
+            FileChange::Updated(changes) => format!(
+                "  ~ {}: +{} ~{} -{} sections",
+                path,
+                changes.added.len(),
+                changes.updated.len(),
+                changes.removed.len()
+            ),
+            FileChange::Removed(count) => format!("  - {}: {} sections removed", path, count),
+        }
+    }
+}
+
+/// 重新解析一个文件，只写入新增或变化的切片并删除消失的切片
+///
+/// 文件内容没有变化（或文件不存在且从未入库）时返回 `None`。
+async fn reindex_file(
+    engine: &SearchEngine,
+    config: &BuildConfig,
+    files: &mut IndexedFiles,
+    file: &WatchedFile,
+) -> Result<Option<FileChange>> {
+    if !Path::new(&file.path).exists() {
+        if !files.contains(&file.path) {
+            return Ok(None);
+        }
+        let removed = files.remove(&file.path);
+        delete_chunks(engine, &removed).await?;
+        return Ok(Some(FileChange::Removed(removed.len())));
+    }
+
+    let (chunks, graph_nodes) = match file.kind {
+        FileKind::Document => {
+            let doc = document_chunks(&file.path, config)?;
+            (doc.chunks, doc.graph_nodes)
+        }
+        FileKind::Source => (
+            source_chunks(&file.path, config.pack.as_deref())?,
+            Vec::new(),
+        ),
+    };
+
+    let is_new = !files.contains(&file.path);
+    let changes = files.diff(&file.path, &chunks);
+    if changes.is_empty() {
+        return Ok(None);
+    }
+
+    let changed: Vec<AstChunk> = chunks
+        .iter()
+        .filter(|chunk| changes.added.contains(&chunk.id) || changes.updated.contains(&chunk.id))
+        .cloned()
+        .collect();
+    if !changed.is_empty() {
+        engine.upsert_batch(changed).await?;
+    }
+    delete_chunks(engine, &changes.removed).await?;
+    files.record(&file.path, &chunks, graph_nodes);
+
+    Ok(Some(if is_new {
+        FileChange::Added(chunks.len())
+    } else {
+        FileChange::Updated(changes)
+    }))
+}
+
+/// 重新索引一批文件，有变化时重建链接图并打印摘要
+///
+/// 单个文件或链接图写入失败时打印错误并继续监听，保存修复后的文件会再次触发重建。
+async fn reindex(
+    engine: &SearchEngine,
+    config: &BuildConfig,
+    files: &mut IndexedFiles,
+    batch: BTreeSet<WatchedFile>,
+) {
+    let started = Instant::now();
+    let mut changed_files = 0;
+    for file in &batch {
+        match reindex_file(engine, config, files, file).await {
+            Ok(Some(change)) => {
+                println!("{}", change.summary(&file.path));
+                changed_files += 1;
+            }
+            Ok(None) => {}
+            Err(e) => eprintln!("  ✗ Failed to reindex {}: {}", file.path, e),
+        }
+    }
+    if changed_files == 0 {
+        return;
+    }
+
+    let link_graph = files.link_graph();
+    let (link_count, unresolved_count) = (link_graph.edges().len(), link_graph.unresolved().len());
+    if let Err(e) = engine.set_link_graph(link_graph) {
+        eprintln!("  ✗ Failed to store link graph: {}", e);
+    }
+    println!(
+        "✓ Reindexed {} files in {} ms (link graph: {} links, {} unresolved)",
+        changed_files,
+        started.elapsed().as_millis(),
+        link_count,
+        unresolved_count
+    );
+}
+
+/// 监听模式构建知识库
+///
+/// 先执行一次完整构建，然后监听文档目录和 `source_paths` 的文件变化。事件经过防抖后，
+/// 只重新解析发生变化的文件，在两个存储中写入新增或变化的切片、删除已消失的切片，
+/// 并重建链接图。按 Ctrl+C 退出。
+///
+/// 与完整构建不同，初始构建中的解析错误不会终止监听。
+///
+/// # Errors
+///
+/// 如果配置文件格式错误、监听的目录不存在或无法注册文件监听，返回错误
+pub async fn build_watch() -> Result<()> {
+    let engine = open_engine().await?;
+    let config = BuildConfig::load()?;
+    let outcome = build_all(&engine, &config).await?;
+    let mut files = outcome.files;
+
+    println!(
+        "\n✓ Initial build complete: {} documents, {} sections, {} source code definitions",
+        outcome.documents_count, outcome.sections_count, outcome.code_chunk_count
+    );
+    if outcome.parse_errors > 0 {
+        eprintln!(
+            "  ✗ {} files failed to build; they will be retried when saved",
+            outcome.parse_errors
+        );
+    }
+
+    let roots = watch_roots(&config)?;
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let mut watcher = notify::recommended_watcher(move |res: notify::Result<Event>| match res {
+        Ok(event) if !matches!(event.kind, EventKind::Access(_)) => {
+            let _ = tx.send(event.paths);
+        }
+        Ok(_) => {}
+        Err(e) => eprintln!("  ✗ Watch error: {}", e),
+    })?;
+    for root in &roots {
+        watcher.watch(root.watch_path(), root.mode())?;
+    }
+
+    println!(
+        "Watching {} for changes (Ctrl+C to stop)...",
+        roots
+            .iter()
+            .map(|root| root.configured.display().to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+
+    let ctrl_c = tokio::signal::ctrl_c();
+    tokio::pin!(ctrl_c);
+    loop {
+        let paths = tokio::select! {
+            _ = &mut ctrl_c => break,
+            paths = next_batch(&mut rx) => paths,
+        };
+        let Some(paths) = paths else { break };
+
+        let batch: BTreeSet<WatchedFile> = paths
+            .iter()
+            .filter_map(|path| roots.iter().find_map(|root| root.resolve(path)))
+            .collect();
+        if !batch.is_empty() {
+            reindex(&engine, &config, &mut files, batch).await;
+        }
+    }
+
+    println!("\nStopped watching.");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn root(kind: RootKind, path: &Path) -> WatchRoot {
+        WatchRoot::new(kind, &path.to_string_lossy()).unwrap()
+    }
+
+    /// 测试：文档目录只接受直接子文件中的文档，路径映射回配置中的目录
+    #[test]
+    fn test_resolve_docs_root() {
+        let temp_dir = TempDir::new().unwrap();
+        let docs = temp_dir.path().join("docs");
+        fs::create_dir_all(docs.join("nested")).unwrap();
+        let root = root(RootKind::Docs, &docs);
+
+        let file = root.resolve(&root.canonical.join("guide.md")).unwrap();
+        assert_eq!(file.path, docs.join("guide.md").to_string_lossy());
+        assert_eq!(file.kind, FileKind::Document);
+
+        assert!(root.resolve(&root.canonical.join("logo.png")).is_none());
+        assert!(root.resolve(&root.canonical.join("nested/a.md")).is_none());
+    }
+
+    /// 测试：源代码目录递归匹配，跳过构建产物和隐藏目录
+    #[test]
+    fn test_resolve_source_roots() {
+        let temp_dir = TempDir::new().unwrap();
+        let src = temp_dir.path().join("src");
+        fs::create_dir_all(&src).unwrap();
+        fs::write(src.join("lib.rs"), "").unwrap();
+        let dir = root(RootKind::SourceDir, &src);
+
+        let file = dir.resolve(&dir.canonical.join("nested/app.ts")).unwrap();
+        assert_eq!(file.path, src.join("nested/app.ts").to_string_lossy());
+        assert_eq!(file.kind, FileKind::Source);
+        assert!(dir
+            .resolve(&dir.canonical.join("target/debug/gen.rs"))
+            .is_none());
+        assert!(dir.resolve(&dir.canonical.join(".git/hook.py")).is_none());
+        assert!(dir.resolve(&dir.canonical.join("README.md")).is_none());
+
+        let single = root(RootKind::SourceFile, &src.join("lib.rs"));
+        assert_eq!(single.watch_path(), dir.canonical);
+        assert!(single.resolve(&single.canonical).is_some());
+        assert!(single.resolve(&dir.canonical.join("other.rs")).is_none());
+    }
+
+    /// 测试：防抖期间的事件合并为一批
+    #[tokio::test]
+    async fn test_next_batch_debounces_events() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        tx.send(vec![PathBuf::from("a.md")]).unwrap();
+        tx.send(vec![PathBuf::from("b.md")]).unwrap();
+
+        let batch = next_batch(&mut rx).await.unwrap();
+        assert_eq!(batch, vec![PathBuf::from("a.md"), PathBuf::from("b.md")]);
+
+        drop(tx);
+        assert!(next_batch(&mut rx).await.is_none());
+    }
+
+    /// 测试：重新解析后的切片与记录比较，得到新增、变化和删除的切片
+    #[test]
+    fn test_indexed_files_diff() {
+        let chunk = |id: &str, content: &str| {
+            AstChunk::without_dependencies(id, "docs/a.md", "A", "prose", content)
+        };
+        let mut files = IndexedFiles::default();
+        files.record(
+            "docs/a.md",
+            &[chunk("1-0", "one"), chunk("1-1", "two")],
+            Vec::new(),
+        );
+
+        let changes = files.diff(
+            "docs/a.md",
+            &[chunk("1-0", "one"), chunk("1-1", "2"), chunk("1-2", "3")],
+        );
+        assert_eq!(changes.added, vec!["1-2"]);
+        assert_eq!(changes.updated, vec!["1-1"]);
+        assert!(changes.removed.is_empty());
+        assert_eq!(
+            FileChange::Updated(changes).summary("docs/a.md"),
+            "  ~ docs/a.md: +1 ~1 -0 sections"
+        );
+
+        let changes = files.diff("docs/a.md", &[chunk("1-0", "one")]);
+        assert_eq!(changes.removed, vec!["1-1"]);
+        assert!(files
+            .diff("docs/a.md", &[chunk("1-0", "one"), chunk("1-1", "two")])
+            .is_empty());
+
+        assert_eq!(
+            files.diff("docs/b.md", &[chunk("2-0", "x")]).added,
+            vec!["2-0"]
+        );
+        assert_eq!(files.remove("docs/a.md"), vec!["1-0", "1-1"]);
+        assert!(!files.contains("docs/a.md"));
+    }
+}
@@ -1,7 +1,7 @@
 use clap::{Parser, Subcommand};
 mod commands;
 
-use commands::{build, init, migrate, scout, serve};
+use commands::{build, build_watch, doctor, init, migrate, scout, serve, RepairMode};
 
 #[derive(Parser)]
 #[command(name = "contextfy")]
@@ -17,11 +17,21 @@ enum Commands {
         #[arg(short, long)]
         template: Option<String>,
     },
-    Build,
+    Build {
+        /// Keep running and reindex changed files as they are saved
+        #[arg(long)]
+        watch: bool,
+    },
     Scout {
         query: String,
     },
     Serve,
+    /// Check that the BM25 and vector stores hold the same documents
+    Doctor {
+        /// Repair detected issues instead of only reporting them
+        #[arg(long, value_enum)]
+        repair: Option<RepairMode>,
+    },
     /// Migrate JSON data to LanceDB
     Migrate {
         /// Path to JSON file or directory of JSON files to migrate
@@ -53,8 +63,12 @@ async fn main() -> anyhow::Result<()> {
         Commands::Init { template } => {
             init(template)?;
         }
-        Commands::Build => {
-            build().await?;
+        Commands::Build { watch } => {
+            if watch {
+                build_watch().await?;
+            } else {
+                build().await?;
+            }
         }
         Commands::Scout { query } => {
             scout(query).await?;
@@ -62,6 +76,9 @@ async fn main() -> anyhow::Result<()> {
         Commands::Serve => {
             serve()?;
         }
+        Commands::Doctor { repair } => {
+            doctor(repair).await?;
+        }
         Commands::Migrate {
             json,
             lancedb_uri,
@@ -73,8 +90,8 @@ async fn main() -> anyhow::Result<()> {
             // Use MigrationConfig defaults which properly expand home directory
             let defaults = contextfy_core::migration::MigrationConfig::default();
 
-            let json_path = json.unwrap_or_else(|| defaults.json_path);
-            let db_uri = lancedb_uri.unwrap_or_else(|| defaults.lancedb_uri);
+            let json_path = json.unwrap_or(defaults.json_path);
+            let db_uri = lancedb_uri.unwrap_or(defaults.lancedb_uri);
 
             migrate(
                 json_path,
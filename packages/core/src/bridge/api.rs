@@ -154,7 +154,7 @@ mod tests {
 
     #[test]
     fn test_bridge_api_default() {
-        let api = BridgeApi::default();
+        let api = <BridgeApi as Default>::default();
         assert!(!api.is_in_runtime_context());
     }
 
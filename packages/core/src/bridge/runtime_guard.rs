@@ -197,7 +197,7 @@ mod tests {
 
         // Inside a runtime context
         let rt = RuntimeGuard::global().expect("Global runtime should initialize");
-        let _ = rt.block_on(async {
+        rt.block_on(async {
             assert!(RuntimeGuard::is_in_runtime());
         });
     }
@@ -209,7 +209,7 @@ mod tests {
 
         // Inside a runtime context
         let rt = RuntimeGuard::global().expect("Global runtime should initialize");
-        let _ = rt.block_on(async {
+        rt.block_on(async {
             assert!(RuntimeGuard::try_current_handle().is_some());
         });
     }
@@ -389,6 +389,7 @@ impl EmbeddingInner for FakeTextEmbedding {
 /// # }
 /// ```
 #[cfg(test)]
+#[derive(Default)]
 pub struct FakeEmbeddingBackend;
 
 #[cfg(test)]
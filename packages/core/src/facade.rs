@@ -11,22 +11,50 @@
 //! Ref: `openspec/changes/refactor-pragmatic-slice-architecture/design.md`
 
 use anyhow::{Context, Result};
-use std::path::Path;
-use std::sync::{Arc, OnceLock};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock, RwLock};
+use std::time::SystemTime;
 
 use crate::embeddings::EmbeddingModel;
-use crate::kernel::types::AstChunk;
-use crate::slices::bm25::trait_::Bm25StoreTrait;
+use crate::kernel::types::{citation, AstChunk, ChunkFilter, PageRange};
+use crate::slices::bm25::trait_::{Bm25Result, Bm25StoreTrait};
 use crate::slices::hybrid::HybridOrchestrator;
 use crate::slices::vector::VectorStoreTrait;
 
 // Re-export DeleteResult for public API use
 pub use crate::slices::hybrid::DeleteResult;
+pub use crate::slices::hybrid::{BackendScore, ExplainedHit, FusionMode};
+pub use crate::slices::hybrid::{ConsistencyReport, RepairReport, RepairStrategy};
+pub use crate::slices::hybrid::RecoveryReport;
+pub use crate::slices::hybrid::{describe_metrics, SearchStage, SEARCH_STAGE_SECONDS};
+pub use crate::slices::hybrid::{ProgressSender, SearchBackend, SearchProgress};
+
+pub use crate::slices::graph::{GraphNode, LinkEdge, LinkGraph, RelatedDocuments, UnresolvedLink};
+
+use crate::slices::graph::LINK_GRAPH_FILE_NAME;
+use crate::slices::bm25::schema::BM25_SCHEMA_VERSION;
+use crate::slices::vector::schema::VECTOR_SCHEMA_VERSION;
+use crate::slices::hybrid::journal::JOURNAL_FILE_NAME;
+use crate::slices::hybrid::OperationJournal;
+use crate::slices::hybrid::metrics::timed;
 
 // Private concrete implementations - invisible to external code
 use crate::slices::bm25::tantivy_impl::TantivyBm25Store;
 use crate::slices::vector::lancedb_impl::LanceDbStore;
 
+/// Set once the shared embedding model has been loaded
+static EMBEDDING_MODEL_LOADED: AtomicBool = AtomicBool::new(false);
+
+/// Whether the shared embedding model has finished loading
+///
+/// Returns false while the first `SearchEngine::new` is still downloading or
+/// loading the model (and if loading failed).
+pub fn embedding_model_loaded() -> bool {
+    EMBEDDING_MODEL_LOADED.load(Ordering::Acquire)
+}
+
 /// Get or initialize the shared embedding model singleton
 ///
 /// This function implements the "initialize once, clone Arc" pattern:
@@ -57,6 +85,7 @@ fn shared_embedding_model() -> Result<Arc<EmbeddingModel>> {
             .map(Arc::new)
             .context("Failed to initialize shared embedding model")?;
         *guard = Some(model.clone());
+        EMBEDDING_MODEL_LOADED.store(true, Ordering::Release);
         Ok(model)
     }
 }
@@ -168,14 +197,34 @@ pub async fn build_hybrid_orchestrator(
 /// ```
 pub struct SearchEngine {
     orchestrator: HybridOrchestrator,
+    /// Resolved document links, replaced as a whole on every build
+    link_graph: RwLock<LinkGraph>,
+    /// Where the link graph is persisted (None = in-memory only)
+    link_graph_path: Option<PathBuf>,
+    /// Stores the engine was opened on (None = built from an orchestrator)
+    location: Option<StoreLocation>,
+}
+
+/// Arguments of `SearchEngine::new`, kept so the engine can be reopened
+#[derive(Debug, Clone)]
+struct StoreLocation {
+    index_dir: Option<PathBuf>,
+    lancedb_uri: String,
+    table_name: String,
 }
 
 impl SearchEngine {
     /// Create a new search engine with default backends
     ///
+    /// When `index_dir` is set, cross-store writes are recorded in a write-ahead
+    /// journal next to it (e.g. `.contextfy/data/journal.jsonl`). Operations left
+    /// unfinished by a previous process are recovered before this returns.
+    /// While another process holds the journal (a running server or build),
+    /// this engine skips recovery and writes without a journal.
+    ///
     /// # Parameters
     ///
-    /// * `index_dir` - Directory for Tantivy BM25 index (None = in-memory)
+    /// * `index_dir` - Directory for Tantivy BM25 index (None = in-memory, no journal)
     /// * `lancedb_uri` - LanceDB connection URI
     /// * `table_name` - LanceDB table name
     ///
@@ -187,9 +236,100 @@ impl SearchEngine {
         lancedb_uri: &str,
         table_name: &str,
     ) -> Result<Self> {
-        let orchestrator = build_hybrid_orchestrator(index_dir, lancedb_uri, table_name).await?;
+        let location = StoreLocation {
+            index_dir: index_dir.map(Path::to_path_buf),
+            lancedb_uri: lancedb_uri.to_string(),
+            table_name: table_name.to_string(),
+        };
+        Self::open(location, None).await
+    }
+
+    /// Open a fresh engine on the same stores
+    ///
+    /// The new engine sees everything committed since this one was opened,
+    /// including by other processes (`contextfy build`), and reloads the link
+    /// graph. It shares this engine's journal, so the journal lock stays with
+    /// the process.
+    ///
+    /// # Errors
+    ///
+    /// Returns error for engines not created with `new`, or when a store
+    /// cannot be opened.
+    pub async fn reopen(&self) -> Result<Self> {
+        let location = self
+            .location
+            .clone()
+            .context("Only engines created with SearchEngine::new can be reopened")?;
+        Self::open(location, self.orchestrator.journal().cloned()).await
+    }
+
+    /// Open the stores at `location`
+    ///
+    /// Uses `journal` when given; otherwise tries to take the journal next to
+    /// the index and recovers its unfinished operations.
+    async fn open(location: StoreLocation, journal: Option<Arc<OperationJournal>>) -> Result<Self> {
+        let index_dir = location.index_dir.as_deref();
+        let mut orchestrator =
+            build_hybrid_orchestrator(index_dir, &location.lancedb_uri, &location.table_name)
+                .await?;
+
+        let data_dir = index_dir.map(|dir| dir.parent().unwrap_or(dir));
+        let mut recover = false;
+        if let Some(journal) = journal {
+            orchestrator = orchestrator.with_journal(journal);
+        } else if let Some(dir) = data_dir {
+            let journal_path = dir.join(JOURNAL_FILE_NAME);
+            let journal = OperationJournal::try_open(&journal_path)
+                .with_context(|| format!("Failed to open journal: {}", journal_path.display()))?;
+            match journal {
+                Some(journal) => {
+                    orchestrator = orchestrator.with_journal(Arc::new(journal));
+                    recover = true;
+                }
+                None => tracing::warn!(
+                    path = %journal_path.display(),
+                    "Journal is held by another process; skipping recovery and writing without a journal"
+                ),
+            }
+        }
+
+        if recover {
+            let recovery = orchestrator
+                .recover_journal()
+                .await
+                .context("Failed to recover unfinished operations from journal")?;
+            if recovery.total() > 0 {
+                tracing::warn!(
+                    adds_completed = recovery.adds_completed,
+                    adds_rolled_back = recovery.adds_rolled_back,
+                    upserts_replayed = recovery.upserts_replayed,
+                    deletes_replayed = recovery.deletes_replayed,
+                    "Recovered unfinished operations from a previous run"
+                );
+            }
+        }
+
+        let mut engine = Self::from_orchestrator(orchestrator);
+        if let Some(dir) = data_dir {
+            let graph_path = dir.join(LINK_GRAPH_FILE_NAME);
+            let graph = LinkGraph::load(&graph_path)
+                .with_context(|| format!("Failed to load link graph: {}", graph_path.display()))?;
+            engine.link_graph = RwLock::new(graph);
+            engine.link_graph_path = Some(graph_path);
+        }
+        engine.location = Some(location);
 
-        Ok(Self { orchestrator })
+        Ok(engine)
+    }
+
+    /// Wrap an orchestrator with an empty, in-memory link graph
+    fn from_orchestrator(orchestrator: HybridOrchestrator) -> Self {
+        Self {
+            orchestrator,
+            link_graph: RwLock::new(LinkGraph::default()),
+            link_graph_path: None,
+            location: None,
+        }
     }
 
     /// Perform hybrid search
@@ -218,6 +358,74 @@ impl SearchEngine {
             .context("Search failed")
     }
 
+    /// Perform hybrid search restricted by a metadata filter
+    ///
+    /// Both backends apply the filter, e.g. to search only chunks tagged
+    /// `events` or belonging to document version `1.20`.
+    ///
+    /// # Parameters
+    ///
+    /// * `query_text` - Search query text
+    /// * `limit` - Maximum number of results to return
+    /// * `filter` - Metadata criteria every result must match
+    pub async fn search_with_filter(
+        &self,
+        query_text: &str,
+        limit: usize,
+        filter: ChunkFilter,
+    ) -> Result<Vec<crate::kernel::types::Hit>> {
+        use crate::kernel::types::Query;
+
+        let query = Query::new(query_text.to_string(), limit).with_filter(filter);
+        self.search_query(&query).await
+    }
+
+    /// Perform hybrid search with a fully specified query
+    ///
+    /// Use this to combine a metadata filter with a node type boost, e.g.
+    /// `Query::new("spawn", 10).with_boost("code", 1.5)` to rank code
+    /// examples above prose.
+    pub async fn search_query(
+        &self,
+        query: &crate::kernel::types::Query,
+    ) -> Result<Vec<crate::kernel::types::Hit>> {
+        self.orchestrator
+            .search(query)
+            .await
+            .context("Search failed")
+    }
+
+    /// Perform a search that reports each backend's rank and score per hit
+    ///
+    /// `mode` selects RRF fusion of both backends (same ranking as
+    /// `search_query`) or a single backend.
+    pub async fn search_explained(
+        &self,
+        query: &crate::kernel::types::Query,
+        mode: FusionMode,
+    ) -> Result<Vec<ExplainedHit>> {
+        self.orchestrator
+            .search_explained(query, mode)
+            .await
+            .context("Search failed")
+    }
+
+    /// Perform an explained search, sending intermediate results to `progress`
+    ///
+    /// Each backend's hits and every stage latency are sent as soon as they
+    /// are known; the fused list is returned as with `search_explained`.
+    pub async fn search_with_progress(
+        &self,
+        query: &crate::kernel::types::Query,
+        mode: FusionMode,
+        progress: &ProgressSender,
+    ) -> Result<Vec<ExplainedHit>> {
+        self.orchestrator
+            .search_with_progress(query, mode, Some(progress))
+            .await
+            .context("Search failed")
+    }
+
     /// Add a document to both BM25 and vector stores
     ///
     /// # Parameters
@@ -260,6 +468,34 @@ impl SearchEngine {
             .context("Failed to add batch documents")
     }
 
+    /// Insert or replace AST chunks in both stores by ID
+    ///
+    /// Unlike `add_batch`, running this repeatedly with the same chunks keeps a
+    /// single copy of each ID, so rebuilds and re-migrations are idempotent.
+    ///
+    /// # Parameters
+    ///
+    /// * `chunks` - AST chunk list
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - Upsert successful
+    /// * `Err(Error)` - Upsert failed
+    pub async fn upsert_batch(&self, chunks: Vec<AstChunk>) -> Result<()> {
+        self.orchestrator
+            .upsert_batch(chunks)
+            .await
+            .context("Failed to upsert documents")
+    }
+
+    /// Insert or replace a single AST chunk in both stores by ID
+    pub async fn upsert(&self, chunk: AstChunk) -> Result<()> {
+        self.orchestrator
+            .upsert(chunk)
+            .await
+            .context("Failed to upsert document")
+    }
+
     /// Delete a document from both stores
     ///
     /// # Parameters
@@ -274,6 +510,20 @@ impl SearchEngine {
         self.orchestrator.delete(id).await
     }
 
+    /// Delete every document matching a filter from both stores
+    ///
+    /// # Parameters
+    ///
+    /// * `filter` - Path prefix, pack and/or node type criteria (AND-ed)
+    ///
+    /// # Returns
+    ///
+    /// Returns DeleteResult with the number of rows removed from each backend.
+    /// An empty filter is rejected by both backends.
+    pub async fn delete_where(&self, filter: &ChunkFilter) -> DeleteResult {
+        self.orchestrator.delete_where(filter).await
+    }
+
     /// Check health of both backends
     ///
     /// Returns true if both backends are healthy.
@@ -284,6 +534,142 @@ impl SearchEngine {
             .context("Health check failed")
     }
 
+    /// Count the documents held by each backend
+    ///
+    /// A backend that cannot be counted is logged and reported as `None`.
+    pub async fn document_counts(&self) -> DocumentCounts {
+        let (bm25, vector) = tokio::join!(
+            self.orchestrator.bm25_store().count(),
+            self.orchestrator.vector_store().count()
+        );
+        let ok = |store: &str, result: std::result::Result<usize, crate::AppError>| match result {
+            Ok(count) => Some(count),
+            Err(e) => {
+                tracing::warn!(error = ?e, store, "Failed to count documents");
+                None
+            }
+        };
+        DocumentCounts {
+            bm25: ok("bm25", bm25),
+            vector: ok("vector", vector),
+        }
+    }
+
+    /// Report the health, size and schema version of each backend
+    ///
+    /// Unlike `health_check`, a failing backend does not short-circuit: its
+    /// error is recorded in the report and the other backend is still checked.
+    pub async fn health_report(&self) -> HealthReport {
+        let (bm25, vector, counts) = tokio::join!(
+            self.orchestrator.bm25_store().health_check(),
+            self.orchestrator.vector_store().health_check(),
+            self.document_counts()
+        );
+        HealthReport {
+            bm25: StoreHealth::new(bm25, counts.bm25, BM25_SCHEMA_VERSION),
+            vector: StoreHealth::new(vector, counts.vector, VECTOR_SCHEMA_VERSION),
+            last_build: self.last_build_time(),
+        }
+    }
+
+    /// Committed version of both indexes, read from disk
+    ///
+    /// Changes whenever either store commits, in this or another process, so
+    /// comparing two readings tells whether the indexes were rebuilt.
+    pub async fn index_version(&self) -> Result<IndexVersion> {
+        let (bm25, vector) = tokio::join!(
+            self.orchestrator.bm25_store().version(),
+            self.orchestrator.vector_store().version()
+        );
+        Ok(IndexVersion {
+            bm25: bm25.context("Failed to read BM25 index version")?,
+            vector: vector.context("Failed to read vector table version")?,
+        })
+    }
+
+    /// Time of the last `contextfy build`
+    ///
+    /// Taken from the link graph file, which every build rewrites. `None` for
+    /// in-memory engines and before the first build.
+    pub fn last_build_time(&self) -> Option<SystemTime> {
+        let path = self.link_graph_path.as_ref()?;
+        std::fs::metadata(path).ok()?.modified().ok()
+    }
+
+    /// Map every chunk ID held by either store to its file path
+    ///
+    /// Full rebuilds use this to delete chunks their source files no longer produce.
+    pub async fn chunk_paths(&self) -> Result<HashMap<String, String>> {
+        self.orchestrator
+            .chunk_paths()
+            .await
+            .context("Failed to list stored chunks")
+    }
+
+    /// Check that the BM25 and vector stores hold the same documents
+    ///
+    /// Reports IDs missing from either store, duplicated IDs and content
+    /// mismatches. Nothing is modified.
+    pub async fn check_consistency(&self) -> Result<ConsistencyReport> {
+        self.orchestrator
+            .check_consistency()
+            .await
+            .context("Consistency check failed")
+    }
+
+    /// Repair drift between the BM25 and vector stores
+    ///
+    /// # Parameters
+    ///
+    /// * `strategy` - Re-sync documents across stores or delete orphans
+    pub async fn repair(&self, strategy: RepairStrategy) -> Result<RepairReport> {
+        self.orchestrator
+            .repair(strategy)
+            .await
+            .context("Repair failed")
+    }
+
+    /// Replace the document link graph and persist it next to the index
+    ///
+    /// Build the graph with `LinkGraph::build` from every indexed section, so
+    /// links across documents resolve.
+    pub fn set_link_graph(&self, graph: LinkGraph) -> Result<()> {
+        if let Some(path) = &self.link_graph_path {
+            graph
+                .save(path)
+                .with_context(|| format!("Failed to save link graph: {}", path.display()))?;
+        }
+        *self
+            .link_graph
+            .write()
+            .map_err(|e| anyhow::anyhow!("Link graph lock poisoned: {}", e))? = graph;
+        Ok(())
+    }
+
+    /// Outbound links, backlinks and orphan status of a section
+    ///
+    /// Returns `None` if the ID is not part of the link graph (e.g. a code
+    /// chunk, or a document added after the last build).
+    pub fn related(&self, id: &str) -> Result<Option<RelatedDocuments>> {
+        Ok(self.read_link_graph()?.related(id))
+    }
+
+    /// Documents that no other document links to, sorted by file path
+    pub fn orphans(&self) -> Result<Vec<String>> {
+        Ok(self.read_link_graph()?.orphans())
+    }
+
+    /// Links that matched no indexed document or heading at the last build
+    pub fn unresolved_links(&self) -> Result<Vec<UnresolvedLink>> {
+        Ok(self.read_link_graph()?.unresolved().to_vec())
+    }
+
+    fn read_link_graph(&self) -> Result<std::sync::RwLockReadGuard<'_, LinkGraph>> {
+        self.link_graph
+            .read()
+            .map_err(|e| anyhow::anyhow!("Link graph lock poisoned: {}", e))
+    }
+
     /// Get internal orchestrator (for advanced usage)
     ///
     /// **NOTE**: This exposes the HybridOrchestrator for advanced use cases.
@@ -294,7 +680,9 @@ impl SearchEngine {
 
     /// Get a document by ID with full content
     ///
-    /// Retrieves document details including full content from the BM25 store.
+    /// Reads from the BM25 store first and falls back to the vector store when
+    /// the document is missing there (e.g. after a partial write failure) or the
+    /// BM25 lookup fails. `DocumentDetails::source` reports which store served it.
     ///
     /// # Parameters
     ///
@@ -302,34 +690,20 @@ impl SearchEngine {
     ///
     /// # Returns
     ///
-    /// Returns document details with full content if found.
+    /// Returns document details with full content if found in either store.
     ///
     /// # Errors
     ///
-    /// Returns error if document retrieval fails.
+    /// Returns error if the vector fallback lookup fails.
     pub async fn get_document(&self, id: &str) -> Result<Option<DocumentDetails>> {
-        self.orchestrator
-            .bm25_store()
-            .get_by_id(id)
-            .await
-            .context("Failed to get document")
-            .map(|opt_result| {
-                #[allow(deprecated)]
-                opt_result.map(|r| DocumentDetails {
-                    id: r.id,
-                    symbol_name: r.symbol_name.clone(),
-                    file_path: r.file_path.clone(),
-                    content: r.content,
-                    // Legacy fields for backward compatibility
-                    title: r.symbol_name,
-                    summary: r.file_path,
-                })
-            })
+        let mut docs = self.get_documents(&[id.to_string()]).await?;
+        Ok(docs.pop().flatten())
     }
 
     /// Get multiple documents by IDs (batch version)
     ///
-    /// More efficient than calling get_document multiple times.
+    /// More efficient than calling get_document multiple times. IDs missing from
+    /// the BM25 store are looked up in the vector store with a single batch call.
     ///
     /// # Parameters
     ///
@@ -338,34 +712,140 @@ impl SearchEngine {
     /// # Returns
     ///
     /// Returns vector of document details in the same order as input IDs.
-    /// Each element is Some(doc) if found, None if not found.
+    /// Each element is Some(doc) if found in either store, None if not found.
     ///
     /// # Errors
     ///
-    /// Returns error if document retrieval fails.
+    /// Returns error if the vector fallback lookup fails.
     pub async fn get_documents(&self, ids: &[String]) -> Result<Vec<Option<DocumentDetails>>> {
-        let results = self
+        timed(SearchStage::Fetch, self.fetch_documents(ids)).await
+    }
+
+    /// BM25 lookup with vector fallback behind `get_documents`
+    async fn fetch_documents(&self, ids: &[String]) -> Result<Vec<Option<DocumentDetails>>> {
+        let mut documents: Vec<Option<DocumentDetails>> = match self
             .orchestrator
             .bm25_store()
             .get_by_ids(ids)
             .await
+        {
+            Ok(results) => results
+                .into_iter()
+                .map(|opt_result| opt_result.map(DocumentDetails::from_bm25))
+                .collect(),
+            Err(e) => {
+                tracing::warn!(error = ?e, "BM25 document lookup failed, falling back to vector store");
+                vec![None; ids.len()]
+            }
+        };
+
+        // Fall back to the vector store for everything BM25 could not serve
+        let missing: Vec<(usize, String)> = documents
+            .iter()
+            .enumerate()
+            .filter(|(_, doc)| doc.is_none())
+            .map(|(i, _)| (i, ids[i].clone()))
+            .collect();
+
+        if missing.is_empty() {
+            return Ok(documents);
+        }
+
+        let missing_ids: Vec<String> = missing.iter().map(|(_, id)| id.clone()).collect();
+        let fallback = self
+            .orchestrator
+            .vector_store()
+            .get_by_ids(&missing_ids)
+            .await
             .context("Failed to get documents")?;
 
-        Ok(results
-            .into_iter()
-            .map(|opt_result| {
-                #[allow(deprecated)]
-                opt_result.map(|r| DocumentDetails {
-                    id: r.id,
-                    symbol_name: r.symbol_name.clone(),
-                    file_path: r.file_path.clone(),
-                    content: r.content,
-                    // Legacy fields for backward compatibility
-                    title: r.symbol_name,
-                    summary: r.file_path,
-                })
-            })
-            .collect())
+        for ((index, _), chunk) in missing.into_iter().zip(fallback) {
+            documents[index] = chunk.map(DocumentDetails::from_vector);
+        }
+
+        Ok(documents)
+    }
+}
+
+/// Number of documents in each backend (`None` when counting failed)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DocumentCounts {
+    pub bm25: Option<usize>,
+    pub vector: Option<usize>,
+}
+
+/// Committed state of both indexes, from `SearchEngine::index_version`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexVersion {
+    /// Digest of the Tantivy index metadata (changes on every commit)
+    pub bm25: Option<String>,
+    /// LanceDB table version
+    pub vector: Option<u64>,
+}
+
+/// Health of both backends, from `SearchEngine::health_report`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HealthReport {
+    pub bm25: StoreHealth,
+    pub vector: StoreHealth,
+    /// Time of the last `contextfy build` (`None` if unknown)
+    pub last_build: Option<SystemTime>,
+}
+
+impl HealthReport {
+    /// Whether both backends passed their health check
+    pub fn is_healthy(&self) -> bool {
+        self.bm25.healthy && self.vector.healthy
+    }
+}
+
+/// Health of one backend
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoreHealth {
+    pub healthy: bool,
+    /// Why the health check failed
+    pub error: Option<String>,
+    /// Stored documents (`None` when counting failed)
+    pub documents: Option<usize>,
+    /// Layout version of the backend's schema
+    pub schema_version: u32,
+}
+
+impl StoreHealth {
+    fn new(
+        check: std::result::Result<bool, crate::AppError>,
+        documents: Option<usize>,
+        schema_version: u32,
+    ) -> Self {
+        let (healthy, error) = match check {
+            Ok(healthy) => (healthy, None),
+            Err(e) => (false, Some(e.to_string())),
+        };
+        Self {
+            healthy,
+            error,
+            documents,
+            schema_version,
+        }
+    }
+}
+
+/// Store that served a document lookup
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DocumentSource {
+    /// Served by the BM25 (Tantivy) store
+    Bm25,
+    /// Served by the vector (LanceDB) store
+    Vector,
+}
+
+impl DocumentSource {
+    /// Get the store name for display and serialization
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Bm25 => "bm25",
+            Self::Vector => "vector",
+        }
     }
 }
 
@@ -385,6 +865,14 @@ pub struct DocumentDetails {
     pub file_path: String,
     /// Full document content (None indicates data integrity issue)
     pub content: Option<String>,
+    /// Node type (`file`, `prose`, `code:rust`, ...; None when the store did not record it)
+    pub node_type: Option<String>,
+    /// Pack the document belongs to
+    pub pack: Option<String>,
+    /// Page range of the section (set for chunks sliced from paginated documents such as PDF)
+    pub pages: Option<PageRange>,
+    /// Store that served this document
+    pub source: DocumentSource,
     /// Legacy field: title (backward compatibility alias for symbol_name)
     #[deprecated(note = "Use `symbol_name` instead")]
     pub title: String,
@@ -393,11 +881,594 @@ pub struct DocumentDetails {
     pub summary: String,
 }
 
+impl DocumentDetails {
+    /// Build details from a BM25 lookup result
+    #[allow(deprecated)]
+    fn from_bm25(r: Bm25Result) -> Self {
+        Self {
+            id: r.id,
+            symbol_name: r.symbol_name.clone(),
+            file_path: r.file_path.clone(),
+            content: r.content,
+            node_type: r.node_type,
+            pack: r.pack,
+            pages: r.pages,
+            source: DocumentSource::Bm25,
+            // Legacy fields for backward compatibility
+            title: r.symbol_name,
+            summary: r.file_path,
+        }
+    }
+
+    /// Build details from a vector store lookup result
+    #[allow(deprecated)]
+    fn from_vector(chunk: AstChunk) -> Self {
+        Self {
+            id: chunk.id,
+            symbol_name: chunk.symbol_name.clone(),
+            file_path: chunk.file_path.clone(),
+            content: Some(chunk.content),
+            node_type: Some(chunk.node_type),
+            pack: chunk.pack,
+            pages: chunk.pages,
+            source: DocumentSource::Vector,
+            // Legacy fields for backward compatibility
+            title: chunk.symbol_name,
+            summary: chunk.file_path,
+        }
+    }
+
+    /// Human-readable citation of the document, e.g. `manual.pdf pp. 3-4`
+    pub fn citation(&self) -> String {
+        citation(&self.file_path, self.pages)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use tempfile::TempDir;
 
+    /// Build an engine over in-memory BM25 and a temporary LanceDB table, using
+    /// the stub embedding model so no model download is needed
+    async fn create_stub_engine(temp_dir: &TempDir) -> SearchEngine {
+        let lancedb_uri = temp_dir.path().join("lancedb");
+        let lancedb_uri_str = lancedb_uri.to_str().expect("Invalid path");
+
+        let bm25_index =
+            crate::slices::bm25::index::create_bm25_index(None).expect("Failed to create index");
+        let bm25_store = TantivyBm25Store::new(bm25_index).expect("Failed to create BM25 store");
+
+        let conn = crate::slices::vector::connection::connect(lancedb_uri_str)
+            .await
+            .expect("Failed to connect to LanceDB");
+        crate::slices::vector::connection::create_table_if_not_exists(&conn, "test_knowledge")
+            .await
+            .expect("Failed to create table");
+        let vector_store =
+            LanceDbStore::new(conn, "test_knowledge", Arc::new(EmbeddingModel::test_stub()));
+
+        SearchEngine::from_orchestrator(HybridOrchestrator::default_with_stores(
+            Arc::new(vector_store),
+            Arc::new(bm25_store),
+        ))
+    }
+
+    #[tokio::test]
+    async fn test_get_document_falls_back_to_vector_store() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let engine = create_stub_engine(&temp_dir).await;
+
+        // Indexed in both stores: served by BM25
+        engine
+            .add("both", "Both Stores", "both.md", "Indexed everywhere", None)
+            .await
+            .expect("Should add document");
+
+        // Simulate a partial failure: document only exists in the vector store
+        engine
+            .orchestrator()
+            .vector_store()
+            .add_batch(vec![AstChunk::without_dependencies(
+                "vector-only",
+                "orphan.md",
+                "Orphan",
+                "file",
+                "Only in LanceDB",
+            )])
+            .await
+            .expect("Should add to vector store");
+
+        let doc = engine
+            .get_document("both")
+            .await
+            .expect("Lookup should succeed")
+            .expect("Document should exist");
+        assert_eq!(doc.source, DocumentSource::Bm25);
+
+        let doc = engine
+            .get_document("vector-only")
+            .await
+            .expect("Lookup should succeed")
+            .expect("Vector-only document should be found via fallback");
+        assert_eq!(doc.source, DocumentSource::Vector);
+        assert_eq!(doc.symbol_name, "Orphan");
+        assert_eq!(doc.content.as_deref(), Some("Only in LanceDB"));
+
+        let docs = engine
+            .get_documents(&[
+                "vector-only".to_string(),
+                "missing".to_string(),
+                "both".to_string(),
+            ])
+            .await
+            .expect("Batch lookup should succeed");
+        assert_eq!(docs[0].as_ref().map(|d| d.source), Some(DocumentSource::Vector));
+        assert!(docs[1].is_none());
+        assert_eq!(docs[2].as_ref().map(|d| d.source), Some(DocumentSource::Bm25));
+    }
+
+    #[tokio::test]
+    async fn test_get_document_returns_page_range() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let engine = create_stub_engine(&temp_dir).await;
+        let pages = PageRange { start: 3, end: 4 };
+
+        engine
+            .add_batch(vec![
+                AstChunk::without_dependencies(
+                    "paged",
+                    "docs/manual.pdf",
+                    "Setup",
+                    "prose",
+                    "Install it",
+                )
+                .with_pages(pages),
+                AstChunk::without_dependencies(
+                    "plain",
+                    "docs/guide.md",
+                    "Guide",
+                    "prose",
+                    "Read it",
+                ),
+            ])
+            .await
+            .expect("Should add to both stores");
+        engine
+            .orchestrator()
+            .vector_store()
+            .add_batch(vec![AstChunk::without_dependencies(
+                "vector-paged",
+                "docs/manual.pdf",
+                "Usage",
+                "prose",
+                "Run it",
+            )
+            .with_pages(PageRange { start: 7, end: 7 })])
+            .await
+            .expect("Should add to vector store");
+
+        let doc = engine
+            .get_document("paged")
+            .await
+            .expect("Lookup should succeed")
+            .expect("Document should exist");
+        assert_eq!(doc.source, DocumentSource::Bm25);
+        assert_eq!(doc.pages, Some(pages));
+        assert_eq!(doc.citation(), "manual.pdf pp. 3-4");
+
+        let docs = engine
+            .get_documents(&["vector-paged".to_string(), "plain".to_string()])
+            .await
+            .expect("Batch lookup should succeed");
+        let vector_doc = docs[0]
+            .as_ref()
+            .expect("Vector-only document should be found");
+        assert_eq!(vector_doc.source, DocumentSource::Vector);
+        assert_eq!(vector_doc.pages, Some(PageRange { start: 7, end: 7 }));
+        assert_eq!(vector_doc.citation(), "manual.pdf p. 7");
+        assert_eq!(docs[1].as_ref().and_then(|d| d.pages), None);
+
+        // Both stores keep the range when listing chunks back
+        let stored = [
+            engine.orchestrator().bm25_store().list_chunks().await,
+            engine.orchestrator().vector_store().list_chunks().await,
+        ];
+        for chunks in stored {
+            let chunks = chunks.expect("Should list chunks");
+            let paged = chunks
+                .iter()
+                .find(|c| c.id == "paged")
+                .expect("Chunk should be stored");
+            assert_eq!(paged.pages, Some(pages));
+        }
+    }
+
+    /// Leave one orphan in each store and one stale vector copy
+    async fn diverge_stores(engine: &SearchEngine) {
+        engine
+            .add_batch(vec![AstChunk::without_dependencies(
+                "synced", "a.md", "Synced", "file", "same everywhere",
+            )])
+            .await
+            .expect("Should add to both stores");
+        engine
+            .orchestrator()
+            .bm25_store()
+            .add_batch(vec![AstChunk::without_dependencies(
+                "bm25-only", "b.md", "BM25 Orphan", "file", "only in tantivy",
+            )])
+            .await
+            .expect("Should add to BM25 store");
+        engine
+            .orchestrator()
+            .vector_store()
+            .add_batch(vec![
+                AstChunk::without_dependencies(
+                    "vector-only", "c.md", "Vector Orphan", "file", "only in lancedb",
+                ),
+                AstChunk::without_dependencies("synced", "a.md", "Synced", "file", "stale copy"),
+            ])
+            .await
+            .expect("Should add to vector store");
+    }
+
+    #[tokio::test]
+    async fn test_check_consistency_and_resync() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let engine = create_stub_engine(&temp_dir).await;
+        diverge_stores(&engine).await;
+
+        let counts = engine.document_counts().await;
+        assert_eq!(counts, DocumentCounts { bm25: Some(2), vector: Some(2) });
+
+        let report = engine.check_consistency().await.expect("Check should succeed");
+        assert_eq!(report.missing_in_vector, vec!["bm25-only"]);
+        assert_eq!(report.missing_in_bm25, vec!["vector-only"]);
+        assert_eq!(report.content_mismatches, vec!["synced"]);
+
+        let repair = engine
+            .repair(RepairStrategy::Resync)
+            .await
+            .expect("Repair should succeed");
+        assert_eq!(repair.vector_written, 2);
+        assert_eq!(repair.bm25_written, 1);
+
+        let report = engine.check_consistency().await.expect("Check should succeed");
+        assert!(report.is_consistent(), "Stores should match after resync: {:?}", report);
+
+        // BM25 is the source of truth for mismatched content
+        let doc = engine
+            .orchestrator()
+            .vector_store()
+            .get_by_id("synced")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(doc.content, "same everywhere");
+    }
+
+    #[tokio::test]
+    async fn test_repair_delete_orphans() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let engine = create_stub_engine(&temp_dir).await;
+        diverge_stores(&engine).await;
+
+        let repair = engine
+            .repair(RepairStrategy::DeleteOrphans)
+            .await
+            .expect("Repair should succeed");
+        assert_eq!(repair.bm25_deleted, 1);
+        assert_eq!(repair.vector_deleted, 1);
+
+        let report = engine.check_consistency().await.expect("Check should succeed");
+        assert!(report.missing_in_vector.is_empty());
+        assert!(report.missing_in_bm25.is_empty());
+        // Mismatches are only fixed by a resync
+        assert_eq!(report.content_mismatches, vec!["synced"]);
+    }
+
+    #[tokio::test]
+    async fn test_journal_recovery_rolls_back_half_written_add() {
+        use crate::slices::hybrid::journal::OperationKind;
+
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let engine = create_stub_engine(&temp_dir).await;
+        let journal_path = temp_dir.path().join(JOURNAL_FILE_NAME);
+
+        // Simulate a crash: intents journaled, only one store written per add
+        {
+            let journal = OperationJournal::open(&journal_path).unwrap();
+            journal
+                .begin(OperationKind::Add, vec!["half".to_string()], &["half written"])
+                .unwrap();
+            journal
+                .begin(OperationKind::Add, vec!["done".to_string()], &["fully written"])
+                .unwrap();
+            journal
+                .begin(OperationKind::Delete, vec!["gone".to_string()], &[])
+                .unwrap();
+        }
+        engine
+            .orchestrator()
+            .bm25_store()
+            .add_batch(vec![AstChunk::without_dependencies(
+                "half", "a.md", "Half", "file", "half written",
+            )])
+            .await
+            .unwrap();
+        engine
+            .add_batch(vec![
+                AstChunk::without_dependencies("done", "b.md", "Done", "file", "fully written"),
+                AstChunk::without_dependencies("gone", "c.md", "Gone", "file", "deleted"),
+            ])
+            .await
+            .unwrap();
+        engine
+            .orchestrator()
+            .bm25_store()
+            .delete("gone")
+            .await
+            .unwrap();
+
+        // Reopen with the journal attached, as SearchEngine::new does
+        let SearchEngine { orchestrator, .. } = engine;
+        let orchestrator = orchestrator
+            .with_journal(Arc::new(OperationJournal::open(&journal_path).unwrap()));
+        let report = orchestrator.recover_journal().await.expect("Recovery should succeed");
+
+        assert_eq!(report.adds_completed, 1);
+        assert_eq!(report.adds_rolled_back, 1);
+        assert_eq!(report.deletes_replayed, 1);
+
+        let engine = SearchEngine::from_orchestrator(orchestrator);
+        assert!(engine.get_document("half").await.unwrap().is_none());
+        assert!(engine.get_document("done").await.unwrap().is_some());
+        assert!(engine.get_document("gone").await.unwrap().is_none());
+        assert!(engine.check_consistency().await.unwrap().is_consistent());
+
+        // Journal is cleared once recovered
+        let journal = engine.orchestrator().journal().expect("Journal should be attached");
+        assert!(journal.pending().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_journal_recovery_reapplies_interrupted_upsert() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let engine = create_stub_engine(&temp_dir).await;
+        let journal_path = temp_dir.path().join(JOURNAL_FILE_NAME);
+        let chunk =
+            |content: &str| AstChunk::without_dependencies("doc", "a.md", "Doc", "file", content);
+
+        // A good version exists in both stores
+        engine.upsert(chunk("old version")).await.unwrap();
+
+        // Simulate a crash: upsert journaled, only the vector store replaced
+        OperationJournal::open(&journal_path)
+            .unwrap()
+            .begin_upsert(&[chunk("new version")])
+            .unwrap();
+        engine
+            .orchestrator()
+            .vector_store()
+            .upsert_batch(vec![chunk("new version")])
+            .await
+            .unwrap();
+
+        let SearchEngine { orchestrator, .. } = engine;
+        let orchestrator = orchestrator
+            .with_journal(Arc::new(OperationJournal::open(&journal_path).unwrap()));
+        let report = orchestrator.recover_journal().await.expect("Recovery should succeed");
+
+        assert_eq!(report.upserts_replayed, 1);
+        assert_eq!(report.adds_rolled_back, 0);
+
+        // The document is kept, with the new version in both stores
+        let engine = SearchEngine::from_orchestrator(orchestrator);
+        let doc = engine.get_document("doc").await.unwrap().expect("Document should be kept");
+        assert_eq!(doc.content.as_deref(), Some("new version"));
+        let report = engine.check_consistency().await.unwrap();
+        assert!(report.is_consistent());
+        assert!(report.content_mismatches.is_empty());
+        let journal = engine.orchestrator().journal().expect("Journal should be attached");
+        assert!(journal.pending().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_link_graph_is_persisted_and_queried() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let graph_path = temp_dir.path().join(LINK_GRAPH_FILE_NAME);
+        let mut engine = create_stub_engine(&temp_dir).await;
+        engine.link_graph_path = Some(graph_path.clone());
+
+        assert!(engine.related("a-0").unwrap().is_none());
+
+        let node = |id: &str, file_path: &str, content: &str| GraphNode {
+            id: id.to_string(),
+            file_path: file_path.to_string(),
+            title: id.to_string(),
+            doc_title: id.to_string(),
+            links: crate::parser::extract_links(content),
+        };
+        let graph = LinkGraph::build(&[
+            node("a-0", "docs/a.md", "See [b](b.md) and [gone](gone.md)."),
+            node("b-0", "docs/b.md", "No links."),
+        ]);
+        engine.set_link_graph(graph.clone()).unwrap();
+
+        let related = engine.related("b-0").unwrap().unwrap();
+        assert_eq!(related.backlinks, vec!["a-0"]);
+        assert!(!related.orphan);
+        assert_eq!(engine.orphans().unwrap(), vec!["docs/a.md"]);
+        assert_eq!(engine.unresolved_links().unwrap()[0].target, "gone.md");
+        assert_eq!(LinkGraph::load(&graph_path).unwrap(), graph);
+    }
+
+    #[tokio::test]
+    async fn test_health_report_covers_both_stores() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let mut engine = create_stub_engine(&temp_dir).await;
+        engine
+            .add("doc", "Doc", "doc.md", "content", None)
+            .await
+            .expect("Should add to both stores");
+
+        let report = engine.health_report().await;
+        assert!(report.is_healthy(), "{:?}", report);
+        assert_eq!(report.bm25.documents, Some(1));
+        assert_eq!(report.vector.documents, Some(1));
+        assert_eq!(report.bm25.schema_version, BM25_SCHEMA_VERSION);
+        assert!(report.bm25.error.is_none());
+        assert_eq!(report.last_build, None);
+
+        // The link graph written at the end of a build marks the build time
+        engine.link_graph_path = Some(temp_dir.path().join(LINK_GRAPH_FILE_NAME));
+        assert_eq!(engine.last_build_time(), None);
+        engine.set_link_graph(LinkGraph::default()).unwrap();
+        let built = engine.last_build_time().expect("Build time should be known");
+        assert!(built.elapsed().unwrap_or_default() < std::time::Duration::from_secs(60));
+    }
+
+    #[tokio::test]
+    async fn test_chunk_paths_lists_both_stores() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let engine = create_stub_engine(&temp_dir).await;
+        engine
+            .add("both", "Both", "docs/both.md", "content", None)
+            .await
+            .unwrap();
+        engine
+            .orchestrator()
+            .vector_store()
+            .add_batch(vec![AstChunk::without_dependencies(
+                "vector-only",
+                "docs/only.md",
+                "Only",
+                "file",
+                "content",
+            )])
+            .await
+            .unwrap();
+
+        let paths = engine.chunk_paths().await.unwrap();
+        assert_eq!(paths.len(), 2);
+        assert_eq!(paths["both"], "docs/both.md");
+        assert_eq!(paths["vector-only"], "docs/only.md");
+    }
+
+    #[tokio::test]
+    async fn test_index_version_changes_on_write() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let engine = create_stub_engine(&temp_dir).await;
+        let before = engine.index_version().await.unwrap();
+        assert!(before.bm25.is_some() && before.vector.is_some());
+        assert_eq!(engine.index_version().await.unwrap(), before);
+
+        engine
+            .add("doc", "Doc", "doc.md", "content", None)
+            .await
+            .expect("Should add to both stores");
+        let after = engine.index_version().await.unwrap();
+        assert_ne!(after.bm25, before.bm25);
+        assert!(after.vector > before.vector);
+
+        // Engines built from an orchestrator do not know where their stores live
+        assert!(engine.reopen().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_journaled_writes_leave_no_pending_operations() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let engine = create_stub_engine(&temp_dir).await;
+        let journal = Arc::new(
+            OperationJournal::open(temp_dir.path().join(JOURNAL_FILE_NAME)).unwrap(),
+        );
+
+        let SearchEngine { orchestrator, .. } = engine;
+        let engine = SearchEngine::from_orchestrator(orchestrator.with_journal(Arc::clone(&journal)));
+
+        engine.add("doc", "Title", "doc.md", "content", None).await.unwrap();
+        engine
+            .add_batch(vec![AstChunk::without_dependencies(
+                "chunk", "doc.md", "Chunk", "file", "chunk content",
+            )])
+            .await
+            .unwrap();
+        assert!(engine.delete("doc").await.both_success());
+
+        assert!(journal.pending().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_upsert_batch_is_idempotent_across_stores() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let engine = create_stub_engine(&temp_dir).await;
+
+        let chunks = vec![
+            AstChunk::without_dependencies("a", "a.md", "A", "file", "alpha"),
+            AstChunk::without_dependencies("b", "b.md", "B", "file", "beta"),
+        ];
+        engine.upsert_batch(chunks.clone()).await.unwrap();
+        engine.upsert_batch(chunks).await.unwrap();
+        engine
+            .upsert(AstChunk::without_dependencies("a", "a.md", "A", "file", "alpha v2"))
+            .await
+            .unwrap();
+
+        let report = engine.check_consistency().await.unwrap();
+        assert!(report.is_consistent(), "Re-running upsert must not duplicate: {:?}", report);
+        assert_eq!(report.bm25_count, 2);
+        assert_eq!(report.vector_count, 2);
+
+        let doc = engine.get_document("a").await.unwrap().unwrap();
+        assert_eq!(doc.content.as_deref(), Some("alpha v2"));
+    }
+
+    #[tokio::test]
+    async fn test_delete_where_removes_matching_sections_from_both_stores() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let engine = create_stub_engine(&temp_dir).await;
+
+        engine
+            .upsert_batch(vec![
+                AstChunk::without_dependencies("old-0", "docs/old.md", "Old", "file", "old intro")
+                    .with_pack("guide"),
+                AstChunk::without_dependencies("old-1", "docs/old.md", "Old", "file", "old body")
+                    .with_pack("guide"),
+                AstChunk::without_dependencies("keep", "docs/keep.md", "Keep", "file", "kept")
+                    .with_pack("guide"),
+                AstChunk::without_dependencies("api", "api/ref.md", "Ref", "file", "api")
+                    .with_pack("api"),
+            ])
+            .await
+            .unwrap();
+
+        let result = engine
+            .delete_where(&ChunkFilter::new().with_path_prefix("docs/old.md"))
+            .await;
+        assert!(result.both_success());
+        assert_eq!(result.bm25_count, 2);
+        assert_eq!(result.vector_count, 2);
+
+        let result = engine.delete_where(&ChunkFilter::new().with_pack("api")).await;
+        assert_eq!((result.bm25_count, result.vector_count), (1, 1));
+
+        let report = engine.check_consistency().await.unwrap();
+        assert!(report.is_consistent());
+        assert_eq!(report.bm25_count, 1);
+        assert!(engine.get_document("keep").await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_delete_where_rejects_empty_filter() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let engine = create_stub_engine(&temp_dir).await;
+
+        let result = engine.delete_where(&ChunkFilter::new()).await;
+        assert!(result.vector_deleted.is_err());
+        assert!(result.bm25_deleted.is_err());
+    }
+
     #[tokio::test]
     async fn test_build_hybrid_orchestrator_in_memory() {
         // Create in-memory BM25 index + temporary LanceDB
@@ -495,6 +1566,12 @@ mod tests {
                 node_type: "class".to_string(),
                 content: "class AuthManager { ... }".to_string(),
                 dependencies: vec!["User".to_string()],
+                pack: None,
+                heading_path: vec![],
+                tags: vec![],
+                version: None,
+                span: None,
+                pages: None,
                 vector: None,
             },
             AstChunk {
@@ -504,6 +1581,12 @@ mod tests {
                 node_type: "class".to_string(),
                 content: "class User { ... }".to_string(),
                 dependencies: vec![],
+                pack: None,
+                heading_path: vec![],
+                tags: vec![],
+                version: None,
+                span: None,
+                pages: None,
                 vector: None,
             },
         ];
@@ -7,4 +7,7 @@ pub mod errors;
 pub mod types;
 
 pub use errors::{AppError, DomainError, InfraError};
-pub use types::{AstChunk, Hit, Query, Score};
+pub use types::{
+    citation, code_node_type, dedupe_last_wins_by, node_type_matches, AstChunk, ChunkFilter, Hit,
+    NodeTypeBoost, PageRange, Query, Score, NODE_TYPE_CODE, NODE_TYPE_PROSE,
+};
@@ -5,18 +5,30 @@
 //! no LanceDB vectors, no Tantivy documents).
 
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::Hash;
+use std::path::Path;
 
 /// A normalized search query
 ///
 /// Contains only the essential query information needed for retrieval.
 /// Infrastructure-specific query parameters should be handled in respective slices.
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Query {
     /// The query text for semantic or lexical search
     pub text: String,
 
     /// Maximum number of results to return
     pub limit: usize,
+
+    /// Optional metadata filter; only chunks matching it are returned
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filter: Option<ChunkFilter>,
+
+    /// Optional score boost for chunks of a given node type (e.g. code examples)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub boost: Option<NodeTypeBoost>,
 }
 
 impl Query {
@@ -25,8 +37,98 @@ impl Query {
         Self {
             text: text.into(),
             limit,
+            filter: None,
+            boost: None,
+        }
+    }
+
+    /// Restrict results to chunks matching `filter` (an empty filter is ignored)
+    pub fn with_filter(mut self, filter: ChunkFilter) -> Self {
+        self.filter = (!filter.is_empty()).then_some(filter);
+        self
+    }
+
+    /// Multiply the scores of chunks matching `node_type` by `factor`
+    ///
+    /// `node_type` matches hierarchically (see [`node_type_matches`]), so
+    /// `"code"` boosts every code example regardless of language.
+    pub fn with_boost(mut self, node_type: impl Into<String>, factor: f64) -> Self {
+        self.boost = Some(NodeTypeBoost {
+            node_type: node_type.into(),
+            factor,
+        });
+        self
+    }
+}
+
+/// Score multiplier applied to chunks of one node type
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct NodeTypeBoost {
+    /// Node type to boost, matched hierarchically
+    pub node_type: String,
+
+    /// Score multiplier (values above 1.0 promote, below 1.0 demote)
+    pub factor: f64,
+}
+
+impl NodeTypeBoost {
+    /// Factor to apply to a chunk with the given node type (1.0 when not matching)
+    pub fn factor_for(&self, node_type: &str) -> f64 {
+        if node_type_matches(node_type, &self.node_type) {
+            self.factor
+        } else {
+            1.0
+        }
+    }
+}
+
+/// Node type of prose sections sliced from documents
+pub const NODE_TYPE_PROSE: &str = "prose";
+
+/// Node type (prefix) of fenced code examples; language-tagged as `code:<lang>`
+pub const NODE_TYPE_CODE: &str = "code";
+
+/// Build the node type for a code example in `language`
+pub fn code_node_type(language: Option<&str>) -> String {
+    match language {
+        Some(language) if !language.is_empty() => format!("{}:{}", NODE_TYPE_CODE, language),
+        _ => NODE_TYPE_CODE.to_string(),
+    }
+}
+
+/// Check whether `actual` is `expected` or one of its `expected:` subtypes
+///
+/// `node_type_matches("code:rust", "code")` is true, while
+/// `node_type_matches("code:rust", "code:py")` and `node_type_matches("codex", "code")` are not.
+pub fn node_type_matches(actual: &str, expected: &str) -> bool {
+    actual == expected
+        || actual
+            .strip_prefix(expected)
+            .is_some_and(|rest| rest.starts_with(':'))
+}
+
+/// Keep only the last item for each key, at the position where the key first appeared
+///
+/// Used before `merge_insert`, which rejects source batches that repeat a key.
+pub fn dedupe_last_wins_by<T, K, F>(items: Vec<T>, key: F) -> Vec<T>
+where
+    K: Eq + Hash,
+    F: Fn(&T) -> K,
+{
+    let mut positions: HashMap<K, usize> = HashMap::with_capacity(items.len());
+    let mut deduped: Vec<T> = Vec::with_capacity(items.len());
+
+    for item in items {
+        match positions.get(&key(&item)) {
+            Some(&index) => deduped[index] = item,
+            None => {
+                positions.insert(key(&item), deduped.len());
+                deduped.push(item);
+            }
         }
     }
+
+    deduped
 }
 
 /// A relevance score for search results
@@ -109,11 +211,56 @@ impl PartialOrd for Hit {
     }
 }
 
+/// 源文件中的行范围（从 1 开始，闭区间）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct LineSpan {
+    /// 起始行
+    pub start: u32,
+    /// 结束行（包含）
+    pub end: u32,
+}
+
+/// 页码范围（从 1 开始，闭区间）
+///
+/// 显示为 `p. 42`（单页）或 `pp. 42-44`（跨页）。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct PageRange {
+    /// 起始页
+    pub start: u32,
+    /// 结束页（包含）
+    pub end: u32,
+}
+
+impl fmt::Display for PageRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.start == self.end {
+            write!(f, "p. {}", self.start)
+        } else {
+            write!(f, "pp. {}-{}", self.start, self.end)
+        }
+    }
+}
+
+/// 引用位置：文件名加页码（如 `manual.pdf p. 42`），没有页码时只有文件名
+pub fn citation(file_path: &str, pages: Option<PageRange>) -> String {
+    let name = Path::new(file_path)
+        .file_name()
+        .and_then(|s| s.to_str())
+        .unwrap_or(file_path);
+    match pages {
+        Some(pages) => format!("{} {}", name, pages),
+        None => name.to_string(),
+    }
+}
+
 /// AST Chunk - 代码语法树节点的语义表示
 ///
 /// 此结构封装了代码分析结果（如来自 Cocoindex），包含文件路径、符号名、
 /// 节点类型、依赖关系等语义信息，并支持向量嵌入用于语义检索。
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct AstChunk {
     /// 唯一标识符（通常是内容哈希签名）
     pub id: String,
@@ -136,6 +283,30 @@ pub struct AstChunk {
     #[serde(default)]
     pub dependencies: Vec<String>,
 
+    /// 所属知识包（如 contextfy.json 中的 `name`），用于按包过滤和批量删除
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pack: Option<String>,
+
+    /// 标题路径（面包屑，如 `["Doc", "Section", "Subsection"]`），参与全文检索
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub heading_path: Vec<String>,
+
+    /// 标签（来自文档 front-matter），可用于过滤
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+
+    /// 文档版本（来自文档 front-matter），可用于过滤
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+
+    /// 源文件中的行范围（源代码切片提供，文档切片为 `None`）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub span: Option<LineSpan>,
+
+    /// 所在的页码范围（PDF 等分页文档的切片提供，其他切片为 `None`）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pages: Option<PageRange>,
+
     /// 向量嵌入（入库时生成，调用方无需提供）
     /// 使用 BGE-small-en 模型生成 384 维向量
     #[serde(skip)]
@@ -159,6 +330,12 @@ impl AstChunk {
             node_type: node_type.into(),
             content: content.into(),
             dependencies,
+            pack: None,
+            heading_path: Vec::new(),
+            tags: Vec::new(),
+            version: None,
+            span: None,
+            pages: None,
             vector: None,
         }
     }
@@ -174,6 +351,42 @@ impl AstChunk {
         Self::new(id, file_path, symbol_name, node_type, content, Vec::new())
     }
 
+    /// Set the pack this chunk belongs to
+    pub fn with_pack(mut self, pack: impl Into<String>) -> Self {
+        self.pack = Some(pack.into());
+        self
+    }
+
+    /// Set the heading breadcrumb of the section this chunk was sliced from
+    pub fn with_heading_path(mut self, heading_path: Vec<String>) -> Self {
+        self.heading_path = heading_path;
+        self
+    }
+
+    /// Set the tags this chunk is filterable by
+    pub fn with_tags(mut self, tags: Vec<String>) -> Self {
+        self.tags = tags;
+        self
+    }
+
+    /// Set the document version this chunk belongs to
+    pub fn with_version(mut self, version: impl Into<String>) -> Self {
+        self.version = Some(version.into());
+        self
+    }
+
+    /// Set the source line span of this chunk
+    pub fn with_span(mut self, start: u32, end: u32) -> Self {
+        self.span = Some(LineSpan { start, end });
+        self
+    }
+
+    /// Set the page range of the paginated document this chunk was sliced from
+    pub fn with_pages(mut self, pages: PageRange) -> Self {
+        self.pages = Some(pages);
+        self
+    }
+
     /// Set the vector embedding (used by storage layer)
     pub fn with_vector(mut self, vector: Vec<f32>) -> Self {
         self.vector = Some(vector);
@@ -181,6 +394,127 @@ impl AstChunk {
     }
 }
 
+/// Metadata filter selecting a set of stored chunks
+///
+/// All set criteria must match (logical AND). Used for bulk deletes, e.g.
+/// removing every section of a deleted file by its path, and to restrict
+/// search results via [`Query::with_filter`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChunkFilter {
+    /// Match chunks whose `file_path` starts with this prefix
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path_prefix: Option<String>,
+
+    /// Match chunks belonging to this pack
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pack: Option<String>,
+
+    /// Match chunks belonging to any of these packs (e.g. the packs an API key may read)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub packs: Vec<String>,
+
+    /// Match chunks with this node type or one of its subtypes
+    /// (`code` matches `code:rust`, see [`node_type_matches`])
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub node_type: Option<String>,
+
+    /// Match chunks carrying this tag
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+
+    /// Match chunks with exactly this document version
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+}
+
+impl ChunkFilter {
+    /// Create an empty filter
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Match chunks whose file path starts with `prefix`
+    pub fn with_path_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.path_prefix = Some(prefix.into());
+        self
+    }
+
+    /// Match chunks belonging to `pack`
+    pub fn with_pack(mut self, pack: impl Into<String>) -> Self {
+        self.pack = Some(pack.into());
+        self
+    }
+
+    /// Match chunks belonging to any of `packs`
+    pub fn with_packs<I, S>(mut self, packs: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.packs = packs.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Match chunks with node type `node_type`
+    pub fn with_node_type(mut self, node_type: impl Into<String>) -> Self {
+        self.node_type = Some(node_type.into());
+        self
+    }
+
+    /// Match chunks carrying `tag`
+    pub fn with_tag(mut self, tag: impl Into<String>) -> Self {
+        self.tag = Some(tag.into());
+        self
+    }
+
+    /// Match chunks with document version `version`
+    pub fn with_version(mut self, version: impl Into<String>) -> Self {
+        self.version = Some(version.into());
+        self
+    }
+
+    /// Check whether no criteria are set
+    ///
+    /// An empty filter would match every chunk, so destructive operations
+    /// must reject it.
+    pub fn is_empty(&self) -> bool {
+        self.path_prefix.is_none()
+            && self.pack.is_none()
+            && self.packs.is_empty()
+            && self.node_type.is_none()
+            && self.tag.is_none()
+            && self.version.is_none()
+    }
+
+    /// Check whether a chunk satisfies every set criterion
+    pub fn matches(&self, chunk: &AstChunk) -> bool {
+        self.path_prefix
+            .as_deref()
+            .is_none_or(|prefix| chunk.file_path.starts_with(prefix))
+            && self
+                .pack
+                .as_deref()
+                .is_none_or(|pack| chunk.pack.as_deref() == Some(pack))
+            && (self.packs.is_empty()
+                || chunk
+                    .pack
+                    .as_ref()
+                    .is_some_and(|pack| self.packs.contains(pack)))
+            && self
+                .node_type
+                .as_deref()
+                .is_none_or(|node_type| node_type_matches(&chunk.node_type, node_type))
+            && self
+                .tag
+                .as_deref()
+                .is_none_or(|tag| chunk.tags.iter().any(|t| t == tag))
+            && self
+                .version
+                .as_deref()
+                .is_none_or(|version| chunk.version.as_deref() == Some(version))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -334,4 +668,122 @@ mod tests {
         assert_eq!(chunk.id, "hash-002");
         assert!(chunk.dependencies.is_empty());
     }
+
+    #[test]
+    fn test_ast_chunk_pack_is_optional_in_json() {
+        let chunk = AstChunk::without_dependencies("id", "a.md", "A", "file", "c");
+        let json = serde_json::to_string(&chunk).unwrap();
+        assert!(!json.contains("pack"));
+
+        let packed: AstChunk = serde_json::from_str(
+            r#"{"id":"id","file_path":"a.md","symbol_name":"A","node_type":"file","content":"c","pack":"docs"}"#,
+        )
+        .unwrap();
+        assert_eq!(packed, chunk.with_pack("docs"));
+    }
+
+    #[test]
+    fn test_chunk_filter_matches_all_criteria() {
+        let chunk = AstChunk::without_dependencies("id", "docs/guide/intro.md", "Intro", "file", "c")
+            .with_pack("guide");
+
+        assert!(ChunkFilter::new().with_path_prefix("docs/guide/").matches(&chunk));
+        assert!(ChunkFilter::new().with_pack("guide").matches(&chunk));
+        assert!(ChunkFilter::new().with_node_type("file").matches(&chunk));
+        assert!(ChunkFilter::new()
+            .with_path_prefix("docs/")
+            .with_pack("guide")
+            .with_node_type("file")
+            .matches(&chunk));
+
+        assert!(!ChunkFilter::new().with_path_prefix("src/").matches(&chunk));
+        assert!(!ChunkFilter::new().with_pack("api").matches(&chunk));
+        assert!(!ChunkFilter::new()
+            .with_path_prefix("docs/")
+            .with_node_type("class")
+            .matches(&chunk));
+    }
+
+    #[test]
+    fn test_chunk_filter_any_of_packs() {
+        let chunk = AstChunk::without_dependencies("id", "a.md", "A", "file", "c").with_pack("guide");
+
+        assert!(ChunkFilter::new().with_packs(["api", "guide"]).matches(&chunk));
+        assert!(!ChunkFilter::new().with_packs(["api"]).matches(&chunk));
+        assert!(!ChunkFilter::new()
+            .with_packs(["guide"])
+            .matches(&AstChunk::without_dependencies("id", "a.md", "A", "file", "c")));
+        assert!(!ChunkFilter::new().with_packs(["guide"]).is_empty());
+    }
+
+    #[test]
+    fn test_chunk_filter_empty() {
+        assert!(ChunkFilter::new().is_empty());
+        assert!(!ChunkFilter::new().with_pack("guide").is_empty());
+        assert!(!ChunkFilter::new().with_tag("events").is_empty());
+    }
+
+    #[test]
+    fn test_chunk_filter_tag_and_version() {
+        let chunk = AstChunk::without_dependencies("id", "a.md", "A", "file", "c")
+            .with_tags(vec!["blocks".into(), "events".into()])
+            .with_version("1.20");
+
+        assert!(ChunkFilter::new().with_tag("events").matches(&chunk));
+        assert!(ChunkFilter::new().with_version("1.20").matches(&chunk));
+        assert!(!ChunkFilter::new().with_tag("items").matches(&chunk));
+        assert!(!ChunkFilter::new().with_version("1.21").matches(&chunk));
+        assert!(!ChunkFilter::new()
+            .with_tag("events")
+            .matches(&AstChunk::without_dependencies("id", "a.md", "A", "file", "c")));
+    }
+
+    #[test]
+    fn test_dedupe_last_wins_by_keeps_first_position() {
+        let deduped = dedupe_last_wins_by(
+            vec![
+                AstChunk::without_dependencies("a", "a.md", "A", "file", "a1"),
+                AstChunk::without_dependencies("b", "b.md", "B", "file", "b1"),
+                AstChunk::without_dependencies("a", "a.md", "A", "file", "a2"),
+            ],
+            |chunk| chunk.id.clone(),
+        );
+
+        let summary: Vec<(&str, &str)> = deduped
+            .iter()
+            .map(|c| (c.id.as_str(), c.content.as_str()))
+            .collect();
+        assert_eq!(summary, vec![("a", "a2"), ("b", "b1")]);
+        assert!(dedupe_last_wins_by(Vec::<AstChunk>::new(), |c| c.id.clone()).is_empty());
+    }
+
+    #[test]
+    fn test_node_type_matches_hierarchically() {
+        assert!(node_type_matches("code:rust", "code"));
+        assert!(node_type_matches("code:rust", "code:rust"));
+        assert!(node_type_matches("code", "code"));
+        assert!(!node_type_matches("code:rust", "code:py"));
+        assert!(!node_type_matches("codex", "code"));
+        assert!(!node_type_matches("prose", "code"));
+
+        let chunk = AstChunk::without_dependencies("id", "a.md", "A", code_node_type(Some("rust")), "fn a() {}");
+        assert_eq!(chunk.node_type, "code:rust");
+        assert!(ChunkFilter::new().with_node_type("code").matches(&chunk));
+        assert_eq!(code_node_type(None), "code");
+    }
+
+    #[test]
+    fn test_node_type_boost_factor() {
+        let query = Query::new("q", 5).with_boost("code", 2.0);
+        let boost = query.boost.unwrap();
+        assert_eq!(boost.factor_for("code:rust"), 2.0);
+        assert_eq!(boost.factor_for("prose"), 1.0);
+    }
+
+    #[test]
+    fn test_query_with_filter_ignores_empty_filter() {
+        assert!(Query::new("q", 5).with_filter(ChunkFilter::new()).filter.is_none());
+        let query = Query::new("q", 5).with_filter(ChunkFilter::new().with_tag("events"));
+        assert_eq!(query.filter.and_then(|f| f.tag).as_deref(), Some("events"));
+    }
 }
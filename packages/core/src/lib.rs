@@ -9,9 +9,24 @@ mod slices;
 
 pub use bridge::{BridgeApi, BridgeError};
 pub use embeddings::EmbeddingModel;
-pub use facade::{build_hybrid_orchestrator, DeleteResult, DocumentDetails, SearchEngine};
-pub use kernel::{AppError, DomainError, Hit, InfraError, Query, Score};
-pub use parser::{parse_markdown, slice_by_headers, ParsedDoc, SlicedDoc, SlicedSection};
+pub use facade::{
+    build_hybrid_orchestrator, describe_metrics, embedding_model_loaded, BackendScore,
+    ConsistencyReport, DeleteResult, DocumentCounts, DocumentDetails, DocumentSource,
+    ExplainedHit, FusionMode, GraphNode, HealthReport, IndexVersion, LinkEdge, LinkGraph,
+    ProgressSender, RecoveryReport, RelatedDocuments, RepairReport, RepairStrategy, SearchBackend,
+    SearchEngine, SearchProgress, SearchStage, StoreHealth, UnresolvedLink, SEARCH_STAGE_SECONDS,
+};
+pub use kernel::{
+    code_node_type, node_type_matches, AppError, AstChunk, ChunkFilter, DomainError, Hit,
+    InfraError, NodeTypeBoost, Query, Score, NODE_TYPE_CODE, NODE_TYPE_PROSE,
+};
+pub use parser::{
+    chunk_source, chunk_source_file, document_parsers, extract_code_blocks, extract_links,
+    is_document_path, is_source_path, parse_document, parse_markdown, parse_markdown_with_config,
+    parse_pdf, parser_for_path, slice_by_headers, slice_hierarchical, slugify, ChunkSize,
+    CodeBlock, DocLink, DocMetadata, DocumentParser, LinkKind, PageRange, ParsedDoc, SliceConfig,
+    SlicedDoc, SlicedSection, SourceLanguage,
+};
 
 // Slice exports (Phase 3)
 // NOTE: Storage traits only - concrete implementations like LanceDbStore should not be exposed
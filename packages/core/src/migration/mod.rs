@@ -47,6 +47,7 @@ pub mod json_reader;
 pub mod transformer;
 
 use std::path::PathBuf;
+use crate::kernel::dedupe_last_wins_by;
 
 pub use error::MigrationError;
 
@@ -110,6 +111,9 @@ pub struct MigrationStats {
 
     /// Skipped records (duplicates or invalid)
     pub skipped: usize,
+
+    /// Successful records that replaced an existing row with the same ID
+    pub updated: usize,
 }
 
 impl MigrationStats {
@@ -204,6 +208,7 @@ pub async fn migrate_json_to_lancedb(
         successful: 0,
         failed: 0,
         skipped: 0,
+        updated: 0,
     };
 
     let transformer = RecordTransformer::new(embedding_model);
@@ -238,6 +243,11 @@ pub async fn migrate_json_to_lancedb(
             }
         }
 
+        // merge_insert rejects source batches that repeat a key, keep the last record per ID
+        let record_count = valid_records.len();
+        let valid_records = dedupe_last_wins_by(valid_records, |record| record.id.clone());
+        stats.skipped += record_count - valid_records.len();
+
         if valid_records.is_empty() {
             continue;
         }
@@ -267,21 +277,22 @@ pub async fn migrate_json_to_lancedb(
             }
         })?;
 
-        // Insert batch into LanceDB
-        // LanceDB's add() expects a type implementing IntoArrow
-        // RecordBatchIterator::new takes (iterator, schema)
+        // Upsert batch into LanceDB keyed by ID, so re-running a migration
+        // replaces existing rows instead of duplicating them
         use arrow::array::RecordBatchReader;
         let schema = record_batch.schema(); // Clone schema before moving batch
         let reader: Box<dyn RecordBatchReader + Send> = Box::new(
             arrow::array::RecordBatchIterator::new(vec![Ok(record_batch)].into_iter(), schema),
         );
-        table
-            .add(reader)
-            .execute()
+        let mut merge = table.merge_insert(&["id"]);
+        merge.when_matched_update_all(None).when_not_matched_insert_all();
+        let merge_result = merge
+            .execute(reader)
             .await
             .map_err(MigrationError::LanceDbError)?;
 
         stats.successful += lancedb_records.len();
+        stats.updated += merge_result.num_updated_rows as usize;
 
         println!(
             "Processed batch {}/{} ({} records)",
@@ -295,7 +306,14 @@ pub async fn migrate_json_to_lancedb(
     create_vector_index(&conn, &config.table_name).await?;
 
     // Step 9: Validate migration (supports incremental runs)
-    validate_migration(&conn, &config.table_name, initial_row_count, stats.successful).await?;
+    // Updated rows replace existing ones and do not change the row count
+    validate_migration(
+        &conn,
+        &config.table_name,
+        initial_row_count,
+        stats.successful - stats.updated,
+    )
+    .await?;
 
     Ok(stats)
 }
@@ -475,6 +493,7 @@ mod tests {
             successful: 80,
             failed: 15,
             skipped: 5,
+            updated: 0,
         };
         assert_eq!(stats.success_rate(), 0.8);
     }
@@ -0,0 +1,401 @@
+//! AsciiDoc 转换（常用子集）
+//!
+//! 把 `.adoc` 文档转换为 Markdown，再交给 Markdown 解析和切片流程：
+//!
+//! - `= 标题` … `====== 标题`：转为 `#` … `######`
+//! - `[source,java]` + `----` 代码块、`....` 字面量块：转为围栏代码块
+//! - `NOTE: ...` 段落提示和 `[NOTE]` + `====` 提示块：转为加粗的提示标题
+//! - `.块标题`：转为加粗行；`____` 引用块：转为 `>` 引用
+//! - `*`、`**` 无序列表和 `.`、`..` 有序列表：转为 Markdown 列表
+//! - `link:url[文本]`、`https://url[文本]`、`xref:file.adoc[文本]`、`<<id,文本>>`：转为链接
+//! - `*粗体*`、`_斜体_`：转为 Markdown 强调
+//! - 文档属性（`:toc:`）、注释、`include::`、`image::` 等块宏：删除
+//!
+//! 不支持条件指令、属性替换和复杂表格，表格按行保留单元格文本。
+
+use super::document::DocumentParser;
+use super::{build_parsed_doc, DocMetadata, ParsedDoc, SliceConfig};
+use anyhow::Result;
+use regex::Regex;
+use std::sync::OnceLock;
+
+/// AsciiDoc 解析器
+pub struct AsciiDocParser;
+
+impl DocumentParser for AsciiDocParser {
+    fn name(&self) -> &'static str {
+        "asciidoc"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["adoc", "asciidoc", "asc"]
+    }
+
+    fn parse(&self, file_path: &str, source: &str, config: &SliceConfig) -> Result<ParsedDoc> {
+        Ok(build_parsed_doc(
+            file_path,
+            asciidoc_to_markdown(source),
+            DocMetadata::default(),
+            config,
+        ))
+    }
+}
+
+/// 提示类型
+const ADMONITIONS: &[&str] = &["NOTE", "TIP", "IMPORTANT", "WARNING", "CAUTION"];
+
+/// 块属性行（`[source,java]`、`[NOTE]`）对下一个块的影响
+#[derive(Default)]
+struct BlockAttributes {
+    /// `[source,lang]` 的语言
+    language: Option<String>,
+    /// `[NOTE]` 等提示类型
+    admonition: Option<String>,
+}
+
+/// 把 AsciiDoc 转换为 Markdown
+///
+/// # 示例
+///
+/// ```ignore
+/// let md = asciidoc_to_markdown("= Guide\n\nNOTE: Requires Java 17.\n");
+/// assert_eq!(md.trim(), "# Guide\n\n**Note:** Requires Java 17.");
+/// ```
+pub fn asciidoc_to_markdown(source: &str) -> String {
+    let lines: Vec<&str> = source.lines().collect();
+    let mut output: Vec<String> = Vec::new();
+    let mut attributes = BlockAttributes::default();
+    let mut i = 0;
+
+    while i < lines.len() {
+        let line = lines[i].trim_end();
+
+        // 分隔块：---- 代码、.... 字面量、==== 示例/提示、**** 侧栏、____ 引用、//// 注释、|=== 表格
+        if let Some(delimiter) = block_delimiter(line) {
+            let end = (i + 1..lines.len())
+                .find(|&j| lines[j].trim_end() == line)
+                .unwrap_or(lines.len());
+            let body = &lines[i + 1..end];
+            let attributes = std::mem::take(&mut attributes);
+            push_block(&mut output, delimiter, body, attributes);
+            i = end + 1;
+            continue;
+        }
+
+        i += 1;
+
+        // 行注释、文档属性、块宏
+        if (line.starts_with("//") && !line.starts_with("///"))
+            || is_attribute_entry(line)
+            || is_block_macro(line)
+        {
+            continue;
+        }
+
+        // 块属性
+        if let Some(inner) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            if !inner.is_empty() && !inner.starts_with('[') {
+                attributes = parse_attributes(inner);
+                continue;
+            }
+        }
+
+        if line.is_empty() {
+            attributes = BlockAttributes::default();
+            output.push(String::new());
+            continue;
+        }
+
+        // 标题
+        if let Some((level, title)) = heading(line) {
+            output.push(format!("{} {}", "#".repeat(level), convert_inline(title)));
+            output.push(String::new());
+            continue;
+        }
+
+        // 块标题：.Title（`. item` 是有序列表）
+        if let Some(title) = line.strip_prefix('.') {
+            if !title.is_empty() && !title.starts_with(['.', ' ']) {
+                output.push(format!("**{}**", convert_inline(title)));
+                output.push(String::new());
+                continue;
+            }
+        }
+
+        // 段落提示：NOTE: text，或 [NOTE] 之后的段落
+        if let Some((kind, text)) = paragraph_admonition(line) {
+            output.push(format!("**{}:** {}", label(kind), convert_inline(text)));
+            continue;
+        }
+        if let Some(kind) = attributes.admonition.take() {
+            output.push(format!("**{}:** {}", label(&kind), convert_inline(line)));
+            continue;
+        }
+
+        output.push(convert_list_item(line).unwrap_or_else(|| convert_inline(line)));
+    }
+
+    let mut markdown = output.join("\n");
+    markdown.push('\n');
+    markdown
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Delimiter {
+    Listing,
+    Literal,
+    Example,
+    Sidebar,
+    Quote,
+    Comment,
+    Table,
+    Open,
+}
+
+fn block_delimiter(line: &str) -> Option<Delimiter> {
+    let repeated = |c: char| line.len() >= 4 && line.chars().all(|x| x == c);
+    if repeated('-') {
+        Some(Delimiter::Listing)
+    } else if repeated('.') {
+        Some(Delimiter::Literal)
+    } else if repeated('=') {
+        Some(Delimiter::Example)
+    } else if repeated('*') {
+        Some(Delimiter::Sidebar)
+    } else if repeated('_') {
+        Some(Delimiter::Quote)
+    } else if repeated('/') {
+        Some(Delimiter::Comment)
+    } else if line == "|===" {
+        Some(Delimiter::Table)
+    } else if line == "--" {
+        Some(Delimiter::Open)
+    } else {
+        None
+    }
+}
+
+fn push_block(
+    output: &mut Vec<String>,
+    delimiter: Delimiter,
+    body: &[&str],
+    attributes: BlockAttributes,
+) {
+    match delimiter {
+        Delimiter::Listing | Delimiter::Literal => {
+            output.push(format!("```{}", attributes.language.unwrap_or_default()));
+            output.extend(body.iter().map(|l| l.trim_end().to_string()));
+            output.push("```".to_string());
+        }
+        Delimiter::Example | Delimiter::Sidebar | Delimiter::Open => {
+            let inner = asciidoc_to_markdown(&body.join("\n"));
+            match attributes.admonition {
+                Some(kind) => output.push(format!("**{}:** {}", label(&kind), inner.trim())),
+                None => output.push(inner.trim_end().to_string()),
+            }
+        }
+        Delimiter::Quote => {
+            let inner = asciidoc_to_markdown(&body.join("\n"));
+            output.extend(
+                inner
+                    .trim_end()
+                    .lines()
+                    .map(|l| format!("> {}", l).trim_end().to_string()),
+            );
+        }
+        Delimiter::Table => {
+            for row in body.iter().map(|l| l.trim()).filter(|l| !l.is_empty()) {
+                let cells: Vec<String> = row
+                    .split('|')
+                    .map(str::trim)
+                    .filter(|c| !c.is_empty())
+                    .map(convert_inline)
+                    .collect();
+                output.push(cells.join(" | "));
+                output.push(String::new());
+            }
+        }
+        Delimiter::Comment => return,
+    }
+    output.push(String::new());
+}
+
+/// `:name: value`、`:name!:`
+fn is_attribute_entry(line: &str) -> bool {
+    static ATTRIBUTE: OnceLock<Regex> = OnceLock::new();
+    ATTRIBUTE
+        .get_or_init(|| Regex::new(r"^:!?[\w-]+!?:(\s.*)?$").expect("valid regex"))
+        .is_match(line)
+}
+
+/// `include::file[]`、`image::a.png[]`、`toc::[]` 等
+fn is_block_macro(line: &str) -> bool {
+    static MACRO: OnceLock<Regex> = OnceLock::new();
+    MACRO
+        .get_or_init(|| Regex::new(r"^[a-z]+::\S*\[.*\]$").expect("valid regex"))
+        .is_match(line)
+}
+
+fn parse_attributes(inner: &str) -> BlockAttributes {
+    let mut parts = inner.split(',').map(str::trim);
+    let style = parts.next().unwrap_or("");
+    if style == "source" {
+        return BlockAttributes {
+            language: parts.next().filter(|l| !l.is_empty()).map(str::to_string),
+            admonition: None,
+        };
+    }
+    BlockAttributes {
+        language: None,
+        admonition: ADMONITIONS.contains(&style).then(|| style.to_string()),
+    }
+}
+
+/// `== Title` 或 Markdown 风格的 `## Title`
+fn heading(line: &str) -> Option<(usize, &str)> {
+    let marker = line.chars().next().filter(|c| *c == '=' || *c == '#')?;
+    let level = line.chars().take_while(|c| *c == marker).count();
+    let title = line[level..].strip_prefix(' ')?.trim();
+    ((1..=6).contains(&level) && !title.is_empty()).then_some((level, title))
+}
+
+fn paragraph_admonition(line: &str) -> Option<(&str, &str)> {
+    let (kind, text) = line.split_once(": ")?;
+    ADMONITIONS.contains(&kind).then_some((kind, text.trim()))
+}
+
+/// `NOTE` → `Note`
+fn label(kind: &str) -> String {
+    let lower = kind.to_ascii_lowercase();
+    let mut chars = lower.chars();
+    match chars.next() {
+        Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+        None => String::new(),
+    }
+}
+
+/// `* a`、`** b`、`. c`、`.. d`、`- e`
+fn convert_list_item(line: &str) -> Option<String> {
+    let marker = line
+        .chars()
+        .next()
+        .filter(|c| matches!(c, '*' | '.' | '-'))?;
+    let depth = line.chars().take_while(|c| *c == marker).count();
+    let text = line[depth..].strip_prefix(' ')?;
+    let bullet = if marker == '.' { "1." } else { "-" };
+    let depth = if marker == '-' { 1 } else { depth };
+    Some(format!(
+        "{}{} {}",
+        "  ".repeat(depth - 1),
+        bullet,
+        convert_inline(text.trim())
+    ))
+}
+
+/// 转换行内标记
+fn convert_inline(text: &str) -> String {
+    static XREF: OnceLock<Regex> = OnceLock::new();
+    static CROSS_REFERENCE: OnceLock<Regex> = OnceLock::new();
+    static URL: OnceLock<Regex> = OnceLock::new();
+    static STRONG: OnceLock<Regex> = OnceLock::new();
+    static EMPHASIS: OnceLock<Regex> = OnceLock::new();
+
+    let xref =
+        XREF.get_or_init(|| Regex::new(r"xref:([^\[\s]+)\[([^\]]*)\]").expect("valid regex"));
+    let cross_reference = CROSS_REFERENCE
+        .get_or_init(|| Regex::new(r"<<([^,>]+)(?:,\s*([^>]+))?>>").expect("valid regex"));
+    let url = URL.get_or_init(|| {
+        Regex::new(r"(?:link:)?((?:https?://|mailto:)?[^\s\[\]]+)\[([^\]]*)\]")
+            .expect("valid regex")
+    });
+    let strong = STRONG.get_or_init(|| {
+        Regex::new(r"(^|[\s(])\*([^*\s](?:[^*]*[^*\s])?)\*($|[\s).,;:!?])").expect("valid regex")
+    });
+    let emphasis = EMPHASIS.get_or_init(|| {
+        Regex::new(r"(^|[\s(])_([^_\s](?:[^_]*[^_\s])?)_($|[\s).,;:!?])").expect("valid regex")
+    });
+
+    let text = xref.replace_all(text, |caps: &regex::Captures<'_>| {
+        let target = &caps[1];
+        let label = if caps[2].is_empty() { target } else { &caps[2] };
+        format!("[{}]({})", label, target)
+    });
+    let text = cross_reference.replace_all(&text, |caps: &regex::Captures<'_>| {
+        let id = caps[1].trim();
+        let label = caps.get(2).map_or(id, |m| m.as_str().trim());
+        format!("[{}](#{})", label, id)
+    });
+    let text = url.replace_all(&text, |caps: &regex::Captures<'_>| {
+        let target = &caps[1];
+        // `link:` 宏以外只有带协议的 URL 才是链接
+        if !caps[0].starts_with("link:") && !target.contains(':') {
+            return caps[0].to_string();
+        }
+        let label = if caps[2].is_empty() { target } else { &caps[2] };
+        format!("[{}]({})", label, target)
+    });
+    let text = strong.replace_all(&text, "$1**$2**$3");
+    let text = emphasis.replace_all(&text, "$1*$2*$3");
+    text.into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_headings_and_attributes() {
+        let md = asciidoc_to_markdown(
+            "= Guide\n:toc: left\n:icons: font\n\n== Install\n\n=== Linux\n\nText.\n",
+        );
+        assert!(md.starts_with("# Guide\n"));
+        assert!(md.contains("\n## Install\n"));
+        assert!(md.contains("\n### Linux\n"));
+        assert!(!md.contains("toc"));
+    }
+
+    #[test]
+    fn test_source_blocks() {
+        let source = "[source,java]\n----\nvoid onBreak() {\n  // not a comment line for adoc\n}\n----\n\n....\nliteral\n....\n";
+        let md = asciidoc_to_markdown(source);
+        assert!(md.contains("```java\nvoid onBreak() {\n  // not a comment line for adoc\n}\n```"));
+        assert!(md.contains("```\nliteral\n```"));
+    }
+
+    #[test]
+    fn test_admonitions_and_block_titles() {
+        let source = "NOTE: Requires *Java 17*.\n\n[WARNING]\n====\nBreaking change.\n====\n\n.Example title\nSee below.\n";
+        let md = asciidoc_to_markdown(source);
+        assert!(md.contains("**Note:** Requires **Java 17**."));
+        assert!(md.contains("**Warning:** Breaking change."));
+        assert!(md.contains("**Example title**"));
+    }
+
+    #[test]
+    fn test_lists_links_and_comments() {
+        let source = "// hidden\n////\nblock comment\n////\n* one\n** nested\n. first\n\nSee link:guide.adoc[the guide], https://example.com[site], xref:events.adoc#blocks[events] and <<usage,Usage>>.\n\ninclude::partials/footer.adoc[]\n";
+        let md = asciidoc_to_markdown(source);
+        assert!(!md.contains("hidden"));
+        assert!(!md.contains("block comment"));
+        assert!(!md.contains("include::"));
+        assert!(md.contains("- one\n  - nested\n1. first"));
+        assert!(md.contains(
+            "See [the guide](guide.adoc), [site](https://example.com), [events](events.adoc#blocks) and [Usage](#usage)."
+        ));
+    }
+
+    #[test]
+    fn test_parse_asciidoc_document() {
+        let source = "= Block Events\n\nOverview.\n\n== Listening\n\n[source,java]\n----\nvoid onBreak(BlockBreakEvent e) {}\n----\n";
+        let doc = AsciiDocParser
+            .parse("docs/events.adoc", source, &SliceConfig::default())
+            .unwrap();
+        assert_eq!(doc.title, "Block Events");
+        let listening = doc
+            .sections
+            .iter()
+            .find(|s| s.section_title == "Listening")
+            .expect("section should exist");
+        assert_eq!(listening.code_blocks[0].language.as_deref(), Some("java"));
+    }
+}
@@ -0,0 +1,798 @@
+//! 基于 tree-sitter 的源代码切片
+//!
+//! 在进程内解析 Rust、TypeScript（含 TSX）、Python、Java 和 Go 源文件，
+//! 把每个定义（函数、类型、impl 块、方法）转换为一个 `AstChunk`，不再依赖外部 Python 进程：
+//!
+//! - `symbol_name`：定义的名称（impl 块为 `Type` 或 `Trait for Type`）
+//! - `node_type`：`function`、`class`、`impl` 或 `method`
+//! - `dependencies`：定义中使用的导入名和调用的函数/方法名
+//! - `heading_path`：外层类型名加上定义名，如 `["Parser", "parse"]`
+//! - `span`：定义在源文件中的行范围（从 1 开始，包含装饰器和属性）
+//!
+//! 类型和 impl 块会完整保留其中的方法，方法本身也会单独生成切片。
+
+use crate::kernel::types::AstChunk;
+use anyhow::{Context, Result};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+use tree_sitter::{Language, Node, Parser};
+
+/// 支持的源代码语言
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SourceLanguage {
+    Rust,
+    TypeScript,
+    Tsx,
+    Python,
+    Java,
+    Go,
+}
+
+impl SourceLanguage {
+    /// 根据文件扩展名识别语言
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+        let language = match ext.as_str() {
+            "rs" => Self::Rust,
+            "ts" | "mts" | "cts" => Self::TypeScript,
+            "tsx" => Self::Tsx,
+            "py" | "pyi" => Self::Python,
+            "java" => Self::Java,
+            "go" => Self::Go,
+            _ => return None,
+        };
+        Some(language)
+    }
+
+    /// 语言名（与代码块语言名一致，TSX 归为 `typescript`）
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Rust => "rust",
+            Self::TypeScript | Self::Tsx => "typescript",
+            Self::Python => "python",
+            Self::Java => "java",
+            Self::Go => "go",
+        }
+    }
+
+    fn grammar(self) -> Language {
+        match self {
+            Self::Rust => tree_sitter_rust::LANGUAGE.into(),
+            Self::TypeScript => tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
+            Self::Tsx => tree_sitter_typescript::LANGUAGE_TSX.into(),
+            Self::Python => tree_sitter_python::LANGUAGE.into(),
+            Self::Java => tree_sitter_java::LANGUAGE.into(),
+            Self::Go => tree_sitter_go::LANGUAGE.into(),
+        }
+    }
+
+    /// 调用表达式的节点类型，以及保存被调用者的字段名
+    fn call_kinds(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Self::Rust => &[
+                ("call_expression", "function"),
+                ("macro_invocation", "macro"),
+            ],
+            Self::TypeScript | Self::Tsx => &[
+                ("call_expression", "function"),
+                ("new_expression", "constructor"),
+            ],
+            Self::Python => &[("call", "function")],
+            Self::Java => &[
+                ("method_invocation", "name"),
+                ("object_creation_expression", "type"),
+            ],
+            Self::Go => &[("call_expression", "function")],
+        }
+    }
+}
+
+/// 判断路径是否为支持切片的源文件
+pub fn is_source_path(path: &Path) -> bool {
+    SourceLanguage::from_path(path).is_some()
+}
+
+/// 读取并切片一个源文件
+pub fn chunk_source_file(file_path: &str) -> Result<Vec<AstChunk>> {
+    let language = SourceLanguage::from_path(Path::new(file_path))
+        .with_context(|| format!("Unsupported source file: {}", file_path))?;
+    let source = std::fs::read_to_string(file_path)
+        .with_context(|| format!("Failed to read source file: {}", file_path))?;
+    chunk_source(file_path, &source, language)
+}
+
+/// 把源代码切分为定义级的 `AstChunk`
+///
+/// 切片 ID 由文件路径和定义的限定名（外层类型 + 名称）计算，代码在文件内移动时保持不变；
+/// 同名定义（如重载）按出现顺序追加序号。
+///
+/// # 示例
+///
+/// ```ignore
+/// let chunks = chunk_source("src/lib.rs", "fn main() { run(); }", SourceLanguage::Rust)?;
+/// assert_eq!(chunks[0].symbol_name, "main");
+/// assert_eq!(chunks[0].dependencies, vec!["run"]);
+/// ```
+pub fn chunk_source(
+    file_path: &str,
+    source: &str,
+    language: SourceLanguage,
+) -> Result<Vec<AstChunk>> {
+    let mut parser = Parser::new();
+    parser
+        .set_language(&language.grammar())
+        .context("Failed to load tree-sitter grammar")?;
+    let tree = parser
+        .parse(source, None)
+        .with_context(|| format!("Failed to parse source file: {}", file_path))?;
+
+    let mut chunker = Chunker {
+        source: source.as_bytes(),
+        language,
+        imports: BTreeSet::new(),
+        definitions: Vec::new(),
+    };
+    let root = tree.root_node();
+    chunker.collect_imports(root);
+    chunker.visit(root, &[], false);
+
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    let chunks = chunker
+        .definitions
+        .iter()
+        .map(|definition| {
+            let qualified = definition.heading_path.join("::");
+            let count = seen.entry(qualified.clone()).or_insert(0);
+            *count += 1;
+            let key = if *count == 1 {
+                qualified
+            } else {
+                format!("{}#{}", qualified, count)
+            };
+
+            let content = node_text(definition.node, chunker.source);
+            AstChunk::new(
+                chunk_id(file_path, &key),
+                file_path,
+                definition.name.clone(),
+                definition.node_type,
+                content,
+                chunker.dependencies(definition),
+            )
+            .with_heading_path(definition.heading_path.clone())
+            .with_span(
+                definition.node.start_position().row as u32 + 1,
+                definition.node.end_position().row as u32 + 1,
+            )
+        })
+        .collect();
+
+    Ok(chunks)
+}
+
+/// 切片 ID：`sha256(文件路径 + 限定名)` 的前 16 个十六进制字符
+fn chunk_id(file_path: &str, qualified_name: &str) -> String {
+    let digest = Sha256::digest(format!("{}::{}", file_path, qualified_name).as_bytes());
+    digest
+        .iter()
+        .take(8)
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+fn node_text<'a>(node: Node<'_>, source: &'a [u8]) -> &'a str {
+    node.utf8_text(source).unwrap_or_default()
+}
+
+/// 找到的一个定义
+struct Definition<'tree> {
+    /// 切片范围对应的节点（Python 装饰器包含在内）
+    node: Node<'tree>,
+    /// 定义体节点（用于提取调用和标识符）
+    body: Node<'tree>,
+    name: String,
+    node_type: &'static str,
+    heading_path: Vec<String>,
+}
+
+struct Chunker<'a, 'tree> {
+    source: &'a [u8],
+    language: SourceLanguage,
+    /// 文件中导入的名称
+    imports: BTreeSet<String>,
+    definitions: Vec<Definition<'tree>>,
+}
+
+impl<'a, 'tree> Chunker<'a, 'tree> {
+    fn text(&self, node: Node<'_>) -> String {
+        node_text(node, self.source).to_string()
+    }
+
+    fn field_text(&self, node: Node<'_>, field: &str) -> Option<String> {
+        node.child_by_field_name(field)
+            .map(|child| self.text(child))
+    }
+
+    fn push(
+        &mut self,
+        node: Node<'tree>,
+        body: Node<'tree>,
+        name: String,
+        node_type: &'static str,
+        owners: &[String],
+    ) {
+        let mut heading_path = owners.to_vec();
+        heading_path.push(name.clone());
+        self.definitions.push(Definition {
+            node,
+            body,
+            name,
+            node_type,
+            heading_path,
+        });
+    }
+
+    /// 递归查找定义；`owners` 为外层类型名，`in_type` 表示当前位于类型体内（函数即方法）
+    fn visit(&mut self, node: Node<'tree>, owners: &[String], in_type: bool) {
+        let mut cursor = node.walk();
+        let children: Vec<Node<'tree>> = node.named_children(&mut cursor).collect();
+        for child in children {
+            match self.language {
+                SourceLanguage::Rust => self.visit_rust(child, owners, in_type),
+                SourceLanguage::TypeScript | SourceLanguage::Tsx => {
+                    self.visit_typescript(child, owners, in_type)
+                }
+                SourceLanguage::Python => self.visit_python(child, child, owners, in_type),
+                SourceLanguage::Java => self.visit_java(child, owners),
+                SourceLanguage::Go => self.visit_go(child),
+            }
+        }
+    }
+
+    fn visit_rust(&mut self, node: Node<'tree>, owners: &[String], in_type: bool) {
+        match node.kind() {
+            "function_item" | "function_signature_item" => {
+                if let Some(name) = self.field_text(node, "name") {
+                    let node_type = if in_type { "method" } else { "function" };
+                    self.push(node, node, name, node_type, owners);
+                }
+            }
+            "struct_item" | "enum_item" | "union_item" | "trait_item" => {
+                if let Some(name) = self.field_text(node, "name") {
+                    self.push(node, node, name.clone(), "class", owners);
+                    if let Some(body) = node.child_by_field_name("body") {
+                        self.visit(body, &with_owner(owners, name), true);
+                    }
+                }
+            }
+            "impl_item" => {
+                let Some(type_name) = self.field_text(node, "type") else {
+                    return;
+                };
+                let name = match self.field_text(node, "trait") {
+                    Some(trait_name) => format!("{} for {}", trait_name, type_name),
+                    None => type_name.clone(),
+                };
+                self.push(node, node, name, "impl", owners);
+                if let Some(body) = node.child_by_field_name("body") {
+                    self.visit(body, &with_owner(owners, type_name), true);
+                }
+            }
+            "mod_item" => {
+                if let (Some(name), Some(body)) = (
+                    self.field_text(node, "name"),
+                    node.child_by_field_name("body"),
+                ) {
+                    self.visit(body, &with_owner(owners, name), false);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn visit_typescript(&mut self, node: Node<'tree>, owners: &[String], in_type: bool) {
+        match node.kind() {
+            "function_declaration" | "generator_function_declaration" | "function_signature" => {
+                if let Some(name) = self.field_text(node, "name") {
+                    self.push(node, node, name, "function", owners);
+                }
+            }
+            "class_declaration"
+            | "abstract_class_declaration"
+            | "interface_declaration"
+            | "enum_declaration" => {
+                if let Some(name) = self.field_text(node, "name") {
+                    self.push(node, node, name.clone(), "class", owners);
+                    if let Some(body) = node.child_by_field_name("body") {
+                        self.visit(body, &with_owner(owners, name), true);
+                    }
+                }
+            }
+            "method_definition" | "abstract_method_signature" | "method_signature" if in_type => {
+                if let Some(name) = self.field_text(node, "name") {
+                    self.push(node, node, name, "method", owners);
+                }
+            }
+            // `const handler = () => {}` / `const handler = function () {}`
+            "lexical_declaration" | "variable_declaration" => {
+                let mut cursor = node.walk();
+                let declarators: Vec<Node<'tree>> = node.named_children(&mut cursor).collect();
+                for declarator in declarators {
+                    let is_function =
+                        declarator
+                            .child_by_field_name("value")
+                            .is_some_and(|value| {
+                                matches!(
+                                    value.kind(),
+                                    "arrow_function" | "function_expression" | "function"
+                                )
+                            });
+                    if let (true, Some(name)) = (is_function, self.field_text(declarator, "name")) {
+                        self.push(node, declarator, name, "function", owners);
+                    }
+                }
+            }
+            "export_statement" => self.visit(node, owners, in_type),
+            "internal_module" | "module" => {
+                if let (Some(name), Some(body)) = (
+                    self.field_text(node, "name"),
+                    node.child_by_field_name("body"),
+                ) {
+                    self.visit(body, &with_owner(owners, name), false);
+                }
+            }
+            "expression_statement" => self.visit(node, owners, in_type),
+            _ => {}
+        }
+    }
+
+    /// `outer` 是包含装饰器的节点（`decorated_definition`），切片范围以它为准
+    fn visit_python(
+        &mut self,
+        node: Node<'tree>,
+        outer: Node<'tree>,
+        owners: &[String],
+        in_type: bool,
+    ) {
+        match node.kind() {
+            "function_definition" => {
+                if let Some(name) = self.field_text(node, "name") {
+                    let node_type = if in_type { "method" } else { "function" };
+                    self.push(outer, node, name, node_type, owners);
+                }
+            }
+            "class_definition" => {
+                if let Some(name) = self.field_text(node, "name") {
+                    self.push(outer, node, name.clone(), "class", owners);
+                    if let Some(body) = node.child_by_field_name("body") {
+                        self.visit(body, &with_owner(owners, name), true);
+                    }
+                }
+            }
+            "decorated_definition" => {
+                if let Some(definition) = node.child_by_field_name("definition") {
+                    self.visit_python(definition, node, owners, in_type);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn visit_java(&mut self, node: Node<'tree>, owners: &[String]) {
+        match node.kind() {
+            "class_declaration"
+            | "interface_declaration"
+            | "enum_declaration"
+            | "record_declaration"
+            | "annotation_type_declaration" => {
+                if let Some(name) = self.field_text(node, "name") {
+                    self.push(node, node, name.clone(), "class", owners);
+                    if let Some(body) = node.child_by_field_name("body") {
+                        self.visit(body, &with_owner(owners, name), true);
+                    }
+                }
+            }
+            "method_declaration" | "constructor_declaration" => {
+                if let Some(name) = self.field_text(node, "name") {
+                    self.push(node, node, name, "method", owners);
+                }
+            }
+            // 枚举体中的方法位于 enum_body_declarations 内
+            "enum_body_declarations" => self.visit(node, owners, true),
+            _ => {}
+        }
+    }
+
+    fn visit_go(&mut self, node: Node<'tree>) {
+        match node.kind() {
+            "function_declaration" => {
+                if let Some(name) = self.field_text(node, "name") {
+                    self.push(node, node, name, "function", &[]);
+                }
+            }
+            "method_declaration" => {
+                if let Some(name) = self.field_text(node, "name") {
+                    let owners: Vec<String> = node
+                        .child_by_field_name("receiver")
+                        .and_then(|receiver| self.go_receiver_type(receiver))
+                        .into_iter()
+                        .collect();
+                    self.push(node, node, name, "method", &owners);
+                }
+            }
+            "type_declaration" => {
+                let mut cursor = node.walk();
+                let specs: Vec<Node<'tree>> = node.named_children(&mut cursor).collect();
+                for spec in specs.into_iter().filter(|spec| spec.kind() == "type_spec") {
+                    if let Some(name) = self.field_text(spec, "name") {
+                        // 单个类型声明时切片包含 `type` 关键字
+                        let range_node = if node.named_child_count() == 1 {
+                            node
+                        } else {
+                            spec
+                        };
+                        self.push(range_node, spec, name, "class", &[]);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Go 方法接收者的类型名（去掉指针和泛型参数）
+    fn go_receiver_type(&self, receiver: Node<'_>) -> Option<String> {
+        let mut stack = vec![receiver];
+        while let Some(node) = stack.pop() {
+            if node.kind() == "type_identifier" {
+                return Some(self.text(node));
+            }
+            let mut cursor = node.walk();
+            let children: Vec<Node<'_>> = node.named_children(&mut cursor).collect();
+            stack.extend(children.into_iter().rev());
+        }
+        None
+    }
+
+    /// 收集文件中导入的名称
+    fn collect_imports(&mut self, root: Node<'_>) {
+        let import_kinds: &[&str] = match self.language {
+            SourceLanguage::Rust => &["use_declaration"],
+            SourceLanguage::TypeScript | SourceLanguage::Tsx => &["import_statement"],
+            SourceLanguage::Python => &["import_statement", "import_from_statement"],
+            SourceLanguage::Java => &["import_declaration"],
+            SourceLanguage::Go => &["import_spec"],
+        };
+
+        let mut stack = vec![root];
+        while let Some(node) = stack.pop() {
+            if import_kinds.contains(&node.kind()) {
+                let names = self.imported_names(node);
+                self.imports.extend(names);
+                continue;
+            }
+            let mut cursor = node.walk();
+            stack.extend(node.named_children(&mut cursor));
+        }
+    }
+
+    fn imported_names(&self, node: Node<'_>) -> Vec<String> {
+        match self.language {
+            // `import "net/http"` → `http`；带别名时使用别名
+            SourceLanguage::Go => {
+                let name = self.field_text(node, "name").or_else(|| {
+                    let path = self.field_text(node, "path")?;
+                    let path = path.trim_matches(|c| c == '"' || c == '`');
+                    path.rsplit('/').next().map(str::to_string)
+                });
+                name.filter(|n| n != "_" && n != ".").into_iter().collect()
+            }
+            // 收集导入语句中的标识符叶子节点（跳过 `from` 模块路径和字符串来源）
+            _ => {
+                let skip = node
+                    .child_by_field_name("module_name")
+                    .or_else(|| node.child_by_field_name("source"));
+                let mut names = Vec::new();
+                let mut stack = vec![node];
+                while let Some(current) = stack.pop() {
+                    if Some(current) == skip {
+                        continue;
+                    }
+                    if current.named_child_count() == 0 && is_identifier_kind(current.kind()) {
+                        let name = self.text(current);
+                        if !matches!(name.as_str(), "self" | "super" | "crate") {
+                            names.push(name);
+                        }
+                        continue;
+                    }
+                    // `crate::store::open`、`java.util.List` 只记录最后一段
+                    let prefix = current
+                        .child_by_field_name("path")
+                        .or_else(|| current.child_by_field_name("scope"));
+                    let mut cursor = current.walk();
+                    stack.extend(
+                        current
+                            .named_children(&mut cursor)
+                            .filter(|child| Some(*child) != prefix),
+                    );
+                }
+                names
+            }
+        }
+    }
+
+    /// 定义使用的导入名和调用的符号，去重排序，不含定义自身的名称
+    fn dependencies(&self, definition: &Definition<'_>) -> Vec<String> {
+        let mut dependencies = BTreeSet::new();
+        let call_kinds = self.language.call_kinds();
+
+        let mut stack = vec![definition.body];
+        while let Some(node) = stack.pop() {
+            if let Some((_, field)) = call_kinds.iter().find(|(kind, _)| *kind == node.kind()) {
+                if let Some(callee) = node
+                    .child_by_field_name(field)
+                    .and_then(|callee| self.callee_name(callee))
+                {
+                    dependencies.insert(callee);
+                }
+            }
+            if node.named_child_count() == 0 && is_identifier_kind(node.kind()) {
+                let name = self.text(node);
+                if self.imports.contains(&name) {
+                    dependencies.insert(name);
+                }
+            }
+            let mut cursor = node.walk();
+            stack.extend(node.named_children(&mut cursor));
+        }
+
+        dependencies.remove(&definition.name);
+        dependencies.into_iter().collect()
+    }
+
+    /// 被调用者的名称：`a.b.c()` → `c`，`Foo::new()` → `new`，`new Foo()` → `Foo`
+    fn callee_name(&self, node: Node<'_>) -> Option<String> {
+        if is_identifier_kind(node.kind()) {
+            return Some(self.text(node));
+        }
+        for field in ["field", "property", "attribute", "name", "type"] {
+            if let Some(child) = node.child_by_field_name(field) {
+                return self.callee_name(child);
+            }
+        }
+        // 泛型调用（`foo::<T>()`、`new Foo<T>()`）等：取第一个具名子节点
+        node.named_child(0)
+            .and_then(|child| self.callee_name(child))
+    }
+}
+
+fn is_identifier_kind(kind: &str) -> bool {
+    matches!(
+        kind,
+        "identifier" | "type_identifier" | "field_identifier" | "property_identifier"
+    )
+}
+
+fn with_owner(owners: &[String], owner: String) -> Vec<String> {
+    let mut owners = owners.to_vec();
+    owners.push(owner);
+    owners
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunk(source: &str, language: SourceLanguage) -> Vec<AstChunk> {
+        chunk_source("src/sample", source, language).expect("chunking should succeed")
+    }
+
+    fn summary(chunks: &[AstChunk]) -> Vec<(&str, &str, u32, u32)> {
+        chunks
+            .iter()
+            .map(|c| {
+                let span = c.span.expect("code chunks carry a span");
+                (
+                    c.symbol_name.as_str(),
+                    c.node_type.as_str(),
+                    span.start,
+                    span.end,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_rust_chunks() {
+        let source = r#"use std::collections::HashMap;
+use crate::store::{Store, open};
+
+pub struct Cache {
+    map: HashMap<String, String>,
+}
+
+impl Cache {
+    pub fn new() -> Self {
+        let store = open();
+        Self { map: HashMap::new() }
+    }
+}
+
+impl Drop for Cache {
+    fn drop(&mut self) {}
+}
+
+fn helper(store: &Store) {
+    store.flush();
+    helper_two::<u8>();
+}
+"#;
+        let chunks = chunk(source, SourceLanguage::Rust);
+        assert_eq!(
+            summary(&chunks),
+            vec![
+                ("Cache", "class", 4, 6),
+                ("Cache", "impl", 8, 13),
+                ("new", "method", 9, 12),
+                ("Drop for Cache", "impl", 15, 17),
+                ("drop", "method", 16, 16),
+                ("helper", "function", 19, 22),
+            ]
+        );
+
+        let new = &chunks[2];
+        assert_eq!(new.heading_path, vec!["Cache", "new"]);
+        assert_eq!(new.dependencies, vec!["HashMap", "open"]);
+        assert!(new.content.starts_with("pub fn new()"));
+
+        assert_eq!(chunks[5].dependencies, vec!["Store", "flush", "helper_two"]);
+    }
+
+    #[test]
+    fn test_typescript_chunks() {
+        let source = r#"import { Router } from "./router";
+import fetchJson from "./http";
+
+export class Api {
+  constructor(private router: Router) {}
+
+  async load(id: string) {
+    return fetchJson(`/items/${id}`);
+  }
+}
+
+export const handler = async () => new Api(new Router());
+
+interface Item { id: string }
+"#;
+        let chunks = chunk(source, SourceLanguage::TypeScript);
+        assert_eq!(
+            summary(&chunks),
+            vec![
+                ("Api", "class", 4, 10),
+                ("constructor", "method", 5, 5),
+                ("load", "method", 7, 9),
+                ("handler", "function", 12, 12),
+                ("Item", "class", 14, 14),
+            ]
+        );
+        assert_eq!(chunks[2].dependencies, vec!["fetchJson"]);
+        assert_eq!(chunks[3].dependencies, vec!["Api", "Router"]);
+    }
+
+    #[test]
+    fn test_python_chunks_include_decorators() {
+        let source = r#"import os
+from pathlib import Path
+
+class Loader:
+    @staticmethod
+    def load(name):
+        return Path(os.path.join("data", name)).read_text()
+
+def main():
+    Loader.load("a")
+"#;
+        let chunks = chunk(source, SourceLanguage::Python);
+        assert_eq!(
+            summary(&chunks),
+            vec![
+                ("Loader", "class", 4, 7),
+                ("load", "method", 5, 7),
+                ("main", "function", 9, 10),
+            ]
+        );
+        assert!(chunks[1].content.starts_with("@staticmethod"));
+        assert_eq!(
+            chunks[1].dependencies,
+            vec!["Path", "join", "os", "read_text"]
+        );
+        assert_eq!(chunks[2].dependencies, vec!["load"]);
+    }
+
+    #[test]
+    fn test_java_chunks() {
+        let source = r#"import java.util.List;
+
+public class Service {
+    public Service() {}
+
+    public List<String> names() {
+        return List.of(format("a"));
+    }
+}
+"#;
+        let chunks = chunk(source, SourceLanguage::Java);
+        assert_eq!(
+            summary(&chunks),
+            vec![
+                ("Service", "class", 3, 9),
+                ("Service", "method", 4, 4),
+                ("names", "method", 6, 8),
+            ]
+        );
+        assert_eq!(chunks[2].dependencies, vec!["List", "format", "of"]);
+        // 构造函数与类同名：限定名不同，ID 也不同
+        assert_ne!(chunks[0].id, chunks[1].id);
+    }
+
+    #[test]
+    fn test_go_chunks() {
+        let source = r#"package server
+
+import (
+	"fmt"
+	log "github.com/sirupsen/logrus"
+)
+
+type Server struct {
+	addr string
+}
+
+func (s *Server) Start() error {
+	log.Info("starting")
+	return fmt.Errorf("not implemented: %s", s.addr)
+}
+
+func New(addr string) *Server {
+	return &Server{addr: addr}
+}
+"#;
+        let chunks = chunk(source, SourceLanguage::Go);
+        assert_eq!(
+            summary(&chunks),
+            vec![
+                ("Server", "class", 8, 10),
+                ("Start", "method", 12, 15),
+                ("New", "function", 17, 19),
+            ]
+        );
+        assert_eq!(chunks[1].heading_path, vec!["Server", "Start"]);
+        assert_eq!(chunks[1].dependencies, vec!["Errorf", "Info", "fmt", "log"]);
+    }
+
+    #[test]
+    fn test_ids_are_stable_and_unique() {
+        let source = "fn a() {}\nfn b() {}\n";
+        let first = chunk(source, SourceLanguage::Rust);
+        let moved = chunk("\n\nfn b() {}\nfn a() {}\n", SourceLanguage::Rust);
+
+        assert_ne!(first[0].id, first[1].id);
+        assert_eq!(first[0].id, moved[1].id);
+        assert_eq!(first[0].id.len(), 16);
+    }
+
+    #[test]
+    fn test_language_from_path() {
+        assert_eq!(
+            SourceLanguage::from_path(Path::new("a/b.rs")),
+            Some(SourceLanguage::Rust)
+        );
+        assert_eq!(
+            SourceLanguage::from_path(Path::new("App.TSX")),
+            Some(SourceLanguage::Tsx)
+        );
+        assert_eq!(SourceLanguage::from_path(Path::new("x.md")), None);
+        assert!(is_source_path(Path::new("main.go")));
+    }
+}
@@ -0,0 +1,188 @@
+//! 文档解析器
+//!
+//! [`DocumentParser`] 把一种格式的源文件解析为 [`ParsedDoc`]，由文件扩展名选择：
+//!
+//! | 格式 | 扩展名 | 解析器 |
+//! |------|--------|--------|
+//! | Markdown / MDX | `md`、`markdown`、`mdx` | [`MarkdownParser`] |
+//! | reStructuredText | `rst`、`rest` | [`RstParser`] |
+//! | AsciiDoc | `adoc`、`asciidoc`、`asc` | [`AsciiDocParser`] |
+//! | HTML | `html`、`htm`、`xhtml` | [`HtmlParser`] |
+//! | PDF | `pdf` | [`PdfParser`] |
+//! | 纯文本 | `txt`、`text` | [`PlainTextParser`] |
+//!
+//! 除 Markdown 外的解析器都先把源文件转换为 Markdown，再走同一套标题提取、
+//! 层级切片和链接提取流程，所以各格式的切片行为一致。
+
+use super::asciidoc::AsciiDocParser;
+use super::html::HtmlParser;
+use super::pdf::PdfParser;
+use super::rst::RstParser;
+use super::{build_parsed_doc, parse_markdown_str, DocMetadata, ParsedDoc, SliceConfig};
+use anyhow::{Context, Result};
+use std::path::Path;
+
+/// 一种文档格式的解析器
+pub trait DocumentParser: Send + Sync {
+    /// 格式名（如 `markdown`、`rst`）
+    fn name(&self) -> &'static str;
+
+    /// 支持的扩展名（小写，不含 `.`）
+    fn extensions(&self) -> &'static [&'static str];
+
+    /// 解析源文本，`file_path` 用于生成默认标题和解析相对链接
+    fn parse(&self, file_path: &str, source: &str, config: &SliceConfig) -> Result<ParsedDoc>;
+
+    /// 读取并解析文件（默认按 UTF-8 文本读取，二进制格式可以覆盖）
+    fn parse_file(&self, file_path: &str, config: &SliceConfig) -> Result<ParsedDoc> {
+        let source = std::fs::read_to_string(file_path)
+            .with_context(|| format!("Failed to read {}", file_path))?;
+        self.parse(file_path, &source, config)
+    }
+}
+
+/// Markdown 和 MDX（front-matter、MDX 预处理见 [`parse_markdown_str`]）
+pub struct MarkdownParser;
+
+impl DocumentParser for MarkdownParser {
+    fn name(&self) -> &'static str {
+        "markdown"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["md", "markdown", "mdx"]
+    }
+
+    fn parse(&self, file_path: &str, source: &str, config: &SliceConfig) -> Result<ParsedDoc> {
+        Ok(parse_markdown_str(file_path, source, config))
+    }
+}
+
+/// 纯文本：标题取文件名，正文按段落切分
+///
+/// 行首的 `#`、`>`、列表符号等会被转义，避免被当作 Markdown 结构。
+pub struct PlainTextParser;
+
+impl DocumentParser for PlainTextParser {
+    fn name(&self) -> &'static str {
+        "text"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["txt", "text"]
+    }
+
+    fn parse(&self, file_path: &str, source: &str, config: &SliceConfig) -> Result<ParsedDoc> {
+        let content = source
+            .lines()
+            .map(escape_markdown_line)
+            .collect::<Vec<_>>()
+            .join("\n");
+        // 纯文本没有标题，正文全部位于「前言」中，必须保留
+        let config = config.clone().with_preamble(true);
+        Ok(build_parsed_doc(
+            file_path,
+            content,
+            DocMetadata::default(),
+            &config,
+        ))
+    }
+}
+
+/// 转义会被解析为 Markdown 块结构的行首字符，并去掉缩进（避免变成缩进代码块）
+pub(crate) fn escape_markdown_line(line: &str) -> String {
+    let trimmed = line.trim_start();
+    let is_ordered_item = trimmed.split_once(['.', ')']).is_some_and(|(number, _)| {
+        !number.is_empty() && number.chars().all(|c| c.is_ascii_digit())
+    });
+
+    if is_ordered_item || trimmed.starts_with(['#', '>', '-', '+', '*', '=', '`', '~', '<', '|']) {
+        format!("\\{}", trimmed)
+    } else {
+        trimmed.to_string()
+    }
+}
+
+/// 内置解析器（按优先级排列）
+static PARSERS: &[&dyn DocumentParser] = &[
+    &MarkdownParser,
+    &RstParser,
+    &AsciiDocParser,
+    &HtmlParser,
+    &PdfParser,
+    &PlainTextParser,
+];
+
+/// 所有内置解析器
+pub fn document_parsers() -> &'static [&'static dyn DocumentParser] {
+    PARSERS
+}
+
+/// 根据扩展名选择解析器（不区分大小写）
+pub fn parser_for_path(path: &Path) -> Option<&'static dyn DocumentParser> {
+    let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+    PARSERS
+        .iter()
+        .copied()
+        .find(|parser| parser.extensions().contains(&ext.as_str()))
+}
+
+/// 判断路径是否为支持的文档格式
+pub fn is_document_path(path: &Path) -> bool {
+    parser_for_path(path).is_some()
+}
+
+/// 按扩展名选择解析器并解析文件
+///
+/// # Errors
+///
+/// 扩展名不受支持、文件不存在或解析失败时返回错误
+pub fn parse_document(file_path: &str, config: &SliceConfig) -> Result<ParsedDoc> {
+    let parser = parser_for_path(Path::new(file_path))
+        .with_context(|| format!("Unsupported document format: {}", file_path))?;
+    if !Path::new(file_path).exists() {
+        anyhow::bail!("File not found: {}", file_path);
+    }
+    parser.parse_file(file_path, config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parser_selection_by_extension() {
+        let name = |path: &str| parser_for_path(Path::new(path)).map(|p| p.name());
+        assert_eq!(name("docs/a.md"), Some("markdown"));
+        assert_eq!(name("docs/a.MDX"), Some("markdown"));
+        assert_eq!(name("docs/index.rst"), Some("rst"));
+        assert_eq!(name("docs/guide.adoc"), Some("asciidoc"));
+        assert_eq!(name("api/Foo.html"), Some("html"));
+        assert_eq!(name("NOTES.txt"), Some("text"));
+        assert_eq!(name("vendor/Manual.PDF"), Some("pdf"));
+        assert_eq!(name("image.png"), None);
+        assert!(!is_document_path(Path::new("Makefile")));
+    }
+
+    #[test]
+    fn test_plain_text_is_sliced_by_paragraph() {
+        let source = "# not a heading\n\nFirst paragraph.\n\n- not a list\n\nSecond paragraph.";
+        let config = SliceConfig::default()
+            .with_preamble(false)
+            .with_max_size(crate::parser::ChunkSize::Chars(40));
+
+        let doc = PlainTextParser
+            .parse("notes/todo.txt", source, &config)
+            .unwrap();
+        assert_eq!(doc.title, "todo");
+        assert!(doc.sections.len() >= 2, "long text should be split");
+        assert!(doc.sections.iter().all(|s| s.heading_path == vec!["todo"]));
+        assert!(doc.sections[0].content.contains("\\# not a heading"));
+    }
+
+    #[test]
+    fn test_parse_document_rejects_unknown_formats() {
+        let error = parse_document("data/file.bin", &SliceConfig::default()).unwrap_err();
+        assert!(error.to_string().contains("Unsupported document format"));
+    }
+}
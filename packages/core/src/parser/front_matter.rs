@@ -0,0 +1,264 @@
+//! Front-matter 元数据解析
+//!
+//! 支持两种格式，必须位于文件开头：
+//!
+//! - YAML：以 `---` 行开始，以 `---`（或 `...`）行结束
+//! - TOML：以 `+++` 行开始，以 `+++` 行结束
+//!
+//! 常用字段（`title`、`description`、`tags`、`version`、`deprecated`）被解析为强类型字段，
+//! 其余字段保留在 `extra` 中。
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// 文档 front-matter 元数据
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DocMetadata {
+    /// 文档标题（存在时覆盖 H1 标题）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+
+    /// 文档描述（存在时作为文档摘要）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// 标签，可写成列表或逗号分隔的字符串
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+
+    /// 文档适用的版本（数字会被转换为字符串，如 `1.20`）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+
+    /// 是否已废弃
+    #[serde(default)]
+    pub deprecated: bool,
+
+    /// 其余未识别的字段
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub extra: BTreeMap<String, Value>,
+}
+
+impl DocMetadata {
+    /// 从 YAML 或 TOML 解析后的键值表构造元数据
+    fn from_map(map: serde_json::Map<String, Value>) -> Self {
+        let mut metadata = Self::default();
+
+        for (key, value) in map {
+            match key.as_str() {
+                "title" => metadata.title = scalar_to_string(&value),
+                "description" => metadata.description = scalar_to_string(&value),
+                "tags" => metadata.tags = to_tags(&value),
+                "version" => metadata.version = scalar_to_string(&value),
+                "deprecated" => metadata.deprecated = to_bool(&value),
+                _ => {
+                    metadata.extra.insert(key, value);
+                }
+            }
+        }
+
+        metadata
+    }
+
+    /// 是否没有任何元数据
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// 把标量值转换为去除首尾空白的非空字符串
+fn scalar_to_string(value: &Value) -> Option<String> {
+    let text = match value {
+        Value::String(s) => s.trim().to_string(),
+        Value::Number(n) => n.to_string(),
+        Value::Bool(b) => b.to_string(),
+        _ => return None,
+    };
+    (!text.is_empty()).then_some(text)
+}
+
+/// 标签既可以是列表（`[a, b]`），也可以是逗号分隔的字符串（`"a, b"`）
+fn to_tags(value: &Value) -> Vec<String> {
+    let mut tags: Vec<String> = match value {
+        Value::Array(items) => items.iter().filter_map(scalar_to_string).collect(),
+        Value::String(s) => s
+            .split(',')
+            .map(|t| t.trim().to_string())
+            .filter(|t| !t.is_empty())
+            .collect(),
+        other => scalar_to_string(other).into_iter().collect(),
+    };
+
+    // 标签以逗号分隔存储，标签内的逗号无法保留
+    for tag in &mut tags {
+        if tag.contains(',') {
+            *tag = tag.replace(',', " ");
+        }
+    }
+    tags.dedup();
+    tags
+}
+
+fn to_bool(value: &Value) -> bool {
+    match value {
+        Value::Bool(b) => *b,
+        Value::String(s) => matches!(s.trim().to_ascii_lowercase().as_str(), "true" | "yes" | "1"),
+        Value::Number(n) => n.as_i64().is_some_and(|n| n != 0),
+        _ => false,
+    }
+}
+
+/// 把 TOML 值转换为 JSON 值（日期时间转为字符串）
+fn toml_to_json(value: toml::Value) -> Value {
+    match value {
+        toml::Value::String(s) => Value::String(s),
+        toml::Value::Integer(i) => Value::from(i),
+        toml::Value::Float(f) => Value::from(f),
+        toml::Value::Boolean(b) => Value::Bool(b),
+        toml::Value::Datetime(dt) => Value::String(dt.to_string()),
+        toml::Value::Array(items) => Value::Array(items.into_iter().map(toml_to_json).collect()),
+        toml::Value::Table(table) => Value::Object(
+            table
+                .into_iter()
+                .map(|(k, v)| (k, toml_to_json(v)))
+                .collect(),
+        ),
+    }
+}
+
+/// 解析结果
+#[derive(Debug)]
+pub struct FrontMatter<'a> {
+    /// 解析出的元数据；front-matter 格式错误时为 `Err`
+    pub metadata: Result<DocMetadata, String>,
+    /// 去掉 front-matter 之后的正文
+    pub body: &'a str,
+}
+
+/// 拆分文件开头的 front-matter 和正文
+///
+/// 没有 front-matter（或缺少结束分隔符）时返回 `None`。front-matter 格式错误时仍返回正文，
+/// 这样错误的元数据不会混入摘要和索引；错误信息放在 `metadata` 中，由调用方决定如何处理。
+///
+/// # 示例
+///
+/// ```ignore
+/// let fm = split_front_matter("---\ntitle: Guide\ntags: [a, b]\n---\n# Body").unwrap();
+/// assert_eq!(fm.metadata.unwrap().title.as_deref(), Some("Guide"));
+/// assert_eq!(fm.body, "# Body");
+/// ```
+pub fn split_front_matter(content: &str) -> Option<FrontMatter<'_>> {
+    // 允许 UTF-8 BOM
+    let text = content.strip_prefix('\u{feff}').unwrap_or(content);
+
+    let mut lines = text.split_inclusive('\n');
+    let opening = lines.next()?;
+    let (is_yaml, closings): (bool, &[&str]) = match opening.trim_end() {
+        "---" => (true, &["---", "..."]),
+        "+++" => (false, &["+++"]),
+        _ => return None,
+    };
+
+    let header_start = opening.len();
+    let mut offset = header_start;
+    for line in lines {
+        if closings.contains(&line.trim_end()) {
+            let raw = &text[header_start..offset];
+            let body = &text[offset + line.len()..];
+
+            let metadata = if is_yaml {
+                parse_yaml(raw)
+            } else {
+                parse_toml(raw)
+            };
+            return Some(FrontMatter { metadata, body });
+        }
+        offset += line.len();
+    }
+
+    None
+}
+
+fn parse_yaml(raw: &str) -> Result<DocMetadata, String> {
+    if raw.trim().is_empty() {
+        return Ok(DocMetadata::default());
+    }
+    match serde_yaml::from_str::<Value>(raw) {
+        Ok(Value::Object(map)) => Ok(DocMetadata::from_map(map)),
+        Ok(Value::Null) => Ok(DocMetadata::default()),
+        Ok(_) => Err("YAML front-matter must be a mapping".to_string()),
+        Err(e) => Err(format!("Invalid YAML front-matter: {}", e)),
+    }
+}
+
+fn parse_toml(raw: &str) -> Result<DocMetadata, String> {
+    let table: toml::Table =
+        toml::from_str(raw).map_err(|e| format!("Invalid TOML front-matter: {}", e))?;
+    match toml_to_json(toml::Value::Table(table)) {
+        Value::Object(map) => Ok(DocMetadata::from_map(map)),
+        _ => unreachable!("TOML table always converts to a JSON object"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_yaml_front_matter() {
+        let content = "---\ntitle: Block Events\ntags: [events, blocks]\nversion: 1.20\ndeprecated: true\nauthor: Steve\n---\n# Heading\n\nBody";
+        let fm = split_front_matter(content).expect("front-matter should be detected");
+        let metadata = fm.metadata.unwrap();
+
+        assert_eq!(metadata.title.as_deref(), Some("Block Events"));
+        assert_eq!(metadata.tags, vec!["events", "blocks"]);
+        assert_eq!(metadata.version.as_deref(), Some("1.2"));
+        assert!(metadata.deprecated);
+        assert_eq!(metadata.extra.get("author"), Some(&Value::from("Steve")));
+        assert_eq!(fm.body, "# Heading\n\nBody");
+    }
+
+    #[test]
+    fn test_quoted_version_is_kept_verbatim() {
+        let fm = split_front_matter("---\nversion: \"1.20.0\"\n---\nBody").unwrap();
+        assert_eq!(fm.metadata.unwrap().version.as_deref(), Some("1.20.0"));
+    }
+
+    #[test]
+    fn test_toml_front_matter() {
+        let content = "+++\ntitle = \"Items\"\ntags = \"items, crafting\"\nupdated = 2024-01-02\n+++\nBody";
+        let fm = split_front_matter(content).unwrap();
+        let metadata = fm.metadata.unwrap();
+
+        assert_eq!(metadata.title.as_deref(), Some("Items"));
+        assert_eq!(metadata.tags, vec!["items", "crafting"]);
+        assert_eq!(metadata.extra.get("updated"), Some(&Value::from("2024-01-02")));
+        assert_eq!(fm.body, "Body");
+    }
+
+    #[test]
+    fn test_no_front_matter() {
+        assert!(split_front_matter("# Title\n\n---\n\nBody").is_none());
+        assert!(split_front_matter("").is_none());
+    }
+
+    #[test]
+    fn test_unterminated_front_matter_is_not_stripped() {
+        assert!(split_front_matter("---\ntitle: x\nno closing").is_none());
+    }
+
+    #[test]
+    fn test_invalid_front_matter_is_stripped_with_error() {
+        let fm = split_front_matter("---\ntitle: [unclosed\n---\nBody").unwrap();
+        assert!(fm.metadata.is_err());
+        assert_eq!(fm.body, "Body");
+    }
+
+    #[test]
+    fn test_empty_front_matter_and_crlf() {
+        let fm = split_front_matter("---\r\n---\r\nBody").unwrap();
+        assert!(fm.metadata.unwrap().is_empty());
+        assert_eq!(fm.body, "Body");
+    }
+}
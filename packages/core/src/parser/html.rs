@@ -0,0 +1,480 @@
+//! HTML 转换
+//!
+//! 从预渲染的 HTML（如 Javadoc、Sphinx 输出、静态站点）中提取正文并转换为 Markdown，
+//! 再交给 Markdown 解析和切片流程，因此按 `<h1>`–`<h6>` 标题切片：
+//!
+//! - 正文定位（readability 风格）：优先使用 `<main>`、`<article>`、`[role=main]` 等正文容器；
+//!   没有时按段落文本长度和链接密度给候选块打分，取得分最高的块
+//! - 删除导航、页眉页脚、侧栏、脚本、样式和表单等非正文元素
+//! - `<pre>` 转为围栏代码块（语言取自 `language-*` / `lang-*` 类名）
+//! - 链接、强调、行内代码、列表、引用、表格和定义列表转为对应的 Markdown
+//! - `<title>` 在正文没有 `<h1>` 时作为文档标题，`<meta name="description">` 作为摘要
+
+use super::document::DocumentParser;
+use super::{build_parsed_doc, DocMetadata, ParsedDoc, SliceConfig};
+use anyhow::Result;
+use regex::Regex;
+use scraper::{ElementRef, Html, Node, Selector};
+use std::sync::OnceLock;
+
+/// HTML 解析器
+pub struct HtmlParser;
+
+impl DocumentParser for HtmlParser {
+    fn name(&self) -> &'static str {
+        "html"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["html", "htm", "xhtml"]
+    }
+
+    fn parse(&self, file_path: &str, source: &str, config: &SliceConfig) -> Result<ParsedDoc> {
+        let document = Html::parse_document(source);
+        let markdown = html_to_markdown_document(&document);
+
+        let has_h1 = markdown.lines().any(|l| l.starts_with("# "));
+        let metadata = DocMetadata {
+            title: if has_h1 {
+                None
+            } else {
+                select_text(&document, "title")
+            },
+            description: select_attr(&document, r#"meta[name="description"]"#, "content"),
+            ..DocMetadata::default()
+        };
+
+        Ok(build_parsed_doc(file_path, markdown, metadata, config))
+    }
+}
+
+/// 提取 HTML 文档的正文并转换为 Markdown
+///
+/// # 示例
+///
+/// ```ignore
+/// let md = html_to_markdown("<nav>Menu</nav><main><h1>Guide</h1><p>Use <code>spawn</code>.</p></main>");
+/// assert_eq!(md.trim(), "# Guide\n\nUse `spawn`.");
+/// ```
+pub fn html_to_markdown(source: &str) -> String {
+    html_to_markdown_document(&Html::parse_document(source))
+}
+
+fn html_to_markdown_document(document: &Html) -> String {
+    let Some(root) = main_content(document) else {
+        return String::new();
+    };
+
+    let mut renderer = Renderer::default();
+    renderer.render_children(root);
+    renderer.finish()
+}
+
+/// 不属于正文的元素
+const SKIPPED_TAGS: &[&str] = &[
+    "script", "style", "noscript", "template", "nav", "header", "footer", "aside", "form",
+    "button", "input", "select", "textarea", "svg", "canvas", "iframe", "img", "picture", "video",
+    "audio", "head", "title", "meta", "link",
+];
+
+/// 明确标记为正文容器的选择器（按优先级）
+const CONTENT_SELECTORS: &[&str] = &[
+    "main",
+    "[role=main]",
+    "article",
+    "#content",
+    "#main-content",
+    ".main-content",
+    ".contentContainer",
+    ".document",
+    "#main",
+    ".content",
+];
+
+/// 选择正文容器
+fn main_content(document: &Html) -> Option<ElementRef<'_>> {
+    for selector in CONTENT_SELECTORS {
+        let selector = Selector::parse(selector).expect("valid selector");
+        if let Some(element) = document
+            .select(&selector)
+            .find(|e| !is_boilerplate(*e) && text_length(*e) > 0)
+        {
+            return Some(element);
+        }
+    }
+
+    // 打分：块内段落文本越多越好，链接文本占比越高越差
+    let candidates = Selector::parse("div, section, td").expect("valid selector");
+    let best = document
+        .select(&candidates)
+        .filter(|e| !is_boilerplate(*e))
+        .map(|e| (content_score(e), e))
+        .filter(|(score, _)| *score > 0.0)
+        .max_by(|a, b| a.0.total_cmp(&b.0))
+        .map(|(_, e)| e);
+
+    best.or_else(|| {
+        let body = Selector::parse("body").expect("valid selector");
+        document.select(&body).next()
+    })
+}
+
+fn content_score(element: ElementRef<'_>) -> f64 {
+    let paragraph = Selector::parse("p, pre, li").expect("valid selector");
+    let anchor = Selector::parse("a").expect("valid selector");
+
+    let text: usize = element.select(&paragraph).map(|p| text_length(p)).sum();
+    let total = text_length(element).max(1);
+    let links: usize = element.select(&anchor).map(|a| text_length(a)).sum();
+    let link_density = links as f64 / total as f64;
+    text as f64 * (1.0 - link_density)
+}
+
+fn text_length(element: ElementRef<'_>) -> usize {
+    element
+        .text()
+        .map(|t| t.split_whitespace().map(str::len).sum::<usize>())
+        .sum()
+}
+
+/// 导航、侧栏、页脚等通过 class / id 标记的非正文区域
+fn is_boilerplate(element: ElementRef<'_>) -> bool {
+    static BOILERPLATE: OnceLock<Regex> = OnceLock::new();
+    let regex = BOILERPLATE.get_or_init(|| {
+        Regex::new(r"(?i)(^|[\s_-])(nav|navbar|navigation|menu|sidebar|sidenav|footer|breadcrumbs?|toc|cookie|banner|skip|subnav|top-nav|bottom-nav)($|[\s_-])")
+            .expect("valid regex")
+    });
+    let value = element.value();
+    value.id().is_some_and(|id| regex.is_match(id))
+        || value
+            .attr("class")
+            .is_some_and(|class| regex.is_match(class))
+        || value
+            .attr("role")
+            .is_some_and(|role| matches!(role, "navigation" | "banner" | "contentinfo"))
+}
+
+fn select_text(document: &Html, selector: &str) -> Option<String> {
+    let selector = Selector::parse(selector).ok()?;
+    let text = document
+        .select(&selector)
+        .next()?
+        .text()
+        .collect::<String>();
+    let text = collapse_whitespace(&text);
+    (!text.is_empty()).then_some(text)
+}
+
+fn select_attr(document: &Html, selector: &str, attr: &str) -> Option<String> {
+    let selector = Selector::parse(selector).ok()?;
+    let value = document.select(&selector).next()?.value().attr(attr)?;
+    let value = collapse_whitespace(value);
+    (!value.is_empty()).then_some(value)
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Markdown 渲染器：块级元素之间用空行分隔，行内内容累积在当前段落中
+#[derive(Default)]
+struct Renderer {
+    blocks: Vec<String>,
+    inline: String,
+}
+
+impl Renderer {
+    fn finish(mut self) -> String {
+        self.end_block();
+        let mut markdown = self.blocks.join("\n\n");
+        markdown.push('\n');
+        markdown
+    }
+
+    /// 结束当前段落
+    fn end_block(&mut self) {
+        let text = self.inline.trim().to_string();
+        if !text.is_empty() {
+            self.blocks.push(text);
+        }
+        self.inline.clear();
+    }
+
+    fn push_block(&mut self, block: String) {
+        self.end_block();
+        if !block.trim().is_empty() {
+            self.blocks.push(block);
+        }
+    }
+
+    fn render_children(&mut self, element: ElementRef<'_>) {
+        for child in element.children() {
+            match child.value() {
+                Node::Text(text) => self.push_text(text),
+                Node::Element(_) => {
+                    if let Some(child) = ElementRef::wrap(child) {
+                        self.render_element(child);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn push_text(&mut self, text: &str) {
+        if text.trim().is_empty() {
+            if !text.is_empty() && !self.inline.ends_with([' ', '\n']) && !self.inline.is_empty() {
+                self.inline.push(' ');
+            }
+            return;
+        }
+        if text.starts_with(char::is_whitespace)
+            && !self.inline.ends_with([' ', '\n'])
+            && !self.inline.is_empty()
+        {
+            self.inline.push(' ');
+        }
+        self.inline.push_str(&collapse_whitespace(text));
+        if text.ends_with(char::is_whitespace) {
+            self.inline.push(' ');
+        }
+    }
+
+    fn render_element(&mut self, element: ElementRef<'_>) {
+        let tag = element.value().name();
+        if SKIPPED_TAGS.contains(&tag) || is_boilerplate(element) {
+            return;
+        }
+
+        match tag {
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                let level = tag[1..].parse::<usize>().unwrap_or(1);
+                let text = inline_markdown(element);
+                if !text.is_empty() {
+                    self.push_block(format!("{} {}", "#".repeat(level), text));
+                }
+            }
+            "p" | "div" | "section" | "article" | "main" | "dd" | "figure" | "figcaption"
+            | "center" => {
+                self.end_block();
+                self.render_children(element);
+                self.end_block();
+            }
+            "dt" => {
+                let text = inline_markdown(element);
+                self.push_block(format!("**{}**", text));
+            }
+            "pre" => self.push_block(code_block(element)),
+            "ul" | "ol" => self.push_block(list_markdown(element, 0)),
+            "blockquote" => {
+                let inner = html_children_markdown(element);
+                let quoted = inner
+                    .trim()
+                    .lines()
+                    .map(|l| format!("> {}", l).trim_end().to_string())
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                self.push_block(quoted);
+            }
+            "table" => self.push_block(table_markdown(element)),
+            "br" => self.inline.push('\n'),
+            "hr" => self.end_block(),
+            _ => self.push_inline(element),
+        }
+    }
+
+    /// 行内元素：链接、强调、代码
+    fn push_inline(&mut self, element: ElementRef<'_>) {
+        let tag = element.value().name();
+        let text = inline_markdown(element);
+        if text.is_empty() {
+            return;
+        }
+        let rendered = match tag {
+            "a" => match element.value().attr("href") {
+                Some(href) if !href.starts_with("javascript:") && !href.is_empty() => {
+                    format!("[{}]({})", text, href)
+                }
+                _ => text,
+            },
+            "strong" | "b" => format!("**{}**", text),
+            "em" | "i" => format!("*{}*", text),
+            "code" | "kbd" | "samp" | "tt" => format!("`{}`", text.replace('`', "")),
+            _ => {
+                // 未知容器（span 等）：按子节点渲染，保留其中的块级结构
+                self.render_children(element);
+                return;
+            }
+        };
+        self.inline.push_str(&rendered);
+    }
+}
+
+/// 把元素的内容渲染为单行 Markdown
+fn inline_markdown(element: ElementRef<'_>) -> String {
+    let mut renderer = Renderer::default();
+    renderer.render_children(element);
+    renderer.end_block();
+    collapse_whitespace(&renderer.blocks.join(" "))
+}
+
+/// 把元素的子节点渲染为 Markdown 块
+fn html_children_markdown(element: ElementRef<'_>) -> String {
+    let mut renderer = Renderer::default();
+    renderer.render_children(element);
+    renderer.finish()
+}
+
+fn code_block(element: ElementRef<'_>) -> String {
+    let code = Selector::parse("code").expect("valid selector");
+    let language = std::iter::once(element)
+        .chain(element.select(&code))
+        .filter_map(|e| e.value().attr("class"))
+        .flat_map(str::split_whitespace)
+        .find_map(|class| {
+            class
+                .strip_prefix("language-")
+                .or_else(|| class.strip_prefix("lang-"))
+                .or_else(|| class.strip_prefix("highlight-"))
+        })
+        .unwrap_or("");
+
+    let text: String = element.text().collect();
+    let text = text.trim_matches('\n').trim_end();
+    format!("```{}\n{}\n```", language, text)
+}
+
+fn list_markdown(element: ElementRef<'_>, depth: usize) -> String {
+    let ordered = element.value().name() == "ol";
+    let mut lines = Vec::new();
+
+    for item in element
+        .child_elements()
+        .filter(|e| e.value().name() == "li")
+    {
+        let mut text = Renderer::default();
+        let mut nested = Vec::new();
+        for child in item.children() {
+            match child.value() {
+                Node::Text(t) => text.push_text(t),
+                Node::Element(e) if matches!(e.name(), "ul" | "ol") => {
+                    if let Some(list) = ElementRef::wrap(child) {
+                        nested.push(list_markdown(list, depth + 1));
+                    }
+                }
+                Node::Element(_) => {
+                    if let Some(child) = ElementRef::wrap(child) {
+                        text.render_element(child);
+                    }
+                }
+                _ => {}
+            }
+        }
+        text.end_block();
+
+        let marker = if ordered { "1." } else { "-" };
+        lines.push(format!(
+            "{}{} {}",
+            "  ".repeat(depth),
+            marker,
+            collapse_whitespace(&text.blocks.join(" "))
+        ));
+        lines.extend(nested);
+    }
+    lines.join("\n")
+}
+
+fn table_markdown(element: ElementRef<'_>) -> String {
+    let rows = Selector::parse("tr").expect("valid selector");
+    let cells = Selector::parse("th, td").expect("valid selector");
+
+    element
+        .select(&rows)
+        .map(|row| {
+            row.select(&cells)
+                .map(inline_markdown)
+                .filter(|c| !c.is_empty())
+                .collect::<Vec<_>>()
+                .join(" | ")
+        })
+        .filter(|row| !row.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_main_content_and_boilerplate_removal() {
+        let html = r#"<html><head><title>Ignored</title><script>var x;</script></head><body>
+            <nav><a href="/">Home</a></nav>
+            <div class="sidebar">Links</div>
+            <main>
+              <h1>Guide</h1>
+              <p>Use <code>spawn()</code> to create <a href="entity.html">entities</a>.</p>
+              <h2>Details</h2>
+              <p>More <strong>text</strong>.</p>
+            </main>
+            <footer>Copyright</footer></body></html>"#;
+
+        let md = html_to_markdown(html);
+        assert_eq!(
+            md,
+            "# Guide\n\nUse `spawn()` to create [entities](entity.html).\n\n## Details\n\nMore **text**.\n"
+        );
+    }
+
+    #[test]
+    fn test_readability_scoring_without_main() {
+        let html = r#"<body>
+            <div id="links"><a href="a">A</a> <a href="b">B</a> <a href="c">C</a></div>
+            <div class="wrapper">
+              <h2>Method Summary</h2>
+              <p>This method spawns an entity in the world and returns its handle.</p>
+              <p>It never blocks.</p>
+            </div></body>"#;
+
+        let md = html_to_markdown(html);
+        assert!(md.starts_with("## Method Summary"));
+        assert!(!md.contains("[A]"));
+    }
+
+    #[test]
+    fn test_code_lists_and_tables() {
+        let html = r#"<main>
+            <pre><code class="language-java">void onBreak() {
+    cancel();
+}</code></pre>
+            <ul><li>One</li><li>Two<ul><li>Nested</li></ul></li></ul>
+            <table><tr><th>Name</th><th>Type</th></tr><tr><td>id</td><td><code>int</code></td></tr></table>
+            <dl><dt>spawn</dt><dd>Creates an entity.</dd></dl>
+        </main>"#;
+
+        let md = html_to_markdown(html);
+        assert!(md.contains("```java\nvoid onBreak() {\n    cancel();\n}\n```"));
+        assert!(md.contains("- One\n- Two\n  - Nested"));
+        assert!(md.contains("Name | Type\n\nid | `int`"));
+        assert!(md.contains("**spawn**\n\nCreates an entity."));
+    }
+
+    #[test]
+    fn test_parse_html_document() {
+        let html = r#"<html><head><title>BlockEvent (API)</title>
+            <meta name="description" content="Block event reference"></head>
+            <body><main><h2>Fields</h2><p>type</p><h2>Methods</h2><p>cancel()</p></main></body></html>"#;
+
+        let doc = HtmlParser
+            .parse("api/BlockEvent.html", html, &SliceConfig::default())
+            .unwrap();
+        assert_eq!(doc.title, "BlockEvent (API)");
+        assert_eq!(doc.summary, "Block event reference");
+        let titles: Vec<&str> = doc
+            .sections
+            .iter()
+            .map(|s| s.section_title.as_str())
+            .collect();
+        assert_eq!(titles, vec!["Fields", "Methods"]);
+    }
+}
@@ -0,0 +1,705 @@
+//! Streaming ingestion pipeline for sidecar parsers
+//!
+//! Spawns the sidecar once per file set, streams its JSONL output and hands
+//! chunks to a [`ChunkSink`] (e.g. `HybridOrchestrator::add_batch`) in
+//! fixed-size batches, so memory stays bounded regardless of how much the
+//! sidecar emits.
+//!
+//! # Failure handling
+//!
+//! - **Per-read timeout**: a sidecar that stops writing is killed and restarted
+//! - **Overall timeout**: each file set has a hard deadline across all restarts
+//! - **Restart**: crashed or hung sidecars are restarted up to `max_restarts`
+//!   times; chunks already delivered are skipped on the rerun (by chunk ID)
+//! - **Dead letters**: lines that fail with [`IpcError::JsonParseFailed`] are
+//!   skipped and appended to an optional JSONL dead-letter log
+//!
+//! # Usage
+//!
+//! ```no_run
+//! use contextfy_core::parser::ingest::{IngestConfig, IngestPipeline};
+//! # use contextfy_core::SearchEngine;
+//!
+//! # async fn run(engine: &SearchEngine) -> anyhow::Result<()> {
+//! let pipeline = IngestPipeline::new(["cocoindex", "parse"]).with_config(
+//!     IngestConfig::default()
+//!         .with_batch_size(128)
+//!         .with_dead_letter_path(".contextfy/data/dead_letters.jsonl"),
+//! );
+//! let report = pipeline.run(&["src/a.py", "src/b.py"], engine.orchestrator()).await?;
+//! println!("Ingested {} chunks", report.chunks_ingested);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::kernel::errors::AppError;
+use crate::kernel::types::AstChunk;
+use crate::parser::ipc::{parse_chunk_line, read_stderr_tail, resolve_command, IpcError};
+use crate::slices::hybrid::HybridOrchestrator;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::process::{Child, Command};
+use tokio::time::{timeout, Instant};
+use tracing::{info, warn};
+
+/// Destination for ingested chunk batches
+#[async_trait]
+pub trait ChunkSink: Send + Sync {
+    /// Store one batch of chunks
+    async fn write_batch(&self, chunks: Vec<AstChunk>) -> Result<(), AppError>;
+}
+
+#[async_trait]
+impl ChunkSink for HybridOrchestrator {
+    async fn write_batch(&self, chunks: Vec<AstChunk>) -> Result<(), AppError> {
+        self.add_batch(chunks).await
+    }
+}
+
+/// Ingestion settings
+#[derive(Debug, Clone)]
+pub struct IngestConfig {
+    /// Chunks per `write_batch` call
+    pub batch_size: usize,
+    /// Files passed to one sidecar invocation
+    pub file_set_size: usize,
+    /// Maximum wait for the next output line
+    pub read_timeout: Duration,
+    /// Deadline for one file set, including restarts
+    pub overall_timeout: Duration,
+    /// Restarts allowed per file set after a crash or hang
+    pub max_restarts: usize,
+    /// JSONL file receiving lines that could not be parsed
+    pub dead_letter_path: Option<PathBuf>,
+}
+
+impl Default for IngestConfig {
+    fn default() -> Self {
+        Self {
+            batch_size: 64,
+            file_set_size: 50,
+            read_timeout: Duration::from_secs(30),
+            overall_timeout: Duration::from_secs(600),
+            max_restarts: 2,
+            dead_letter_path: None,
+        }
+    }
+}
+
+impl IngestConfig {
+    /// Set the batch size (at least 1)
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Set how many files one sidecar process handles (at least 1)
+    pub fn with_file_set_size(mut self, file_set_size: usize) -> Self {
+        self.file_set_size = file_set_size.max(1);
+        self
+    }
+
+    /// Set the per-read timeout
+    pub fn with_read_timeout(mut self, read_timeout: Duration) -> Self {
+        self.read_timeout = read_timeout;
+        self
+    }
+
+    /// Set the per-file-set deadline
+    pub fn with_overall_timeout(mut self, overall_timeout: Duration) -> Self {
+        self.overall_timeout = overall_timeout;
+        self
+    }
+
+    /// Set the number of restarts allowed per file set
+    pub fn with_max_restarts(mut self, max_restarts: usize) -> Self {
+        self.max_restarts = max_restarts;
+        self
+    }
+
+    /// Log unparseable lines to the given JSONL file
+    pub fn with_dead_letter_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.dead_letter_path = Some(path.into());
+        self
+    }
+}
+
+/// Summary of an ingestion run
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IngestReport {
+    /// Chunks written to the sink
+    pub chunks_ingested: usize,
+    /// `write_batch` calls made
+    pub batches: usize,
+    /// Sidecar processes spawned (file sets plus restarts)
+    pub spawns: usize,
+    /// Restarts after crashes or hangs
+    pub restarts: usize,
+    /// Lines skipped because they were not valid chunk JSON
+    pub dead_letters: usize,
+}
+
+/// One entry of the dead-letter log
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeadLetter {
+    /// Files the sidecar was parsing
+    pub files: Vec<String>,
+    /// 1-based line number within the sidecar output
+    pub line_number: usize,
+    /// Offending line (truncated)
+    pub raw_line: String,
+    /// Deserialization error
+    pub cause: String,
+}
+
+/// Why one sidecar attempt stopped early
+enum AttemptError {
+    Sidecar(IpcError),
+    Sink(AppError),
+    DeadLetterLog(anyhow::Error),
+}
+
+/// Streaming sidecar ingestion pipeline
+pub struct IngestPipeline {
+    command: Vec<String>,
+    config: IngestConfig,
+}
+
+impl IngestPipeline {
+    /// Create a pipeline for a sidecar command; file paths are appended as arguments
+    pub fn new<A, I>(command: I) -> Self
+    where
+        A: AsRef<str>,
+        I: IntoIterator<Item = A>,
+    {
+        Self {
+            command: command
+                .into_iter()
+                .map(|s| s.as_ref().to_string())
+                .collect(),
+            config: IngestConfig::default(),
+        }
+    }
+
+    /// Replace the ingestion settings
+    pub fn with_config(mut self, config: IngestConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Ingest files, spawning one sidecar per `file_set_size` files
+    ///
+    /// # Errors
+    ///
+    /// Returns the first unrecoverable failure: the sidecar cannot start,
+    /// it keeps failing after `max_restarts`, the overall deadline expires,
+    /// the sink rejects a batch, or the dead-letter log cannot be written.
+    /// Batches written before the failure stay in the sink.
+    pub async fn run<P: AsRef<Path>>(
+        &self,
+        files: &[P],
+        sink: &dyn ChunkSink,
+    ) -> Result<IngestReport> {
+        let files: Vec<String> = files
+            .iter()
+            .map(|f| f.as_ref().to_string_lossy().into_owned())
+            .collect();
+
+        let mut report = IngestReport::default();
+        for file_set in files.chunks(self.config.file_set_size.max(1)) {
+            self.run_file_set(file_set, sink, &mut report).await?;
+        }
+
+        info!(
+            "Ingested {} chunks in {} batches ({} restarts, {} dead letters)",
+            report.chunks_ingested, report.batches, report.restarts, report.dead_letters
+        );
+        Ok(report)
+    }
+
+    async fn run_file_set(
+        &self,
+        files: &[String],
+        sink: &dyn ChunkSink,
+        report: &mut IngestReport,
+    ) -> Result<()> {
+        let deadline = Instant::now() + self.config.overall_timeout;
+        // IDs already written and lines already dead-lettered, so a rerun after a restart does not repeat them
+        let mut delivered: HashSet<String> = HashSet::new();
+        let mut dead_lettered: HashSet<String> = HashSet::new();
+        let mut restarts = 0;
+
+        loop {
+            report.spawns += 1;
+            let attempt = self
+                .run_attempt(
+                    files,
+                    sink,
+                    deadline,
+                    &mut delivered,
+                    &mut dead_lettered,
+                    report,
+                )
+                .await;
+
+            match attempt {
+                Ok(()) => return Ok(()),
+                Err(AttemptError::Sink(e)) => {
+                    return Err(anyhow::Error::new(e).context("Failed to store ingested chunks"));
+                }
+                Err(AttemptError::DeadLetterLog(e)) => return Err(e),
+                Err(AttemptError::Sidecar(e)) if e.is_restartable() => {
+                    if restarts >= self.config.max_restarts {
+                        return Err(anyhow::anyhow!(IpcError::RestartLimitExceeded {
+                            restarts,
+                            cause: e.to_string(),
+                        }));
+                    }
+                    restarts += 1;
+                    report.restarts += 1;
+                    warn!(
+                        "Restarting sidecar ({}/{}): {}",
+                        restarts, self.config.max_restarts, e
+                    );
+                }
+                Err(AttemptError::Sidecar(e)) => return Err(anyhow::anyhow!(e)),
+            }
+        }
+    }
+
+    /// Run the sidecar once over a file set, flushing batches as they fill
+    async fn run_attempt(
+        &self,
+        files: &[String],
+        sink: &dyn ChunkSink,
+        deadline: Instant,
+        delivered: &mut HashSet<String>,
+        dead_lettered: &mut HashSet<String>,
+        report: &mut IngestReport,
+    ) -> Result<(), AttemptError> {
+        let mut child = self.spawn(files).map_err(AttemptError::Sidecar)?;
+        let stdout = child.stdout.take().ok_or_else(|| {
+            AttemptError::Sidecar(IpcError::ChildStartFailed {
+                command: self.command.join(" "),
+                cause: "stdout not captured".to_string(),
+            })
+        })?;
+        // Drain stderr concurrently so a chatty sidecar cannot block on a full pipe
+        let stderr_task = child
+            .stderr
+            .take()
+            .map(|stderr| tokio::spawn(read_stderr_tail(stderr)));
+
+        let mut lines = BufReader::new(stdout).lines();
+        let mut batch: Vec<AstChunk> = Vec::with_capacity(self.config.batch_size);
+        let mut line_number = 0;
+
+        let outcome = loop {
+            let wait = self.next_wait(deadline);
+            let line = match timeout(wait, lines.next_line()).await {
+                Ok(Ok(Some(line))) => line,
+                Ok(Ok(None)) => break Ok(()),
+                Ok(Err(e)) => {
+                    break Err(IpcError::StreamReadFailed {
+                        cause: e.to_string(),
+                    })
+                }
+                Err(_) => break Err(self.timeout_error(deadline, line_number)),
+            };
+
+            line_number += 1;
+            let line = line.trim_end();
+            if line.is_empty() {
+                continue;
+            }
+
+            match parse_chunk_line(line, line_number) {
+                Ok(chunk) => {
+                    if delivered.insert(chunk.id.clone()) {
+                        batch.push(chunk);
+                    }
+                }
+                Err(IpcError::JsonParseFailed {
+                    line_number,
+                    raw_line,
+                    cause,
+                }) => {
+                    if dead_lettered.insert(raw_line.clone()) {
+                        self.dead_letter(DeadLetter {
+                            files: files.to_vec(),
+                            line_number,
+                            raw_line,
+                            cause,
+                        })
+                        .map_err(AttemptError::DeadLetterLog)?;
+                        report.dead_letters += 1;
+                    }
+                }
+                Err(e) => break Err(e),
+            }
+
+            if batch.len() >= self.config.batch_size {
+                self.flush(&mut batch, sink, report).await?;
+            }
+        };
+
+        // Chunks read before a failure are complete; keep them
+        self.flush(&mut batch, sink, report).await?;
+
+        if let Err(e) = outcome {
+            let _ = child.kill().await;
+            return Err(AttemptError::Sidecar(e));
+        }
+
+        let status = match timeout(self.next_wait(deadline), child.wait()).await {
+            Ok(Ok(status)) => status,
+            Ok(Err(e)) => {
+                return Err(AttemptError::Sidecar(IpcError::StreamReadFailed {
+                    cause: format!("failed to wait for child process: {e}"),
+                }))
+            }
+            Err(_) => {
+                let _ = child.kill().await;
+                return Err(AttemptError::Sidecar(
+                    self.timeout_error(deadline, line_number),
+                ));
+            }
+        };
+        if !status.success() {
+            let stderr = match stderr_task {
+                Some(task) => task.await.unwrap_or_default(),
+                None => String::new(),
+            };
+            return Err(AttemptError::Sidecar(IpcError::ChildExitedAbnormally {
+                exit_code: status.code(),
+                stderr,
+            }));
+        }
+
+        Ok(())
+    }
+
+    fn spawn(&self, files: &[String]) -> Result<Child, IpcError> {
+        let mut args = self.command.clone();
+        args.extend(files.iter().cloned());
+        let (program, full_args) = resolve_command(&args)?;
+
+        Command::new(&program)
+            .args(&full_args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| IpcError::ChildStartFailed {
+                command: format!("{} {:?}", program, full_args),
+                cause: e.to_string(),
+            })
+    }
+
+    /// Time to wait for the next event: the read timeout, capped by the deadline
+    fn next_wait(&self, deadline: Instant) -> Duration {
+        self.config
+            .read_timeout
+            .min(deadline.saturating_duration_since(Instant::now()))
+    }
+
+    fn timeout_error(&self, deadline: Instant, line_number: usize) -> IpcError {
+        if Instant::now() >= deadline {
+            IpcError::OverallTimeout {
+                timeout_ms: self.config.overall_timeout.as_millis() as u64,
+            }
+        } else {
+            IpcError::ReadTimeout {
+                timeout_ms: self.config.read_timeout.as_millis() as u64,
+                line_number: line_number + 1,
+            }
+        }
+    }
+
+    async fn flush(
+        &self,
+        batch: &mut Vec<AstChunk>,
+        sink: &dyn ChunkSink,
+        report: &mut IngestReport,
+    ) -> Result<(), AttemptError> {
+        if batch.is_empty() {
+            return Ok(());
+        }
+        let chunks = std::mem::take(batch);
+        let count = chunks.len();
+        sink.write_batch(chunks).await.map_err(AttemptError::Sink)?;
+        report.chunks_ingested += count;
+        report.batches += 1;
+        Ok(())
+    }
+
+    fn dead_letter(&self, entry: DeadLetter) -> Result<()> {
+        warn!(
+            "Skipping unparseable sidecar line {}: {}",
+            entry.line_number, entry.cause
+        );
+        let Some(path) = &self.config.dead_letter_path else {
+            return Ok(());
+        };
+
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Failed to open dead-letter log {}", path.display()))?;
+        writeln!(file, "{}", serde_json::to_string(&entry)?)
+            .with_context(|| format!("Failed to write dead-letter log {}", path.display()))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+    use tempfile::TempDir;
+
+    /// Sink recording every batch
+    #[derive(Default)]
+    struct RecordingSink {
+        batches: Mutex<Vec<Vec<String>>>,
+    }
+
+    impl RecordingSink {
+        fn batches(&self) -> Vec<Vec<String>> {
+            self.batches.lock().unwrap().clone()
+        }
+
+        fn ids(&self) -> Vec<String> {
+            self.batches().concat()
+        }
+    }
+
+    #[async_trait]
+    impl ChunkSink for RecordingSink {
+        async fn write_batch(&self, chunks: Vec<AstChunk>) -> Result<(), AppError> {
+            let ids = chunks.into_iter().map(|c| c.id).collect();
+            self.batches.lock().unwrap().push(ids);
+            Ok(())
+        }
+    }
+
+    fn chunk_json(id: &str) -> String {
+        format!(
+            r#"{{"id":"{id}","file_path":"{id}.py","symbol_name":"{id}","node_type":"function","content":"pass"}}"#
+        )
+    }
+
+    /// Write a fake sidecar shell script and return the command running it
+    fn fake_sidecar(dir: &TempDir, body: &str) -> Vec<String> {
+        let script = dir.path().join("sidecar.sh");
+        std::fs::write(&script, body).unwrap();
+        vec!["sh".to_string(), script.to_string_lossy().into_owned()]
+    }
+
+    fn ipc_error(error: &anyhow::Error) -> &IpcError {
+        error
+            .downcast_ref::<IpcError>()
+            .expect("error should be an IpcError")
+    }
+
+    #[tokio::test]
+    async fn test_chunks_are_grouped_into_batches() {
+        let dir = TempDir::new().unwrap();
+        let lines: String = (1..=5)
+            .map(|i| format!("echo '{}'\n", chunk_json(&format!("c{i}"))))
+            .collect();
+        let command = fake_sidecar(&dir, &lines);
+
+        let sink = RecordingSink::default();
+        let report = IngestPipeline::new(command)
+            .with_config(IngestConfig::default().with_batch_size(2))
+            .run(&["a.py"], &sink)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            sink.batches(),
+            vec![vec!["c1", "c2"], vec!["c3", "c4"], vec!["c5"]]
+        );
+        assert_eq!(report.chunks_ingested, 5);
+        assert_eq!(report.batches, 3);
+        assert_eq!(report.spawns, 1);
+    }
+
+    #[tokio::test]
+    async fn test_sidecar_is_spawned_per_file_set() {
+        let dir = TempDir::new().unwrap();
+        // One chunk per file argument, ID taken from the file name
+        let command = fake_sidecar(&dir, "for f in \"$@\"; do echo \"{\\\"id\\\":\\\"$f\\\",\\\"file_path\\\":\\\"$f\\\",\\\"symbol_name\\\":\\\"s\\\",\\\"node_type\\\":\\\"function\\\",\\\"content\\\":\\\"x\\\"}\"; done\n");
+
+        let sink = RecordingSink::default();
+        let report = IngestPipeline::new(command)
+            .with_config(IngestConfig::default().with_file_set_size(2))
+            .run(&["a.py", "b.py", "c.py"], &sink)
+            .await
+            .unwrap();
+
+        assert_eq!(report.spawns, 2);
+        assert_eq!(sink.batches(), vec![vec!["a.py", "b.py"], vec!["c.py"]]);
+    }
+
+    #[tokio::test]
+    async fn test_bad_lines_go_to_dead_letter_log() {
+        let dir = TempDir::new().unwrap();
+        let command = fake_sidecar(
+            &dir,
+            &format!(
+                "echo '{}'\necho 'not json'\necho '{}'\n",
+                chunk_json("c1"),
+                chunk_json("c2")
+            ),
+        );
+        let dead_letters = dir.path().join("logs/dead_letters.jsonl");
+
+        let sink = RecordingSink::default();
+        let report = IngestPipeline::new(command)
+            .with_config(IngestConfig::default().with_dead_letter_path(&dead_letters))
+            .run(&["a.py"], &sink)
+            .await
+            .unwrap();
+
+        assert_eq!(sink.ids(), vec!["c1", "c2"]);
+        assert_eq!(report.dead_letters, 1);
+
+        let log = std::fs::read_to_string(&dead_letters).unwrap();
+        let entries: Vec<DeadLetter> = log
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].line_number, 2);
+        assert_eq!(entries[0].raw_line, "not json");
+        assert_eq!(entries[0].files, vec!["a.py"]);
+    }
+
+    #[tokio::test]
+    async fn test_crashed_sidecar_is_restarted_without_duplicates() {
+        let dir = TempDir::new().unwrap();
+        let marker = dir.path().join("crashed");
+        // First run emits c1 then crashes; the rerun emits everything
+        let command = fake_sidecar(
+            &dir,
+            &format!(
+                "echo '{c1}'\nif [ ! -f '{m}' ]; then touch '{m}'; echo boom >&2; exit 3; fi\necho '{c2}'\n",
+                c1 = chunk_json("c1"),
+                c2 = chunk_json("c2"),
+                m = marker.display()
+            ),
+        );
+
+        let sink = RecordingSink::default();
+        let report = IngestPipeline::new(command)
+            .run(&["a.py"], &sink)
+            .await
+            .unwrap();
+
+        assert_eq!(sink.ids(), vec!["c1", "c2"]);
+        assert_eq!(report.restarts, 1);
+        assert_eq!(report.spawns, 2);
+    }
+
+    #[tokio::test]
+    async fn test_restart_limit() {
+        let dir = TempDir::new().unwrap();
+        let command = fake_sidecar(&dir, "echo boom >&2\nexit 1\n");
+
+        let sink = RecordingSink::default();
+        let error = IngestPipeline::new(command)
+            .with_config(IngestConfig::default().with_max_restarts(1))
+            .run(&["a.py"], &sink)
+            .await
+            .unwrap_err();
+
+        match ipc_error(&error) {
+            IpcError::RestartLimitExceeded { restarts, cause } => {
+                assert_eq!(*restarts, 1);
+                assert!(cause.contains("boom"), "stderr should be reported: {cause}");
+            }
+            other => panic!("unexpected error: {other}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_hung_sidecar_hits_read_timeout() {
+        let dir = TempDir::new().unwrap();
+        let command = fake_sidecar(&dir, &format!("echo '{}'\nsleep 5\n", chunk_json("c1")));
+
+        let sink = RecordingSink::default();
+        let started = std::time::Instant::now();
+        let error = IngestPipeline::new(command)
+            .with_config(
+                IngestConfig::default()
+                    .with_read_timeout(Duration::from_millis(200))
+                    .with_max_restarts(0),
+            )
+            .run(&["a.py"], &sink)
+            .await
+            .unwrap_err();
+
+        assert!(started.elapsed() < Duration::from_secs(4));
+        assert!(matches!(
+            ipc_error(&error),
+            IpcError::RestartLimitExceeded { cause, .. } if cause.contains("Timed out")
+        ));
+        // The chunk read before the hang is kept
+        assert_eq!(sink.ids(), vec!["c1"]);
+    }
+
+    #[tokio::test]
+    async fn test_overall_timeout_is_not_restarted() {
+        let dir = TempDir::new().unwrap();
+        let command = fake_sidecar(
+            &dir,
+            &format!(
+                "while true; do echo '{}'; sleep 0.05; done\n",
+                chunk_json("c1")
+            ),
+        );
+
+        let sink = RecordingSink::default();
+        let error = IngestPipeline::new(command)
+            .with_config(IngestConfig::default().with_overall_timeout(Duration::from_millis(300)))
+            .run(&["a.py"], &sink)
+            .await
+            .unwrap_err();
+
+        assert!(matches!(
+            ipc_error(&error),
+            IpcError::OverallTimeout { timeout_ms: 300 }
+        ));
+        assert_eq!(sink.ids(), vec!["c1"]);
+    }
+
+    #[tokio::test]
+    async fn test_missing_sidecar_fails_to_start() {
+        let sink = RecordingSink::default();
+        let error = IngestPipeline::new(["contextfy-no-such-sidecar"])
+            .run(&["a.py"], &sink)
+            .await
+            .unwrap_err();
+
+        assert!(matches!(
+            ipc_error(&error),
+            IpcError::ChildStartFailed { .. }
+        ));
+    }
+}
@@ -9,6 +9,9 @@
 //! - **Panic Safe**: All errors are captured and returned as `Result`, never panics
 //! - **Type Safe**: Each JSONL line is deserialized into `AstChunk` using `serde_json`
 //!
+//! This is the one-way, one-process-per-invocation reader. Long-lived sidecars that
+//! accept requests over stdin use [`crate::parser::protocol`] instead.
+//!
 //! # Usage
 //!
 //! ```no_run
@@ -36,6 +39,10 @@ use crate::kernel::types::AstChunk;
 use anyhow::{Context, Result};
 use std::io::{BufRead, Read};
 use std::process::{Child, ChildStderr, Command, Stdio};
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+/// Bytes of sidecar stderr kept for error messages
+pub(crate) const STDERR_TAIL_BYTES: usize = 64 * 1024;
 
 /// IPC-specific errors
 ///
@@ -64,6 +71,123 @@ pub enum IpcError {
         exit_code: Option<i32>,
         stderr: String,
     },
+
+    /// No output line arrived within the per-read timeout
+    #[error("Timed out waiting for sidecar output after {timeout_ms}ms (line {line_number})")]
+    ReadTimeout { timeout_ms: u64, line_number: usize },
+
+    /// The overall ingestion deadline for a file set expired
+    #[error("Sidecar did not finish within {timeout_ms}ms")]
+    OverallTimeout { timeout_ms: u64 },
+
+    /// Stream write operation failed
+    #[error("Failed to write to stdin: {cause}")]
+    StreamWriteFailed { cause: String },
+
+    /// The sidecar did not complete the protocol handshake
+    #[error("Sidecar handshake failed: {cause}")]
+    HandshakeFailed { cause: String },
+
+    /// The sidecar speaks a protocol version this host does not understand
+    #[error("Unsupported sidecar protocol version {actual} (supported: 1..={supported})")]
+    UnsupportedProtocol { supported: u32, actual: u32 },
+
+    /// The sidecar reported an error for a request
+    #[error("Sidecar request {request_id:?} failed: {message}")]
+    RequestFailed {
+        request_id: Option<u64>,
+        message: String,
+    },
+
+    /// The sidecar kept failing after all allowed restarts
+    #[error("Sidecar failed after {restarts} restarts: {cause}")]
+    RestartLimitExceeded { restarts: usize, cause: String },
+}
+
+impl IpcError {
+    /// Whether restarting the sidecar may fix the failure
+    ///
+    /// Crashes, hangs and broken pipes are transient; start failures and
+    /// deadline expiry are not.
+    pub fn is_restartable(&self) -> bool {
+        matches!(
+            self,
+            Self::StreamReadFailed { .. }
+                | Self::StreamWriteFailed { .. }
+                | Self::ChildExitedAbnormally { .. }
+                | Self::ReadTimeout { .. }
+        )
+    }
+}
+
+/// Resolve the program and arguments to run, honouring `COCOINDEX_MODE`
+pub(crate) fn resolve_command(args: &[String]) -> Result<(String, Vec<String>), IpcError> {
+    // Validate that args is not empty
+    if args.is_empty() {
+        return Err(IpcError::ChildStartFailed {
+            command: "<empty>".to_string(),
+            cause: "at least one argument is required".to_string(),
+        });
+    }
+
+    if std::env::var("COCOINDEX_MODE").as_deref() == Ok("dev") {
+        // Development mode: use `uv run`
+        let mut uv_args = vec!["run".to_string()];
+        uv_args.extend(args.iter().cloned());
+        Ok(("uv".to_string(), uv_args))
+    } else {
+        // Production mode: direct invocation
+        Ok((args[0].clone(), args[1..].to_vec()))
+    }
+}
+
+/// Drain a sidecar's stderr, keeping only the last [`STDERR_TAIL_BYTES`]
+///
+/// The pipe is read to the end so the sidecar never blocks on it, however much
+/// it writes; earlier output is dropped.
+pub(crate) async fn read_stderr_tail(mut pipe: impl AsyncRead + Unpin) -> String {
+    let mut tail = Vec::new();
+    let mut buffer = [0u8; 8192];
+    let mut truncated = false;
+    while let Ok(n @ 1..) = pipe.read(&mut buffer).await {
+        tail.extend_from_slice(&buffer[..n]);
+        // Trim in bulk so each byte is moved a bounded number of times
+        if tail.len() > 2 * STDERR_TAIL_BYTES {
+            tail.drain(..tail.len() - STDERR_TAIL_BYTES);
+            truncated = true;
+        }
+    }
+    if tail.len() > STDERR_TAIL_BYTES {
+        tail.drain(..tail.len() - STDERR_TAIL_BYTES);
+        truncated = true;
+    }
+    if truncated {
+        // Do not start in the middle of a UTF-8 sequence
+        let start = tail
+            .iter()
+            .position(|b| b & 0xC0 != 0x80)
+            .unwrap_or(tail.len());
+        tail.drain(..start);
+    }
+    String::from_utf8_lossy(&tail).into_owned()
+}
+
+/// Deserialize one JSONL line into an `AstChunk`
+///
+/// The raw line in the error is truncated to 100 characters.
+pub(crate) fn parse_chunk_line(line: &str, line_number: usize) -> Result<AstChunk, IpcError> {
+    serde_json::from_str(line).map_err(|e| IpcError::JsonParseFailed {
+        line_number,
+        raw_line: {
+            let prefix: String = line.chars().take(100).collect();
+            if prefix.len() < line.len() {
+                format!("{prefix}...")
+            } else {
+                line.to_string()
+            }
+        },
+        cause: e.to_string(),
+    })
 }
 
 /// IPC sidecar process handler
@@ -101,26 +225,7 @@ impl SidecarIPC {
     {
         let args: Vec<String> = args.into_iter().map(|s| s.as_ref().to_string()).collect();
 
-        // Validate that args is not empty
-        if args.is_empty() {
-            return Err(anyhow::anyhow!(IpcError::ChildStartFailed {
-                command: "<empty>".to_string(),
-                cause: "at least one argument is required".to_string(),
-            }));
-        }
-
-        // Determine command based on environment
-        let (program, full_args) = if std::env::var("COCOINDEX_MODE").as_deref() == Ok("dev") {
-            // Development mode: use `uv run`
-            ("uv".to_string(), {
-                let mut uv_args = vec!["run".to_string()];
-                uv_args.extend(args.clone());
-                uv_args
-            })
-        } else {
-            // Production mode: direct invocation
-            (args[0].clone(), args[1..].to_vec())
-        };
+        let (program, full_args) = resolve_command(&args)?;
 
         let mut cmd = Command::new(&program);
         cmd.args(&full_args);
@@ -194,18 +299,7 @@ impl SidecarIPC {
         let line = line.trim_end();
 
         // Deserialize JSON
-        let chunk: AstChunk = serde_json::from_str(line).map_err(|e| IpcError::JsonParseFailed {
-            line_number: self.line_number,
-            raw_line: {
-                let prefix: String = line.chars().take(100).collect();
-                if prefix.len() < line.len() {
-                    format!("{prefix}...")
-                } else {
-                    line.to_string()
-                }
-            },
-            cause: e.to_string(),
-        })?;
+        let chunk = parse_chunk_line(line, self.line_number)?;
 
         Ok(Some(chunk))
     }
@@ -252,6 +346,19 @@ impl SidecarIPC {
 mod tests {
     use super::*;
 
+    #[tokio::test]
+    async fn test_stderr_tail_is_bounded() {
+        let mut output = "é".repeat(STDERR_TAIL_BYTES);
+        output.push_str("fatal: boom");
+
+        let tail = read_stderr_tail(output.as_bytes()).await;
+        assert!(tail.len() <= STDERR_TAIL_BYTES);
+        assert!(tail.ends_with("fatal: boom"));
+        assert!(tail.starts_with('é'), "tail must start on a char boundary");
+
+        assert_eq!(read_stderr_tail(&b"short"[..]).await, "short");
+    }
+
     #[test]
     fn test_spawn_child_process_success() {
         // Use echo to simulate valid JSONL output
@@ -0,0 +1,323 @@
+//! 文档链接提取
+//!
+//! 从 Markdown 中提取指向其他文档或章节的链接，用于构建文档关系图：
+//!
+//! - 行内链接：`[文本](other.md#anchor)`、`[文本](#anchor)`
+//! - 引用链接：`[文本][label]` + `[label]: other.md`（定义可以在文档的任意位置）
+//! - Wiki 链接：`[[Page]]`、`[[Page#Heading]]`、`[[Page|显示文本]]`
+//!
+//! 外部链接（`https://`、`mailto:` 等）、图片和代码中的内容会被忽略。
+//! 链接只记录原始目标，解析为切片 ID 的工作在构建索引时完成。
+
+use pulldown_cmark::{BrokenLink, CowStr, Event, LinkType, Options, Parser, Tag};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// 链接的书写形式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LinkKind {
+    /// `[text](target)`
+    Inline,
+    /// `[text][label]`、`[label][]`、`[label]`
+    Reference,
+    /// `[[Page]]`
+    Wiki,
+}
+
+/// 文档中的一个内部链接
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DocLink {
+    /// 目标路径（Wiki 链接为页面名）；只有锚点的章节内链接为空字符串
+    pub target: String,
+    /// `#` 之后的锚点（不含 `#`）
+    pub anchor: Option<String>,
+    /// 链接文本
+    pub text: String,
+    /// 链接形式
+    pub kind: LinkKind,
+}
+
+impl DocLink {
+    /// 是否是同一文档内的锚点链接（如 `#usage`）
+    pub fn is_local(&self) -> bool {
+        self.target.is_empty()
+    }
+}
+
+/// 提取文档中的引用链接定义（`[label]: target`），标签统一转为小写
+///
+/// 切片只包含文档的一部分，引用链接的定义通常位于文档末尾，因此需要先对整篇文档
+/// 收集定义，再传给 [`extract_links_with_definitions`]。
+pub fn link_definitions(content: &str) -> HashMap<String, String> {
+    static DEFINITION: OnceLock<Regex> = OnceLock::new();
+    let regex = DEFINITION.get_or_init(|| {
+        Regex::new(r#"(?m)^ {0,3}\[([^\]]+)\]:[ \t]*<?([^\s>]+)>?"#).expect("valid regex")
+    });
+
+    let mut definitions = HashMap::new();
+    for caps in regex.captures_iter(content) {
+        let label = normalize_label(&caps[1]);
+        // 与 CommonMark 一致：同名定义以第一个为准
+        definitions.entry(label).or_insert_with(|| caps[2].to_string());
+    }
+    definitions
+}
+
+/// 提取内容中的内部链接（只使用内容自身的引用定义）
+pub fn extract_links(content: &str) -> Vec<DocLink> {
+    extract_links_with_definitions(content, &link_definitions(content))
+}
+
+/// 提取内容中的内部链接，引用链接使用给定的定义表解析
+pub fn extract_links_with_definitions(
+    content: &str,
+    definitions: &HashMap<String, String>,
+) -> Vec<DocLink> {
+    let mut callback = |broken: BrokenLink<'_>| {
+        definitions
+            .get(&normalize_label(&broken.reference))
+            .map(|target| (CowStr::from(target.clone()), CowStr::from(String::new())))
+    };
+    let parser =
+        Parser::new_with_broken_link_callback(content, Options::empty(), Some(&mut callback));
+
+    let mut links = Vec::new();
+    let mut open_link: Option<(LinkType, String, String)> = None;
+    let mut in_code_block = false;
+    let mut text_run = String::new();
+
+    for event in parser {
+        // Wiki 链接可能被拆成多个 Text 事件，先把连续的文本拼接起来再扫描
+        if !matches!(event, Event::Text(_)) && !text_run.is_empty() {
+            links.extend(wiki_links(&text_run));
+            text_run.clear();
+        }
+
+        match event {
+            Event::Start(Tag::CodeBlock(_)) => in_code_block = true,
+            Event::End(Tag::CodeBlock(_)) => in_code_block = false,
+            Event::Start(Tag::Link(link_type, dest, _)) => {
+                open_link = Some((link_type, dest.to_string(), String::new()));
+            }
+            Event::End(Tag::Link(..)) => {
+                if let Some((link_type, dest, text)) = open_link.take() {
+                    let kind = match link_type {
+                        LinkType::Inline => LinkKind::Inline,
+                        LinkType::Reference
+                        | LinkType::ReferenceUnknown
+                        | LinkType::Collapsed
+                        | LinkType::CollapsedUnknown
+                        | LinkType::Shortcut
+                        | LinkType::ShortcutUnknown => LinkKind::Reference,
+                        // 自动链接和邮件地址总是外部链接
+                        LinkType::Autolink | LinkType::Email => continue,
+                    };
+                    if let Some(link) = internal_link(&dest, text.trim(), kind) {
+                        links.push(link);
+                    }
+                }
+            }
+            Event::Text(text) | Event::Code(text) if open_link.is_some() => {
+                if let Some((_, _, link_text)) = &mut open_link {
+                    link_text.push_str(&text);
+                }
+            }
+            Event::Text(text) if !in_code_block => text_run.push_str(&text),
+            _ => {}
+        }
+    }
+    if !text_run.is_empty() {
+        links.extend(wiki_links(&text_run));
+    }
+
+    links
+}
+
+/// 把链接目标拆成路径和锚点；外部链接返回 `None`
+fn internal_link(dest: &str, text: &str, kind: LinkKind) -> Option<DocLink> {
+    let dest = dest.trim();
+    if dest.is_empty() || is_external(dest) {
+        return None;
+    }
+
+    let (path, anchor) = match dest.split_once('#') {
+        Some((path, anchor)) => (path, Some(anchor)),
+        None => (dest, None),
+    };
+    // 查询参数与文档关系无关
+    let path = path.split('?').next().unwrap_or("");
+    let anchor = anchor.filter(|a| !a.is_empty()).map(decode_spaces);
+    if path.is_empty() && anchor.is_none() {
+        return None;
+    }
+
+    Some(DocLink {
+        target: decode_spaces(path),
+        anchor,
+        text: text.to_string(),
+        kind,
+    })
+}
+
+/// 带协议（`https:`、`mailto:`）或协议相对（`//host`）的链接视为外部链接
+fn is_external(dest: &str) -> bool {
+    if dest.starts_with("//") {
+        return true;
+    }
+    match dest.split_once(':') {
+        Some((scheme, _)) => {
+            !scheme.is_empty()
+                && !scheme.contains('/')
+                && scheme.chars().all(|c| c.is_ascii_alphanumeric() || "+-.".contains(c))
+        }
+        None => false,
+    }
+}
+
+/// 提取文本中的 `[[Page]]`、`[[Page#Heading]]`、`[[Page|Alias]]`
+fn wiki_links(text: &str) -> Vec<DocLink> {
+    static WIKI_LINK: OnceLock<Regex> = OnceLock::new();
+    let regex = WIKI_LINK.get_or_init(|| Regex::new(r"\[\[([^\[\]|]+)(?:\|([^\[\]]*))?\]\]").expect("valid regex"));
+
+    regex
+        .captures_iter(text)
+        .filter_map(|caps| {
+            let target = caps[1].trim();
+            let (page, anchor) = match target.split_once('#') {
+                Some((page, anchor)) => (page.trim(), Some(anchor.trim())),
+                None => (target, None),
+            };
+            let anchor = anchor.filter(|a| !a.is_empty()).map(str::to_string);
+            if page.is_empty() && anchor.is_none() {
+                return None;
+            }
+
+            let text = caps
+                .get(2)
+                .map(|alias| alias.as_str().trim())
+                .filter(|alias| !alias.is_empty())
+                .unwrap_or(target);
+            Some(DocLink {
+                target: page.to_string(),
+                anchor,
+                text: text.to_string(),
+                kind: LinkKind::Wiki,
+            })
+        })
+        .collect()
+}
+
+/// 引用标签不区分大小写，连续空白视为一个空格
+fn normalize_label(label: &str) -> String {
+    label.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase()
+}
+
+fn decode_spaces(text: &str) -> String {
+    text.replace("%20", " ")
+}
+
+/// 生成标题锚点（GitHub 风格）
+///
+/// 转为小写，空格替换为 `-`，删除除 `-`、`_` 以外的标点；中文等非 ASCII 字母保留。
+///
+/// # 示例
+///
+/// ```ignore
+/// assert_eq!(slugify("Getting Started!"), "getting-started");
+/// assert_eq!(slugify("方块 事件"), "方块-事件");
+/// ```
+pub fn slugify(heading: &str) -> String {
+    heading
+        .trim()
+        .to_lowercase()
+        .chars()
+        .filter_map(|c| match c {
+            ' ' => Some('-'),
+            '-' | '_' => Some(c),
+            c if c.is_alphanumeric() => Some(c),
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn targets(links: &[DocLink]) -> Vec<(&str, Option<&str>, LinkKind)> {
+        links
+            .iter()
+            .map(|l| (l.target.as_str(), l.anchor.as_deref(), l.kind))
+            .collect()
+    }
+
+    #[test]
+    fn test_inline_links() {
+        let links = extract_links(
+            "See [events](events.md#block-events), [below](#usage) and [site](https://example.com).",
+        );
+        assert_eq!(
+            targets(&links),
+            vec![
+                ("events.md", Some("block-events"), LinkKind::Inline),
+                ("", Some("usage"), LinkKind::Inline),
+            ]
+        );
+        assert_eq!(links[0].text, "events");
+        assert!(links[1].is_local());
+    }
+
+    #[test]
+    fn test_reference_links_use_external_definitions() {
+        let document = "## A\n\nRead [the guide][guide] and [Items].\n\n## B\n\n[guide]: ./guide.md\n[items]: items.md#crafting\n";
+        let definitions = link_definitions(document);
+        let section = "Read [the guide][guide] and [Items].";
+
+        let links = extract_links_with_definitions(section, &definitions);
+        assert_eq!(
+            targets(&links),
+            vec![
+                ("./guide.md", None, LinkKind::Reference),
+                ("items.md", Some("crafting"), LinkKind::Reference),
+            ]
+        );
+        // 没有定义时不是链接
+        assert!(extract_links(section).is_empty());
+    }
+
+    #[test]
+    fn test_wiki_links() {
+        let links = extract_links("Related: [[Block Events]], [[Items#Crafting|crafting]] and [[#Usage]].");
+        assert_eq!(
+            targets(&links),
+            vec![
+                ("Block Events", None, LinkKind::Wiki),
+                ("Items", Some("Crafting"), LinkKind::Wiki),
+                ("", Some("Usage"), LinkKind::Wiki),
+            ]
+        );
+        assert_eq!(links[1].text, "crafting");
+    }
+
+    #[test]
+    fn test_links_in_code_are_ignored() {
+        let content = "```md\n[a](a.md) [[B]]\n```\n\n`[[C]]` and [d](d.md)";
+        assert_eq!(targets(&extract_links(content)), vec![("d.md", None, LinkKind::Inline)]);
+    }
+
+    #[test]
+    fn test_external_links_are_ignored() {
+        let content = "<https://a.com> [m](mailto:x@y.z) [p](//cdn.com/x) [ok](dir/a:b.md)";
+        assert_eq!(targets(&extract_links(content)), vec![("dir/a:b.md", None, LinkKind::Inline)]);
+    }
+
+    #[test]
+    fn test_slugify() {
+        assert_eq!(slugify("Getting Started!"), "getting-started");
+        assert_eq!(slugify("API: get_item()"), "api-get_item");
+        assert_eq!(slugify("方块 事件"), "方块-事件");
+    }
+}
@@ -0,0 +1,376 @@
+//! MDX 预处理
+//!
+//! 把 MDX 转换为普通 Markdown，再交给 Markdown 解析和切片流程：
+//!
+//! - 删除顶层 ESM `import` / `export` 语句
+//! - 删除 JSX 注释 `{/* ... */}`
+//! - `<Tabs>` / `<TabItem label="..">`：去掉容器，标签页标题转为加粗行
+//! - `<Callout type="..">` / `<Admonition>`：转为加粗的提示标题，保留正文
+//! - `<CodeBlock language="..">`：转为围栏代码块
+//! - 其他组件（首字母大写的标签）：转为 `[Name]` 形式的可读占位符，保留子内容
+//!
+//! 围栏代码块内的内容保持原样，不会被当作 JSX 处理。
+
+use regex::Regex;
+use std::sync::OnceLock;
+
+/// 判断文件路径是否为 MDX
+pub fn is_mdx_path(path: &str) -> bool {
+    std::path::Path::new(path)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("mdx"))
+}
+
+/// 把 MDX 源码转换为 Markdown
+///
+/// # 示例
+///
+/// ```ignore
+/// let md = mdx_to_markdown("import Tabs from '@theme/Tabs';\n\n<Callout type=\"warning\">Careful</Callout>");
+/// assert_eq!(md.trim(), "**Warning:** Careful");
+/// ```
+pub fn mdx_to_markdown(source: &str) -> String {
+    let mut output = String::with_capacity(source.len());
+    let mut prose = String::new();
+
+    for segment in split_fences(source) {
+        match segment {
+            Segment::Prose(text) => prose.push_str(text),
+            Segment::Fence(text) => {
+                output.push_str(&convert_prose(&prose));
+                prose.clear();
+                output.push_str(text);
+            }
+        }
+    }
+    output.push_str(&convert_prose(&prose));
+
+    output
+}
+
+enum Segment<'a> {
+    Prose(&'a str),
+    Fence(&'a str),
+}
+
+/// 按行拆分出围栏代码块（``` 或 ~~~，结束围栏长度不小于开始围栏）
+fn split_fences(source: &str) -> Vec<Segment<'_>> {
+    let mut segments = Vec::new();
+    let mut start = 0;
+    let mut offset = 0;
+    let mut fence: Option<(char, usize)> = None;
+
+    for line in source.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        let indent = line.len() - trimmed.len();
+        let marker = trimmed.chars().next().filter(|c| *c == '`' || *c == '~');
+        let run = marker.map_or(0, |c| trimmed.chars().take_while(|x| *x == c).count());
+
+        match fence {
+            None if indent <= 3 && run >= 3 => {
+                if offset > start {
+                    segments.push(Segment::Prose(&source[start..offset]));
+                }
+                start = offset;
+                fence = marker.map(|c| (c, run));
+            }
+            Some((c, len))
+                if marker == Some(c) && run >= len && trimmed[run..].trim().is_empty() =>
+            {
+                let end = offset + line.len();
+                segments.push(Segment::Fence(&source[start..end]));
+                start = end;
+                fence = None;
+            }
+            _ => {}
+        }
+        offset += line.len();
+    }
+
+    if start < source.len() {
+        // 未闭合的围栏按代码处理，避免把代码当作 JSX 改写
+        if fence.is_some() {
+            segments.push(Segment::Fence(&source[start..]));
+        } else {
+            segments.push(Segment::Prose(&source[start..]));
+        }
+    }
+
+    segments
+}
+
+fn get_comment_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"(?s)\{/\*.*?\*/\}").expect("valid regex"))
+}
+
+fn get_code_block_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"(?s)<CodeBlock(\s[^<>]*)?>(.*?)</CodeBlock\s*>").expect("valid regex")
+    })
+}
+
+fn get_tag_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"<(/?)([A-Z][A-Za-z0-9_.]*)(\s[^<>]*)?>").expect("valid regex")
+    })
+}
+
+fn get_attr_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r#"([A-Za-z_][\w-]*)=(?:"([^"]*)"|'([^']*)'|\{\s*["'`]([^"'`]*)["'`]\s*\})"#)
+            .expect("valid regex")
+    })
+}
+
+/// 转换围栏代码块之外的 MDX 文本
+fn convert_prose(text: &str) -> String {
+    if text.is_empty() {
+        return String::new();
+    }
+
+    let text = strip_esm(text);
+    let text = get_comment_regex().replace_all(&text, "");
+
+    // CodeBlock 先转换为围栏代码块，其内容不再参与组件替换
+    let mut output = String::with_capacity(text.len());
+    let mut last = 0;
+    for caps in get_code_block_regex().captures_iter(&text) {
+        let whole = caps.get(0).expect("match");
+        output.push_str(&convert_tags(&text[last..whole.start()]));
+
+        let attrs = caps.get(1).map_or("", |m| m.as_str());
+        let language = attr(attrs, "language").or_else(|| attr(attrs, "lang")).unwrap_or_default();
+        let code = unwrap_template_literal(caps.get(2).map_or("", |m| m.as_str()));
+        output.push_str(&format!("\n```{}\n{}\n```\n", language, code));
+
+        last = whole.end();
+    }
+    output.push_str(&convert_tags(&text[last..]));
+
+    output
+}
+
+/// 删除顶层 `import` / `export` 语句（支持跨多行的语句）
+fn strip_esm(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut in_statement = false;
+    let mut depth: i32 = 0;
+
+    for line in text.split_inclusive('\n') {
+        if !in_statement {
+            let trimmed = line.trim_start();
+            let is_esm = line.len() == trimmed.len()
+                && (trimmed.starts_with("import ") || trimmed.starts_with("export "));
+            if !is_esm {
+                output.push_str(line);
+                continue;
+            }
+            in_statement = true;
+            depth = 0;
+        }
+
+        depth += bracket_delta(line);
+        // 括号配平且行尾不是续行符号时，语句结束
+        let finished = depth <= 0 && !line.trim_end().ends_with(['{', '(', '[', ',', '=']);
+        if finished {
+            in_statement = false;
+            // 保留换行，避免相邻段落粘连
+            output.push('\n');
+        }
+    }
+
+    output
+}
+
+fn bracket_delta(line: &str) -> i32 {
+    line.chars().fold(0, |depth, c| match c {
+        '{' | '(' | '[' => depth + 1,
+        '}' | ')' | ']' => depth - 1,
+        _ => depth,
+    })
+}
+
+/// 去掉 `{`...`}` 模板字符串包装
+fn unwrap_template_literal(code: &str) -> &str {
+    let trimmed = code.trim();
+    trimmed
+        .strip_prefix('{')
+        .and_then(|s| s.strip_suffix('}'))
+        .map(str::trim)
+        .and_then(|s| {
+            s.strip_prefix('`')
+                .and_then(|s| s.strip_suffix('`'))
+                .or_else(|| s.strip_prefix('"').and_then(|s| s.strip_suffix('"')))
+        })
+        .unwrap_or(trimmed)
+        .trim_matches('\n')
+}
+
+/// 读取 JSX 属性值（字符串或 `{"..."}` 形式）
+fn attr(attrs: &str, name: &str) -> Option<String> {
+    get_attr_regex().captures_iter(attrs).find_map(|caps| {
+        (caps.get(1)?.as_str() == name).then(|| {
+            caps.get(2)
+                .or_else(|| caps.get(3))
+                .or_else(|| caps.get(4))
+                .map_or(String::new(), |m| m.as_str().to_string())
+        })
+    })
+}
+
+/// 首字母大写
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// 替换组件标签
+fn convert_tags(text: &str) -> String {
+    get_tag_regex()
+        .replace_all(text, |caps: &regex::Captures| {
+            let closing = !caps[1].is_empty();
+            let name = &caps[2];
+            let attrs = caps.get(3).map_or("", |m| m.as_str());
+            let base = name.rsplit('.').next().unwrap_or(name);
+
+            if closing {
+                return match base {
+                    "Tabs" | "TabItem" | "Tab" | "Callout" | "Admonition" | "Note" | "Tip"
+                    | "Warning" => "\n".to_string(),
+                    _ => String::new(),
+                };
+            }
+
+            match base {
+                "Tabs" => "\n".to_string(),
+                "TabItem" | "Tab" => {
+                    let label = attr(attrs, "label")
+                        .or_else(|| attr(attrs, "title"))
+                        .or_else(|| attr(attrs, "value"));
+                    match label {
+                        Some(label) if !label.is_empty() => format!("\n**{}**\n\n", label),
+                        _ => "\n".to_string(),
+                    }
+                }
+                "Callout" | "Admonition" | "Note" | "Tip" | "Warning" => {
+                    let kind = attr(attrs, "type")
+                        .filter(|t| !t.is_empty())
+                        .map(|t| capitalize(&t))
+                        .unwrap_or_else(|| if base == "Callout" || base == "Admonition" {
+                            "Note".to_string()
+                        } else {
+                            base.to_string()
+                        });
+                    match attr(attrs, "title").filter(|t| !t.is_empty()) {
+                        Some(title) => format!("\n**{}: {}**\n\n", kind, title),
+                        None => format!("\n**{}:** ", kind),
+                    }
+                }
+                _ => {
+                    let label = attr(attrs, "title")
+                        .or_else(|| attr(attrs, "label"))
+                        .or_else(|| attr(attrs, "alt"))
+                        .filter(|l| !l.is_empty());
+                    match label {
+                        Some(label) => format!("[{}: {}]", name, label),
+                        None => format!("[{}]", name),
+                    }
+                }
+            }
+        })
+        .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_mdx_path() {
+        assert!(is_mdx_path("docs/intro.mdx"));
+        assert!(is_mdx_path("docs/INTRO.MDX"));
+        assert!(!is_mdx_path("docs/intro.md"));
+    }
+
+    #[test]
+    fn test_strips_imports_and_exports() {
+        let source = "import Tabs from '@theme/Tabs';\nimport {\n  A,\n  B,\n} from './x';\nexport const meta = {\n  a: 1,\n};\n\n# Title\n\nText mentioning import of data.";
+        let md = mdx_to_markdown(source);
+
+        assert!(!md.contains("import Tabs"));
+        assert!(!md.contains("from './x'"));
+        assert!(!md.contains("a: 1"));
+        assert!(md.contains("# Title"));
+        assert!(md.contains("Text mentioning import of data."));
+    }
+
+    #[test]
+    fn test_tabs_become_labelled_sections() {
+        let source = "<Tabs groupId=\"lang\">\n  <TabItem value=\"rs\" label=\"Rust\">\n\nUse cargo.\n\n  </TabItem>\n  <TabItem value=\"py\">\n\nUse pip.\n\n  </TabItem>\n</Tabs>";
+        let md = mdx_to_markdown(source);
+
+        assert!(md.contains("**Rust**"));
+        assert!(md.contains("**py**"));
+        assert!(md.contains("Use cargo."));
+        assert!(!md.contains("<Tab"));
+        assert!(!md.contains("</Tab"));
+    }
+
+    #[test]
+    fn test_callout_keeps_text() {
+        let md = mdx_to_markdown("<Callout type=\"warning\">Back up your world first.</Callout>");
+        assert_eq!(md.trim(), "**Warning:** Back up your world first.");
+
+        let md = mdx_to_markdown("<Callout title=\"Heads up\">\nText\n</Callout>");
+        assert!(md.contains("**Note: Heads up**"));
+        assert!(md.contains("Text"));
+    }
+
+    #[test]
+    fn test_code_block_becomes_fence() {
+        let source = "<CodeBlock language=\"ts\">{`const x: Array<Item> = [];`}</CodeBlock>";
+        let md = mdx_to_markdown(source);
+        assert_eq!(md.trim(), "```ts\nconst x: Array<Item> = [];\n```");
+    }
+
+    #[test]
+    fn test_unknown_components_become_placeholders() {
+        let md = mdx_to_markdown("See <Video title=\"Intro\" src=\"a.mp4\" /> and <Diagram />.\n\n<Card>\nInside\n</Card>");
+        assert!(md.contains("[Video: Intro]"));
+        assert!(md.contains("[Diagram]"));
+        assert!(md.contains("[Card]"));
+        assert!(md.contains("Inside"));
+        assert!(!md.contains("</Card>"));
+    }
+
+    #[test]
+    fn test_multiline_opening_tag() {
+        let md = mdx_to_markdown("<TabItem\n  value=\"a\"\n  label=\"Alpha\"\n>\nBody\n</TabItem>");
+        assert!(md.contains("**Alpha**"));
+        assert!(!md.contains("value="));
+    }
+
+    #[test]
+    fn test_fenced_code_is_untouched() {
+        let source = "```tsx\nimport React from 'react';\nexport const A = () => <Callout />;\n```\n\n<Callout>Hi</Callout>";
+        let md = mdx_to_markdown(source);
+
+        assert!(md.contains("import React from 'react';"));
+        assert!(md.contains("<Callout />;"));
+        assert!(md.contains("**Note:** Hi"));
+    }
+
+    #[test]
+    fn test_jsx_comments_removed_and_html_kept() {
+        let md = mdx_to_markdown("Text {/* hidden */} here <br/> <div>kept</div>");
+        assert_eq!(md, "Text  here <br/> <div>kept</div>");
+    }
+}
@@ -1,11 +1,28 @@
 use anyhow::Result;
-use pulldown_cmark::{Event, HeadingLevel, Parser, Tag};
+use pulldown_cmark::{CodeBlockKind, Event, HeadingLevel, Parser, Tag};
 use regex::Regex;
 use std::collections::HashSet;
 use std::fs;
 use std::path::Path;
 use std::sync::OnceLock;
 
+pub use crate::kernel::types::{citation, PageRange};
+pub use asciidoc::{asciidoc_to_markdown, AsciiDocParser};
+pub use code_chunker::{chunk_source, chunk_source_file, is_source_path, SourceLanguage};
+pub use document::{
+    document_parsers, is_document_path, parse_document, parser_for_path, DocumentParser,
+    MarkdownParser, PlainTextParser,
+};
+pub use front_matter::{split_front_matter, DocMetadata, FrontMatter};
+pub use links::{
+    extract_links, extract_links_with_definitions, link_definitions, slugify, DocLink, LinkKind,
+};
+pub use html::{html_to_markdown, HtmlParser};
+pub use mdx::{is_mdx_path, mdx_to_markdown};
+pub use pdf::{parse_pdf, PdfParser};
+pub use rst::{rst_to_markdown, RstParser};
+pub use slicer::{estimate_tokens, slice_hierarchical, ChunkSize, SliceConfig};
+
 /// 智能提取内容摘要
 ///
 /// 提取内容的摘要
@@ -177,6 +194,104 @@ pub fn extract_code_block_keywords(content: &str) -> Vec<String> {
     result
 }
 
+/// 文档中的一个围栏代码块
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CodeBlock {
+    /// 规范化后的语言名（如 `rust`、`typescript`），围栏未标注语言时为 `None`
+    pub language: Option<String>,
+    /// 代码内容（不含围栏）
+    pub content: String,
+    /// 代码中定义或引用的标识符（由 `extract_identifiers_from_code` 提取，去重排序）
+    pub symbols: Vec<String>,
+}
+
+/// 提取 Markdown 内容中的所有围栏代码块
+///
+/// 语言取自围栏信息串的第一个词（`rust,ignore`、`ts title="a.ts"` 也能识别），
+/// 并把常见别名规范化（`rs` → `rust`、`ts` → `typescript`、`py` → `python` 等）。
+/// 缩进代码块没有语言信息，不会被提取。
+pub fn extract_code_blocks(content: &str) -> Vec<CodeBlock> {
+    let mut blocks = Vec::new();
+    let mut current: Option<(Option<String>, String)> = None;
+
+    for event in Parser::new(content) {
+        match event {
+            Event::Start(Tag::CodeBlock(CodeBlockKind::Fenced(info))) => {
+                current = Some((normalize_language(&info), String::new()));
+            }
+            Event::End(Tag::CodeBlock(CodeBlockKind::Fenced(_))) => {
+                if let Some((language, code)) = current.take() {
+                    if code.trim().is_empty() {
+                        continue;
+                    }
+                    let symbols = code_symbols(&code);
+                    blocks.push(CodeBlock {
+                        language,
+                        content: code.trim_end_matches('\n').to_string(),
+                        symbols,
+                    });
+                }
+            }
+            Event::Text(text) => {
+                if let Some((_, code)) = &mut current {
+                    code.push_str(&text);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    blocks
+}
+
+/// 规范化围栏信息串中的语言名
+fn normalize_language(info: &str) -> Option<String> {
+    let word = info
+        .split(|c: char| c.is_whitespace() || c == ',' || c == '{')
+        .next()
+        .unwrap_or("")
+        .trim()
+        .to_ascii_lowercase();
+    if word.is_empty() {
+        return None;
+    }
+
+    let language = match word.as_str() {
+        "rs" => "rust",
+        "ts" => "typescript",
+        "js" => "javascript",
+        "py" | "python3" => "python",
+        "sh" | "shell" | "zsh" => "bash",
+        "yml" => "yaml",
+        "golang" => "go",
+        "c++" | "cc" => "cpp",
+        "cs" | "c#" => "csharp",
+        other => other,
+    };
+    Some(language.to_string())
+}
+
+/// 提取一段代码中的标识符（与 `extract_code_block_keywords` 使用相同的过滤规则）
+fn code_symbols(code: &str) -> Vec<String> {
+    let mut identifiers: HashSet<&str> = HashSet::new();
+    extract_identifiers_from_code(
+        code,
+        get_function_regex(),
+        get_class_regex(),
+        get_camelcase_regex(),
+        get_snakecase_regex(),
+        &mut identifiers,
+    );
+
+    let mut symbols: Vec<String> = identifiers
+        .into_iter()
+        .filter(|kw| !is_language_keyword(kw) && kw.len() >= 3)
+        .map(str::to_string)
+        .collect();
+    symbols.sort();
+    symbols
+}
+
 /// 从代码块内容中提取标识符（零拷贝版本）
 fn extract_identifiers_from_code<'a>(
     code: &'a str,
@@ -476,6 +591,10 @@ pub struct ParsedDoc {
     pub summary: String,
     pub content: String,
     pub sections: Vec<SlicedSection>, // 拥有所有权的切片
+    /// front-matter 元数据（没有 front-matter 时为空）
+    pub metadata: DocMetadata,
+    /// 整篇文档中指向其他文档或章节的链接
+    pub links: Vec<DocLink>,
 }
 
 /// 拥有所有权的文档切片（用于 ParsedDoc）
@@ -488,6 +607,21 @@ pub struct SlicedSection {
     pub content: String,
     pub parent_doc_title: String,
     pub summary: String,
+    /// 完整标题路径（面包屑），如 `["Doc", "Section", "Subsection"]`
+    pub heading_path: Vec<String>,
+    /// 切片内的围栏代码块
+    pub code_blocks: Vec<CodeBlock>,
+    /// 切片内指向其他文档或章节的链接（引用链接按整篇文档的定义解析）
+    pub links: Vec<DocLink>,
+    /// 切片所在的页码范围（PDF 等分页文档提供，其他格式为 `None`）
+    pub pages: Option<PageRange>,
+}
+
+impl SlicedSection {
+    /// 引用位置：文件名加页码（如 `manual.pdf p. 42`），没有页码时只有文件名
+    pub fn citation(&self, file_path: &str) -> String {
+        citation(file_path, self.pages)
+    }
 }
 
 /// 表示一个按 H2 标题切片后的文档片段（零拷贝版本）
@@ -498,6 +632,8 @@ pub struct SlicedSection {
 /// * `content` - 该 H2 下的完整内容（**不包含 H2 标题本身**，从标题之后到下一个 H2 之前，借用切片）
 /// * `parent_doc_title` - 父文档的 H1 标题（借用切片）
 /// * `summary` - 切片摘要（智能提取首段或代码块，拥有所有权）
+/// * `heading_path` - 从父文档标题到当前标题的完整路径（面包屑）
+/// * `code_blocks` - 切片内的围栏代码块（语言、内容和标识符）
 ///
 /// # 零拷贝设计
 ///
@@ -519,14 +655,60 @@ pub struct SlicedDoc<'a> {
     pub content: &'a str,
     pub parent_doc_title: &'a str,
     pub summary: String,
+    pub heading_path: Vec<String>,
+    pub code_blocks: Vec<CodeBlock>,
 }
 
+/// 解析 Markdown（或 MDX）文件，使用默认的层级切片配置（见 [`SliceConfig::default`]）
 pub fn parse_markdown(file_path: &str) -> Result<ParsedDoc> {
+    parse_markdown_with_config(file_path, &SliceConfig::default())
+}
+
+/// 解析 Markdown（或 MDX）文件，按给定配置切片
+///
+/// `.mdx` 文件会先经过 [`mdx_to_markdown`] 预处理。
+pub fn parse_markdown_with_config(file_path: &str, config: &SliceConfig) -> Result<ParsedDoc> {
     if !Path::new(file_path).exists() {
         anyhow::bail!("File not found: {}", file_path);
     }
 
-    let content = fs::read_to_string(file_path)?;
+    let raw = fs::read_to_string(file_path)?;
+    Ok(parse_markdown_str(file_path, &raw, config))
+}
+
+/// 解析 Markdown（或 MDX）文本，`file_path` 用于判断 MDX 和生成默认标题
+pub fn parse_markdown_str(file_path: &str, raw: &str, config: &SliceConfig) -> ParsedDoc {
+    // 拆出 front-matter，避免 `title:`、`tags:` 等行污染摘要和索引
+    let (metadata, content) = match split_front_matter(raw) {
+        Some(front_matter) => {
+            let metadata = front_matter.metadata.unwrap_or_else(|e| {
+                tracing::warn!(file = file_path, error = %e, "Ignoring malformed front-matter");
+                DocMetadata::default()
+            });
+            (metadata, front_matter.body.to_string())
+        }
+        None => (DocMetadata::default(), raw.to_string()),
+    };
+
+    // MDX：删除 import/export，把 JSX 组件转换为 Markdown
+    let content = if is_mdx_path(file_path) {
+        mdx_to_markdown(&content)
+    } else {
+        content
+    };
+
+    build_parsed_doc(file_path, content, metadata, config)
+}
+
+/// 由 Markdown 内容构建 `ParsedDoc`：提取标题和摘要、层级切片、提取链接
+///
+/// 其他格式的解析器先把源文件转换为 Markdown，再调用这里，保证切片行为一致。
+pub(crate) fn build_parsed_doc(
+    file_path: &str,
+    content: String,
+    metadata: DocMetadata,
+    config: &SliceConfig,
+) -> ParsedDoc {
     let parser = Parser::new(&content);
 
     let mut title = String::new();
@@ -547,6 +729,11 @@ pub fn parse_markdown(file_path: &str) -> Result<ParsedDoc> {
         }
     }
 
+    // front-matter 中的标题优先于 H1
+    if let Some(front_matter_title) = &metadata.title {
+        title = front_matter_title.clone();
+    }
+
     if title.is_empty() {
         title = Path::new(file_path)
             .file_stem()
@@ -558,31 +745,41 @@ pub fn parse_markdown(file_path: &str) -> Result<ParsedDoc> {
     // 性能优化：先 trim，再调用 extract_summary，避免重复 trim
     // 这样可以确保 extract_summary 接收的是已清理的内容，内部无需再次 trim
     let content_cleaned = content.trim().to_string();
-    let summary = extract_summary(&content_cleaned);
+    let summary = match &metadata.description {
+        Some(description) => description.clone(),
+        None => extract_summary(&content_cleaned),
+    };
 
     // 调用零拷贝切片函数，然后将结果转换为拥有所有权的版本
     // 性能考虑：这里需要复制数据，但权衡是简化了生命周期管理
-    let zero_copy_slices = slice_by_headers(&content_cleaned, &title);
+    let zero_copy_slices = slice_hierarchical(&content_cleaned, &title, config);
+    let definitions = link_definitions(&content_cleaned);
+    let links = extract_links_with_definitions(&content_cleaned, &definitions);
     let sections: Vec<SlicedSection> = zero_copy_slices
         .into_iter()
         .map(|slice| SlicedSection {
+            links: extract_links_with_definitions(slice.content, &definitions),
             section_title: slice.section_title,
             content: slice.content.to_string(), // 借用 → 拥有所有权
             parent_doc_title: slice.parent_doc_title.to_string(),
             summary: slice.summary, // 已经拥有所有权，直接移动
+            heading_path: slice.heading_path,
+            code_blocks: slice.code_blocks,
+            pages: None,
         })
         .collect();
 
-    Ok(ParsedDoc {
+    ParsedDoc {
         path: file_path.to_string(),
         title,
         summary,
-        // 存储原始内容，不进行 trim
-        // 这样 ParsedDoc.content 保存的是完整的原始文档
+        // 存储原始内容（已去除 front-matter），不进行 trim
         // trim 仅在生成 summary 和 sections 时使用
         content,
         sections,
-    })
+        metadata,
+        links,
+    }
 }
 
 /// 根据 H2 标题将 Markdown 内容切片为多个片段
@@ -723,11 +920,15 @@ pub fn slice_by_headers<'a>(content: &'a str, parent_title: &'a str) -> Vec<Slic
         // 性能优化：传入已 trim 的内容，避免 extract_summary 内部重复 trim
         let summary = extract_summary(slice_content_trimmed);
 
+        let heading_path = vec![parent_title.to_string(), section_title.clone()];
+
         slices.push(SlicedDoc {
             section_title,
             content: slice_content,
             parent_doc_title: parent_title,
             summary,
+            heading_path,
+            code_blocks: extract_code_blocks(slice_content),
         });
     }
 
@@ -845,6 +1046,68 @@ mod tests {
         assert!(result.is_ok());
         let doc = result.unwrap();
         assert_eq!(doc.title, "sample_without_h1");
+        assert!(doc.metadata.is_empty());
+    }
+
+    #[test]
+    fn test_extract_code_blocks() {
+        let content = "Intro.\n\n```rs,ignore\npub fn create_item() -> Item {\n    Item::new()\n}\n```\n\n```\nplain text\n```\n\n    indented code\n\n```ts title=\"a.ts\"\nclass Widget {}\n```";
+        let blocks = extract_code_blocks(content);
+
+        assert_eq!(blocks.len(), 3);
+        assert_eq!(blocks[0].language.as_deref(), Some("rust"));
+        assert_eq!(blocks[0].content, "pub fn create_item() -> Item {\n    Item::new()\n}");
+        assert!(blocks[0].symbols.contains(&"create_item".to_string()));
+        assert!(blocks[0].symbols.contains(&"Item".to_string()));
+        assert_eq!(blocks[1].language, None);
+        assert_eq!(blocks[2].language.as_deref(), Some("typescript"));
+        assert!(blocks[2].symbols.contains(&"Widget".to_string()));
+    }
+
+    #[test]
+    fn test_sections_carry_code_blocks() {
+        let content = "## Example\n\nText.\n\n```python\ndef handler(event):\n    pass\n```";
+        let slices = slice_by_headers(content, "Doc");
+        assert_eq!(slices[0].code_blocks.len(), 1);
+        assert_eq!(slices[0].code_blocks[0].language.as_deref(), Some("python"));
+        assert!(slices[0].code_blocks[0].symbols.contains(&"handler".to_string()));
+
+        let slices = slice_hierarchical(content, "Doc", &SliceConfig::default());
+        assert_eq!(slices[0].code_blocks, extract_code_blocks(content));
+    }
+
+    #[test]
+    fn test_parse_mdx() {
+        let doc = parse_markdown("test_data/sample.mdx").unwrap();
+
+        assert_eq!(doc.title, "MDX Guide");
+        assert!(!doc.content.contains("import "));
+        assert!(!doc.content.contains("<TabItem"));
+
+        let install = doc
+            .sections
+            .iter()
+            .find(|s| s.section_title == "Install")
+            .expect("Install section should exist");
+        assert!(install.content.contains("**Cargo**"));
+        assert!(install.content.contains("Run the installer."));
+        assert!(install.content.contains("**Tip:** Restart afterwards."));
+    }
+
+    #[test]
+    fn test_parse_with_front_matter() {
+        let doc = parse_markdown("test_data/sample_with_front_matter.md").unwrap();
+
+        // front-matter 标题覆盖 H1
+        assert_eq!(doc.title, "Front Matter Title");
+        assert_eq!(doc.metadata.tags, vec!["blocks", "events"]);
+        assert_eq!(doc.metadata.version.as_deref(), Some("1.20"));
+
+        // front-matter 不进入正文、摘要和切片
+        assert!(!doc.content.contains("tags:"));
+        assert!(!doc.summary.contains("title:"));
+        assert!(doc.sections.iter().all(|s| !s.content.contains("version:")));
+        assert_eq!(doc.sections[0].heading_path, vec!["Front Matter Title"]);
     }
 
     // Summary extraction tests
@@ -964,6 +1227,8 @@ Content for section three.
         assert!(slices[0].content.contains("Content for section one."));
         assert!(slices[1].content.contains("Content for section two."));
         assert!(slices[2].content.contains("Content for section three."));
+
+        assert_eq!(slices[0].heading_path, vec!["Parent Doc", "Section One"]);
     }
 
     #[test]
@@ -1300,4 +1565,16 @@ fn create_item() {
     }
 }
 
+pub mod asciidoc;
+pub mod code_chunker;
+pub mod document;
+pub mod front_matter;
+pub mod html;
+pub mod ingest;
 pub mod ipc;
+pub mod links;
+pub mod mdx;
+pub mod pdf;
+pub mod protocol;
+pub mod rst;
+pub mod slicer;
@@ -0,0 +1,648 @@
+//! PDF 文本提取
+//!
+//! 逐页提取 PDF 中的文本，重建标题后转换为 Markdown，再交给层级切片流程：
+//!
+//! - 有书签（大纲）时：书签作为标题，层级取书签深度（一级书签为 `##`），
+//!   并替换页面中与书签同名的文本行
+//! - 没有书签时：按字号重建标题，比正文字号大的短行视为标题，字号越大层级越高
+//! - 每个切片记录所在页码（[`SlicedSection::pages`]），检索结果可以引用为
+//!   `manual.pdf p. 42`
+//!
+//! 文档信息字典中的 `Title` 作为文档标题。扫描件（没有文本层）提取不到正文。
+
+use super::document::{escape_markdown_line, DocumentParser};
+use super::{build_parsed_doc, DocMetadata, PageRange, ParsedDoc, SliceConfig, SlicedSection};
+use anyhow::{anyhow, Context, Result};
+use pdf_extract::{Document, MediaBox, Object, OutputDev, OutputError, Transform};
+use std::collections::HashMap;
+
+/// 字号至少是正文字号的这个倍数才可能是标题
+const HEADING_SIZE_RATIO: f64 = 1.15;
+
+/// 标题行的最大字符数（更长的行视为正文）
+const MAX_HEADING_CHARS: usize = 120;
+
+/// PDF 解析器
+pub struct PdfParser;
+
+impl DocumentParser for PdfParser {
+    fn name(&self) -> &'static str {
+        "pdf"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["pdf"]
+    }
+
+    fn parse(&self, file_path: &str, _source: &str, _config: &SliceConfig) -> Result<ParsedDoc> {
+        anyhow::bail!(
+            "PDF is a binary format and must be parsed from bytes: {}",
+            file_path
+        )
+    }
+
+    fn parse_file(&self, file_path: &str, config: &SliceConfig) -> Result<ParsedDoc> {
+        let bytes =
+            std::fs::read(file_path).with_context(|| format!("Failed to read {}", file_path))?;
+        parse_pdf(file_path, &bytes, config)
+    }
+}
+
+/// 解析内存中的 PDF
+///
+/// # Errors
+///
+/// PDF 无法加载（损坏、加密）或文本提取失败时返回错误
+pub fn parse_pdf(file_path: &str, bytes: &[u8], config: &SliceConfig) -> Result<ParsedDoc> {
+    let document =
+        Document::load_mem(bytes).with_context(|| format!("Failed to load PDF {}", file_path))?;
+
+    let mut collector = PageCollector::default();
+    pdf_extract::output_doc(&document, &mut collector)
+        .map_err(|e| anyhow!("Failed to extract text from {}: {}", file_path, e))?;
+    let pages = collector.finish();
+
+    let outline = outline_entries(&document);
+    let rendered: Vec<(u32, String)> = if outline.is_empty() {
+        render_by_font_size(&pages)
+    } else {
+        render_by_outline(&pages, &outline)
+    };
+
+    let content = rendered
+        .iter()
+        .map(|(_, markdown)| markdown.as_str())
+        .filter(|markdown| !markdown.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    let metadata = DocMetadata {
+        title: info_title(&document),
+        ..DocMetadata::default()
+    };
+
+    let mut doc = build_parsed_doc(file_path, content, metadata, config);
+    assign_pages(&mut doc.sections, &rendered);
+    Ok(doc)
+}
+
+/// 页面中的一行文本
+#[derive(Debug, Clone, PartialEq)]
+struct TextLine {
+    text: String,
+    /// 行内最大字号（已乘以文本矩阵的缩放）
+    font_size: f64,
+    /// 与上一行之间有段落间距
+    paragraph_break: bool,
+}
+
+#[derive(Debug, Default)]
+struct PdfPage {
+    number: u32,
+    lines: Vec<TextLine>,
+}
+
+/// 收集每页文本行的输出设备
+///
+/// 换行和空格的判断与 `pdf_extract::PlainTextOutput` 一致：纵向移动超过半个字号换行，
+/// 超过 1.5 个字号视为段落间距；单词开头与上一个字符的间距超过 0.1 个字号补空格。
+#[derive(Default)]
+struct PageCollector {
+    pages: Vec<PdfPage>,
+    line: String,
+    line_size: f64,
+    paragraph_break: bool,
+    last_y: Option<f64>,
+    last_end: f64,
+    word_start: bool,
+}
+
+impl PageCollector {
+    fn flush_line(&mut self) {
+        let text = self.line.split_whitespace().collect::<Vec<_>>().join(" ");
+        if !text.is_empty() {
+            if let Some(page) = self.pages.last_mut() {
+                page.lines.push(TextLine {
+                    text,
+                    font_size: self.line_size,
+                    paragraph_break: self.paragraph_break,
+                });
+            }
+        }
+        self.line.clear();
+        self.line_size = 0.0;
+        self.paragraph_break = false;
+    }
+
+    fn finish(mut self) -> Vec<PdfPage> {
+        self.flush_line();
+        self.pages
+    }
+}
+
+impl OutputDev for PageCollector {
+    fn begin_page(
+        &mut self,
+        page_num: u32,
+        _media_box: &MediaBox,
+        _art_box: Option<(f64, f64, f64, f64)>,
+    ) -> Result<(), OutputError> {
+        self.flush_line();
+        self.pages.push(PdfPage {
+            number: page_num,
+            lines: Vec::new(),
+        });
+        self.last_y = None;
+        Ok(())
+    }
+
+    fn end_page(&mut self) -> Result<(), OutputError> {
+        self.flush_line();
+        Ok(())
+    }
+
+    fn output_character(
+        &mut self,
+        trm: &Transform,
+        width: f64,
+        _spacing: f64,
+        font_size: f64,
+        char: &str,
+    ) -> Result<(), OutputError> {
+        // 与面积相同的正方形边长，对旋转和非等比缩放的文本也成立
+        let size = (font_size * (trm.m11 + trm.m21) * font_size * (trm.m12 + trm.m22))
+            .abs()
+            .sqrt();
+        let (x, y) = (trm.m31, trm.m32);
+
+        if let Some(last_y) = self.last_y {
+            let moved = (y - last_y).abs();
+            if moved > size * 0.5 {
+                self.flush_line();
+                self.paragraph_break = moved > size * 1.5;
+            } else if self.word_start && x > self.last_end + size * 0.1 {
+                self.line.push(' ');
+            }
+        }
+
+        self.line.push_str(char);
+        self.line_size = self.line_size.max(size);
+        self.last_y = Some(y);
+        self.last_end = x + width * size;
+        self.word_start = false;
+        Ok(())
+    }
+
+    fn begin_word(&mut self) -> Result<(), OutputError> {
+        self.word_start = true;
+        Ok(())
+    }
+
+    fn end_word(&mut self) -> Result<(), OutputError> {
+        Ok(())
+    }
+
+    fn end_line(&mut self) -> Result<(), OutputError> {
+        Ok(())
+    }
+}
+
+/// 书签条目
+#[derive(Debug, Clone, PartialEq)]
+struct OutlineEntry {
+    /// 深度（一级书签为 1）
+    level: usize,
+    title: String,
+    page: u32,
+}
+
+/// 读取书签（没有书签或书签损坏时为空）
+fn outline_entries(document: &Document) -> Vec<OutlineEntry> {
+    let Ok(toc) = document.get_toc() else {
+        return Vec::new();
+    };
+    toc.toc
+        .into_iter()
+        .filter(|entry| !entry.title.trim().is_empty())
+        .map(|entry| OutlineEntry {
+            level: entry.level.max(1),
+            title: entry.title.trim().to_string(),
+            page: entry.page as u32,
+        })
+        .collect()
+}
+
+/// 文档信息字典中的标题
+fn info_title(document: &Document) -> Option<String> {
+    let info = document.trailer.get(b"Info").ok()?;
+    let info = match info {
+        Object::Reference(id) => document.get_dictionary(*id).ok()?,
+        Object::Dictionary(dict) => dict,
+        _ => return None,
+    };
+    let title = match info.get(b"Title").ok()? {
+        Object::String(bytes, _) => decode_text_string(bytes),
+        _ => return None,
+    };
+    let title = title.trim();
+    (!title.is_empty()).then(|| title.to_string())
+}
+
+/// 解码 PDF 文本字符串（带 BOM 的 UTF-16BE，否则按 Latin-1/PDFDocEncoding 近似处理）
+fn decode_text_string(bytes: &[u8]) -> String {
+    match bytes {
+        [0xfe, 0xff, rest @ ..] => {
+            let units: Vec<u16> = rest
+                .chunks_exact(2)
+                .map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+                .collect();
+            String::from_utf16_lossy(&units)
+        }
+        _ => bytes.iter().map(|&b| b as char).collect(),
+    }
+}
+
+fn heading_line(level: usize, title: &str) -> String {
+    format!("{} {}", "#".repeat(level.clamp(2, 6)), title)
+}
+
+fn push_body_line(markdown: &mut Vec<String>, line: &TextLine) {
+    if line.paragraph_break && markdown.last().is_some_and(|l| !l.is_empty()) {
+        markdown.push(String::new());
+    }
+    markdown.push(escape_markdown_line(&line.text));
+}
+
+fn push_heading(markdown: &mut Vec<String>, heading: String) {
+    if markdown.last().is_some_and(|l| !l.is_empty()) {
+        markdown.push(String::new());
+    }
+    markdown.push(heading);
+    markdown.push(String::new());
+}
+
+/// 比较标题时忽略大小写、空白和标点
+fn normalize_heading(text: &str) -> String {
+    text.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// 按书签重建标题：书签替换页面中同名的文本行，找不到同名行时插入到上一个书签之后
+fn render_by_outline(pages: &[PdfPage], outline: &[OutlineEntry]) -> Vec<(u32, String)> {
+    let mut by_page: HashMap<u32, Vec<&OutlineEntry>> = HashMap::new();
+    for entry in outline {
+        by_page.entry(entry.page).or_default().push(entry);
+    }
+
+    pages
+        .iter()
+        .map(|page| {
+            // (插入位置, 是否替换该行, 书签)
+            let mut placements: Vec<(usize, bool, &OutlineEntry)> = Vec::new();
+            let mut cursor = 0;
+            for entry in by_page.get(&page.number).into_iter().flatten() {
+                let title = normalize_heading(&entry.title);
+                match page.lines[cursor..]
+                    .iter()
+                    .position(|line| normalize_heading(&line.text) == title)
+                {
+                    Some(offset) => {
+                        placements.push((cursor + offset, true, entry));
+                        cursor += offset + 1;
+                    }
+                    None => placements.push((cursor, false, entry)),
+                }
+            }
+
+            let mut markdown = Vec::new();
+            let mut placements = placements.into_iter().peekable();
+            for index in 0..=page.lines.len() {
+                let mut replaced = false;
+                while let Some((_, replaces, entry)) = placements.next_if(|(at, _, _)| *at == index)
+                {
+                    push_heading(&mut markdown, heading_line(entry.level + 1, &entry.title));
+                    replaced |= replaces;
+                }
+                if let Some(line) = page.lines.get(index).filter(|_| !replaced) {
+                    push_body_line(&mut markdown, line);
+                }
+            }
+            (page.number, markdown.join("\n").trim().to_string())
+        })
+        .collect()
+}
+
+/// 字号按 0.5pt 取整，避免浮点误差产生过多层级
+fn size_key(size: f64) -> u32 {
+    (size * 2.0).round() as u32
+}
+
+/// 按字号重建标题：正文字号为字符数最多的字号，更大的短行是标题，字号越大层级越高
+fn render_by_font_size(pages: &[PdfPage]) -> Vec<(u32, String)> {
+    let mut chars_by_size: HashMap<u32, usize> = HashMap::new();
+    for line in pages.iter().flat_map(|page| &page.lines) {
+        *chars_by_size.entry(size_key(line.font_size)).or_default() += line.text.chars().count();
+    }
+    let body_size = chars_by_size
+        .iter()
+        .max_by_key(|(size, chars)| (**chars, std::cmp::Reverse(**size)))
+        .map_or(0, |(size, _)| *size);
+
+    let is_heading = |line: &TextLine| {
+        f64::from(size_key(line.font_size)) >= f64::from(body_size) * HEADING_SIZE_RATIO
+            && line.text.chars().count() <= MAX_HEADING_CHARS
+    };
+
+    let mut heading_sizes: Vec<u32> = pages
+        .iter()
+        .flat_map(|page| &page.lines)
+        .filter(|line| is_heading(line))
+        .map(|line| size_key(line.font_size))
+        .collect();
+    heading_sizes.sort_unstable_by(|a, b| b.cmp(a));
+    heading_sizes.dedup();
+
+    pages
+        .iter()
+        .map(|page| {
+            let mut markdown = Vec::new();
+            for line in &page.lines {
+                if is_heading(line) {
+                    let rank = heading_sizes
+                        .iter()
+                        .position(|size| *size == size_key(line.font_size))
+                        .unwrap_or(0);
+                    push_heading(&mut markdown, heading_line(rank + 2, &line.text));
+                } else {
+                    push_body_line(&mut markdown, line);
+                }
+            }
+            (page.number, markdown.join("\n").trim().to_string())
+        })
+        .collect()
+}
+
+/// 根据切片内容在各页 Markdown 中的位置推算页码范围
+///
+/// 切片按文档顺序排列，所以从上一个切片的起始页开始向后查找。
+fn assign_pages(sections: &mut [SlicedSection], rendered: &[(u32, String)]) {
+    let mut cursor = 0;
+    for section in sections.iter_mut() {
+        let probes: Vec<&str> = section
+            .content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .collect();
+        let first = probes
+            .first()
+            .copied()
+            .unwrap_or(section.section_title.as_str());
+        let last = probes.last().copied().unwrap_or(first);
+
+        let find = |from: usize, probe: &str| {
+            rendered[from..]
+                .iter()
+                .position(|(_, markdown)| markdown.contains(probe))
+                .map(|offset| from + offset)
+        };
+        let Some(start) = find(cursor, first) else {
+            continue;
+        };
+        let end = find(start, last).unwrap_or(start);
+
+        section.pages = Some(PageRange {
+            start: rendered[start].0,
+            end: rendered[end].0,
+        });
+        cursor = start;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pdf_extract::content::{Content, Operation};
+    use pdf_extract::{dictionary, Bookmark, Stream};
+
+    /// 一行文本：(字号, 文本)；空文本表示额外的段落间距
+    type Line<'a> = (f64, &'a str);
+
+    /// 生成测试用 PDF：每页若干行 Helvetica 文本，书签为 (标题, 页序号, 父书签序号)
+    fn build_pdf(
+        title: Option<&str>,
+        pages: &[&[Line]],
+        bookmarks: &[(&str, usize, Option<usize>)],
+    ) -> Vec<u8> {
+        let mut doc = Document::with_version("1.5");
+        let pages_id = doc.new_object_id();
+        let font_id = doc.add_object(dictionary! {
+            "Type" => "Font",
+            "Subtype" => "Type1",
+            "BaseFont" => "Helvetica",
+        });
+        let resources_id = doc.add_object(dictionary! {
+            "Font" => dictionary! { "F1" => font_id },
+        });
+
+        let mut page_ids = Vec::new();
+        for lines in pages {
+            let mut operations = Vec::new();
+            let mut y = 800.0;
+            for (size, text) in lines.iter() {
+                y -= size * 1.2;
+                if text.is_empty() {
+                    continue;
+                }
+                operations.extend([
+                    Operation::new("BT", vec![]),
+                    Operation::new("Tf", vec!["F1".into(), (*size).into()]),
+                    Operation::new("Td", vec![50.into(), y.into()]),
+                    Operation::new("Tj", vec![Object::string_literal(*text)]),
+                    Operation::new("ET", vec![]),
+                ]);
+            }
+            let content = Content { operations };
+            let content_id = doc.add_object(Stream::new(dictionary! {}, content.encode().unwrap()));
+            page_ids.push(doc.add_object(dictionary! {
+                "Type" => "Page",
+                "Parent" => pages_id,
+                "Contents" => content_id,
+                "MediaBox" => vec![0.into(), 0.into(), 595.into(), 842.into()],
+            }));
+        }
+
+        let kids: Vec<Object> = page_ids.iter().map(|id| (*id).into()).collect();
+        doc.objects.insert(
+            pages_id,
+            Object::Dictionary(dictionary! {
+                "Type" => "Pages",
+                "Kids" => kids,
+                "Count" => page_ids.len() as i64,
+                "Resources" => resources_id,
+            }),
+        );
+
+        let mut ids = Vec::new();
+        for (title, page, parent) in bookmarks {
+            let bookmark = Bookmark::new(title.to_string(), [0.0; 3], 0, page_ids[*page]);
+            ids.push(doc.add_bookmark(bookmark, parent.map(|p| ids[p])));
+        }
+
+        let mut catalog = dictionary! { "Type" => "Catalog", "Pages" => pages_id };
+        if let Some(outline_id) = doc.build_outline() {
+            catalog.set("Outlines", outline_id);
+        }
+        let catalog_id = doc.add_object(catalog);
+        doc.trailer.set("Root", catalog_id);
+        if let Some(title) = title {
+            let info_id = doc.add_object(dictionary! { "Title" => Object::string_literal(title) });
+            doc.trailer.set("Info", info_id);
+        }
+
+        let mut bytes = Vec::new();
+        doc.save_to(&mut bytes).unwrap();
+        bytes
+    }
+
+    fn section<'a>(doc: &'a ParsedDoc, title: &str) -> &'a SlicedSection {
+        doc.sections
+            .iter()
+            .find(|s| s.section_title == title)
+            .unwrap_or_else(|| panic!("missing section {title}: {:#?}", doc.sections))
+    }
+
+    #[test]
+    fn test_extracts_text_per_page() {
+        let bytes = build_pdf(
+            None,
+            &[
+                &[(11.0, "First page body."), (11.0, "Still the first page.")],
+                &[(11.0, "Second page body.")],
+            ],
+            &[],
+        );
+        let mut collector = PageCollector::default();
+        let document = Document::load_mem(&bytes).unwrap();
+        pdf_extract::output_doc(&document, &mut collector).unwrap();
+        let pages = collector.finish();
+
+        assert_eq!(pages.len(), 2);
+        assert_eq!(pages[0].number, 1);
+        let texts: Vec<&str> = pages[0].lines.iter().map(|l| l.text.as_str()).collect();
+        assert_eq!(texts, vec!["First page body.", "Still the first page."]);
+        assert_eq!(pages[1].lines[0].text, "Second page body.");
+        assert!((pages[0].lines[0].font_size - 11.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_slices_by_outline_with_page_numbers() {
+        let bytes = build_pdf(
+            Some("Vendor SDK Manual"),
+            &[
+                &[(18.0, "Installation"), (11.0, "Run the installer first.")],
+                &[(11.0, "The installer needs admin rights.")],
+                &[
+                    (18.0, "Configuration"),
+                    (11.0, "Edit sdk.toml to configure the SDK."),
+                ],
+                &[(14.0, "Logging"), (11.0, "Set log_level to debug.")],
+            ],
+            &[
+                ("Installation", 0, None),
+                ("Configuration", 2, None),
+                ("Logging", 3, Some(1)),
+            ],
+        );
+        let doc = parse_pdf("vendor/manual.pdf", &bytes, &SliceConfig::default()).unwrap();
+
+        assert_eq!(doc.title, "Vendor SDK Manual");
+        assert!(doc.content.contains("## Installation"));
+        assert!(doc.content.contains("### Logging"));
+        assert!(
+            !doc.content.contains("\nInstallation\n"),
+            "outline titles replace body lines"
+        );
+
+        let installation = section(&doc, "Installation");
+        assert!(installation.content.contains("admin rights"));
+        assert_eq!(installation.pages, Some(PageRange { start: 1, end: 2 }));
+        assert_eq!(installation.citation(&doc.path), "manual.pdf pp. 1-2");
+
+        let logging = section(&doc, "Logging");
+        assert_eq!(
+            logging.heading_path,
+            vec!["Vendor SDK Manual", "Configuration", "Logging"]
+        );
+        assert_eq!(logging.pages, Some(PageRange { start: 4, end: 4 }));
+        assert_eq!(logging.citation("manual.pdf"), "manual.pdf p. 4");
+    }
+
+    #[test]
+    fn test_headings_from_font_size_without_outline() {
+        let bytes = build_pdf(
+            None,
+            &[
+                &[
+                    (20.0, "Getting Started"),
+                    (11.0, "This guide explains the basics of the SDK."),
+                    (11.0, "Read it before anything else."),
+                    (14.0, "Requirements"),
+                    (11.0, "A supported operating system."),
+                ],
+                &[(20.0, "Reference"), (11.0, "The full API is listed here.")],
+            ],
+            &[],
+        );
+        let doc = parse_pdf("guide.pdf", &bytes, &SliceConfig::default()).unwrap();
+
+        assert_eq!(doc.title, "guide");
+        assert!(doc.content.contains("## Getting Started"));
+        assert!(doc.content.contains("### Requirements"));
+        assert!(doc.content.contains("## Reference"));
+        assert_eq!(
+            section(&doc, "Requirements").heading_path,
+            vec!["guide", "Getting Started", "Requirements"]
+        );
+        assert_eq!(
+            section(&doc, "Reference").pages,
+            Some(PageRange { start: 2, end: 2 })
+        );
+    }
+
+    #[test]
+    fn test_paragraph_breaks_and_escaping() {
+        let bytes = build_pdf(
+            None,
+            &[&[
+                (11.0, "1. Numbered line from the PDF."),
+                (11.0, ""),
+                (11.0, "# not a heading"),
+            ]],
+            &[],
+        );
+        let doc = parse_pdf("notes.pdf", &bytes, &SliceConfig::default()).unwrap();
+        assert_eq!(
+            doc.content,
+            "\\1. Numbered line from the PDF.\n\n\\# not a heading"
+        );
+    }
+
+    #[test]
+    fn test_parse_file_and_invalid_pdf() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("broken.pdf");
+        std::fs::write(&path, b"not a pdf").unwrap();
+        let error = PdfParser
+            .parse_file(path.to_str().unwrap(), &SliceConfig::default())
+            .unwrap_err();
+        assert!(error.to_string().contains("Failed to load PDF"));
+
+        let path = temp_dir.path().join("ok.pdf");
+        std::fs::write(&path, build_pdf(None, &[&[(11.0, "Hello PDF.")]], &[])).unwrap();
+        let doc =
+            crate::parser::parse_document(path.to_str().unwrap(), &SliceConfig::default()).unwrap();
+        assert!(doc.content.contains("Hello PDF."));
+    }
+}
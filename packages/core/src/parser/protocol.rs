@@ -0,0 +1,737 @@
+//! Bidirectional sidecar protocol
+//!
+//! A long-lived sidecar serves a whole build instead of one process per file.
+//! Both directions use JSON Lines with a `type` tag:
+//!
+//! ```text
+//! sidecar → host  {"type":"hello","protocol_version":1,"name":"cocoindex","languages":["python"]}
+//! host → sidecar  {"type":"parse","request_id":1,"files":["a.py","b.py"]}
+//! sidecar → host  {"type":"progress","request_id":1,"file":"a.py","done":1,"total":2}
+//! sidecar → host  {"type":"chunk","request_id":1,"chunk":{...AstChunk...}}
+//! sidecar → host  {"type":"warning","request_id":1,"file":"b.py","message":"..."}
+//! sidecar → host  {"type":"done","request_id":1}
+//! host → sidecar  {"type":"cancel","request_id":1}
+//! host → sidecar  {"type":"shutdown"}
+//! ```
+//!
+//! The sidecar must send `hello` first; the host rejects protocol versions it
+//! does not know. Unknown message types and unknown chunk fields are ignored,
+//! so sidecars can add information without breaking older hosts.
+//!
+//! # Usage
+//!
+//! ```no_run
+//! use contextfy_core::parser::protocol::SidecarSession;
+//! # use contextfy_core::SearchEngine;
+//!
+//! # async fn run(engine: &SearchEngine) -> anyhow::Result<()> {
+//! let mut session = SidecarSession::spawn(["cocoindex", "serve"]).await?;
+//! println!("Connected to {} ({:?})", session.hello().name, session.hello().languages);
+//!
+//! let summary = session.parse_files(&["a.py", "b.py"], engine.orchestrator(), 64).await?;
+//! println!("{} chunks, {} warnings", summary.chunks, summary.warnings.len());
+//!
+//! session.shutdown().await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::kernel::types::AstChunk;
+use crate::parser::ingest::ChunkSink;
+use crate::parser::ipc::{read_stderr_tail, resolve_command, IpcError};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::process::Stdio;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, Lines};
+use tokio::process::{Child, ChildStdin, ChildStdout, Command};
+use tokio::time::timeout;
+use tracing::{debug, warn};
+
+/// Highest protocol version this host understands (versions start at 1)
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// Default wait for the handshake and for each message
+const DEFAULT_READ_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Wait for the sidecar to exit after `shutdown`
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Handshake sent by the sidecar on startup
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SidecarHello {
+    /// Protocol version spoken by the sidecar
+    pub protocol_version: u32,
+    /// Sidecar name
+    pub name: String,
+    /// Sidecar version
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// Languages the sidecar can parse (e.g. `python`)
+    #[serde(default)]
+    pub languages: Vec<String>,
+}
+
+impl SidecarHello {
+    /// Whether the sidecar announced support for a language (case-insensitive)
+    pub fn supports(&self, language: &str) -> bool {
+        self.languages
+            .iter()
+            .any(|l| l.eq_ignore_ascii_case(language))
+    }
+}
+
+/// Messages sent by the sidecar on stdout
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SidecarMessage {
+    /// Handshake, always the first message
+    Hello(SidecarHello),
+    /// A parsed chunk
+    Chunk {
+        request_id: u64,
+        chunk: Box<AstChunk>,
+    },
+    /// A file finished parsing
+    Progress {
+        request_id: u64,
+        file: String,
+        #[serde(default)]
+        done: usize,
+        #[serde(default)]
+        total: usize,
+    },
+    /// A non-fatal problem (e.g. a file that could not be parsed)
+    Warning {
+        #[serde(default)]
+        request_id: Option<u64>,
+        #[serde(default)]
+        file: Option<String>,
+        message: String,
+    },
+    /// A request finished (or was cancelled)
+    Done {
+        request_id: u64,
+        #[serde(default)]
+        cancelled: bool,
+    },
+    /// A request (or the sidecar itself when `request_id` is absent) failed
+    Error {
+        #[serde(default)]
+        request_id: Option<u64>,
+        message: String,
+    },
+    /// A message type introduced by a newer sidecar
+    #[serde(other)]
+    Unknown,
+}
+
+/// Requests sent by the host on stdin
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SidecarRequest {
+    /// Parse files; results carry the same `request_id`
+    Parse { request_id: u64, files: Vec<String> },
+    /// Stop working on a request
+    Cancel { request_id: u64 },
+    /// Finish and exit
+    Shutdown,
+}
+
+/// Outcome of [`SidecarSession::parse_files`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ParseSummary {
+    /// Chunks written to the sink
+    pub chunks: usize,
+    /// `write_batch` calls made
+    pub batches: usize,
+    /// Files reported through progress messages
+    pub files_done: usize,
+    /// Warnings reported by the sidecar, plus skipped unparseable lines
+    pub warnings: Vec<String>,
+    /// Whether the sidecar acknowledged a cancellation
+    pub cancelled: bool,
+}
+
+/// A running sidecar speaking the bidirectional protocol
+pub struct SidecarSession {
+    child: Child,
+    stdin: ChildStdin,
+    lines: Lines<BufReader<ChildStdout>>,
+    stderr: Arc<Mutex<String>>,
+    hello: SidecarHello,
+    read_timeout: Duration,
+    next_request_id: u64,
+    line_number: usize,
+}
+
+impl SidecarSession {
+    /// Spawn a sidecar and perform the handshake
+    ///
+    /// `COCOINDEX_MODE=dev` runs the command through `uv run`, as with
+    /// [`SidecarIPC`](crate::parser::ipc::SidecarIPC).
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the process cannot start, does not send `hello` within
+    /// the read timeout, or speaks an unsupported protocol version.
+    pub async fn spawn<A, I>(command: I) -> Result<Self>
+    where
+        A: AsRef<str>,
+        I: IntoIterator<Item = A>,
+    {
+        Self::spawn_with_timeout(command, DEFAULT_READ_TIMEOUT).await
+    }
+
+    /// Spawn a sidecar with a custom handshake and per-message timeout
+    pub async fn spawn_with_timeout<A, I>(command: I, read_timeout: Duration) -> Result<Self>
+    where
+        A: AsRef<str>,
+        I: IntoIterator<Item = A>,
+    {
+        let args: Vec<String> = command
+            .into_iter()
+            .map(|s| s.as_ref().to_string())
+            .collect();
+        let (program, full_args) = resolve_command(&args)?;
+        let start_failed = |cause: String| IpcError::ChildStartFailed {
+            command: format!("{} {:?}", program, full_args),
+            cause,
+        };
+
+        let mut child = Command::new(&program)
+            .args(&full_args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| start_failed(e.to_string()))?;
+
+        let stdin = child
+            .stdin
+            .take()
+            .ok_or_else(|| start_failed("stdin not captured".to_string()))?;
+        let stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| start_failed("stdout not captured".to_string()))?;
+
+        // Drain stderr in the background so the sidecar never blocks on it
+        let stderr = Arc::new(Mutex::new(String::new()));
+        if let Some(pipe) = child.stderr.take() {
+            let stderr = Arc::clone(&stderr);
+            tokio::spawn(async move {
+                let output = read_stderr_tail(pipe).await;
+                if let Ok(mut buffer) = stderr.lock() {
+                    buffer.push_str(&output);
+                }
+            });
+        }
+
+        let mut session = Self {
+            child,
+            stdin,
+            lines: BufReader::new(stdout).lines(),
+            stderr,
+            hello: SidecarHello {
+                protocol_version: 0,
+                name: String::new(),
+                version: None,
+                languages: Vec::new(),
+            },
+            read_timeout,
+            next_request_id: 1,
+            line_number: 0,
+        };
+        session.hello = session.handshake().await?;
+        Ok(session)
+    }
+
+    async fn handshake(&mut self) -> Result<SidecarHello> {
+        let hello = match self.next_message().await {
+            Ok(Some(SidecarMessage::Hello(hello))) => hello,
+            Ok(Some(other)) => {
+                return Err(anyhow::anyhow!(IpcError::HandshakeFailed {
+                    cause: format!("expected hello, got {:?}", other),
+                }))
+            }
+            Ok(None) => {
+                return Err(anyhow::anyhow!(IpcError::HandshakeFailed {
+                    cause: format!("sidecar exited before hello: {}", self.stderr_output()),
+                }))
+            }
+            Err(e) => {
+                return Err(anyhow::anyhow!(IpcError::HandshakeFailed {
+                    cause: e.to_string(),
+                }))
+            }
+        };
+
+        if !(1..=PROTOCOL_VERSION).contains(&hello.protocol_version) {
+            let _ = self.child.kill().await;
+            return Err(anyhow::anyhow!(IpcError::UnsupportedProtocol {
+                supported: PROTOCOL_VERSION,
+                actual: hello.protocol_version,
+            }));
+        }
+
+        debug!(
+            "Sidecar {} speaks protocol v{} ({:?})",
+            hello.name, hello.protocol_version, hello.languages
+        );
+        Ok(hello)
+    }
+
+    /// The handshake sent by the sidecar
+    pub fn hello(&self) -> &SidecarHello {
+        &self.hello
+    }
+
+    /// Send a request to the sidecar
+    pub async fn send(&mut self, request: &SidecarRequest) -> Result<()> {
+        let mut line = serde_json::to_string(request).context("Failed to encode request")?;
+        line.push('\n');
+        let write = async {
+            self.stdin.write_all(line.as_bytes()).await?;
+            self.stdin.flush().await
+        };
+        write.await.map_err(|e| {
+            anyhow::anyhow!(IpcError::StreamWriteFailed {
+                cause: e.to_string(),
+            })
+        })
+    }
+
+    /// Ask the sidecar to parse files; returns the request ID
+    pub async fn parse<P: AsRef<Path>>(&mut self, files: &[P]) -> Result<u64> {
+        let request_id = self.next_request_id;
+        self.next_request_id += 1;
+        let files = files
+            .iter()
+            .map(|f| f.as_ref().to_string_lossy().into_owned())
+            .collect();
+        self.send(&SidecarRequest::Parse { request_id, files })
+            .await?;
+        Ok(request_id)
+    }
+
+    /// Ask the sidecar to stop working on a request
+    ///
+    /// The sidecar answers with `done` (`cancelled: true`); chunks already in
+    /// flight may still arrive before it.
+    pub async fn cancel(&mut self, request_id: u64) -> Result<()> {
+        self.send(&SidecarRequest::Cancel { request_id }).await
+    }
+
+    /// Read the next message
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(Some(message))` - A message arrived
+    /// - `Ok(None)` - The sidecar closed stdout
+    /// - `Err(...)` - Read timeout, IO error or a line that is not a valid message
+    ///   (`IpcError::JsonParseFailed`; the session stays usable)
+    pub async fn next_message(&mut self) -> Result<Option<SidecarMessage>> {
+        loop {
+            let line = match timeout(self.read_timeout, self.lines.next_line()).await {
+                Ok(Ok(Some(line))) => line,
+                Ok(Ok(None)) => return Ok(None),
+                Ok(Err(e)) => {
+                    return Err(anyhow::anyhow!(IpcError::StreamReadFailed {
+                        cause: e.to_string(),
+                    }))
+                }
+                Err(_) => {
+                    return Err(anyhow::anyhow!(IpcError::ReadTimeout {
+                        timeout_ms: self.read_timeout.as_millis() as u64,
+                        line_number: self.line_number + 1,
+                    }))
+                }
+            };
+
+            self.line_number += 1;
+            let line = line.trim_end();
+            if line.is_empty() {
+                continue;
+            }
+
+            return serde_json::from_str(line).map(Some).map_err(|e| {
+                let prefix: String = line.chars().take(100).collect();
+                anyhow::anyhow!(IpcError::JsonParseFailed {
+                    line_number: self.line_number,
+                    raw_line: if prefix.len() < line.len() {
+                        format!("{prefix}...")
+                    } else {
+                        line.to_string()
+                    },
+                    cause: e.to_string(),
+                })
+            });
+        }
+    }
+
+    /// Parse files and stream the chunks into a sink in batches
+    ///
+    /// Waits for the request's `done` message. Messages for other requests are
+    /// ignored; unparseable lines are skipped and recorded as warnings.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the sidecar reports an error for the request, exits or
+    /// stalls before `done`, or the sink rejects a batch. Batches written
+    /// before the failure stay in the sink.
+    pub async fn parse_files<P: AsRef<Path>>(
+        &mut self,
+        files: &[P],
+        sink: &dyn ChunkSink,
+        batch_size: usize,
+    ) -> Result<ParseSummary> {
+        let batch_size = batch_size.max(1);
+        let request_id = self.parse(files).await?;
+        let mut summary = ParseSummary::default();
+        let mut batch: Vec<AstChunk> = Vec::with_capacity(batch_size);
+
+        loop {
+            let message = match self.next_message().await {
+                Ok(Some(message)) => message,
+                Ok(None) => {
+                    flush(&mut batch, sink, &mut summary).await?;
+                    let status = self.child.wait().await.ok();
+                    return Err(anyhow::anyhow!(IpcError::ChildExitedAbnormally {
+                        exit_code: status.and_then(|s| s.code()),
+                        stderr: self.stderr_output(),
+                    }));
+                }
+                Err(e) => match e.downcast_ref::<IpcError>() {
+                    Some(IpcError::JsonParseFailed { .. }) => {
+                        warn!("Skipping sidecar output: {}", e);
+                        summary.warnings.push(e.to_string());
+                        continue;
+                    }
+                    _ => {
+                        flush(&mut batch, sink, &mut summary).await?;
+                        return Err(e);
+                    }
+                },
+            };
+
+            match message {
+                SidecarMessage::Chunk {
+                    request_id: id,
+                    chunk,
+                } if id == request_id => {
+                    batch.push(*chunk);
+                    if batch.len() >= batch_size {
+                        flush(&mut batch, sink, &mut summary).await?;
+                    }
+                }
+                SidecarMessage::Progress {
+                    request_id: id,
+                    file,
+                    done,
+                    total,
+                } if id == request_id => {
+                    debug!("Sidecar parsed {} ({}/{})", file, done, total);
+                    summary.files_done += 1;
+                }
+                SidecarMessage::Warning {
+                    request_id: id,
+                    file,
+                    message,
+                } if id.is_none() || id == Some(request_id) => {
+                    let warning = match file {
+                        Some(file) => format!("{}: {}", file, message),
+                        None => message,
+                    };
+                    warn!("Sidecar warning: {}", warning);
+                    summary.warnings.push(warning);
+                }
+                SidecarMessage::Done {
+                    request_id: id,
+                    cancelled,
+                } if id == request_id => {
+                    flush(&mut batch, sink, &mut summary).await?;
+                    summary.cancelled = cancelled;
+                    return Ok(summary);
+                }
+                SidecarMessage::Error {
+                    request_id: id,
+                    message,
+                } if id.is_none() || id == Some(request_id) => {
+                    flush(&mut batch, sink, &mut summary).await?;
+                    return Err(anyhow::anyhow!(IpcError::RequestFailed {
+                        request_id: id,
+                        message,
+                    }));
+                }
+                other => debug!("Ignoring sidecar message: {:?}", other),
+            }
+        }
+    }
+
+    /// Ask the sidecar to exit and wait for it
+    ///
+    /// The sidecar is killed if it does not exit within a few seconds.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the sidecar exits with a non-zero status.
+    pub async fn shutdown(mut self) -> Result<()> {
+        // A sidecar that already exited cannot receive the request; its exit status still counts
+        let _ = self.send(&SidecarRequest::Shutdown).await;
+
+        let status = match timeout(SHUTDOWN_TIMEOUT, self.child.wait()).await {
+            Ok(status) => status.context("Failed to wait for sidecar")?,
+            Err(_) => {
+                warn!("Sidecar {} ignored shutdown, killing it", self.hello.name);
+                self.child.kill().await.context("Failed to kill sidecar")?;
+                return Ok(());
+            }
+        };
+
+        if !status.success() {
+            return Err(anyhow::anyhow!(IpcError::ChildExitedAbnormally {
+                exit_code: status.code(),
+                stderr: self.stderr_output(),
+            }));
+        }
+        Ok(())
+    }
+
+    fn stderr_output(&self) -> String {
+        self.stderr
+            .lock()
+            .map(|buffer| buffer.clone())
+            .unwrap_or_default()
+    }
+}
+
+async fn flush(
+    batch: &mut Vec<AstChunk>,
+    sink: &dyn ChunkSink,
+    summary: &mut ParseSummary,
+) -> Result<()> {
+    if batch.is_empty() {
+        return Ok(());
+    }
+    let chunks = std::mem::take(batch);
+    let count = chunks.len();
+    sink.write_batch(chunks)
+        .await
+        .context("Failed to store parsed chunks")?;
+    summary.chunks += count;
+    summary.batches += 1;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kernel::errors::AppError;
+    use async_trait::async_trait;
+    use tempfile::TempDir;
+
+    #[derive(Default)]
+    struct RecordingSink {
+        batches: Mutex<Vec<Vec<String>>>,
+    }
+
+    #[async_trait]
+    impl ChunkSink for RecordingSink {
+        async fn write_batch(&self, chunks: Vec<AstChunk>) -> Result<(), AppError> {
+            let ids = chunks.into_iter().map(|c| c.id).collect();
+            self.batches.lock().unwrap().push(ids);
+            Ok(())
+        }
+    }
+
+    const HELLO: &str = r#"{"type":"hello","protocol_version":1,"name":"fake","version":"0.1","languages":["python","go"]}"#;
+
+    /// Fake sidecar: answers each `parse` with progress, one chunk per file
+    /// (plus a chunk with an unknown field), a warning, an unknown message and `done`
+    const FAKE_SIDECAR: &str = r#"
+echo "$HELLO"
+while read -r line; do
+  id=$(echo "$line" | sed -n 's/.*"request_id":\([0-9]*\).*/\1/p')
+  case "$line" in
+    *'"type":"shutdown"'*) exit 0 ;;
+    *'"type":"cancel"'*) echo "{\"type\":\"done\",\"request_id\":$id,\"cancelled\":true}" ;;
+    *'"type":"parse"'*)
+      files=$(echo "$line" | sed 's/.*"files":\[\(.*\)\].*/\1/' | tr -d '"' | tr ',' ' ')
+      for f in $files; do
+        echo "{\"type\":\"progress\",\"request_id\":$id,\"file\":\"$f\",\"done\":1,\"total\":1}"
+        echo "{\"type\":\"chunk\",\"request_id\":$id,\"chunk\":{\"id\":\"$id-$f\",\"file_path\":\"$f\",\"symbol_name\":\"s\",\"node_type\":\"function\",\"content\":\"x\",\"future_field\":42}}"
+      done
+      echo "{\"type\":\"telemetry\",\"request_id\":$id}"
+      echo "garbage"
+      echo "{\"type\":\"warning\",\"request_id\":$id,\"file\":\"x.py\",\"message\":\"skipped\"}"
+      echo "{\"type\":\"done\",\"request_id\":$id}"
+      ;;
+  esac
+done
+"#;
+
+    fn script(dir: &TempDir, body: &str) -> Vec<String> {
+        let path = dir.path().join("sidecar.sh");
+        std::fs::write(&path, format!("HELLO='{}'\n{}", HELLO, body)).unwrap();
+        vec!["sh".to_string(), path.to_string_lossy().into_owned()]
+    }
+
+    fn ipc_error(error: &anyhow::Error) -> &IpcError {
+        error
+            .downcast_ref::<IpcError>()
+            .expect("error should be an IpcError")
+    }
+
+    #[test]
+    fn test_message_round_trip() {
+        let request = SidecarRequest::Parse {
+            request_id: 7,
+            files: vec!["a.py".to_string()],
+        };
+        let json = serde_json::to_string(&request).unwrap();
+        assert_eq!(json, r#"{"type":"parse","request_id":7,"files":["a.py"]}"#);
+        assert_eq!(
+            serde_json::to_string(&SidecarRequest::Shutdown).unwrap(),
+            r#"{"type":"shutdown"}"#
+        );
+
+        let hello: SidecarMessage = serde_json::from_str(HELLO).unwrap();
+        match hello {
+            SidecarMessage::Hello(hello) => {
+                assert_eq!(hello.name, "fake");
+                assert!(hello.supports("Python"));
+                assert!(!hello.supports("rust"));
+            }
+            other => panic!("unexpected message: {:?}", other),
+        }
+
+        let unknown: SidecarMessage =
+            serde_json::from_str(r#"{"type":"heartbeat","at":1}"#).unwrap();
+        assert_eq!(unknown, SidecarMessage::Unknown);
+    }
+
+    #[tokio::test]
+    async fn test_long_lived_session_serves_multiple_requests() {
+        let dir = TempDir::new().unwrap();
+        let mut session = SidecarSession::spawn(script(&dir, FAKE_SIDECAR))
+            .await
+            .unwrap();
+        assert_eq!(session.hello().languages, vec!["python", "go"]);
+
+        let sink = RecordingSink::default();
+        let first = session
+            .parse_files(&["a.py", "b.py", "c.py"], &sink, 2)
+            .await
+            .unwrap();
+        assert_eq!(first.chunks, 3);
+        assert_eq!(first.batches, 2);
+        assert_eq!(first.files_done, 3);
+        assert!(!first.cancelled);
+        // The sidecar warning plus the skipped garbage line
+        assert_eq!(first.warnings.len(), 2);
+        assert_eq!(first.warnings[1], "x.py: skipped");
+
+        let second = session.parse_files(&["d.go"], &sink, 10).await.unwrap();
+        assert_eq!(second.chunks, 1);
+
+        assert_eq!(
+            *sink.batches.lock().unwrap(),
+            vec![vec!["1-a.py", "1-b.py"], vec!["1-c.py"], vec!["2-d.go"]]
+        );
+        session.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_cancel_is_acknowledged() {
+        let dir = TempDir::new().unwrap();
+        let mut session = SidecarSession::spawn(script(&dir, FAKE_SIDECAR))
+            .await
+            .unwrap();
+
+        session.cancel(42).await.unwrap();
+        let message = session.next_message().await.unwrap();
+        assert_eq!(
+            message,
+            Some(SidecarMessage::Done {
+                request_id: 42,
+                cancelled: true
+            })
+        );
+        session.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_unsupported_protocol_is_rejected() {
+        let dir = TempDir::new().unwrap();
+        let command = script(
+            &dir,
+            r#"echo '{"type":"hello","protocol_version":99,"name":"future"}'; sleep 5"#,
+        );
+
+        let error = SidecarSession::spawn(command).await.err().unwrap();
+        assert!(matches!(
+            ipc_error(&error),
+            IpcError::UnsupportedProtocol {
+                supported: PROTOCOL_VERSION,
+                actual: 99
+            }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_handshake_requires_hello() {
+        let dir = TempDir::new().unwrap();
+
+        let command = script(&dir, r#"echo '{"type":"done","request_id":1}'"#);
+        let error = SidecarSession::spawn(command).await.err().unwrap();
+        assert!(matches!(
+            ipc_error(&error),
+            IpcError::HandshakeFailed { .. }
+        ));
+
+        let command = script(&dir, "sleep 5");
+        let error = SidecarSession::spawn_with_timeout(command, Duration::from_millis(200))
+            .await
+            .err()
+            .unwrap();
+        match ipc_error(&error) {
+            IpcError::HandshakeFailed { cause } => assert!(cause.contains("Timed out")),
+            other => panic!("unexpected error: {other}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_request_error_and_crash() {
+        let dir = TempDir::new().unwrap();
+        let command = script(
+            &dir,
+            r#"echo "$HELLO"
+read -r line
+echo '{"type":"error","request_id":1,"message":"parser exploded"}'
+read -r line
+echo fatal >&2
+exit 2"#,
+        );
+        let mut session = SidecarSession::spawn(command).await.unwrap();
+        let sink = RecordingSink::default();
+
+        let error = session.parse_files(&["a.py"], &sink, 8).await.unwrap_err();
+        assert!(matches!(
+            ipc_error(&error),
+            IpcError::RequestFailed { request_id: Some(1), message } if message == "parser exploded"
+        ));
+
+        let error = session.parse_files(&["b.py"], &sink, 8).await.unwrap_err();
+        assert!(matches!(
+            ipc_error(&error),
+            IpcError::ChildExitedAbnormally {
+                exit_code: Some(2),
+                ..
+            }
+        ));
+    }
+}
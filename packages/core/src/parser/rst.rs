@@ -0,0 +1,397 @@
+//! reStructuredText 转换
+//!
+//! 把 Sphinx 风格的 `.rst` 文档转换为 Markdown，再交给 Markdown 解析和切片流程：
+//!
+//! - 节标题（下划线或上下划线）：按装饰样式首次出现的顺序确定层级
+//! - `.. code-block:: python` / `.. code::` / `.. sourcecode::`：转为带语言的围栏代码块
+//! - 以 `::` 结尾的段落后面的缩进块：转为围栏代码块
+//! - `.. note::`、`.. warning::` 等提示：转为加粗的提示标题，保留正文
+//! - 其他指令（`toctree`、`image` 等）、注释和链接目标：删除
+//! - 行内标记：``` ``code`` ```、`` `文本 <url>`_ ``、`:func:`、`:ref:`、`:doc:` 等角色
+
+use super::document::DocumentParser;
+use super::{build_parsed_doc, DocMetadata, ParsedDoc, SliceConfig};
+use anyhow::Result;
+use regex::Regex;
+use std::sync::OnceLock;
+
+/// reStructuredText 解析器
+pub struct RstParser;
+
+impl DocumentParser for RstParser {
+    fn name(&self) -> &'static str {
+        "rst"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["rst", "rest"]
+    }
+
+    fn parse(&self, file_path: &str, source: &str, config: &SliceConfig) -> Result<ParsedDoc> {
+        Ok(build_parsed_doc(
+            file_path,
+            rst_to_markdown(source),
+            DocMetadata::default(),
+            config,
+        ))
+    }
+}
+
+/// 提示类指令
+const ADMONITIONS: &[&str] = &[
+    "note",
+    "warning",
+    "tip",
+    "hint",
+    "important",
+    "caution",
+    "danger",
+    "attention",
+    "error",
+    "seealso",
+    "admonition",
+    "deprecated",
+    "versionadded",
+    "versionchanged",
+];
+
+/// 把 reStructuredText 转换为 Markdown
+///
+/// # 示例
+///
+/// ```ignore
+/// let md = rst_to_markdown("Title\n=====\n\nUse ``spawn()``.\n");
+/// assert_eq!(md.trim(), "# Title\n\nUse `spawn()`.");
+/// ```
+pub fn rst_to_markdown(source: &str) -> String {
+    let lines: Vec<&str> = source.lines().collect();
+    let mut styles: Vec<(char, bool)> = Vec::new();
+    let mut output: Vec<String> = Vec::new();
+    let mut i = 0;
+
+    while i < lines.len() {
+        let line = lines[i];
+
+        // 上下划线标题：=====\nTitle\n=====
+        if let Some(c) = adornment_char(line) {
+            if let (Some(title), Some(under)) = (lines.get(i + 1), lines.get(i + 2)) {
+                if !title.trim().is_empty() && adornment_char(under) == Some(c) {
+                    push_heading(&mut output, &mut styles, (c, true), title.trim());
+                    i += 3;
+                    continue;
+                }
+            }
+        }
+
+        // 下划线标题：Title\n=====
+        if !line.trim().is_empty() && !line.starts_with([' ', '\t']) {
+            if let Some(c) = lines.get(i + 1).and_then(|next| adornment_char(next)) {
+                if lines[i + 1].trim().chars().count() >= line.trim().chars().count().min(3) {
+                    push_heading(&mut output, &mut styles, (c, false), line.trim());
+                    i += 2;
+                    continue;
+                }
+            }
+        }
+
+        // 指令、注释和链接目标
+        if let Some(rest) = line.strip_prefix("..") {
+            if rest.is_empty() || rest.starts_with(' ') {
+                let (body, next) = indented_block(&lines, i + 1);
+                if let Some((name, argument)) = parse_directive(rest.trim()) {
+                    push_directive(&mut output, &name, argument, &body);
+                }
+                i = next;
+                continue;
+            }
+        }
+
+        // `::` 结尾的段落：后面的缩进块是字面量块
+        let trimmed = line.trim_end();
+        if trimmed.ends_with("::") {
+            let (body, next) = indented_block(&lines, i + 1);
+            if !body.is_empty() {
+                let text = match trimmed.strip_suffix("::") {
+                    Some(text) if text.trim().is_empty() => String::new(),
+                    Some(text) if text.ends_with(' ') => text.trim_end().to_string(),
+                    Some(text) => format!("{}:", text),
+                    None => unreachable!(),
+                };
+                if !text.is_empty() {
+                    output.push(convert_inline(&text));
+                    output.push(String::new());
+                }
+                push_fence(&mut output, None, &body);
+                i = next;
+                continue;
+            }
+        }
+
+        output.push(convert_line(line));
+        i += 1;
+    }
+
+    let mut markdown = output.join("\n");
+    markdown.push('\n');
+    markdown
+}
+
+/// 装饰行：至少 3 个相同的标点字符
+fn adornment_char(line: &str) -> Option<char> {
+    let line = line.trim_end();
+    let c = line.chars().next()?;
+    let is_adornment =
+        line.chars().count() >= 3 && c.is_ascii_punctuation() && line.chars().all(|x| x == c);
+    is_adornment.then_some(c)
+}
+
+fn push_heading(
+    output: &mut Vec<String>,
+    styles: &mut Vec<(char, bool)>,
+    style: (char, bool),
+    title: &str,
+) {
+    let level = match styles.iter().position(|s| *s == style) {
+        Some(index) => index + 1,
+        None => {
+            styles.push(style);
+            styles.len()
+        }
+    };
+    output.push(format!(
+        "{} {}",
+        "#".repeat(level.min(6)),
+        convert_inline(title)
+    ));
+    output.push(String::new());
+}
+
+/// 收集从 `start` 开始的缩进块（允许中间有空行），返回去掉公共缩进后的行和下一行的位置
+fn indented_block(lines: &[&str], start: usize) -> (Vec<String>, usize) {
+    let mut end = start;
+    while end < lines.len() && (lines[end].trim().is_empty() || lines[end].starts_with([' ', '\t']))
+    {
+        end += 1;
+    }
+
+    let block = &lines[start..end];
+    let indent = block
+        .iter()
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| l.len() - l.trim_start().len())
+        .min()
+        .unwrap_or(0);
+
+    let mut body: Vec<String> = block
+        .iter()
+        .map(|l| l.get(indent..).unwrap_or("").trim_end().to_string())
+        .collect();
+    while body.first().is_some_and(|l| l.is_empty()) {
+        body.remove(0);
+    }
+    while body.last().is_some_and(|l| l.is_empty()) {
+        body.pop();
+    }
+    (body, end)
+}
+
+/// 解析 `name:: argument`；注释和链接目标返回 `None`
+fn parse_directive(text: &str) -> Option<(String, &str)> {
+    static DIRECTIVE: OnceLock<Regex> = OnceLock::new();
+    let regex =
+        DIRECTIVE.get_or_init(|| Regex::new(r"^([\w:.+-]+)::(?:\s+(.*))?$").expect("valid regex"));
+    let caps = regex.captures(text)?;
+    let name = caps.get(1)?.as_str().to_ascii_lowercase();
+    let argument = caps.get(2).map_or("", |m| m.as_str().trim());
+    Some((name, argument))
+}
+
+fn push_directive(output: &mut Vec<String>, name: &str, argument: &str, body: &[String]) {
+    // 指令选项（`:linenos:`、`:caption: ...`）位于正文开头
+    let content: Vec<String> = body
+        .iter()
+        .skip_while(|l| l.starts_with(':'))
+        .cloned()
+        .collect();
+
+    match name {
+        "code-block" | "code" | "sourcecode" => {
+            let language = argument.split_whitespace().next();
+            push_fence(output, language, trim_blank(&content));
+        }
+        name if ADMONITIONS.contains(&name) => {
+            let label = if name == "admonition" && !argument.is_empty() {
+                argument.to_string()
+            } else {
+                let mut label = name.replace("version", "version ");
+                if let Some(first) = label.get_mut(0..1) {
+                    first.make_ascii_uppercase();
+                }
+                if name.starts_with("version") && !argument.is_empty() {
+                    label = format!("{} {}", label, argument);
+                }
+                label
+            };
+            // 普通提示的参数就是正文第一行（`.. warning:: Deprecated.`）
+            let mut text = content.join("\n");
+            if !name.starts_with("version") && name != "admonition" && !argument.is_empty() {
+                text = format!("{}\n{}", argument, text);
+            }
+            let inner = rst_to_markdown(&text);
+            output.push(format!("**{}:** {}", label.trim(), inner.trim()));
+            output.push(String::new());
+        }
+        // toctree、image、figure、include、目录等不产生正文
+        _ => {}
+    }
+}
+
+fn trim_blank(lines: &[String]) -> &[String] {
+    let start = lines
+        .iter()
+        .position(|l| !l.is_empty())
+        .unwrap_or(lines.len());
+    let end = lines
+        .iter()
+        .rposition(|l| !l.is_empty())
+        .map_or(start, |i| i + 1);
+    &lines[start..end]
+}
+
+fn push_fence(output: &mut Vec<String>, language: Option<&str>, body: &[String]) {
+    output.push(format!("```{}", language.unwrap_or("")));
+    output.extend(body.iter().cloned());
+    output.push("```".to_string());
+    output.push(String::new());
+}
+
+/// 转换普通行：`#.` 自动编号列表转为 `1.`，其余做行内转换
+fn convert_line(line: &str) -> String {
+    let trimmed = line.trim_start();
+    let indent = &line[..line.len() - trimmed.len()];
+    match trimmed.strip_prefix("#. ") {
+        Some(rest) => format!("{}1. {}", indent, convert_inline(rest)),
+        None => convert_inline(line),
+    }
+}
+
+/// 转换行内标记
+fn convert_inline(text: &str) -> String {
+    static HYPERLINK: OnceLock<Regex> = OnceLock::new();
+    static ROLE: OnceLock<Regex> = OnceLock::new();
+    static LITERAL: OnceLock<Regex> = OnceLock::new();
+
+    let hyperlink =
+        HYPERLINK.get_or_init(|| Regex::new(r"`([^`<]*?)\s*<([^`>]+)>`__?").expect("valid regex"));
+    let role = ROLE.get_or_init(|| Regex::new(r":([\w:.+-]+):`([^`]+)`").expect("valid regex"));
+    let literal = LITERAL.get_or_init(|| Regex::new(r"``([^`]+)``").expect("valid regex"));
+
+    // 先转换 ``literal``，避免其中的内容被当作链接或角色
+    let text = literal.replace_all(text, "`$1`");
+    let text = hyperlink.replace_all(&text, |caps: &regex::Captures<'_>| {
+        let target = &caps[2];
+        let label = if caps[1].is_empty() { target } else { &caps[1] };
+        format!("[{}]({})", label, target)
+    });
+    let text = role.replace_all(&text, |caps: &regex::Captures<'_>| {
+        convert_role(&caps[1], &caps[2])
+    });
+    text.into_owned()
+}
+
+/// 转换解释文本角色，如 `:func:`spawn``、`:doc:`指南 <guide>``
+fn convert_role(role: &str, content: &str) -> String {
+    let (label, target) = match content.rsplit_once('<') {
+        Some((label, target)) if target.ends_with('>') && !label.trim().is_empty() => {
+            (Some(label.trim()), target.trim_end_matches('>').trim())
+        }
+        _ => (None, content.trim()),
+    };
+
+    match role.rsplit(':').next().unwrap_or(role) {
+        // 文档引用：目标不带扩展名
+        "doc" => {
+            let file = format!("{}.rst", target.trim_start_matches('/'));
+            format!("[{}]({})", label.unwrap_or(target), file)
+        }
+        "ref" | "term" | "abbr" | "dfn" | "emphasis" | "strong" | "sub" | "sup"
+        | "title-reference" => label.unwrap_or(target).to_string(),
+        // 代码对象：`~pkg.mod.func` 只显示最后一段，`!` 表示不生成链接
+        _ => {
+            let name = match label {
+                Some(label) => label.to_string(),
+                None => {
+                    let target = target.trim_start_matches('!');
+                    match target.strip_prefix('~') {
+                        Some(short) => short.rsplit('.').next().unwrap_or(short).to_string(),
+                        None => target.to_string(),
+                    }
+                }
+            };
+            format!("`{}`", name)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_headings_follow_adornment_order() {
+        let source = "=====\nGuide\n=====\n\nIntro.\n\nInstall\n-------\n\nStep\n~~~~\n\nText.\n\nUsage\n-------\n";
+        let md = rst_to_markdown(source);
+        assert!(md.contains("# Guide\n"));
+        assert!(md.contains("## Install\n"));
+        assert!(md.contains("### Step\n"));
+        assert!(md.contains("## Usage\n"));
+    }
+
+    #[test]
+    fn test_code_blocks_and_literal_blocks() {
+        let source = "Example::\n\n    x = 1\n    print(x)\n\n.. code-block:: python\n   :linenos:\n\n   def spawn():\n       pass\n\nAfter.\n";
+        let md = rst_to_markdown(source);
+        assert!(md.contains("Example:\n\n```\nx = 1\nprint(x)\n```"));
+        assert!(md.contains("```python\ndef spawn():\n    pass\n```"));
+        assert!(md.contains("After."));
+        assert!(!md.contains("linenos"));
+    }
+
+    #[test]
+    fn test_directives_and_admonitions() {
+        let source = ".. _install:\n\n.. toctree::\n   :maxdepth: 2\n\n   intro\n\n.. note::\n   Requires **Python 3**.\n\n.. warning:: Deprecated.\n\nBody.\n";
+        let md = rst_to_markdown(source);
+        assert!(!md.contains("toctree"));
+        assert!(!md.contains("intro"));
+        assert!(md.contains("**Note:** Requires **Python 3**."));
+        assert!(md.contains("**Warning:** Deprecated."));
+        assert!(md.contains("Body."));
+    }
+
+    #[test]
+    fn test_inline_markup() {
+        let md = convert_inline(
+            "Call ``spawn()`` or :func:`~world.entity.spawn`, see :doc:`the guide <guides/events>`, :ref:`install` and `Docs <https://example.com>`_.",
+        );
+        assert_eq!(
+            md,
+            "Call `spawn()` or `spawn`, see [the guide](guides/events.rst), install and [Docs](https://example.com)."
+        );
+    }
+
+    #[test]
+    fn test_parse_rst_document() {
+        let source = "Block Events\n============\n\nOverview.\n\nListening\n---------\n\n.. code-block:: java\n\n   @EventHandler\n   void onBreak(BlockBreakEvent e) {}\n";
+        let doc = RstParser
+            .parse("docs/events.rst", source, &SliceConfig::default())
+            .unwrap();
+        assert_eq!(doc.title, "Block Events");
+        let listening = doc
+            .sections
+            .iter()
+            .find(|s| s.section_title == "Listening")
+            .expect("section should exist");
+        assert_eq!(listening.heading_path, vec!["Block Events", "Listening"]);
+        assert_eq!(listening.code_blocks[0].language.as_deref(), Some("java"));
+    }
+}
@@ -0,0 +1,660 @@
+//! 层级标题切片
+//!
+//! `slice_by_headers` 只按 H2 切分，并丢弃第一个 H2 之前的内容。本模块提供可配置的
+//! 切片器：
+//!
+//! - 按 H2..H4 中选定的层级切分，每个切片记录完整的标题路径（`Doc > Section > Subsection`）
+//! - 第一个切分标题之前的前言（preamble）作为独立切片保留
+//! - 超过大小上限的切片按段落（Markdown 顶层块）拆分，代码块不会被截断
+//! - 拆分后的子切片之间可选重叠
+
+use pulldown_cmark::{Event, HeadingLevel, Parser, Tag};
+use std::ops::Range;
+
+use super::{
+    extract_code_blocks, extract_summary, generate_smart_title, skip_leading_whitespace, SlicedDoc,
+};
+
+/// 切片大小的度量单位
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChunkSize {
+    /// 按字符数（Unicode 标量值）计量
+    Chars(usize),
+    /// 按估算的 token 数计量（见 [`estimate_tokens`]）
+    Tokens(usize),
+}
+
+impl ChunkSize {
+    /// 上限值
+    pub fn limit(self) -> usize {
+        match self {
+            Self::Chars(n) | Self::Tokens(n) => n,
+        }
+    }
+
+    /// 按当前单位度量文本大小
+    pub fn measure(self, text: &str) -> usize {
+        match self {
+            Self::Chars(_) => text.chars().count(),
+            Self::Tokens(_) => estimate_tokens(text),
+        }
+    }
+}
+
+/// 层级切片配置
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SliceConfig {
+    /// 最深的切分层级（2..=4），H2 到该层级的标题都会开启新切片
+    pub max_level: u8,
+    /// 单个切片的大小上限；`None` 表示不限制
+    pub max_size: Option<ChunkSize>,
+    /// 拆分后的子切片与前一个子切片的重叠量（与 `max_size` 同一单位）
+    pub overlap: usize,
+    /// 是否把第一个切分标题之前的内容保留为独立切片
+    pub keep_preamble: bool,
+}
+
+impl Default for SliceConfig {
+    /// 默认按 H2/H3 切分，单个切片最多 2000 字符，不重叠，保留前言
+    fn default() -> Self {
+        Self {
+            max_level: 3,
+            max_size: Some(ChunkSize::Chars(2000)),
+            overlap: 0,
+            keep_preamble: true,
+        }
+    }
+}
+
+impl SliceConfig {
+    /// 只按 H2 切分、不限制大小的配置（与 `slice_by_headers` 的切分边界一致）
+    pub fn h2_only() -> Self {
+        Self {
+            max_level: 2,
+            max_size: None,
+            overlap: 0,
+            keep_preamble: true,
+        }
+    }
+
+    /// 设置最深切分层级（会被限制在 2..=4）
+    pub fn with_max_level(mut self, level: u8) -> Self {
+        self.max_level = level.clamp(2, 4);
+        self
+    }
+
+    /// 设置切片大小上限
+    pub fn with_max_size(mut self, size: ChunkSize) -> Self {
+        self.max_size = Some(size);
+        self
+    }
+
+    /// 设置子切片重叠量
+    pub fn with_overlap(mut self, overlap: usize) -> Self {
+        self.overlap = overlap;
+        self
+    }
+
+    /// 设置是否保留前言切片
+    pub fn with_preamble(mut self, keep: bool) -> Self {
+        self.keep_preamble = keep;
+        self
+    }
+
+    fn splits_on(&self, level: u8) -> bool {
+        (2..=self.max_level.clamp(2, 4)).contains(&level)
+    }
+}
+
+/// 估算文本的 token 数
+///
+/// 粗略规则：每个 CJK 字符计 1 个 token，其余按空白和标点分隔的单词各计 1 个 token。
+pub fn estimate_tokens(text: &str) -> usize {
+    let mut in_word = false;
+    text.chars().map(|c| token_step(c, &mut in_word)).sum()
+}
+
+/// 读入一个字符后新增的 token 数；`in_word` 记录是否处于单词中
+///
+/// 单词只在边界处计数一次，因此正序或逆序读入同一段文本得到的总数相同。
+fn token_step(c: char, in_word: &mut bool) -> usize {
+    if is_cjk(c) {
+        *in_word = false;
+        1
+    } else if c.is_alphanumeric() || c == '_' {
+        let starts_word = !*in_word;
+        *in_word = true;
+        usize::from(starts_word)
+    } else {
+        // 标点单独计数，空白只作为分隔符
+        *in_word = false;
+        usize::from(!c.is_whitespace())
+    }
+}
+
+/// 增量度量：逐段追加文本并累计大小，避免反复度量不断变长的区间
+#[derive(Debug, Clone, Copy)]
+struct SizeMeter {
+    size: ChunkSize,
+    total: usize,
+    in_word: bool,
+}
+
+impl SizeMeter {
+    fn new(size: ChunkSize) -> Self {
+        Self {
+            size,
+            total: 0,
+            in_word: false,
+        }
+    }
+
+    /// 度量一段文本，结果与 [`ChunkSize::measure`] 相同
+    fn of(size: ChunkSize, text: &str) -> Self {
+        let mut meter = Self::new(size);
+        meter.push(text);
+        meter
+    }
+
+    fn push(&mut self, text: &str) {
+        text.chars().for_each(|c| self.push_char(c));
+    }
+
+    fn push_char(&mut self, c: char) {
+        self.total += match self.size {
+            ChunkSize::Chars(_) => 1,
+            ChunkSize::Tokens(_) => token_step(c, &mut self.in_word),
+        };
+    }
+}
+
+fn is_cjk(c: char) -> bool {
+    matches!(c as u32,
+        0x4E00..=0x9FFF      // CJK 统一表意文字
+        | 0x3400..=0x4DBF    // 扩展 A
+        | 0x3040..=0x30FF    // 平假名、片假名
+        | 0xAC00..=0xD7AF    // 韩文音节
+        | 0xF900..=0xFAFF    // 兼容表意文字
+    )
+}
+
+/// 文档中的一个标题
+struct Heading {
+    level: u8,
+    title: String,
+    /// 标题在原文中的字节范围
+    range: Range<usize>,
+}
+
+fn heading_level(level: HeadingLevel) -> u8 {
+    match level {
+        HeadingLevel::H1 => 1,
+        HeadingLevel::H2 => 2,
+        HeadingLevel::H3 => 3,
+        HeadingLevel::H4 => 4,
+        HeadingLevel::H5 => 5,
+        HeadingLevel::H6 => 6,
+    }
+}
+
+/// 收集文档中所有标题（代码块中的 `#` 不会被识别为标题）
+fn collect_headings(content: &str) -> Vec<Heading> {
+    let mut headings = Vec::new();
+    let mut current: Option<(u8, String, usize)> = None;
+
+    for (event, range) in Parser::new(content).into_offset_iter() {
+        match event {
+            Event::Start(Tag::Heading(level, ..)) => {
+                current = Some((heading_level(level), String::new(), range.start));
+            }
+            Event::End(Tag::Heading(..)) => {
+                if let Some((level, title, start)) = current.take() {
+                    headings.push(Heading {
+                        level,
+                        title: title.trim().to_string(),
+                        range: start..range.end,
+                    });
+                }
+            }
+            Event::Text(text) | Event::Code(text) => {
+                if let Some((_, title, _)) = &mut current {
+                    title.push_str(&text);
+                }
+            }
+            Event::SoftBreak | Event::HardBreak => {
+                if let Some((_, title, _)) = &mut current {
+                    title.push(' ');
+                }
+            }
+            _ => {}
+        }
+    }
+
+    headings
+}
+
+/// 按层级标题切片 Markdown 内容
+///
+/// # 参数
+///
+/// * `content` - 要切片的 Markdown 内容
+/// * `parent_title` - 父文档标题（通常是 H1），作为所有标题路径的第一级
+/// * `config` - 切分层级、大小上限、重叠和前言配置
+///
+/// # 行为
+///
+/// - H2..`max_level` 的标题开启新切片，更深的标题保留在切片内容中
+/// - 切片内容不包含标题本身；空切片（标题后直接是子标题）会被跳过，但其标题仍出现在子切片的路径中
+/// - 前言切片的标题和路径都是 `parent_title`；开头的 H1 标题行不计入前言
+/// - 超过 `max_size` 的切片按顶层块（段落、列表、代码块等）拆分；单个块仍超限时按行拆分
+///
+/// # 示例
+///
+/// ```ignore
+/// let content = "# Doc\n\nIntro\n\n## A\n\n### B\n\nText";
+/// let slices = slice_hierarchical(content, "Doc", &SliceConfig::default());
+/// assert_eq!(slices[0].heading_path, vec!["Doc"]);           // 前言
+/// assert_eq!(slices[1].heading_path, vec!["Doc", "A", "B"]); // A 下没有正文，被跳过
+/// ```
+pub fn slice_hierarchical<'a>(
+    content: &'a str,
+    parent_title: &'a str,
+    config: &SliceConfig,
+) -> Vec<SlicedDoc<'a>> {
+    let headings = collect_headings(content);
+    let splits: Vec<&Heading> = headings
+        .iter()
+        .filter(|h| config.splits_on(h.level))
+        .collect();
+
+    let mut slices = Vec::new();
+
+    // 前言：第一个切分标题之前的内容（跳过开头的 H1 标题）
+    if config.keep_preamble {
+        let preamble_end = splits.first().map_or(content.len(), |h| h.range.start);
+        let preamble_start = headings
+            .iter()
+            .find(|h| h.level == 1 && h.range.end <= preamble_end)
+            .filter(|h| content[..h.range.start].trim().is_empty())
+            .map_or(0, |h| h.range.end);
+
+        push_section(
+            &mut slices,
+            content,
+            preamble_start..preamble_end,
+            parent_title.to_string(),
+            vec![parent_title.to_string()],
+            parent_title,
+            config,
+        );
+    }
+
+    // 标题栈：(层级, 标题)，用于构造面包屑路径
+    let mut stack: Vec<(u8, String)> = Vec::new();
+
+    for (i, heading) in splits.iter().enumerate() {
+        let body_end = splits.get(i + 1).map_or(content.len(), |next| next.range.start);
+        let body = heading.range.end..body_end;
+
+        let title = if heading.title.is_empty() {
+            generate_smart_title(content[body.clone()].trim())
+        } else {
+            heading.title.clone()
+        };
+
+        stack.retain(|(level, _)| *level < heading.level);
+        stack.push((heading.level, title.clone()));
+
+        let heading_path = std::iter::once(parent_title.to_string())
+            .chain(stack.iter().map(|(_, t)| t.clone()))
+            .collect();
+
+        push_section(&mut slices, content, body, title, heading_path, parent_title, config);
+    }
+
+    slices
+}
+
+/// 把一个标题下的正文加入结果，必要时按大小上限拆分
+fn push_section<'a>(
+    slices: &mut Vec<SlicedDoc<'a>>,
+    content: &'a str,
+    range: Range<usize>,
+    section_title: String,
+    heading_path: Vec<String>,
+    parent_title: &'a str,
+    config: &SliceConfig,
+) {
+    let raw = &content[range.clone()];
+    let start = range.start + (raw.len() - skip_leading_whitespace(raw).len());
+    let end = range.start + raw.trim_end().len();
+    if start >= end {
+        return;
+    }
+
+    let pieces = match config.max_size {
+        Some(size) if size.measure(&content[start..end]) > size.limit() => {
+            split_by_size(content, start..end, size, config.overlap)
+        }
+        _ => vec![Range { start, end }],
+    };
+
+    for piece in pieces {
+        let text = &content[piece];
+        slices.push(SlicedDoc {
+            section_title: section_title.clone(),
+            content: text,
+            parent_doc_title: parent_title,
+            summary: extract_summary(text.trim()),
+            heading_path: heading_path.clone(),
+            code_blocks: extract_code_blocks(text),
+        });
+    }
+}
+
+/// 按段落拆分超出大小上限的正文
+///
+/// 先按顶层块分组，单个块超限时再按行（最后按字符）拆分。返回的范围都是原文中的
+/// 连续字节区间；开启重叠时，后一个范围会向前延伸到前一个范围的末尾部分。
+fn split_by_size(content: &str, range: Range<usize>, size: ChunkSize, overlap: usize) -> Vec<Range<usize>> {
+    let limit = size.limit().max(1);
+
+    // 1. 收集原子单元：顶层块，超限的块拆成行，超限的行按字符硬切
+    let mut units: Vec<Range<usize>> = Vec::new();
+    for block in top_level_blocks(content, range.clone()) {
+        if size.measure(&content[block.clone()]) <= limit {
+            units.push(block);
+            continue;
+        }
+        for line in line_ranges(content, block) {
+            if size.measure(&content[line.clone()]) <= limit {
+                units.push(line);
+            } else {
+                units.extend(hard_split(content, line, size, limit));
+            }
+        }
+    }
+
+    // 2. 贪心合并相邻单元，范围之间保留原文中的分隔符；当前片的大小随合并累计
+    let mut pieces: Vec<Range<usize>> = Vec::new();
+    let mut current: Option<(Range<usize>, SizeMeter)> = None;
+    for unit in units {
+        current = match current {
+            Some((cur, meter)) => {
+                let mut merged = meter;
+                merged.push(&content[cur.end..unit.end]);
+                if merged.total <= limit {
+                    Some((cur.start..unit.end, merged))
+                } else {
+                    pieces.push(cur);
+                    Some((unit.clone(), SizeMeter::of(size, &content[unit])))
+                }
+            }
+            None => Some((unit.clone(), SizeMeter::of(size, &content[unit]))),
+        };
+    }
+    pieces.extend(current.map(|(cur, _)| cur));
+
+    // 3. 重叠：后一片向前延伸，起点对齐到空白边界
+    if overlap > 0 {
+        for i in 1..pieces.len() {
+            let floor = pieces[i - 1].start;
+            pieces[i].start = overlap_start(content, floor, pieces[i].start, size, overlap);
+        }
+    }
+
+    pieces
+}
+
+/// 在 `range` 内找出 Markdown 顶层块（段落、列表、代码块、表格等）的字节范围
+fn top_level_blocks(content: &str, range: Range<usize>) -> Vec<Range<usize>> {
+    let text = &content[range.clone()];
+    let mut blocks = Vec::new();
+    let mut depth = 0usize;
+    let mut block_start = 0usize;
+
+    for (event, r) in Parser::new(text).into_offset_iter() {
+        match event {
+            Event::Start(_) => {
+                if depth == 0 {
+                    block_start = r.start;
+                }
+                depth += 1;
+            }
+            Event::End(_) => {
+                depth = depth.saturating_sub(1);
+                if depth == 0 {
+                    blocks.push(range.start + block_start..range.start + r.end);
+                }
+            }
+            // 水平线、HTML 等没有 Start/End 的顶层事件
+            _ if depth == 0 => blocks.push(range.start + r.start..range.start + r.end),
+            _ => {}
+        }
+    }
+
+    // 去掉块尾部的换行，避免切片以空白结尾
+    blocks
+        .into_iter()
+        .map(|b| b.start..b.start + content[b.clone()].trim_end().len())
+        .filter(|b| b.start < b.end)
+        .collect()
+}
+
+/// 把范围拆成非空行
+fn line_ranges(content: &str, range: Range<usize>) -> Vec<Range<usize>> {
+    let mut lines = Vec::new();
+    let mut offset = range.start;
+    for line in content[range.clone()].split_inclusive('\n') {
+        let trimmed = line.trim_end().len();
+        if trimmed > 0 {
+            lines.push(offset..offset + trimmed);
+        }
+        offset += line.len();
+    }
+    lines
+}
+
+/// 按字符边界硬切超长的单行
+fn hard_split(content: &str, range: Range<usize>, size: ChunkSize, limit: usize) -> Vec<Range<usize>> {
+    let mut pieces = Vec::new();
+    let mut start = range.start;
+    let mut last_fit = range.start;
+    let mut meter = SizeMeter::new(size);
+
+    for (offset, c) in content[range.clone()].char_indices() {
+        let end = range.start + offset + c.len_utf8();
+        meter.push_char(c);
+        if meter.total > limit && last_fit > start {
+            pieces.push(start..last_fit);
+            start = last_fit;
+            meter = SizeMeter::of(size, &content[start..end]);
+        }
+        last_fit = end;
+    }
+    if start < range.end {
+        pieces.push(start..range.end);
+    }
+    pieces
+}
+
+/// 计算重叠后的起点：从 `start` 向前回退到空白边界，直到重叠部分达到 `overlap`
+fn overlap_start(content: &str, floor: usize, start: usize, size: ChunkSize, overlap: usize) -> usize {
+    let mut candidate = start;
+    // 逆序累计 `content[next..start]` 的大小（度量结果与读入方向无关）
+    let mut meter = SizeMeter::new(size);
+    for (offset, c) in content[floor..start].char_indices().rev() {
+        let position = floor + offset;
+        if c.is_whitespace() {
+            let next = position + c.len_utf8();
+            if next < start && meter.total >= overlap {
+                return next;
+            }
+            candidate = next;
+        }
+        meter.push_char(c);
+    }
+    // 前一片中没有足够长的空白边界时，只回退到最远的边界
+    if candidate < start && candidate > floor {
+        candidate
+    } else {
+        start
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn titles<'a>(slices: &'a [SlicedDoc<'_>]) -> Vec<&'a str> {
+        slices.iter().map(|s| s.section_title.as_str()).collect()
+    }
+
+    #[test]
+    fn test_preamble_is_kept_without_h1() {
+        let content = "# Doc\n\nIntro paragraph.\n\n## Setup\n\nInstall it.";
+        let slices = slice_hierarchical(content, "Doc", &SliceConfig::default());
+
+        assert_eq!(titles(&slices), vec!["Doc", "Setup"]);
+        assert_eq!(slices[0].content, "Intro paragraph.");
+        assert_eq!(slices[0].heading_path, vec!["Doc"]);
+        assert_eq!(slices[1].heading_path, vec!["Doc", "Setup"]);
+    }
+
+    #[test]
+    fn test_preamble_can_be_dropped() {
+        let content = "Intro.\n\n## Setup\n\nInstall it.";
+        let config = SliceConfig::default().with_preamble(false);
+        let slices = slice_hierarchical(content, "Doc", &config);
+
+        assert_eq!(titles(&slices), vec!["Setup"]);
+    }
+
+    #[test]
+    fn test_heading_path_breadcrumbs() {
+        let content = "## Guide\n\nOverview.\n\n### Install\n\nSteps.\n\n#### Linux\n\napt.\n\n## API\n\nRef.";
+        let slices = slice_hierarchical(content, "Doc", &SliceConfig::default().with_max_level(4));
+
+        let paths: Vec<Vec<String>> = slices.iter().map(|s| s.heading_path.clone()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                vec!["Doc", "Guide"],
+                vec!["Doc", "Guide", "Install"],
+                vec!["Doc", "Guide", "Install", "Linux"],
+                vec!["Doc", "API"],
+            ]
+        );
+        assert_eq!(slices[1].content, "Steps.");
+    }
+
+    #[test]
+    fn test_deeper_headings_stay_in_content() {
+        let content = "## Guide\n\nOverview.\n\n### Install\n\nSteps.";
+        let slices = slice_hierarchical(content, "Doc", &SliceConfig::h2_only());
+
+        assert_eq!(slices.len(), 1);
+        assert!(slices[0].content.contains("### Install"));
+    }
+
+    #[test]
+    fn test_empty_parent_section_is_skipped_but_kept_in_path() {
+        let content = "## Guide\n\n### Install\n\nSteps.";
+        let slices = slice_hierarchical(content, "Doc", &SliceConfig::default());
+
+        assert_eq!(titles(&slices), vec!["Install"]);
+        assert_eq!(slices[0].heading_path, vec!["Doc", "Guide", "Install"]);
+    }
+
+    #[test]
+    fn test_headings_in_code_blocks_are_ignored() {
+        let content = "## Real\n\n```bash\n## not a heading\n```";
+        let slices = slice_hierarchical(content, "Doc", &SliceConfig::default());
+
+        assert_eq!(titles(&slices), vec!["Real"]);
+        assert!(slices[0].content.contains("## not a heading"));
+    }
+
+    #[test]
+    fn test_oversized_section_splits_on_paragraphs() {
+        let content = "## Long\n\nAAAA AAAA.\n\nBBBB BBBB.\n\nCCCC CCCC.";
+        let config = SliceConfig::h2_only().with_max_size(ChunkSize::Chars(24));
+        let slices = slice_hierarchical(content, "Doc", &config);
+
+        let contents: Vec<&str> = slices.iter().map(|s| s.content).collect();
+        assert_eq!(contents, vec!["AAAA AAAA.\n\nBBBB BBBB.", "CCCC CCCC."]);
+        assert!(slices.iter().all(|s| s.heading_path == vec!["Doc", "Long"]));
+    }
+
+    #[test]
+    fn test_code_block_is_not_split_across_paragraph_boundary() {
+        let content = "## Code\n\nIntro.\n\n```rust\nfn a() {}\n\nfn b() {}\n```\n\nOutro.";
+        let config = SliceConfig::h2_only().with_max_size(ChunkSize::Chars(40));
+        let slices = slice_hierarchical(content, "Doc", &config);
+
+        assert!(slices
+            .iter()
+            .any(|s| s.content.contains("fn a() {}\n\nfn b() {}")));
+    }
+
+    #[test]
+    fn test_oversized_single_paragraph_splits_by_line_and_chars() {
+        let content = "## Wall\n\nline one\nline two\n0123456789abcdef";
+        let config = SliceConfig::h2_only().with_max_size(ChunkSize::Chars(10));
+        let slices = slice_hierarchical(content, "Doc", &config);
+
+        let contents: Vec<&str> = slices.iter().map(|s| s.content).collect();
+        assert_eq!(contents, vec!["line one", "line two", "0123456789", "abcdef"]);
+    }
+
+    #[test]
+    fn test_overlap_extends_back_to_word_boundary() {
+        let content = "## Long\n\nalpha beta gamma.\n\ndelta epsilon.";
+        let config = SliceConfig::h2_only()
+            .with_max_size(ChunkSize::Chars(18))
+            .with_overlap(6);
+        let slices = slice_hierarchical(content, "Doc", &config);
+
+        assert_eq!(slices.len(), 2);
+        assert_eq!(slices[0].content, "alpha beta gamma.");
+        assert_eq!(slices[1].content, "gamma.\n\ndelta epsilon.");
+    }
+
+    #[test]
+    fn test_token_size_limit() {
+        let content = "## T\n\none two three.\n\nfour five six.";
+        let config = SliceConfig::h2_only().with_max_size(ChunkSize::Tokens(4));
+        let slices = slice_hierarchical(content, "Doc", &config);
+
+        assert_eq!(slices.len(), 2);
+    }
+
+    #[test]
+    fn test_size_meter_matches_measure_in_both_directions() {
+        let text = "Install 依赖 with `cargo add foo_bar`, then run it. 完成！";
+        for size in [ChunkSize::Chars(0), ChunkSize::Tokens(0)] {
+            let mut forward = SizeMeter::new(size);
+            forward.push(&text[..18]);
+            forward.push(&text[18..]);
+            assert_eq!(forward.total, size.measure(text));
+
+            let mut backward = SizeMeter::new(size);
+            text.chars().rev().for_each(|c| backward.push_char(c));
+            assert_eq!(backward.total, size.measure(text));
+        }
+    }
+
+    #[test]
+    fn test_estimate_tokens() {
+        assert_eq!(estimate_tokens(""), 0);
+        assert_eq!(estimate_tokens("hello world"), 2);
+        assert_eq!(estimate_tokens("snake_case, ok."), 4);
+        assert_eq!(estimate_tokens("中文分词"), 4);
+    }
+
+    #[test]
+    fn test_max_level_is_clamped() {
+        assert_eq!(SliceConfig::default().with_max_level(9).max_level, 4);
+        assert_eq!(SliceConfig::default().with_max_level(1).max_level, 2);
+    }
+}
@@ -33,7 +33,7 @@
 pub mod trait_;
 
 // Concrete implementations and helpers are private to prevent infrastructure leakage
-mod schema;
+pub(crate) mod schema;
 // index is pub(crate) for facade factory access
 pub(crate) mod index;
 // tantivy_impl is pub(crate) for facade factory access
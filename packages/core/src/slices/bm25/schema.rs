@@ -16,6 +16,31 @@ pub(crate) const FIELD_SYMBOL_NAME: &str = "symbol_name";  // **Highest BM25 wei
 pub(crate) const FIELD_NODE_TYPE: &str = "node_type";
 pub(crate) const FIELD_CONTENT: &str = "content";
 pub(crate) const FIELD_DEPENDENCIES: &str = "dependencies";
+pub(crate) const FIELD_PACK: &str = "pack";
+pub(crate) const FIELD_HEADING_PATH: &str = "heading_path";
+pub(crate) const FIELD_TAGS: &str = "tags";
+pub(crate) const FIELD_VERSION: &str = "version";
+pub(crate) const FIELD_START_LINE: &str = "start_line";
+pub(crate) const FIELD_END_LINE: &str = "end_line";
+pub(crate) const FIELD_START_PAGE: &str = "start_page";
+pub(crate) const FIELD_END_PAGE: &str = "end_page";
+
+/// Version of the index layout, reported by health checks
+///
+/// Bump whenever a field is added, removed or changes options. Indexes built
+/// with another layout are rejected by `validate_bm25_schema` when opened.
+pub(crate) const BM25_SCHEMA_VERSION: u32 = 2;
+
+/// Fields indexed as untokenized STRING values for exact matching
+const EXACT_MATCH_FIELDS: &[&str] = &[FIELD_ID, FIELD_PACK, FIELD_TAGS, FIELD_VERSION];
+
+/// Fields stored as u64 values (not searchable)
+const NUMERIC_FIELDS: &[&str] = &[
+    FIELD_START_LINE,
+    FIELD_END_LINE,
+    FIELD_START_PAGE,
+    FIELD_END_PAGE,
+];
 
 /// Create Tantivy schema for AST chunk BM25 full-text search
 ///
@@ -29,6 +54,12 @@ pub(crate) const FIELD_DEPENDENCIES: &str = "dependencies";
 /// - `node_type`: Node type (TEXT, TOKENIZED, STORED, with jieba tokenizer)
 /// - `content`: Full content (TEXT, TOKENIZED, STORED, with jieba tokenizer)
 /// - `dependencies`: Dependencies as multi-value TEXT field (TOKENIZED, STORED, with jieba tokenizer)
+/// - `pack`: Owning pack name (STRING, STORED, not tokenized, absent when unset)
+/// - `heading_path`: Heading breadcrumb as multi-value TEXT field (TOKENIZED, STORED, with jieba tokenizer)
+/// - `tags`: Front-matter tags as multi-value STRING field (STORED, not tokenized)
+/// - `version`: Front-matter document version (STRING, STORED, not tokenized, absent when unset)
+/// - `start_line` / `end_line`: 1-based source line span (U64, STORED, absent when unset)
+/// - `start_page` / `end_page`: 1-based page range of paginated documents (U64, STORED, absent when unset)
 ///
 /// # Tokenization
 ///
@@ -38,12 +69,13 @@ pub(crate) const FIELD_DEPENDENCIES: &str = "dependencies";
 /// # Field Weights
 ///
 /// - `symbol_name`: 5.0x (highest priority for precise symbol retrieval)
+/// - `heading_path`: 3.0x (section breadcrumbs are strong topical signals)
 /// - `dependencies`: 2.0x (secondary priority for dependency matching)
 /// - `content`: 1.0x (baseline weight)
 ///
 /// # Invariants
 ///
-/// - ID field is STRING type for exact matching (not tokenized)
+/// - ID, pack, tags and version fields are STRING type for exact matching (not tokenized)
 /// - TEXT fields support tokenization and are stored for retrieval
 /// - Jieba tokenizer with name "jieba" must be registered on the index
 pub(crate) fn create_bm25_schema() -> Schema {
@@ -65,7 +97,25 @@ pub(crate) fn create_bm25_schema() -> Schema {
     schema_builder.add_text_field(FIELD_SYMBOL_NAME, text_options.clone());  // **Highest weight**
     schema_builder.add_text_field(FIELD_NODE_TYPE, text_options.clone());
     schema_builder.add_text_field(FIELD_CONTENT, text_options.clone());
-    schema_builder.add_text_field(FIELD_DEPENDENCIES, text_options);  // Multi-value field
+    schema_builder.add_text_field(FIELD_DEPENDENCIES, text_options.clone());  // Multi-value field
+
+    // Add pack field (STRING type, exact match for filtering and bulk deletes)
+    schema_builder.add_text_field(FIELD_PACK, tantivy::schema::STRING | STORED);
+
+    // Add heading path field (multi-value, one value per breadcrumb level)
+    schema_builder.add_text_field(FIELD_HEADING_PATH, text_options);
+
+    // Add front-matter metadata fields (STRING type, exact match for filtering)
+    schema_builder.add_text_field(FIELD_TAGS, tantivy::schema::STRING | STORED);  // Multi-value field
+    schema_builder.add_text_field(FIELD_VERSION, tantivy::schema::STRING | STORED);
+
+    // Add source line span fields (stored only, returned with the chunk)
+    schema_builder.add_u64_field(FIELD_START_LINE, STORED);
+    schema_builder.add_u64_field(FIELD_END_LINE, STORED);
+
+    // Add page range fields (stored only, returned with the chunk for citations)
+    schema_builder.add_u64_field(FIELD_START_PAGE, STORED);
+    schema_builder.add_u64_field(FIELD_END_PAGE, STORED);
 
     schema_builder.build()
 }
@@ -100,9 +150,9 @@ pub(crate) fn create_bm25_schema() -> Schema {
 /// 1. Field count matches expected count
 /// 2. All required field names exist
 /// 3. Each field has the correct `FieldType` (including all options)
-/// 4. ID field uses "raw" tokenizer (no tokenization)
+/// 4. Exact-match fields (ID, pack, tags, version) use "raw" tokenizer (no tokenization)
 /// 5. TEXT fields use "jieba" tokenizer (Chinese text segmentation)
-/// 6. All fields are stored
+/// 6. All fields are stored (numeric line span and page fields are checked by type only)
 ///
 /// # Parameters
 ///
@@ -135,6 +185,14 @@ pub(crate) fn validate_bm25_schema(schema: &Schema) -> Result<(), String> {
         FIELD_NODE_TYPE,
         FIELD_CONTENT,
         FIELD_DEPENDENCIES,
+        FIELD_PACK,
+        FIELD_HEADING_PATH,
+        FIELD_TAGS,
+        FIELD_VERSION,
+        FIELD_START_LINE,
+        FIELD_END_LINE,
+        FIELD_START_PAGE,
+        FIELD_END_PAGE,
     ] {
         // Check field exists
         let field = schema
@@ -164,8 +222,13 @@ pub(crate) fn validate_bm25_schema(schema: &Schema) -> Result<(), String> {
             ));
         }
 
-        // For TEXT fields (all except ID), validate tokenizer and storage
-        if *field_name != FIELD_ID {
+        // Numeric fields carry no tokenizer; the type comparison above covers them
+        if NUMERIC_FIELDS.contains(field_name) {
+            continue;
+        }
+
+        // For TEXT fields (all except the exact-match fields), validate tokenizer and storage
+        if !EXACT_MATCH_FIELDS.contains(field_name) {
             // TEXT fields should have indexing options with tokenizer
             let text_options = match entry.field_type() {
                 tantivy::schema::FieldType::Str(opts) => opts,
@@ -192,13 +255,13 @@ pub(crate) fn validate_bm25_schema(schema: &Schema) -> Result<(), String> {
                 ));
             }
         } else {
-            // ID field should be STRING type (indexed but not tokenized with "raw" tokenizer)
+            // Exact-match fields should be STRING type (indexed but not tokenized with "raw" tokenizer)
             let text_options = match entry.field_type() {
                 tantivy::schema::FieldType::Str(opts) => opts,
                 _ => return Err(format!("Field '{}' should be Str type", field_name)),
             };
 
-            // ID field should have indexing options with "raw" tokenizer (not tokenized)
+            // Should have indexing options with "raw" tokenizer (not tokenized)
             let indexing = text_options.get_indexing_options().ok_or_else(|| {
                 format!(
                     "Field '{}' should have indexing options with 'raw' tokenizer",
@@ -214,7 +277,7 @@ pub(crate) fn validate_bm25_schema(schema: &Schema) -> Result<(), String> {
                 ));
             }
 
-            // Verify ID field is stored
+            // Verify the field is stored
             if !text_options.is_stored() {
                 return Err(format!(
                     "Field '{}' should be STORED but is not",
@@ -236,8 +299,8 @@ mod tests {
     fn test_create_bm25_schema() {
         let schema = create_bm25_schema();
 
-        // Verify 6 fields
-        assert_eq!(schema.fields().count(), 6);
+        // Verify 14 fields
+        assert_eq!(schema.fields().count(), 14);
 
         // Verify field names
         let field_names: Vec<_> = schema
@@ -252,7 +315,15 @@ mod tests {
                 FIELD_SYMBOL_NAME,
                 FIELD_NODE_TYPE,
                 FIELD_CONTENT,
-                FIELD_DEPENDENCIES
+                FIELD_DEPENDENCIES,
+                FIELD_PACK,
+                FIELD_HEADING_PATH,
+                FIELD_TAGS,
+                FIELD_VERSION,
+                FIELD_START_LINE,
+                FIELD_END_LINE,
+                FIELD_START_PAGE,
+                FIELD_END_PAGE
             ]
         );
     }
@@ -278,7 +349,7 @@ mod tests {
     #[test]
     fn test_validate_bm25_schema_missing_field() {
         // Create a schema with correct field count but missing symbol_name field
-        // We need to add an extra field to keep count at 6
+        // We need to add an extra field to keep count at 14
         // All TEXT fields must use jieba tokenizer to match expected schema
         let text_indexing = TextFieldIndexing::default().set_tokenizer("jieba");
         let text_options = TextOptions::default()
@@ -291,6 +362,14 @@ mod tests {
         builder.add_text_field(FIELD_NODE_TYPE, text_options.clone());
         builder.add_text_field(FIELD_CONTENT, text_options.clone());
         builder.add_text_field(FIELD_DEPENDENCIES, text_options.clone());
+        builder.add_text_field(FIELD_PACK, tantivy::schema::STRING | STORED);
+        builder.add_text_field(FIELD_HEADING_PATH, text_options.clone());
+        builder.add_text_field(FIELD_TAGS, tantivy::schema::STRING | STORED);
+        builder.add_text_field(FIELD_VERSION, tantivy::schema::STRING | STORED);
+        builder.add_u64_field(FIELD_START_LINE, STORED);
+        builder.add_u64_field(FIELD_END_LINE, STORED);
+        builder.add_u64_field(FIELD_START_PAGE, STORED);
+        builder.add_u64_field(FIELD_END_PAGE, STORED);
         builder.add_text_field("extra_field", text_options); // Extra field to maintain count
         let wrong_schema = builder.build();
 
@@ -311,6 +390,14 @@ mod tests {
         builder.add_text_field(FIELD_NODE_TYPE, TEXT | STORED);
         builder.add_text_field(FIELD_CONTENT, TEXT | STORED);
         builder.add_text_field(FIELD_DEPENDENCIES, TEXT | STORED);
+        builder.add_text_field(FIELD_PACK, tantivy::schema::STRING | STORED);
+        builder.add_text_field(FIELD_HEADING_PATH, TEXT | STORED);
+        builder.add_text_field(FIELD_TAGS, tantivy::schema::STRING | STORED);
+        builder.add_text_field(FIELD_VERSION, tantivy::schema::STRING | STORED);
+        builder.add_u64_field(FIELD_START_LINE, STORED);
+        builder.add_u64_field(FIELD_END_LINE, STORED);
+        builder.add_u64_field(FIELD_START_PAGE, STORED);
+        builder.add_u64_field(FIELD_END_PAGE, STORED);
         let wrong_schema = builder.build();
 
         let result = validate_bm25_schema(&wrong_schema);
@@ -329,6 +416,14 @@ mod tests {
         builder.add_text_field(FIELD_NODE_TYPE, TEXT | STORED);
         builder.add_text_field(FIELD_CONTENT, TEXT | STORED);
         builder.add_text_field(FIELD_DEPENDENCIES, TEXT | STORED);
+        builder.add_text_field(FIELD_PACK, tantivy::schema::STRING | STORED);
+        builder.add_text_field(FIELD_HEADING_PATH, TEXT | STORED);
+        builder.add_text_field(FIELD_TAGS, tantivy::schema::STRING | STORED);
+        builder.add_text_field(FIELD_VERSION, tantivy::schema::STRING | STORED);
+        builder.add_u64_field(FIELD_START_LINE, STORED);
+        builder.add_u64_field(FIELD_END_LINE, STORED);
+        builder.add_u64_field(FIELD_START_PAGE, STORED);
+        builder.add_u64_field(FIELD_END_PAGE, STORED);
         let wrong_schema = builder.build();
 
         let result = validate_bm25_schema(&wrong_schema);
@@ -348,6 +443,14 @@ mod tests {
         builder.add_text_field(FIELD_NODE_TYPE, TEXT | STORED);
         builder.add_text_field(FIELD_CONTENT, TEXT | STORED);
         builder.add_text_field(FIELD_DEPENDENCIES, TEXT | STORED);
+        builder.add_text_field(FIELD_PACK, tantivy::schema::STRING | STORED);
+        builder.add_text_field(FIELD_HEADING_PATH, TEXT | STORED);
+        builder.add_text_field(FIELD_TAGS, tantivy::schema::STRING | STORED);
+        builder.add_text_field(FIELD_VERSION, tantivy::schema::STRING | STORED);
+        builder.add_u64_field(FIELD_START_LINE, STORED);
+        builder.add_u64_field(FIELD_END_LINE, STORED);
+        builder.add_u64_field(FIELD_START_PAGE, STORED);
+        builder.add_u64_field(FIELD_END_PAGE, STORED);
         let wrong_schema = builder.build();
 
         let result = validate_bm25_schema(&wrong_schema);
@@ -372,6 +475,14 @@ mod tests {
         builder.add_text_field(FIELD_NODE_TYPE, TEXT | STORED);
         builder.add_text_field(FIELD_CONTENT, TEXT | STORED);
         builder.add_text_field(FIELD_DEPENDENCIES, TEXT | STORED);
+        builder.add_text_field(FIELD_PACK, tantivy::schema::STRING | STORED);
+        builder.add_text_field(FIELD_HEADING_PATH, TEXT | STORED);
+        builder.add_text_field(FIELD_TAGS, tantivy::schema::STRING | STORED);
+        builder.add_text_field(FIELD_VERSION, tantivy::schema::STRING | STORED);
+        builder.add_u64_field(FIELD_START_LINE, STORED);
+        builder.add_u64_field(FIELD_END_LINE, STORED);
+        builder.add_u64_field(FIELD_START_PAGE, STORED);
+        builder.add_u64_field(FIELD_END_PAGE, STORED);
         let wrong_schema = builder.build();
 
         let result = validate_bm25_schema(&wrong_schema);
@@ -393,5 +504,36 @@ mod tests {
         assert_eq!(FIELD_NODE_TYPE, "node_type");
         assert_eq!(FIELD_CONTENT, "content");
         assert_eq!(FIELD_DEPENDENCIES, "dependencies");
+        assert_eq!(FIELD_PACK, "pack");
+        assert_eq!(FIELD_HEADING_PATH, "heading_path");
+        assert_eq!(FIELD_TAGS, "tags");
+        assert_eq!(FIELD_VERSION, "version");
+        assert_eq!(FIELD_START_LINE, "start_line");
+        assert_eq!(FIELD_END_LINE, "end_line");
+        assert_eq!(FIELD_START_PAGE, "start_page");
+        assert_eq!(FIELD_END_PAGE, "end_page");
+    }
+
+    #[test]
+    fn test_validate_bm25_schema_pack_tokenized() {
+        // Pack must be an exact-match STRING field, not tokenized TEXT
+        let mut builder = Schema::builder();
+        builder.add_text_field(FIELD_ID, tantivy::schema::STRING | STORED);
+        builder.add_text_field(FIELD_FILE_PATH, TEXT | STORED);
+        builder.add_text_field(FIELD_SYMBOL_NAME, TEXT | STORED);
+        builder.add_text_field(FIELD_NODE_TYPE, TEXT | STORED);
+        builder.add_text_field(FIELD_CONTENT, TEXT | STORED);
+        builder.add_text_field(FIELD_DEPENDENCIES, TEXT | STORED);
+        builder.add_text_field(FIELD_PACK, TEXT | STORED);
+        builder.add_text_field(FIELD_HEADING_PATH, TEXT | STORED);
+        builder.add_text_field(FIELD_TAGS, tantivy::schema::STRING | STORED);
+        builder.add_text_field(FIELD_VERSION, tantivy::schema::STRING | STORED);
+        builder.add_u64_field(FIELD_START_LINE, STORED);
+        builder.add_u64_field(FIELD_END_LINE, STORED);
+        builder.add_u64_field(FIELD_START_PAGE, STORED);
+        builder.add_u64_field(FIELD_END_PAGE, STORED);
+        let wrong_schema = builder.build();
+
+        assert!(validate_bm25_schema(&wrong_schema).is_err());
     }
 }
@@ -7,20 +7,27 @@ use anyhow::{Context, Result as AnyhowResult};
 ///
 /// Ref: `openspec/changes/refactor-pragmatic-slice-architecture/design.md` - Rule 2
 use async_trait::async_trait;
+use sha2::{Digest, Sha256};
 use std::sync::Arc;
 use tantivy::{
     collector::TopDocs,
-    query::QueryParser,
+    query::{BooleanQuery, ConstScoreQuery, Occur, Query as TantivyQuery, QueryParser, TermQuery},
     schema::{Field, TantivyDocument, Value},
     Index, IndexReader, IndexWriter,
 };
-use tokio::sync::Mutex;
+use tokio::sync::{Mutex, MutexGuard};
 
-use crate::kernel::errors::{AppError, InfraError};
-use crate::kernel::types::{AstChunk, Query, Score};
+use crate::kernel::errors::{AppError, DomainError, InfraError};
+use crate::kernel::types::{
+    node_type_matches, AstChunk, ChunkFilter, LineSpan, PageRange, Query, Score,
+};
 
 use super::index::{create_bm25_index, create_index_reader};
-use super::schema::{FIELD_CONTENT, FIELD_DEPENDENCIES, FIELD_FILE_PATH, FIELD_ID, FIELD_NODE_TYPE, FIELD_SYMBOL_NAME};
+use super::schema::{
+    FIELD_CONTENT, FIELD_DEPENDENCIES, FIELD_END_LINE, FIELD_END_PAGE, FIELD_FILE_PATH,
+    FIELD_HEADING_PATH, FIELD_ID, FIELD_NODE_TYPE, FIELD_PACK, FIELD_START_LINE, FIELD_START_PAGE,
+    FIELD_SYMBOL_NAME, FIELD_TAGS, FIELD_VERSION,
+};
 use super::trait_::{Bm25Result, Bm25StoreTrait};
 
 // TODO(BM25-Tuning): The hardcoded BM25_MAX_SCORE of 20.0 can compress/clip real BM25 scores
@@ -32,6 +39,12 @@ use super::trait_::{Bm25Result, Bm25StoreTrait};
 /// We normalize to [0.0, 1.0] range by dividing by this constant.
 const BM25_MAX_SCORE: f32 = 20.0;
 
+/// Index metadata file, rewritten by every commit
+const META_FILE_NAME: &str = "meta.json";
+
+/// Memory budget of the index writer (50MB)
+const WRITER_MEMORY_BYTES: usize = 50_000_000;
+
 /// Tantivy BM25 store implementation
 ///
 /// This struct holds the Tantivy index and implements Bm25StoreTrait.
@@ -39,7 +52,7 @@ const BM25_MAX_SCORE: f32 = 20.0;
 /// # Fields
 ///
 /// * `index` - Tantivy index
-/// * `writer` - Index writer for adding documents
+/// * `write_lock` - Serializes writes; each write opens its own index writer
 /// * `reader` - Index reader for searching
 ///
 /// **NOTE**: This struct is public for testing purposes only.
@@ -47,10 +60,34 @@ const BM25_MAX_SCORE: f32 = 20.0;
 #[allow(dead_code)]
 pub struct TantivyBm25Store {
     index: Index,
-    writer: Arc<Mutex<IndexWriter>>,
+    write_lock: Arc<Mutex<()>>,
     reader: Arc<IndexReader>,
 }
 
+/// An index writer held for a single write
+///
+/// Dropping it discards uncommitted changes and releases Tantivy's directory
+/// lock before the next write in this process may start.
+struct WriterGuard<'a> {
+    // Declared first so the writer is dropped before the write lock is released
+    writer: IndexWriter,
+    _serial: MutexGuard<'a, ()>,
+}
+
+impl std::ops::Deref for WriterGuard<'_> {
+    type Target = IndexWriter;
+
+    fn deref(&self) -> &IndexWriter {
+        &self.writer
+    }
+}
+
+impl std::ops::DerefMut for WriterGuard<'_> {
+    fn deref_mut(&mut self) -> &mut IndexWriter {
+        &mut self.writer
+    }
+}
+
 #[allow(dead_code)]
 impl TantivyBm25Store {
     /// Create a new Tantivy BM25 store
@@ -66,21 +103,35 @@ impl TantivyBm25Store {
     pub fn new(index: Index) -> AnyhowResult<Self> {
         let _schema = index.schema();
 
-        // Create index writer with 50MB buffer
-        let writer = index
-            .writer(50_000_000)
-            .context("Failed to create index writer")?;
-
         // Create index reader
         let reader = create_index_reader(&index).context("Failed to create index reader")?;
 
         Ok(Self {
             index,
-            writer: Arc::new(Mutex::new(writer)),
+            write_lock: Arc::new(Mutex::new(())),
             reader: Arc::new(reader),
         })
     }
 
+    /// Open an index writer for a single write
+    ///
+    /// Opening a writer takes Tantivy's directory lock, which excludes writers
+    /// in other processes. The lock is only held until the returned guard is
+    /// dropped, so a running server does not block `contextfy build`; while
+    /// another process is writing, this fails instead of waiting.
+    /// Must be called from a blocking context.
+    fn open_writer<'a>(write_lock: &'a Mutex<()>, index: &Index) -> AnyhowResult<WriterGuard<'a>> {
+        let serial = write_lock.blocking_lock();
+        // Create index writer with 50MB buffer
+        let writer = index
+            .writer(WRITER_MEMORY_BYTES)
+            .context("Failed to create index writer (is another process writing to the index?)")?;
+        Ok(WriterGuard {
+            writer,
+            _serial: serial,
+        })
+    }
+
     /// Create TantivyBm25Store from directory
     ///
     /// This is a convenience function for creating a store from a directory.
@@ -108,6 +159,162 @@ impl TantivyBm25Store {
         String::new()
     }
 
+    /// Extract the stored page range, if the chunk was sliced from a paginated document
+    fn extract_pages(
+        doc: &TantivyDocument,
+        start_field: Field,
+        end_field: Field,
+    ) -> Option<PageRange> {
+        let page = |field| {
+            doc.get_first(field)
+                .and_then(|value| value.as_u64())
+                .map(|page| page as u32)
+        };
+        Some(PageRange {
+            start: page(start_field)?,
+            end: page(end_field)?,
+        })
+    }
+
+    /// Read every live document back into AST chunks
+    ///
+    /// Reloads the reader first so the latest commit is visible. Must be called
+    /// from a blocking context.
+    fn load_chunks(reader: &IndexReader, index: &Index) -> AnyhowResult<Vec<AstChunk>> {
+        reader.reload().context("Failed to reload index reader")?;
+
+        let searcher = reader.searcher();
+        let schema = index.schema();
+
+        let id_field = schema.get_field(FIELD_ID).context("Missing id field")?;
+        let file_path_field = schema.get_field(FIELD_FILE_PATH).context("Missing file_path field")?;
+        let symbol_name_field = schema.get_field(FIELD_SYMBOL_NAME).context("Missing symbol_name field")?;
+        let node_type_field = schema.get_field(FIELD_NODE_TYPE).context("Missing node_type field")?;
+        let content_field = schema.get_field(FIELD_CONTENT).context("Missing content field")?;
+        let dependencies_field = schema.get_field(FIELD_DEPENDENCIES).context("Missing dependencies field")?;
+        let pack_field = schema.get_field(FIELD_PACK).context("Missing pack field")?;
+        let heading_path_field = schema.get_field(FIELD_HEADING_PATH).context("Missing heading_path field")?;
+        let tags_field = schema.get_field(FIELD_TAGS).context("Missing tags field")?;
+        let version_field = schema.get_field(FIELD_VERSION).context("Missing version field")?;
+        let start_line_field = schema.get_field(FIELD_START_LINE).context("Missing start_line field")?;
+        let end_line_field = schema.get_field(FIELD_END_LINE).context("Missing end_line field")?;
+        let start_page_field = schema.get_field(FIELD_START_PAGE).context("Missing start_page field")?;
+        let end_page_field = schema.get_field(FIELD_END_PAGE).context("Missing end_page field")?;
+
+        let doc_addresses = searcher
+            .search(&tantivy::query::AllQuery, &tantivy::collector::DocSetCollector)
+            .context("Failed to enumerate documents")?;
+
+        let mut chunks = Vec::with_capacity(doc_addresses.len());
+        for doc_address in doc_addresses {
+            let retrieved_doc: TantivyDocument = searcher
+                .doc(doc_address)
+                .context("Failed to retrieve document")?;
+
+            let dependencies = retrieved_doc
+                .get_all(dependencies_field)
+                .filter_map(|value| value.as_str().map(str::to_string))
+                .collect();
+
+            let mut chunk = AstChunk::new(
+                Self::extract_text_value(&retrieved_doc, id_field),
+                Self::extract_text_value(&retrieved_doc, file_path_field),
+                Self::extract_text_value(&retrieved_doc, symbol_name_field),
+                Self::extract_text_value(&retrieved_doc, node_type_field),
+                Self::extract_text_value(&retrieved_doc, content_field),
+                dependencies,
+            );
+            chunk.pack = retrieved_doc
+                .get_first(pack_field)
+                .and_then(|value| value.as_str().map(str::to_string));
+            chunk.heading_path = retrieved_doc
+                .get_all(heading_path_field)
+                .filter_map(|value| value.as_str().map(str::to_string))
+                .collect();
+            chunk.tags = retrieved_doc
+                .get_all(tags_field)
+                .filter_map(|value| value.as_str().map(str::to_string))
+                .collect();
+            chunk.version = retrieved_doc
+                .get_first(version_field)
+                .and_then(|value| value.as_str().map(str::to_string));
+            let line = |field| {
+                retrieved_doc
+                    .get_first(field)
+                    .and_then(|value| value.as_u64())
+                    .map(|line| line as u32)
+            };
+            if let (Some(start), Some(end)) = (line(start_line_field), line(end_line_field)) {
+                chunk.span = Some(LineSpan { start, end });
+            }
+            chunk.pages = Self::extract_pages(&retrieved_doc, start_page_field, end_page_field);
+            chunks.push(chunk);
+        }
+
+        Ok(chunks)
+    }
+
+    /// Combine a parsed text query with the exact-match criteria of a filter
+    ///
+    /// Pack, tag and version are untokenized STRING fields, so each becomes a
+    /// required zero-score `TermQuery` that leaves BM25 scores unchanged; a pack
+    /// list becomes a required disjunction of such terms. Path prefix and node
+    /// type are checked by the caller.
+    fn apply_filter(
+        schema: &tantivy::schema::Schema,
+        parsed_query: Box<dyn TantivyQuery>,
+        filter: &ChunkFilter,
+    ) -> AnyhowResult<Box<dyn TantivyQuery>> {
+        let mut clauses: Vec<(Occur, Box<dyn TantivyQuery>)> = Vec::new();
+
+        for (field_name, value) in [
+            (FIELD_PACK, &filter.pack),
+            (FIELD_TAGS, &filter.tag),
+            (FIELD_VERSION, &filter.version),
+        ] {
+            if let Some(value) = value {
+                let field = schema
+                    .get_field(field_name)
+                    .with_context(|| format!("Missing {} field in schema", field_name))?;
+                let term = tantivy::Term::from_field_text(field, value);
+                let term_query = TermQuery::new(term, tantivy::schema::IndexRecordOption::Basic);
+                clauses.push((
+                    Occur::Must,
+                    Box::new(ConstScoreQuery::new(Box::new(term_query), 0.0)),
+                ));
+            }
+        }
+
+        if !filter.packs.is_empty() {
+            let field = schema
+                .get_field(FIELD_PACK)
+                .with_context(|| format!("Missing {} field in schema", FIELD_PACK))?;
+            let any_pack: Vec<(Occur, Box<dyn TantivyQuery>)> = filter
+                .packs
+                .iter()
+                .map(|pack| {
+                    let term = tantivy::Term::from_field_text(field, pack);
+                    let term_query: Box<dyn TantivyQuery> = Box::new(TermQuery::new(
+                        term,
+                        tantivy::schema::IndexRecordOption::Basic,
+                    ));
+                    (Occur::Should, term_query)
+                })
+                .collect();
+            clauses.push((
+                Occur::Must,
+                Box::new(ConstScoreQuery::new(Box::new(BooleanQuery::new(any_pack)), 0.0)),
+            ));
+        }
+
+        if clauses.is_empty() {
+            return Ok(parsed_query);
+        }
+
+        clauses.insert(0, (Occur::Must, parsed_query));
+        Ok(Box::new(BooleanQuery::new(clauses)))
+    }
+
     /// Convert BM25 score to normalized Score
     ///
     /// Tantivy returns BM25 scores which can be any positive value.
@@ -142,6 +349,8 @@ impl Bm25StoreTrait for TantivyBm25Store {
         let reader_clone = Arc::clone(&self.reader);
         let index_clone = self.index.clone();
         let limit = query.limit;
+        let filter = query.filter.clone();
+        let boost = query.boost.clone();
 
         // Use spawn_blocking to avoid blocking Tokio runtime
         let search_result = tokio::task::spawn_blocking(move || {
@@ -170,6 +379,9 @@ impl Bm25StoreTrait for TantivyBm25Store {
             let node_type_field = schema
                 .get_field(FIELD_NODE_TYPE)
                 .context("Missing node_type field in schema")?;
+            let heading_path_field = schema
+                .get_field(FIELD_HEADING_PATH)
+                .context("Missing heading_path field in schema")?;
 
             let mut query_parser = QueryParser::for_index(
                 &index_clone,
@@ -179,11 +391,13 @@ impl Bm25StoreTrait for TantivyBm25Store {
                     dependencies_field,
                     file_path_field,
                     node_type_field,
+                    heading_path_field,
                 ],
             );
 
-            // **Field Weights**: symbol_name^5.0, dependencies^2.0, content^1.0
+            // **Field Weights**: symbol_name^5.0, heading_path^3.0, dependencies^2.0, content^1.0
             query_parser.set_field_boost(symbol_name_field, 5.0);
+            query_parser.set_field_boost(heading_path_field, 3.0);
             query_parser.set_field_boost(dependencies_field, 2.0);
             query_parser.set_field_boost(content_field, 1.0);
 
@@ -192,9 +406,27 @@ impl Bm25StoreTrait for TantivyBm25Store {
                 .parse_query(&query_text)
                 .with_context(|| format!("Failed to parse query: {}", query_text))?;
 
+            // Exact-match filter criteria become required term clauses
+            let filtered_query = match &filter {
+                Some(filter) => Self::apply_filter(&schema, parsed_query, filter)?,
+                None => parsed_query,
+            };
+
+            // Path prefix and node type are tokenized fields, so they are checked on the
+            // stored values; a node type boost reorders results. In both cases every
+            // match is collected and the list is truncated afterwards.
+            let needs_post_filter = filter
+                .as_ref()
+                .is_some_and(|f| f.path_prefix.is_some() || f.node_type.is_some());
+            let collect_limit = if needs_post_filter || boost.is_some() {
+                (searcher.num_docs() as usize).max(limit)
+            } else {
+                limit
+            };
+
             // Execute search with TopDocs collector
             let top_docs = searcher
-                .search(&parsed_query, &TopDocs::with_limit(limit))
+                .search(&filtered_query, &TopDocs::with_limit(collect_limit.max(1)))
                 .context("Failed to execute search")?;
 
             // Extract field references for result conversion
@@ -204,13 +436,14 @@ impl Bm25StoreTrait for TantivyBm25Store {
             let symbol_name_field = schema
                 .get_field(FIELD_SYMBOL_NAME)
                 .context("Missing symbol_name field in schema")?;
-            let file_path_field = schema
-                .get_field(FIELD_FILE_PATH)
-                .context("Missing file_path field in schema")?;
 
             // Convert search results
-            let mut results = Vec::new();
+            let mut scored: Vec<(f32, String, String, String)> = Vec::new();
             for (bm25_score, doc_address) in top_docs {
+                if boost.is_none() && scored.len() >= limit {
+                    break;
+                }
+
                 let retrieved_doc = searcher
                     .doc(doc_address)
                     .context("Failed to retrieve document")?;
@@ -218,11 +451,39 @@ impl Bm25StoreTrait for TantivyBm25Store {
                 let id = Self::extract_text_value(&retrieved_doc, id_field);
                 let symbol_name = Self::extract_text_value(&retrieved_doc, symbol_name_field);
                 let file_path = Self::extract_text_value(&retrieved_doc, file_path_field);
-                let score = Self::normalize_score(bm25_score);
+                let node_type = Self::extract_text_value(&retrieved_doc, node_type_field);
+
+                if let Some(filter) = &filter {
+                    let path_ok = filter
+                        .path_prefix
+                        .as_deref()
+                        .is_none_or(|prefix| file_path.starts_with(prefix));
+                    let node_type_ok = filter
+                        .node_type
+                        .as_deref()
+                        .is_none_or(|expected| node_type_matches(&node_type, expected));
+                    if !path_ok || !node_type_ok {
+                        continue;
+                    }
+                }
 
-                results.push(Bm25Result::new(id, symbol_name, file_path, score));
+                // Boost is applied to the raw BM25 score, before normalization clamps it
+                let factor = boost.as_ref().map_or(1.0, |b| b.factor_for(&node_type)) as f32;
+                scored.push((bm25_score * factor, id, symbol_name, file_path));
             }
 
+            if boost.is_some() {
+                scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+                scored.truncate(limit);
+            }
+
+            let results: Vec<Bm25Result> = scored
+                .into_iter()
+                .map(|(bm25_score, id, symbol_name, file_path)| {
+                    Bm25Result::new(id, symbol_name, file_path, Self::normalize_score(bm25_score))
+                })
+                .collect();
+
             Ok::<Vec<Bm25Result>, anyhow::Error>(results)
         })
         .await
@@ -263,7 +524,7 @@ impl Bm25StoreTrait for TantivyBm25Store {
         let content = content.to_string();
         let keywords = keywords.to_string();
 
-        let writer_clone = Arc::clone(&self.writer);
+        let write_lock = Arc::clone(&self.write_lock);
         let index_clone = self.index.clone();
 
         // Use spawn_blocking to avoid blocking Tokio runtime
@@ -304,8 +565,8 @@ impl Bm25StoreTrait for TantivyBm25Store {
                 doc.add_text(dependencies_field, keyword);
             }
 
-            // Get writer lock
-            let mut writer = writer_clone.blocking_lock();
+            // Open a writer for this write
+            let mut writer = Self::open_writer(&write_lock, &index_clone)?;
 
             // Upsert: Delete existing document with same ID first (if exists)
             // This prevents duplicate documents when updating an existing entry
@@ -345,7 +606,7 @@ impl Bm25StoreTrait for TantivyBm25Store {
     async fn delete(&self, id: &str) -> Result<bool, AppError> {
         let id = id.to_string();
 
-        let writer_clone = Arc::clone(&self.writer);
+        let write_lock = Arc::clone(&self.write_lock);
         let reader_clone = Arc::clone(&self.reader);
         let index_clone = self.index.clone();
 
@@ -384,7 +645,7 @@ impl Bm25StoreTrait for TantivyBm25Store {
             }
 
             // Document exists, proceed with deletion
-            let mut writer = writer_clone.blocking_lock();
+            let mut writer = Self::open_writer(&write_lock, &index_clone)?;
 
             // Delete document by term
             writer.delete_term(term);
@@ -471,6 +732,18 @@ impl Bm25StoreTrait for TantivyBm25Store {
             let content_field = schema
                 .get_field(FIELD_CONTENT)
                 .context("Missing content field in schema")?;
+            let node_type_field = schema
+                .get_field(FIELD_NODE_TYPE)
+                .context("Missing node_type field in schema")?;
+            let pack_field = schema
+                .get_field(FIELD_PACK)
+                .context("Missing pack field in schema")?;
+            let start_page_field = schema
+                .get_field(FIELD_START_PAGE)
+                .context("Missing start_page field in schema")?;
+            let end_page_field = schema
+                .get_field(FIELD_END_PAGE)
+                .context("Missing end_page field in schema")?;
 
             // Create query for exact ID match
             let term = tantivy::Term::from_field_text(id_field, &id);
@@ -498,15 +771,19 @@ impl Bm25StoreTrait for TantivyBm25Store {
             let symbol_name = Self::extract_text_value(&retrieved_doc, symbol_name_field);
             let file_path = Self::extract_text_value(&retrieved_doc, file_path_field);
             let content = Self::extract_text_value(&retrieved_doc, content_field);
+            let node_type = Self::extract_text_value(&retrieved_doc, node_type_field);
+            let pack = retrieved_doc
+                .get_first(pack_field)
+                .and_then(|value| value.as_str().map(str::to_string));
+            let pages = Self::extract_pages(&retrieved_doc, start_page_field, end_page_field);
 
             // Return result with content and default score (not relevant for get_by_id)
-            Ok(Some(Bm25Result::with_content(
-                doc_id,
-                symbol_name,
-                file_path,
-                content,
-                Score::new(1.0),
-            )))
+            Ok(Some(
+                Bm25Result::with_content(doc_id, symbol_name, file_path, content, Score::new(1.0))
+                    .with_node_type(node_type)
+                    .with_pack(pack)
+                    .with_pages(pages),
+            ))
         })
         .await
         .map_err(|e| {
@@ -558,6 +835,18 @@ impl Bm25StoreTrait for TantivyBm25Store {
             let content_field = schema
                 .get_field(FIELD_CONTENT)
                 .context("Missing content field in schema")?;
+            let node_type_field = schema
+                .get_field(FIELD_NODE_TYPE)
+                .context("Missing node_type field in schema")?;
+            let pack_field = schema
+                .get_field(FIELD_PACK)
+                .context("Missing pack field in schema")?;
+            let start_page_field = schema
+                .get_field(FIELD_START_PAGE)
+                .context("Missing start_page field in schema")?;
+            let end_page_field = schema
+                .get_field(FIELD_END_PAGE)
+                .context("Missing end_page field in schema")?;
 
             let mut results = Vec::with_capacity(ids.len());
 
@@ -593,10 +882,18 @@ impl Bm25StoreTrait for TantivyBm25Store {
                 let symbol_name = Self::extract_text_value(&retrieved_doc, symbol_name_field);
                 let file_path = Self::extract_text_value(&retrieved_doc, file_path_field);
                 let content = Self::extract_text_value(&retrieved_doc, content_field);
+                let node_type = Self::extract_text_value(&retrieved_doc, node_type_field);
+                let pack = retrieved_doc
+                    .get_first(pack_field)
+                    .and_then(|value| value.as_str().map(str::to_string));
+                let pages = Self::extract_pages(&retrieved_doc, start_page_field, end_page_field);
 
                 doc_map.insert(
                     doc_id.clone(),
-                    Bm25Result::with_content(doc_id, symbol_name, file_path, content, Score::new(1.0)),
+                    Bm25Result::with_content(doc_id, symbol_name, file_path, content, Score::new(1.0))
+                        .with_node_type(node_type)
+                        .with_pack(pack)
+                        .with_pages(pages),
                 );
             }
 
@@ -640,60 +937,86 @@ impl Bm25StoreTrait for TantivyBm25Store {
             return Ok(());
         }
 
-        let writer_clone = Arc::clone(&self.writer);
+        let write_lock = Arc::clone(&self.write_lock);
         let index_clone = self.index.clone();
 
         tokio::task::spawn_blocking(move || {
-            // Use a separate scope to ensure rollback happens on any error
-            let result: Result<(), anyhow::Error> = (|| {
-                let schema = index_clone.schema();
-
-                // Get field references
-                let id_field = schema.get_field(FIELD_ID).context("Missing id field")?;
-                let file_path_field = schema.get_field(FIELD_FILE_PATH).context("Missing file_path field")?;
-                let symbol_name_field = schema.get_field(FIELD_SYMBOL_NAME).context("Missing symbol_name field")?;
-                let node_type_field = schema.get_field(FIELD_NODE_TYPE).context("Missing node_type field")?;
-                let content_field = schema.get_field(FIELD_CONTENT).context("Missing content field")?;
-                let dependencies_field = schema.get_field(FIELD_DEPENDENCIES).context("Missing dependencies field")?;
-
-                let mut writer = writer_clone.blocking_lock();
-
-                // **Defense Line**: Single transaction batch add
-                for chunk in &chunks {
-                    // Upsert: Delete existing document with same ID first
-                    let term = tantivy::Term::from_field_text(id_field, &chunk.id);
-                    writer.delete_term(term);
-
-                    // Create document
-                    let mut doc = TantivyDocument::new();
-                    doc.add_text(id_field, &chunk.id);
-                    doc.add_text(file_path_field, &chunk.file_path);
-                    doc.add_text(symbol_name_field, &chunk.symbol_name);
-                    doc.add_text(node_type_field, &chunk.node_type);
-                    doc.add_text(content_field, &chunk.content);
-
-                    // Dependencies: Multi-value field - add each dependency separately
-                    for dep in &chunk.dependencies {
-                        doc.add_text(dependencies_field, dep);
-                    }
+            let schema = index_clone.schema();
 
-                    writer.add_document(doc)
-                        .context("Failed to add document to batch")?;
+            // Get field references
+            let id_field = schema.get_field(FIELD_ID).context("Missing id field")?;
+            let file_path_field = schema.get_field(FIELD_FILE_PATH).context("Missing file_path field")?;
+            let symbol_name_field = schema.get_field(FIELD_SYMBOL_NAME).context("Missing symbol_name field")?;
+            let node_type_field = schema.get_field(FIELD_NODE_TYPE).context("Missing node_type field")?;
+            let content_field = schema.get_field(FIELD_CONTENT).context("Missing content field")?;
+            let dependencies_field = schema.get_field(FIELD_DEPENDENCIES).context("Missing dependencies field")?;
+            let pack_field = schema.get_field(FIELD_PACK).context("Missing pack field")?;
+            let heading_path_field = schema.get_field(FIELD_HEADING_PATH).context("Missing heading_path field")?;
+            let tags_field = schema.get_field(FIELD_TAGS).context("Missing tags field")?;
+            let version_field = schema.get_field(FIELD_VERSION).context("Missing version field")?;
+            let start_line_field = schema.get_field(FIELD_START_LINE).context("Missing start_line field")?;
+            let end_line_field = schema.get_field(FIELD_END_LINE).context("Missing end_line field")?;
+            let start_page_field = schema.get_field(FIELD_START_PAGE).context("Missing start_page field")?;
+            let end_page_field = schema.get_field(FIELD_END_PAGE).context("Missing end_page field")?;
+
+            // Dropping the writer on any error discards the uncommitted batch
+            let mut writer = Self::open_writer(&write_lock, &index_clone)?;
+
+            // **Defense Line**: Single transaction batch add
+            for chunk in &chunks {
+                // Upsert: Delete existing document with same ID first
+                let term = tantivy::Term::from_field_text(id_field, &chunk.id);
+                writer.delete_term(term);
+
+                // Create document
+                let mut doc = TantivyDocument::new();
+                doc.add_text(id_field, &chunk.id);
+                doc.add_text(file_path_field, &chunk.file_path);
+                doc.add_text(symbol_name_field, &chunk.symbol_name);
+                doc.add_text(node_type_field, &chunk.node_type);
+                doc.add_text(content_field, &chunk.content);
+
+                // Dependencies: Multi-value field - add each dependency separately
+                for dep in &chunk.dependencies {
+                    doc.add_text(dependencies_field, dep);
                 }
 
-                // **Defense Line**: Single commit - NEVER in a loop
-                writer.commit().context("Failed to commit batch")?;
+                if let Some(pack) = &chunk.pack {
+                    doc.add_text(pack_field, pack);
+                }
 
-                Ok(())
-            })();
+                // Heading path: Multi-value field - one value per breadcrumb level
+                for heading in &chunk.heading_path {
+                    doc.add_text(heading_path_field, heading);
+                }
 
-            // If the operation failed, rollback to clear in-memory state
-            if result.is_err() {
-                let mut writer = writer_clone.blocking_lock();
-                let _ = writer.rollback(); // Best-effort rollback, ignore errors
+                // Tags: Multi-value exact-match field
+                for tag in &chunk.tags {
+                    doc.add_text(tags_field, tag);
+                }
+
+                if let Some(version) = &chunk.version {
+                    doc.add_text(version_field, version);
+                }
+
+                if let Some(span) = chunk.span {
+                    doc.add_u64(start_line_field, u64::from(span.start));
+                    doc.add_u64(end_line_field, u64::from(span.end));
+                }
+
+                if let Some(pages) = chunk.pages {
+                    doc.add_u64(start_page_field, u64::from(pages.start));
+                    doc.add_u64(end_page_field, u64::from(pages.end));
+                }
+
+                writer.add_document(doc)
+                    .context("Failed to add document to batch")?;
             }
 
-            result
+            // **Defense Line**: Single commit - NEVER in a loop
+            writer.commit().context("Failed to commit batch")?;
+
+            Ok::<(), anyhow::Error>(())
         })
         .await
         .map_err(|e| {
@@ -704,6 +1027,119 @@ impl Bm25StoreTrait for TantivyBm25Store {
         })?
         .map_err(|e| AppError::Infra(InfraError::database("add_batch failed", Some(e))))
     }
+
+    /// Insert or replace AST chunks by ID
+    ///
+    /// # Implementation Notes
+    ///
+    /// `add_batch` already issues a `delete_term` on the ID before each add in
+    /// the same commit, so it has upsert semantics. Later duplicates in the
+    /// input delete earlier ones, so the last chunk wins.
+    async fn upsert_batch(&self, chunks: Vec<AstChunk>) -> Result<(), AppError> {
+        self.add_batch(chunks).await
+    }
+
+    /// List every stored document as an AST chunk
+    ///
+    /// # Implementation Notes
+    ///
+    /// 1. Uses spawn_blocking to avoid blocking Tokio runtime
+    /// 2. Collects all live documents with `AllQuery` + `DocSetCollector`
+    /// 3. Rebuilds chunks from stored fields (dependencies are multi-valued)
+    async fn list_chunks(&self) -> Result<Vec<AstChunk>, AppError> {
+        let reader_clone = Arc::clone(&self.reader);
+        let index_clone = self.index.clone();
+
+        tokio::task::spawn_blocking(move || Self::load_chunks(&reader_clone, &index_clone))
+        .await
+        .map_err(|e| {
+            AppError::Infra(InfraError::database(
+                "list_chunks task failed",
+                Some::<anyhow::Error>(e.into()),
+            ))
+        })?
+        .map_err(|e| AppError::Infra(InfraError::database("list_chunks failed", Some(e))))
+    }
+
+    /// Count live documents from segment metadata (deleted documents excluded)
+    async fn count(&self) -> Result<usize, AppError> {
+        self.reader.reload().map_err(|e| {
+            AppError::Infra(InfraError::database(
+                "Failed to reload index reader",
+                Some::<anyhow::Error>(e.into()),
+            ))
+        })?;
+        Ok(self.reader.searcher().num_docs() as usize)
+    }
+
+    /// SHA-256 of `meta.json`, which Tantivy rewrites atomically on every commit
+    async fn version(&self) -> Result<Option<String>, AppError> {
+        use tantivy::Directory;
+
+        let meta = self
+            .index
+            .directory()
+            .atomic_read(std::path::Path::new(META_FILE_NAME))
+            .map_err(|e| {
+                AppError::Infra(InfraError::database(
+                    "Failed to read index metadata",
+                    Some::<anyhow::Error>(e.into()),
+                ))
+            })?;
+        Ok(Some(format!("{:x}", Sha256::digest(&meta))))
+    }
+
+    /// Delete every document matching a filter
+    ///
+    /// # Implementation Notes
+    ///
+    /// `file_path` and `node_type` are jieba-tokenized, so there is no single
+    /// term to delete by. Matching documents are selected from stored fields
+    /// with `ChunkFilter::matches` and then removed with one `delete_term` per
+    /// ID in a single commit.
+    async fn delete_where(&self, filter: &ChunkFilter) -> Result<usize, AppError> {
+        if filter.is_empty() {
+            return Err(AppError::Domain(DomainError::not_allowed(
+                "delete_where requires at least one filter criterion",
+            )));
+        }
+
+        let filter = filter.clone();
+        let write_lock = Arc::clone(&self.write_lock);
+        let reader_clone = Arc::clone(&self.reader);
+        let index_clone = self.index.clone();
+
+        tokio::task::spawn_blocking(move || {
+            let matched: Vec<AstChunk> = Self::load_chunks(&reader_clone, &index_clone)?
+                .into_iter()
+                .filter(|chunk| filter.matches(chunk))
+                .collect();
+
+            if matched.is_empty() {
+                return Ok(0);
+            }
+
+            let id_field = index_clone.schema().get_field(FIELD_ID).context("Missing id field")?;
+            let mut writer = Self::open_writer(&write_lock, &index_clone)?;
+            let ids: std::collections::BTreeSet<&str> =
+                matched.iter().map(|chunk| chunk.id.as_str()).collect();
+            for id in ids {
+                writer.delete_term(tantivy::Term::from_field_text(id_field, id));
+            }
+
+            writer.commit().context("Failed to commit bulk delete")?;
+
+            Ok::<usize, anyhow::Error>(matched.len())
+        })
+        .await
+        .map_err(|e| {
+            AppError::Infra(InfraError::database(
+                "delete_where task failed",
+                Some::<anyhow::Error>(e.into()),
+            ))
+        })?
+        .map_err(|e| AppError::Infra(InfraError::database("delete_where failed", Some(e))))
+    }
 }
 
 #[cfg(test)]
@@ -795,7 +1231,7 @@ mod tests {
         // Delete it
         let result = store.delete("doc-1").await;
         assert!(result.is_ok());
-        assert_eq!(result.unwrap(), true);
+        assert!(result.unwrap());
 
         // Search should return None (no results)
         let query = Query::new("Test", 10);
@@ -831,7 +1267,7 @@ mod tests {
         let result = store.health_check().await;
 
         assert!(result.is_ok());
-        assert_eq!(result.unwrap(), true);
+        assert!(result.unwrap());
     }
 
     #[tokio::test]
@@ -944,6 +1380,12 @@ mod tests {
                 node_type: "class".to_string(),
                 content: "class AuthManager { ... }".to_string(),
                 dependencies: vec!["User".to_string()],
+                pack: None,
+                heading_path: vec![],
+                tags: vec![],
+                version: None,
+                span: None,
+                pages: None,
                 vector: None,
             },
             AstChunk {
@@ -953,6 +1395,12 @@ mod tests {
                 node_type: "class".to_string(),
                 content: "class User { ... }".to_string(),
                 dependencies: vec![],
+                pack: None,
+                heading_path: vec![],
+                tags: vec![],
+                version: None,
+                span: None,
+                pages: None,
                 vector: None,
             },
         ];
@@ -994,6 +1442,12 @@ mod tests {
             node_type: "function".to_string(),
             content: "fn test() {}".to_string(),
             dependencies: vec![],
+            pack: None,
+            heading_path: vec![],
+            tags: vec![],
+            version: None,
+            span: None,
+            pages: None,
             vector: None,
         }];
 
@@ -1012,6 +1466,12 @@ mod tests {
             node_type: "function".to_string(),
             content: "fn test() { updated }".to_string(),
             dependencies: vec![],
+            pack: None,
+            heading_path: vec![],
+            tags: vec![],
+            version: None,
+            span: None,
+            pages: None,
             vector: None,
         }];
 
@@ -1023,4 +1483,286 @@ mod tests {
         assert_eq!(results2.len(), 1, "Should have exactly one document after upsert");
         assert_eq!(results2[0].id, "rollback-test");
     }
+
+    #[tokio::test]
+    async fn test_list_chunks_returns_all_documents() {
+        let (store, _temp_dir) = create_test_store().await;
+
+        assert!(store.list_chunks().await.unwrap().is_empty());
+
+        store
+            .add_batch(vec![
+                AstChunk::new(
+                    "list-1",
+                    "src/auth.rs",
+                    "AuthManager",
+                    "class",
+                    "struct AuthManager;",
+                    vec!["User".to_string(), "Session".to_string()],
+                ),
+                AstChunk::without_dependencies("list-2", "src/user.rs", "User", "class", "struct User;"),
+            ])
+            .await
+            .expect("Batch add should succeed");
+
+        let mut chunks = store.list_chunks().await.expect("list_chunks should succeed");
+        chunks.sort_by(|a, b| a.id.cmp(&b.id));
+
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[0].id, "list-1");
+        assert_eq!(chunks[0].node_type, "class");
+        assert_eq!(chunks[0].content, "struct AuthManager;");
+        assert_eq!(chunks[0].dependencies, vec!["User", "Session"]);
+        assert_eq!(chunks[1].id, "list-2");
+        assert!(chunks[1].dependencies.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_count_excludes_deleted_documents() {
+        let (store, _temp_dir) = create_test_store().await;
+        assert_eq!(store.count().await.unwrap(), 0);
+
+        store
+            .add_batch(vec![
+                AstChunk::without_dependencies("count-1", "a.md", "A", "doc", "alpha"),
+                AstChunk::without_dependencies("count-2", "b.md", "B", "doc", "beta"),
+            ])
+            .await
+            .expect("Batch add should succeed");
+        assert_eq!(store.count().await.unwrap(), 2);
+
+        assert!(store.delete("count-1").await.unwrap());
+        assert_eq!(store.count().await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_writer_is_released_after_each_write() {
+        let (writer_store, temp_dir) = create_test_store().await;
+        // A second store on the same directory (another process) can write in turn
+        let reader_store =
+            TantivyBm25Store::from_directory(temp_dir.path()).expect("Read-only store should open");
+        let before = reader_store.version().await.unwrap();
+        assert!(before.is_some());
+
+        writer_store
+            .add_batch(vec![AstChunk::without_dependencies(
+                "v-1", "a.md", "A", "doc", "alpha",
+            )])
+            .await
+            .expect("Batch add should succeed");
+
+        let after = reader_store.version().await.unwrap();
+        assert_ne!(
+            after, before,
+            "A commit from another store changes the version"
+        );
+        assert_eq!(after, writer_store.version().await.unwrap());
+        assert_eq!(reader_store.count().await.unwrap(), 1);
+
+        // Neither store keeps the directory lock after committing
+        reader_store
+            .add_batch(vec![AstChunk::without_dependencies(
+                "v-2", "b.md", "B", "doc", "beta",
+            )])
+            .await
+            .expect("Second store should write after the first committed");
+        assert!(writer_store.delete("v-1").await.unwrap());
+        assert_eq!(reader_store.count().await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_heading_path_is_stored_and_searchable() {
+        let (store, _temp_dir) = create_test_store().await;
+
+        store
+            .add_batch(vec![
+                AstChunk::without_dependencies("hp-1", "docs/guide.md", "Linux", "file", "apt install foo")
+                    .with_heading_path(vec!["Guide".into(), "Installation".into(), "Linux".into()]),
+                AstChunk::without_dependencies("hp-2", "docs/guide.md", "Usage", "file", "run foo"),
+            ])
+            .await
+            .expect("Batch add should succeed");
+
+        let results = store
+            .search(&Query::new("Installation", 10))
+            .await
+            .unwrap()
+            .expect("Breadcrumb terms should match");
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].id, "hp-1");
+
+        let mut chunks = store.list_chunks().await.unwrap();
+        chunks.sort_by(|a, b| a.id.cmp(&b.id));
+        assert_eq!(chunks[0].heading_path, vec!["Guide", "Installation", "Linux"]);
+        assert!(chunks[1].heading_path.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_search_with_filter() {
+        let (store, _temp_dir) = create_test_store().await;
+
+        store
+            .add_batch(vec![
+                AstChunk::without_dependencies("f-1", "docs/blocks.md", "Blocks", "file", "event handling")
+                    .with_tags(vec!["blocks".into(), "events".into()])
+                    .with_version("1.20")
+                    .with_pack("guide"),
+                AstChunk::without_dependencies("f-2", "docs/items.md", "Items", "file", "event handling")
+                    .with_tags(vec!["items".into()])
+                    .with_version("1.21")
+                    .with_pack("api"),
+                AstChunk::without_dependencies("f-3", "src/events.rs", "Events", "class", "event handling"),
+            ])
+            .await
+            .expect("Batch add should succeed");
+
+        let search_ids = |filter: ChunkFilter| {
+            let store = &store;
+            async move {
+                let mut ids: Vec<String> = store
+                    .search(&Query::new("event", 10).with_filter(filter))
+                    .await
+                    .unwrap()
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|r| r.id)
+                    .collect();
+                ids.sort();
+                ids
+            }
+        };
+
+        assert_eq!(search_ids(ChunkFilter::new().with_tag("events")).await, vec!["f-1"]);
+        assert_eq!(search_ids(ChunkFilter::new().with_version("1.21")).await, vec!["f-2"]);
+        assert_eq!(search_ids(ChunkFilter::new().with_path_prefix("docs/")).await, vec!["f-1", "f-2"]);
+        assert_eq!(search_ids(ChunkFilter::new().with_node_type("class")).await, vec!["f-3"]);
+        assert!(search_ids(ChunkFilter::new().with_tag("events").with_node_type("class")).await.is_empty());
+        assert_eq!(search_ids(ChunkFilter::new().with_packs(["guide", "api"])).await, vec!["f-1", "f-2"]);
+        assert_eq!(search_ids(ChunkFilter::new().with_packs(["guide", "other"])).await, vec!["f-1"]);
+        let f2 = store.get_by_ids(&["f-2".to_string(), "f-3".to_string()]).await.unwrap();
+        assert_eq!(f2[0].as_ref().unwrap().pack.as_deref(), Some("api"));
+        assert_eq!(f2[1].as_ref().unwrap().pack, None);
+
+        let mut chunks = store.list_chunks().await.unwrap();
+        chunks.sort_by(|a, b| a.id.cmp(&b.id));
+        assert_eq!(chunks[0].tags, vec!["blocks", "events"]);
+        assert_eq!(chunks[0].version.as_deref(), Some("1.20"));
+        assert!(chunks[2].tags.is_empty() && chunks[2].version.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_code_node_type_filter_and_boost() {
+        let (store, _temp_dir) = create_test_store().await;
+
+        store
+            .add_batch(vec![
+                AstChunk::without_dependencies("p-1", "docs/a.md", "Spawn", "prose", "spawn spawn"),
+                AstChunk::without_dependencies("c-1", "docs/a.md", "Spawn", "code:rust", "spawn entity"),
+                AstChunk::without_dependencies("c-2", "docs/b.md", "Spawn", "code:typescript", "spawn entity"),
+            ])
+            .await
+            .unwrap();
+
+        let ids = |results: Vec<Bm25Result>| {
+            let mut ids: Vec<String> = results.into_iter().map(|r| r.id).collect();
+            ids.sort();
+            ids
+        };
+
+        let code = store
+            .search(&Query::new("spawn", 10).with_filter(ChunkFilter::new().with_node_type("code")))
+            .await
+            .unwrap()
+            .unwrap_or_default();
+        assert_eq!(ids(code), vec!["c-1", "c-2"]);
+
+        let rust = store
+            .search(&Query::new("spawn", 10).with_filter(ChunkFilter::new().with_node_type("code:rust")))
+            .await
+            .unwrap()
+            .unwrap_or_default();
+        assert_eq!(ids(rust), vec!["c-1"]);
+
+        // Without a boost the prose chunk with the higher term frequency ranks first
+        let plain = store.search(&Query::new("spawn", 1)).await.unwrap().unwrap();
+        assert_eq!(plain[0].id, "p-1");
+
+        let boosted = store
+            .search(&Query::new("spawn", 1).with_boost("code:rust", 10.0))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(boosted.len(), 1);
+        assert_eq!(boosted[0].id, "c-1");
+    }
+
+    #[tokio::test]
+    async fn test_upsert_batch_is_idempotent() {
+        let (store, _temp_dir) = create_test_store().await;
+
+        let chunks = vec![
+            AstChunk::without_dependencies("upsert-1", "a.md", "Alpha", "file", "alpha v1"),
+            AstChunk::without_dependencies("upsert-2", "b.md", "Beta", "file", "beta v1"),
+        ];
+        store.upsert_batch(chunks.clone()).await.expect("First upsert should succeed");
+        store.upsert_batch(chunks).await.expect("Repeated upsert should succeed");
+        store
+            .upsert(AstChunk::without_dependencies("upsert-1", "a.md", "Alpha", "file", "alpha v2"))
+            .await
+            .expect("Single upsert should succeed");
+
+        let mut chunks = store.list_chunks().await.unwrap();
+        chunks.sort_by(|a, b| a.id.cmp(&b.id));
+        let summary: Vec<(&str, &str)> = chunks
+            .iter()
+            .map(|c| (c.id.as_str(), c.content.as_str()))
+            .collect();
+        assert_eq!(summary, vec![("upsert-1", "alpha v2"), ("upsert-2", "beta v1")]);
+    }
+
+    #[tokio::test]
+    async fn test_delete_where_by_path_prefix_pack_and_node_type() {
+        let (store, _temp_dir) = create_test_store().await;
+
+        store
+            .add_batch(vec![
+                AstChunk::without_dependencies("a-0", "docs/a.md", "A", "file", "a0").with_pack("guide"),
+                AstChunk::without_dependencies("a-1", "docs/a.md", "A", "file", "a1").with_pack("guide"),
+                AstChunk::without_dependencies("b-0", "docs/b.md", "B", "class", "b0").with_pack("guide"),
+                AstChunk::without_dependencies("c-0", "src/c.rs", "C", "class", "c0").with_pack("core"),
+            ])
+            .await
+            .unwrap();
+
+        let deleted = store
+            .delete_where(&ChunkFilter::new().with_path_prefix("docs/a"))
+            .await
+            .expect("Prefix delete should succeed");
+        assert_eq!(deleted, 2);
+
+        let deleted = store
+            .delete_where(&ChunkFilter::new().with_pack("guide").with_node_type("class"))
+            .await
+            .expect("Pack + node type delete should succeed");
+        assert_eq!(deleted, 1);
+
+        let remaining = store.list_chunks().await.unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].id, "c-0");
+        assert_eq!(remaining[0].pack.as_deref(), Some("core"));
+
+        let deleted = store
+            .delete_where(&ChunkFilter::new().with_pack("missing"))
+            .await
+            .unwrap();
+        assert_eq!(deleted, 0);
+    }
+
+    #[tokio::test]
+    async fn test_delete_where_rejects_empty_filter() {
+        let (store, _temp_dir) = create_test_store().await;
+
+        let result = store.delete_where(&ChunkFilter::new()).await;
+        assert!(matches!(result, Err(AppError::Domain(DomainError::NotAllowed(_)))));
+    }
 }
@@ -8,7 +8,7 @@
 use async_trait::async_trait;
 
 use crate::kernel::errors::AppError;
-use crate::kernel::types::{AstChunk, Hit, Query, Score};
+use crate::kernel::types::{AstChunk, ChunkFilter, Hit, PageRange, Query, Score};
 
 /// BM25 search result with document metadata
 ///
@@ -24,6 +24,12 @@ pub struct Bm25Result {
     pub file_path: String,
     /// Document content (optional, only populated when retrieved via get_by_id)
     pub content: Option<String>,
+    /// Node type (optional, only populated when retrieved via get_by_id)
+    pub node_type: Option<String>,
+    /// Pack the chunk belongs to (only populated when retrieved via get_by_id)
+    pub pack: Option<String>,
+    /// Page range of paginated sources (only populated when retrieved via get_by_id)
+    pub pages: Option<PageRange>,
     /// BM25 relevance score
     pub score: Score,
 }
@@ -36,6 +42,9 @@ impl Bm25Result {
             symbol_name,
             file_path,
             content: None,
+            node_type: None,
+            pack: None,
+            pages: None,
             score,
         }
     }
@@ -53,10 +62,31 @@ impl Bm25Result {
             symbol_name,
             file_path,
             content: Some(content),
+            node_type: None,
+            pack: None,
+            pages: None,
             score,
         }
     }
 
+    /// Attach the node type of the stored chunk
+    pub fn with_node_type(mut self, node_type: impl Into<String>) -> Self {
+        self.node_type = Some(node_type.into());
+        self
+    }
+
+    /// Attach the pack of the stored chunk
+    pub fn with_pack(mut self, pack: Option<String>) -> Self {
+        self.pack = pack;
+        self
+    }
+
+    /// Attach the page range of the stored chunk
+    pub fn with_pages(mut self, pages: Option<PageRange>) -> Self {
+        self.pages = pages;
+        self
+    }
+
     /// Convert to kernel Hit type
     pub fn to_hit(self) -> Hit {
         Hit::new(self.id, self.score)
@@ -206,6 +236,71 @@ pub trait Bm25StoreTrait: Send + Sync {
     /// * `Ok(())` - Batch add successful
     /// * `Err(AppError)` - Batch add failed
     async fn add_batch(&self, chunks: Vec<AstChunk>) -> Result<(), AppError>;
+
+    /// Insert or replace AST chunks by ID
+    ///
+    /// Unlike `add_batch`, re-running `upsert_batch` with the same chunks leaves
+    /// exactly one row per ID, so rebuilding an index is idempotent. If the input
+    /// repeats an ID, the last chunk wins.
+    ///
+    /// # Parameters
+    ///
+    /// * `chunks` - AST chunk list
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - Upsert successful
+    /// * `Err(AppError)` - Upsert failed
+    async fn upsert_batch(&self, chunks: Vec<AstChunk>) -> Result<(), AppError>;
+
+    /// Insert or replace a single AST chunk by ID
+    ///
+    /// # Default Implementation
+    ///
+    /// Delegates to `upsert_batch` with a single chunk.
+    async fn upsert(&self, chunk: AstChunk) -> Result<(), AppError> {
+        self.upsert_batch(vec![chunk]).await
+    }
+
+    /// List every stored document as an AST chunk
+    ///
+    /// Used by consistency checks to enumerate the full index. Rows that share
+    /// an ID are all returned, so callers can detect duplicates.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(chunks)` - All stored documents (`chunk.vector` is always `None`)
+    /// * `Err(AppError)` - Enumeration failed
+    async fn list_chunks(&self) -> Result<Vec<AstChunk>, AppError>;
+
+    /// Number of stored documents (duplicate rows are all counted)
+    ///
+    /// The default implementation enumerates every document with `list_chunks`;
+    /// implementations should override it with a cheaper count.
+    async fn count(&self) -> Result<usize, AppError> {
+        Ok(self.list_chunks().await?.len())
+    }
+
+    /// Identifier of the committed index state
+    ///
+    /// Changes whenever a commit becomes visible on disk, including commits
+    /// made by another process, so callers can notice external rebuilds.
+    /// The default implementation returns `None` (not tracked).
+    async fn version(&self) -> Result<Option<String>, AppError> {
+        Ok(None)
+    }
+
+    /// Delete every stored document matching a filter
+    ///
+    /// # Parameters
+    ///
+    /// * `filter` - Path prefix, pack and/or node type criteria (AND-ed)
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(count)` - Number of rows removed (duplicate rows are all counted)
+    /// * `Err(AppError)` - Filter was empty or the delete failed
+    async fn delete_where(&self, filter: &ChunkFilter) -> Result<usize, AppError>;
 }
 
 #[cfg(test)]
@@ -248,6 +343,18 @@ mod tests {
         async fn add_batch(&self, _chunks: Vec<AstChunk>) -> Result<(), AppError> {
             Ok(())
         }
+
+        async fn upsert_batch(&self, _chunks: Vec<AstChunk>) -> Result<(), AppError> {
+            Ok(())
+        }
+
+        async fn list_chunks(&self) -> Result<Vec<AstChunk>, AppError> {
+            Ok(vec![])
+        }
+
+        async fn delete_where(&self, _filter: &ChunkFilter) -> Result<usize, AppError> {
+            Ok(0)
+        }
     }
 
     #[tokio::test]
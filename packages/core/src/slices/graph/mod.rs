@@ -0,0 +1,451 @@
+//! Document link graph
+//!
+//! The parser records the raw links of every section (`DocLink`). At build time
+//! they are resolved to section IDs and stored as an adjacency table, which
+//! answers three questions for doc owners:
+//!
+//! - **Outbound**: which sections does this section link to?
+//! - **Backlinks**: which sections link to this one?
+//! - **Orphans**: which documents are never linked from any other document?
+//!
+//! ## Resolution Rules
+//!
+//! - `#anchor` resolves within the source document
+//! - `other.md`, `../dir/other.md` resolve relative to the source file; the
+//!   extension may be omitted (`.md` and `.mdx` are tried)
+//! - `/dir/other.md` matches any indexed file whose path ends with `dir/other.md`
+//! - `[[Page]]` matches a file stem or document title (case-insensitive)
+//! - An anchor selects the section whose heading slug matches; without an
+//!   anchor the link points to the document's first section
+//!
+//! Links that match no file or heading are kept as `unresolved` so broken
+//! links can be reported.
+//!
+//! ## File Format
+//!
+//! The graph is stored as a single JSON file (`links.json`) next to the index
+//! directories and is replaced atomically on every build.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::kernel::errors::{AppError, InfraError};
+use crate::parser::{slugify, DocLink, LinkKind};
+
+/// Default link graph file name, stored next to the index directories
+pub const LINK_GRAPH_FILE_NAME: &str = "links.json";
+
+/// A section to be added to the link graph
+#[derive(Debug, Clone)]
+pub struct GraphNode {
+    /// Section (chunk) ID
+    pub id: String,
+    /// Source file path
+    pub file_path: String,
+    /// Section heading, matched against link anchors
+    pub title: String,
+    /// Document title, matched against wiki-link page names
+    pub doc_title: String,
+    /// Raw links found in the section
+    pub links: Vec<DocLink>,
+}
+
+/// Stored information about a graph node
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NodeInfo {
+    /// Source file path
+    pub file_path: String,
+    /// Section heading
+    pub title: String,
+}
+
+/// A resolved link between two sections
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct LinkEdge {
+    /// Linking section ID
+    pub source: String,
+    /// Linked section ID
+    pub target: String,
+    /// How the link was written
+    pub kind: LinkKind,
+}
+
+/// A link that matched no indexed file or heading
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UnresolvedLink {
+    /// Linking section ID
+    pub source: String,
+    /// Link target as written (`path#anchor`)
+    pub target: String,
+}
+
+/// Relations of a single section, returned by `SearchEngine::related`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RelatedDocuments {
+    /// Section ID
+    pub id: String,
+    /// Sections this section links to
+    pub outbound: Vec<String>,
+    /// Sections linking to this section
+    pub backlinks: Vec<String>,
+    /// True if no other document links to this section's document
+    pub orphan: bool,
+}
+
+/// Adjacency table of resolved document links
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LinkGraph {
+    /// Every indexed section, keyed by ID
+    nodes: BTreeMap<String, NodeInfo>,
+    /// Resolved links, sorted and deduplicated
+    edges: Vec<LinkEdge>,
+    /// Links that could not be resolved
+    unresolved: Vec<UnresolvedLink>,
+}
+
+impl LinkGraph {
+    /// Resolve the links of all sections and build the adjacency table
+    ///
+    /// `nodes` must be in document order: the first section of a file is the
+    /// target of links without an anchor.
+    pub fn build(nodes: &[GraphNode]) -> Self {
+        let resolver = Resolver::new(nodes);
+        let mut edges = BTreeSet::new();
+        let mut unresolved = Vec::new();
+
+        for node in nodes {
+            for link in &node.links {
+                match resolver.resolve(node, link) {
+                    // Self-links carry no relation
+                    Some(target) if target == node.id => {}
+                    Some(target) => {
+                        edges.insert(LinkEdge {
+                            source: node.id.clone(),
+                            target: target.to_string(),
+                            kind: link.kind,
+                        });
+                    }
+                    None => unresolved.push(UnresolvedLink {
+                        source: node.id.clone(),
+                        target: match &link.anchor {
+                            Some(anchor) => format!("{}#{}", link.target, anchor),
+                            None => link.target.clone(),
+                        },
+                    }),
+                }
+            }
+        }
+
+        Self {
+            nodes: nodes
+                .iter()
+                .map(|node| {
+                    let info = NodeInfo {
+                        file_path: node.file_path.clone(),
+                        title: node.title.clone(),
+                    };
+                    (node.id.clone(), info)
+                })
+                .collect(),
+            edges: edges.into_iter().collect(),
+            unresolved,
+        }
+    }
+
+    /// Load a graph from disk; a missing file yields an empty graph
+    pub fn load(path: &Path) -> Result<Self, AppError> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let data = fs::read_to_string(path).map_err(|e| {
+            AppError::Infra(InfraError::io(path, "Failed to read link graph", Some(e)))
+        })?;
+        serde_json::from_str(&data).map_err(|e| {
+            AppError::Infra(InfraError::serialization("Invalid link graph", Some(e)))
+        })
+    }
+
+    /// Write the graph to disk, replacing any previous file atomically
+    pub fn save(&self, path: &Path) -> Result<(), AppError> {
+        let io_error = |e: std::io::Error| {
+            AppError::Infra(InfraError::io(path, "Failed to write link graph", Some(e)))
+        };
+        let data = serde_json::to_string_pretty(self).map_err(|e| {
+            AppError::Infra(InfraError::serialization("Failed to serialize link graph", Some(e)))
+        })?;
+
+        let tmp_path = path.with_extension("json.tmp");
+        fs::write(&tmp_path, data).map_err(io_error)?;
+        fs::rename(&tmp_path, path).map_err(io_error)
+    }
+
+    /// Whether the section is part of the graph
+    pub fn contains(&self, id: &str) -> bool {
+        self.nodes.contains_key(id)
+    }
+
+    /// Stored information about a section
+    pub fn node(&self, id: &str) -> Option<&NodeInfo> {
+        self.nodes.get(id)
+    }
+
+    /// All resolved links
+    pub fn edges(&self) -> &[LinkEdge] {
+        &self.edges
+    }
+
+    /// Links that matched no indexed file or heading
+    pub fn unresolved(&self) -> &[UnresolvedLink] {
+        &self.unresolved
+    }
+
+    /// Sections the given section links to
+    pub fn outbound(&self, id: &str) -> Vec<String> {
+        let mut targets: Vec<String> = self
+            .edges
+            .iter()
+            .filter(|edge| edge.source == id)
+            .map(|edge| edge.target.clone())
+            .collect();
+        targets.dedup();
+        targets
+    }
+
+    /// Sections linking to the given section
+    pub fn backlinks(&self, id: &str) -> Vec<String> {
+        let sources: BTreeSet<&str> = self
+            .edges
+            .iter()
+            .filter(|edge| edge.target == id)
+            .map(|edge| edge.source.as_str())
+            .collect();
+        sources.into_iter().map(str::to_string).collect()
+    }
+
+    /// Files that no other file links to, sorted by path
+    ///
+    /// Links within the same document do not make it reachable.
+    pub fn orphans(&self) -> Vec<String> {
+        let linked: BTreeSet<&str> = self
+            .edges
+            .iter()
+            .filter_map(|edge| {
+                let source = self.nodes.get(&edge.source)?;
+                let target = self.nodes.get(&edge.target)?;
+                (source.file_path != target.file_path).then_some(target.file_path.as_str())
+            })
+            .collect();
+
+        let files: BTreeSet<&str> = self.nodes.values().map(|n| n.file_path.as_str()).collect();
+        files
+            .into_iter()
+            .filter(|file| !linked.contains(file))
+            .map(str::to_string)
+            .collect()
+    }
+
+    /// Outbound links, backlinks and orphan status of a section
+    ///
+    /// Returns `None` if the section is not part of the graph.
+    pub fn related(&self, id: &str) -> Option<RelatedDocuments> {
+        let node = self.nodes.get(id)?;
+        let orphan = self.orphans().contains(&node.file_path);
+        Some(RelatedDocuments {
+            id: id.to_string(),
+            outbound: self.outbound(id),
+            backlinks: self.backlinks(id),
+            orphan,
+        })
+    }
+}
+
+/// Lookup tables used while resolving links
+struct Resolver<'a> {
+    /// Normalized file path → sections in document order
+    files: HashMap<String, Vec<&'a GraphNode>>,
+    /// Lowercase file stem or document title → normalized file path
+    pages: HashMap<String, String>,
+}
+
+impl<'a> Resolver<'a> {
+    fn new(nodes: &'a [GraphNode]) -> Self {
+        let mut files: HashMap<String, Vec<&GraphNode>> = HashMap::new();
+        let mut pages = HashMap::new();
+
+        for node in nodes {
+            let path = normalize_path(Path::new(&node.file_path));
+            if let Some(stem) = Path::new(&path).file_stem().and_then(|s| s.to_str()) {
+                pages.entry(stem.to_lowercase()).or_insert_with(|| path.clone());
+            }
+            pages
+                .entry(node.doc_title.to_lowercase())
+                .or_insert_with(|| path.clone());
+            files.entry(path).or_default().push(node);
+        }
+
+        Self { files, pages }
+    }
+
+    fn resolve(&self, source: &GraphNode, link: &DocLink) -> Option<&'a str> {
+        let file = if link.is_local() {
+            normalize_path(Path::new(&source.file_path))
+        } else if link.kind == LinkKind::Wiki {
+            self.pages.get(&link.target.to_lowercase())?.clone()
+        } else {
+            self.resolve_path(&source.file_path, &link.target)?
+        };
+
+        let sections = self.files.get(&file)?;
+        match &link.anchor {
+            Some(anchor) => {
+                let slug = slugify(anchor);
+                sections
+                    .iter()
+                    .find(|section| slugify(&section.title) == slug)
+                    .map(|section| section.id.as_str())
+            }
+            None => sections.first().map(|section| section.id.as_str()),
+        }
+    }
+
+    /// Resolve a path link to the normalized path of an indexed file
+    fn resolve_path(&self, source_file: &str, target: &str) -> Option<String> {
+        let candidates = |base: String| {
+            [
+                base.clone(),
+                format!("{}.md", base),
+                format!("{}.mdx", base),
+            ]
+        };
+
+        if let Some(absolute) = target.strip_prefix('/') {
+            let suffix = normalize_path(Path::new(absolute));
+            return candidates(suffix).into_iter().find_map(|suffix| {
+                self.files
+                    .keys()
+                    .filter(|file| *file == &suffix || file.ends_with(&format!("/{}", suffix)))
+                    .min()
+                    .cloned()
+            });
+        }
+
+        let base_dir = Path::new(source_file).parent().unwrap_or(Path::new(""));
+        let joined = normalize_path(&base_dir.join(target));
+        candidates(joined)
+            .into_iter()
+            .find(|candidate| self.files.contains_key(candidate))
+    }
+}
+
+/// Lexically normalize a path (`./`, `..`) and use `/` as separator
+fn normalize_path(path: &Path) -> String {
+    let mut parts: Vec<String> = Vec::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if matches!(parts.last(), Some(last) if last != "..") {
+                    parts.pop();
+                } else {
+                    parts.push("..".to_string());
+                }
+            }
+            Component::Normal(part) => parts.push(part.to_string_lossy().into_owned()),
+            Component::RootDir | Component::Prefix(_) => {}
+        }
+    }
+    PathBuf::from_iter(&parts).to_string_lossy().replace('\\', "/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::extract_links;
+    use tempfile::TempDir;
+
+    fn node(id: &str, file_path: &str, title: &str, doc_title: &str, content: &str) -> GraphNode {
+        GraphNode {
+            id: id.to_string(),
+            file_path: file_path.to_string(),
+            title: title.to_string(),
+            doc_title: doc_title.to_string(),
+            links: extract_links(content),
+        }
+    }
+
+    fn sample_graph() -> LinkGraph {
+        LinkGraph::build(&[
+            node("g-0", "docs/guide.md", "Intro", "Guide", "See [events](events.md) and [usage](#usage)."),
+            node("g-1", "docs/guide.md", "Usage", "Guide", "Craft with [[Items#Crafting]]."),
+            node("e-0", "docs/events.md", "Events", "Block Events", "Back to [guide](./guide)."),
+            node("e-1", "docs/events.md", "Block Events", "Block Events", "[broken](missing.md) [bad](#nope)"),
+            node("i-0", "docs/sub/items.md", "Items", "Items", "Top [events](../events.md#block-events)."),
+            node("i-1", "docs/sub/items.md", "Crafting", "Items", "Absolute [link](/docs/guide.md#usage)."),
+            node("o-0", "docs/orphan.md", "Orphan", "Orphan", "Links out to [[Block Events]]."),
+        ])
+    }
+
+    #[test]
+    fn test_outbound_links_resolve_paths_anchors_and_wiki_pages() {
+        let graph = sample_graph();
+
+        assert_eq!(graph.outbound("g-0"), vec!["e-0", "g-1"]);
+        assert_eq!(graph.outbound("g-1"), vec!["i-1"]);
+        assert_eq!(graph.outbound("e-0"), vec!["g-0"]);
+        assert_eq!(graph.outbound("i-0"), vec!["e-1"]);
+        assert_eq!(graph.outbound("i-1"), vec!["g-1"]);
+        assert_eq!(graph.outbound("o-0"), vec!["e-0"]);
+    }
+
+    #[test]
+    fn test_backlinks_and_unresolved_links() {
+        let graph = sample_graph();
+
+        assert_eq!(graph.backlinks("e-0"), vec!["g-0", "o-0"]);
+        assert_eq!(graph.backlinks("g-1"), vec!["g-0", "i-1"]);
+        assert!(graph.backlinks("o-0").is_empty());
+
+        let unresolved: Vec<&str> = graph.unresolved().iter().map(|u| u.target.as_str()).collect();
+        assert_eq!(unresolved, vec!["missing.md", "#nope"]);
+    }
+
+    #[test]
+    fn test_orphans_ignore_links_within_the_same_document() {
+        let graph = sample_graph();
+        assert_eq!(graph.orphans(), vec!["docs/orphan.md"]);
+
+        let related = graph.related("o-0").unwrap();
+        assert!(related.orphan);
+        assert_eq!(related.outbound, vec!["e-0"]);
+        assert!(!graph.related("g-0").unwrap().orphan);
+        assert!(graph.related("missing").is_none());
+
+        let self_linked = LinkGraph::build(&[
+            node("a-0", "a.md", "A", "A", "[next](#b)"),
+            node("a-1", "a.md", "B", "A", "[self](#b)"),
+        ]);
+        assert_eq!(self_linked.outbound("a-1"), Vec::<String>::new());
+        assert_eq!(self_linked.orphans(), vec!["a.md"]);
+    }
+
+    #[test]
+    fn test_save_and_load_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join(LINK_GRAPH_FILE_NAME);
+
+        assert_eq!(LinkGraph::load(&path).unwrap(), LinkGraph::default());
+
+        let graph = sample_graph();
+        graph.save(&path).unwrap();
+        assert_eq!(LinkGraph::load(&path).unwrap(), graph);
+    }
+
+    #[test]
+    fn test_normalize_path() {
+        assert_eq!(normalize_path(Path::new("./docs/../docs/a.md")), "docs/a.md");
+        assert_eq!(normalize_path(Path::new("../a.md")), "../a.md");
+    }
+}
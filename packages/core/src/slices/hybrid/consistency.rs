@@ -0,0 +1,199 @@
+//! Cross-store consistency checking
+//!
+//! `HybridOrchestrator` writes every document to both the BM25 and the vector
+//! store. When a write fails half-way and the compensating rollback also fails,
+//! the stores drift apart. This module compares full listings of both stores
+//! and describes the drift so it can be reported or repaired.
+//!
+//! ## Checks
+//!
+//! - IDs present in only one store (orphans)
+//! - IDs stored more than once in the same store (duplicates)
+//! - IDs present in both stores with different content
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::kernel::types::AstChunk;
+
+/// Result of comparing the BM25 and vector stores
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConsistencyReport {
+    /// Number of rows in the BM25 store (duplicates included)
+    pub bm25_count: usize,
+    /// Number of rows in the vector store (duplicates included)
+    pub vector_count: usize,
+    /// IDs stored in BM25 but missing from the vector store
+    pub missing_in_vector: Vec<String>,
+    /// IDs stored in the vector store but missing from BM25
+    pub missing_in_bm25: Vec<String>,
+    /// IDs stored more than once in BM25
+    pub duplicates_bm25: Vec<String>,
+    /// IDs stored more than once in the vector store
+    pub duplicates_vector: Vec<String>,
+    /// IDs stored in both stores whose content differs
+    pub content_mismatches: Vec<String>,
+}
+
+impl ConsistencyReport {
+    /// Compare full listings of both stores
+    ///
+    /// All ID lists in the report are sorted for stable output.
+    pub fn from_chunks(bm25_chunks: &[AstChunk], vector_chunks: &[AstChunk]) -> Self {
+        let bm25 = group_by_id(bm25_chunks);
+        let vector = group_by_id(vector_chunks);
+
+        let missing_in_vector = bm25
+            .keys()
+            .filter(|id| !vector.contains_key(*id))
+            .map(|id| id.to_string())
+            .collect();
+
+        let missing_in_bm25 = vector
+            .keys()
+            .filter(|id| !bm25.contains_key(*id))
+            .map(|id| id.to_string())
+            .collect();
+
+        let content_mismatches = bm25
+            .iter()
+            .filter_map(|(id, bm25_rows)| {
+                let vector_rows = vector.get(id)?;
+                let bm25_contents: BTreeSet<&str> =
+                    bm25_rows.iter().map(|c| c.content.as_str()).collect();
+                let vector_contents: BTreeSet<&str> =
+                    vector_rows.iter().map(|c| c.content.as_str()).collect();
+                (bm25_contents != vector_contents).then(|| id.to_string())
+            })
+            .collect();
+
+        Self {
+            bm25_count: bm25_chunks.len(),
+            vector_count: vector_chunks.len(),
+            missing_in_vector,
+            missing_in_bm25,
+            duplicates_bm25: duplicate_ids(&bm25),
+            duplicates_vector: duplicate_ids(&vector),
+            content_mismatches,
+        }
+    }
+
+    /// Check whether both stores hold exactly the same documents
+    pub fn is_consistent(&self) -> bool {
+        self.issue_count() == 0
+    }
+
+    /// Total number of problems found
+    pub fn issue_count(&self) -> usize {
+        self.missing_in_vector.len()
+            + self.missing_in_bm25.len()
+            + self.duplicates_bm25.len()
+            + self.duplicates_vector.len()
+            + self.content_mismatches.len()
+    }
+}
+
+/// How `HybridOrchestrator::repair` fixes a diverged index
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RepairStrategy {
+    /// Copy orphans to the store that misses them and rewrite duplicated or
+    /// mismatched IDs from the BM25 copy
+    Resync,
+    /// Delete orphans from the store that holds them; duplicates and content
+    /// mismatches are left untouched
+    DeleteOrphans,
+}
+
+/// Summary of the changes made by a repair
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RepairReport {
+    /// Documents written to the BM25 store
+    pub bm25_written: usize,
+    /// Documents written to the vector store
+    pub vector_written: usize,
+    /// Documents deleted from the BM25 store
+    pub bm25_deleted: usize,
+    /// Documents deleted from the vector store
+    pub vector_deleted: usize,
+}
+
+impl RepairReport {
+    /// Total number of write and delete operations performed
+    pub fn total_changes(&self) -> usize {
+        self.bm25_written + self.vector_written + self.bm25_deleted + self.vector_deleted
+    }
+}
+
+/// Group rows by ID, keeping every row so duplicates stay visible
+pub(crate) fn group_by_id(chunks: &[AstChunk]) -> BTreeMap<&str, Vec<&AstChunk>> {
+    let mut grouped: BTreeMap<&str, Vec<&AstChunk>> = BTreeMap::new();
+    for chunk in chunks {
+        grouped.entry(chunk.id.as_str()).or_default().push(chunk);
+    }
+    grouped
+}
+
+fn duplicate_ids(grouped: &BTreeMap<&str, Vec<&AstChunk>>) -> Vec<String> {
+    grouped
+        .iter()
+        .filter(|(_, rows)| rows.len() > 1)
+        .map(|(id, _)| id.to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunk(id: &str, content: &str) -> AstChunk {
+        AstChunk::without_dependencies(id, "doc.md", "Title", "file", content)
+    }
+
+    #[test]
+    fn test_identical_stores_are_consistent() {
+        let chunks = vec![chunk("a", "alpha"), chunk("b", "beta")];
+        let report = ConsistencyReport::from_chunks(&chunks, &chunks);
+
+        assert!(report.is_consistent());
+        assert_eq!(report.bm25_count, 2);
+        assert_eq!(report.vector_count, 2);
+    }
+
+    #[test]
+    fn test_detects_orphans_on_both_sides() {
+        let bm25 = vec![chunk("a", "alpha"), chunk("bm25-only", "x")];
+        let vector = vec![chunk("a", "alpha"), chunk("vec-only", "y")];
+        let report = ConsistencyReport::from_chunks(&bm25, &vector);
+
+        assert_eq!(report.missing_in_vector, vec!["bm25-only"]);
+        assert_eq!(report.missing_in_bm25, vec!["vec-only"]);
+        assert_eq!(report.issue_count(), 2);
+    }
+
+    #[test]
+    fn test_detects_duplicates_and_content_mismatches() {
+        let bm25 = vec![chunk("a", "alpha"), chunk("b", "beta")];
+        let vector = vec![
+            chunk("a", "alpha"),
+            chunk("a", "alpha"),
+            chunk("b", "beta (stale)"),
+        ];
+        let report = ConsistencyReport::from_chunks(&bm25, &vector);
+
+        assert!(report.duplicates_bm25.is_empty());
+        assert_eq!(report.duplicates_vector, vec!["a"]);
+        // Identical duplicates are not a content mismatch
+        assert_eq!(report.content_mismatches, vec!["b"]);
+        assert!(!report.is_consistent());
+    }
+
+    #[test]
+    fn test_repair_report_total_changes() {
+        let report = RepairReport {
+            bm25_written: 1,
+            vector_written: 2,
+            bm25_deleted: 3,
+            vector_deleted: 4,
+        };
+        assert_eq!(report.total_changes(), 10);
+    }
+}
@@ -0,0 +1,597 @@
+//! Write-ahead operation journal for cross-store writes
+//!
+//! `HybridOrchestrator` writes every document to two independent stores. The
+//! compensating rollback in `add`/`add_batch` only runs if the process stays
+//! alive; a crash or Ctrl-C between the two writes leaves them diverged.
+//!
+//! The journal records the intent of each write before touching either store
+//! and marks it committed once both stores agree. Intents that are still open
+//! on the next start are resolved by `HybridOrchestrator::recover_journal`.
+//!
+//! ## File Format
+//!
+//! One JSON object per line (JSONL), appended only:
+//!
+//! ```text
+//! {"op":"begin","txn":1,"kind":"add","ids":["a"],"content_hashes":["9f86d0..."]}
+//! {"op":"commit","txn":1}
+//! {"op":"begin","txn":2,"kind":"upsert","ids":["b"],"content_hashes":["..."],"chunks":[{"id":"b",...}]}
+//! ```
+//!
+//! Upserts journal the chunks themselves: an upsert may replace an existing
+//! document, so recovery re-applies it rather than deleting the ID.
+//!
+//! Begin entries are flushed to disk before the stores are written. The file
+//! is truncated whenever no operation is in flight, so it stays small.
+//! Operations whose stores could not be reconciled are abandoned: they stop
+//! blocking truncation, but their begin entries are kept for the next recovery.
+//!
+//! ## Ownership
+//!
+//! A journal belongs to one process at a time. Opening it takes an exclusive
+//! OS lock on a `.lock` file next to it, held until the journal is dropped, so
+//! no other process can truncate or recover entries that are still in flight.
+//! `try_open` returns `None` while another process holds the lock.
+
+use std::collections::{BTreeMap, HashSet};
+use std::fs::{File, OpenOptions, TryLockError};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::kernel::errors::{AppError, InfraError};
+use crate::kernel::types::AstChunk;
+
+/// Default journal file name, stored next to the index directories
+pub const JOURNAL_FILE_NAME: &str = "journal.jsonl";
+
+/// Kind of cross-store operation recorded in the journal
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OperationKind {
+    /// Documents written to both stores
+    Add,
+    /// Documents inserted or replaced in both stores
+    Upsert,
+    /// Documents deleted from both stores
+    Delete,
+}
+
+/// A single journal line
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+enum JournalEntry {
+    /// Intent recorded before any store is touched
+    Begin {
+        txn: u64,
+        kind: OperationKind,
+        ids: Vec<String>,
+        /// SHA-256 of each document's content (same order as `ids`, empty for deletes)
+        #[serde(default)]
+        content_hashes: Vec<String>,
+        /// Full chunks, so recovery can re-apply the write (upserts only)
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        chunks: Vec<AstChunk>,
+    },
+    /// Both stores reached the intended state
+    Commit { txn: u64 },
+}
+
+/// An operation that was started but never committed
+#[derive(Debug, Clone, PartialEq)]
+pub struct PendingOperation {
+    /// Transaction number
+    pub txn: u64,
+    /// Operation kind
+    pub kind: OperationKind,
+    /// Affected document IDs
+    pub ids: Vec<String>,
+    /// Expected content hash per ID (adds and upserts)
+    pub content_hashes: Vec<String>,
+    /// Chunks to write again (upserts only)
+    pub chunks: Vec<AstChunk>,
+}
+
+/// Outcome of `HybridOrchestrator::recover_journal`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RecoveryReport {
+    /// Journaled adds found complete in both stores
+    pub adds_completed: usize,
+    /// Journaled adds removed from both stores because they were half-written
+    pub adds_rolled_back: usize,
+    /// Journaled upserts applied again to both stores
+    pub upserts_replayed: usize,
+    /// Journaled deletes applied again to both stores
+    pub deletes_replayed: usize,
+}
+
+impl RecoveryReport {
+    /// Number of journaled documents that were examined
+    pub fn total(&self) -> usize {
+        self.adds_completed + self.adds_rolled_back + self.upserts_replayed + self.deletes_replayed
+    }
+}
+
+/// Mutable journal state guarded by a single lock
+struct JournalState {
+    next_txn: u64,
+    in_flight: HashSet<u64>,
+    /// Unresolved operations whose begin entries must survive truncation
+    abandoned: HashSet<u64>,
+}
+
+/// Append-only journal of cross-store operations
+pub struct OperationJournal {
+    path: PathBuf,
+    /// Lock file handle; the OS lock is released when it is closed
+    _lock: File,
+    state: Mutex<JournalState>,
+}
+
+impl OperationJournal {
+    /// Open (or create) the journal at the given path
+    ///
+    /// Existing entries are kept so they can be recovered; new transaction
+    /// numbers continue after the highest one found in the file.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if another process holds the journal, if the parent
+    /// directory cannot be created or if the existing file cannot be read.
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self, AppError> {
+        let path = path.into();
+        Self::try_open(&path)?.ok_or_else(|| {
+            AppError::Infra(InfraError::Other(format!(
+                "Journal {} is in use by another process",
+                path.display()
+            )))
+        })
+    }
+
+    /// Open the journal unless another process holds it
+    ///
+    /// Returns `None` while the lock is held elsewhere; that process owns the
+    /// journal's unfinished entries and will commit or recover them itself.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`open`](Self::open), except that a held lock is not an error.
+    pub fn try_open(path: impl Into<PathBuf>) -> Result<Option<Self>, AppError> {
+        let path = path.into();
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| {
+                AppError::Infra(InfraError::io(
+                    parent,
+                    "Failed to create journal directory",
+                    Some(e),
+                ))
+            })?;
+        }
+
+        let lock_path = path.with_extension("lock");
+        let lock = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&lock_path)
+            .map_err(|e| {
+                AppError::Infra(InfraError::io(&lock_path, "Failed to open journal lock", Some(e)))
+            })?;
+        match lock.try_lock() {
+            Ok(()) => {}
+            Err(TryLockError::WouldBlock) => return Ok(None),
+            Err(TryLockError::Error(e)) => {
+                return Err(AppError::Infra(InfraError::io(
+                    &lock_path,
+                    "Failed to lock journal",
+                    Some(e),
+                )))
+            }
+        }
+
+        let max_txn = read_entries(&path)?
+            .iter()
+            .map(|entry| match entry {
+                JournalEntry::Begin { txn, .. } | JournalEntry::Commit { txn } => *txn,
+            })
+            .max()
+            .unwrap_or(0);
+
+        Ok(Some(Self {
+            path,
+            _lock: lock,
+            state: Mutex::new(JournalState {
+                next_txn: max_txn + 1,
+                in_flight: HashSet::new(),
+                abandoned: HashSet::new(),
+            }),
+        }))
+    }
+
+    /// Journal file location
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Record the intent of an operation before touching the stores
+    ///
+    /// The entry is synced to disk before returning.
+    ///
+    /// # Parameters
+    ///
+    /// * `kind` - Operation kind
+    /// * `ids` - Affected document IDs
+    /// * `contents` - Document contents for adds (hashed, not stored); empty for deletes
+    ///
+    /// # Returns
+    ///
+    /// The transaction number to pass to `commit`.
+    pub fn begin(
+        &self,
+        kind: OperationKind,
+        ids: Vec<String>,
+        contents: &[&str],
+    ) -> Result<u64, AppError> {
+        self.append_begin(|txn| JournalEntry::Begin {
+            txn,
+            kind,
+            ids,
+            content_hashes: contents.iter().map(|c| content_hash(c)).collect(),
+            chunks: Vec::new(),
+        })
+    }
+
+    /// Record an upsert before touching the stores
+    ///
+    /// Unlike `begin`, the chunks themselves are journaled so that recovery
+    /// can re-apply the upsert instead of deleting documents it may have been
+    /// replacing. The entry is synced to disk before returning.
+    pub fn begin_upsert(&self, chunks: &[AstChunk]) -> Result<u64, AppError> {
+        self.append_begin(|txn| JournalEntry::Begin {
+            txn,
+            kind: OperationKind::Upsert,
+            ids: chunks.iter().map(|chunk| chunk.id.clone()).collect(),
+            content_hashes: chunks
+                .iter()
+                .map(|chunk| content_hash(&chunk.content))
+                .collect(),
+            chunks: chunks.to_vec(),
+        })
+    }
+
+    /// Append and sync a begin entry under the next transaction number
+    fn append_begin(&self, entry: impl FnOnce(u64) -> JournalEntry) -> Result<u64, AppError> {
+        let mut state = self.lock_state()?;
+        let txn = state.next_txn;
+
+        self.append(&entry(txn), true)?;
+
+        state.next_txn += 1;
+        state.in_flight.insert(txn);
+        Ok(txn)
+    }
+
+    /// Mark an operation as completed in both stores
+    ///
+    /// When no other operation is in flight the journal is truncated, keeping
+    /// only the begin entries of abandoned operations.
+    pub fn commit(&self, txn: u64) -> Result<(), AppError> {
+        let mut state = self.lock_state()?;
+        state.in_flight.remove(&txn);
+
+        if state.in_flight.is_empty() {
+            // Nothing left to recover in this process: drop the history
+            self.compact(&state.abandoned)
+        } else {
+            self.append(&JournalEntry::Commit { txn }, false)
+        }
+    }
+
+    /// Give up on an operation that left the stores diverged
+    ///
+    /// The operation no longer counts as in flight, so later commits can
+    /// truncate the journal again, but its begin entry is kept on disk for
+    /// the next `recover_journal`.
+    pub fn abandon(&self, txn: u64) -> Result<(), AppError> {
+        let mut state = self.lock_state()?;
+        state.in_flight.remove(&txn);
+        state.abandoned.insert(txn);
+        Ok(())
+    }
+
+    /// List operations that were started but never committed
+    ///
+    /// Only meaningful at startup, before new operations begin.
+    pub fn pending(&self) -> Result<Vec<PendingOperation>, AppError> {
+        let mut open: BTreeMap<u64, PendingOperation> = BTreeMap::new();
+
+        for entry in read_entries(&self.path)? {
+            match entry {
+                JournalEntry::Begin {
+                    txn,
+                    kind,
+                    ids,
+                    content_hashes,
+                    chunks,
+                } => {
+                    open.insert(
+                        txn,
+                        PendingOperation {
+                            txn,
+                            kind,
+                            ids,
+                            content_hashes,
+                            chunks,
+                        },
+                    );
+                }
+                JournalEntry::Commit { txn } => {
+                    open.remove(&txn);
+                }
+            }
+        }
+
+        Ok(open.into_values().collect())
+    }
+
+    /// Discard all journal entries
+    pub fn clear(&self) -> Result<(), AppError> {
+        let mut state = self.lock_state()?;
+        state.in_flight.clear();
+        state.abandoned.clear();
+        self.truncate()
+    }
+
+    fn lock_state(&self) -> Result<std::sync::MutexGuard<'_, JournalState>, AppError> {
+        self.state.lock().map_err(|e| {
+            AppError::Infra(InfraError::Other(format!(
+                "Journal lock poisoned: {}",
+                e
+            )))
+        })
+    }
+
+    fn append(&self, entry: &JournalEntry, sync: bool) -> Result<(), AppError> {
+        let mut line = serde_json::to_string(entry).map_err(|e| {
+            AppError::Infra(InfraError::serialization(
+                "Failed to serialize journal entry",
+                Some(e),
+            ))
+        })?;
+        line.push('\n');
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .map_err(|e| {
+                AppError::Infra(InfraError::io(&self.path, "Failed to open journal", Some(e)))
+            })?;
+
+        file.write_all(line.as_bytes()).map_err(|e| {
+            AppError::Infra(InfraError::io(&self.path, "Failed to write journal", Some(e)))
+        })?;
+
+        if sync {
+            file.sync_data().map_err(|e| {
+                AppError::Infra(InfraError::io(&self.path, "Failed to sync journal", Some(e)))
+            })?;
+        }
+
+        Ok(())
+    }
+
+    /// Rewrite the journal with only the begin entries of `keep`
+    fn compact(&self, keep: &HashSet<u64>) -> Result<(), AppError> {
+        if keep.is_empty() {
+            return self.truncate();
+        }
+
+        let mut contents = String::new();
+        for entry in read_entries(&self.path)? {
+            if matches!(&entry, JournalEntry::Begin { txn, .. } if keep.contains(txn)) {
+                let line = serde_json::to_string(&entry).map_err(|e| {
+                    AppError::Infra(InfraError::serialization(
+                        "Failed to serialize journal entry",
+                        Some(e),
+                    ))
+                })?;
+                contents.push_str(&line);
+                contents.push('\n');
+            }
+        }
+
+        // Write aside and rename, so a crash leaves either version intact
+        let temp_path = self.path.with_extension("jsonl.tmp");
+        let write = || -> std::io::Result<()> {
+            let mut file = File::create(&temp_path)?;
+            file.write_all(contents.as_bytes())?;
+            file.sync_data()?;
+            std::fs::rename(&temp_path, &self.path)
+        };
+        write().map_err(|e| {
+            AppError::Infra(InfraError::io(&self.path, "Failed to compact journal", Some(e)))
+        })
+    }
+
+    fn truncate(&self) -> Result<(), AppError> {
+        File::create(&self.path).map(|_| ()).map_err(|e| {
+            AppError::Infra(InfraError::io(&self.path, "Failed to truncate journal", Some(e)))
+        })
+    }
+}
+
+/// SHA-256 hex digest of a document's content
+pub fn content_hash(content: &str) -> String {
+    format!("{:x}", Sha256::digest(content.as_bytes()))
+}
+
+/// Read all entries, skipping a torn last line from an interrupted write
+fn read_entries(path: &Path) -> Result<Vec<JournalEntry>, AppError> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => {
+            return Err(AppError::Infra(InfraError::io(
+                path,
+                "Failed to open journal",
+                Some(e),
+            )))
+        }
+    };
+
+    let mut entries = Vec::new();
+    for line in BufReader::new(file).lines() {
+        let line = line.map_err(|e| {
+            AppError::Infra(InfraError::io(path, "Failed to read journal", Some(e)))
+        })?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        match serde_json::from_str(&line) {
+            Ok(entry) => entries.push(entry),
+            Err(e) => {
+                tracing::warn!(error = %e, path = %path.display(), "Skipping unreadable journal line");
+            }
+        }
+    }
+
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn open_temp() -> (OperationJournal, tempfile::TempDir) {
+        let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
+        let journal = OperationJournal::open(temp_dir.path().join("nested").join(JOURNAL_FILE_NAME))
+            .expect("Failed to open journal");
+        (journal, temp_dir)
+    }
+
+    #[test]
+    fn test_begin_without_commit_is_pending() {
+        let (journal, _temp_dir) = open_temp();
+
+        let txn = journal
+            .begin(OperationKind::Add, vec!["a".to_string()], &["alpha"])
+            .unwrap();
+
+        let pending = journal.pending().unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].txn, txn);
+        assert_eq!(pending[0].kind, OperationKind::Add);
+        assert_eq!(pending[0].ids, vec!["a"]);
+        assert_eq!(pending[0].content_hashes, vec![content_hash("alpha")]);
+    }
+
+    #[test]
+    fn test_upsert_journals_chunks() {
+        let (journal, _temp_dir) = open_temp();
+        let chunk = AstChunk::without_dependencies("a", "a.md", "A", "file", "alpha");
+
+        journal.begin_upsert(std::slice::from_ref(&chunk)).unwrap();
+
+        // Reopen as after a crash: the chunk survives the round trip
+        let path = journal.path().to_path_buf();
+        drop(journal);
+        let pending = OperationJournal::open(&path).unwrap().pending().unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].kind, OperationKind::Upsert);
+        assert_eq!(pending[0].ids, vec!["a"]);
+        assert_eq!(pending[0].content_hashes, vec![content_hash("alpha")]);
+        assert_eq!(pending[0].chunks, vec![chunk]);
+    }
+
+    #[test]
+    fn test_commit_truncates_when_idle() {
+        let (journal, _temp_dir) = open_temp();
+
+        let first = journal.begin(OperationKind::Add, vec!["a".to_string()], &["alpha"]).unwrap();
+        let second = journal.begin(OperationKind::Delete, vec!["b".to_string()], &[]).unwrap();
+
+        // One operation still in flight: only the other one is pending
+        journal.commit(first).unwrap();
+        let pending = journal.pending().unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].txn, second);
+
+        journal.commit(second).unwrap();
+        assert!(journal.pending().unwrap().is_empty());
+        assert_eq!(std::fs::metadata(journal.path()).unwrap().len(), 0);
+    }
+
+    #[test]
+    fn test_abandoned_operation_survives_truncation() {
+        let (journal, _temp_dir) = open_temp();
+
+        let stuck = journal.begin(OperationKind::Add, vec!["a".to_string()], &["alpha"]).unwrap();
+        journal.abandon(stuck).unwrap();
+
+        // Later writes still truncate, but keep the abandoned intent
+        let txn = journal.begin(OperationKind::Delete, vec!["b".to_string()], &[]).unwrap();
+        journal.commit(txn).unwrap();
+        let pending = journal.pending().unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].txn, stuck);
+        assert_eq!(pending[0].ids, vec!["a"]);
+
+        journal.clear().unwrap();
+        assert_eq!(std::fs::metadata(journal.path()).unwrap().len(), 0);
+    }
+
+    #[test]
+    fn test_journal_is_held_by_one_owner() {
+        let (journal, _temp_dir) = open_temp();
+        journal.begin(OperationKind::Add, vec!["a".to_string()], &["alpha"]).unwrap();
+
+        // Another owner cannot open the journal while it is held
+        let path = journal.path().to_path_buf();
+        assert!(OperationJournal::try_open(&path).unwrap().is_none());
+        assert!(OperationJournal::open(&path).is_err());
+
+        drop(journal);
+        let reopened = OperationJournal::try_open(&path).unwrap().expect("Lock should be released");
+        assert_eq!(reopened.pending().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_reopen_continues_transaction_numbers() {
+        let (journal, _temp_dir) = open_temp();
+        let txn = journal.begin(OperationKind::Add, vec!["a".to_string()], &["alpha"]).unwrap();
+
+        // Simulate a crash: reopen without committing
+        let path = journal.path().to_path_buf();
+        drop(journal);
+        let reopened = OperationJournal::open(&path).unwrap();
+        assert_eq!(reopened.pending().unwrap().len(), 1);
+
+        let next = reopened.begin(OperationKind::Delete, vec!["a".to_string()], &[]).unwrap();
+        assert!(next > txn);
+    }
+
+    #[test]
+    fn test_torn_line_is_skipped() {
+        let (journal, _temp_dir) = open_temp();
+        journal.begin(OperationKind::Add, vec!["a".to_string()], &["alpha"]).unwrap();
+
+        let mut file = OpenOptions::new().append(true).open(journal.path()).unwrap();
+        file.write_all(b"{\"op\":\"begin\",\"txn\":").unwrap();
+
+        assert_eq!(journal.pending().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_content_hash_is_stable() {
+        assert_eq!(
+            content_hash("test"),
+            "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08"
+        );
+    }
+}
@@ -0,0 +1,72 @@
+//! Search stage metrics
+//!
+//! Stage latencies are recorded through the `metrics` facade as the histogram
+//! `contextfy_search_stage_seconds`, labelled with `stage`. Nothing is kept
+//! unless the host application installs a recorder (the server exports them
+//! in Prometheus format).
+
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+/// Histogram holding the latency of each search stage
+pub const SEARCH_STAGE_SECONDS: &str = "contextfy_search_stage_seconds";
+
+/// A timed step of a search request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchStage {
+    /// Embedding the query text
+    Embed,
+    /// BM25 full-text search
+    Bm25,
+    /// Vector similarity search (excluding query embedding)
+    Vector,
+    /// Fusing the backend result lists
+    Fusion,
+    /// Loading the stored documents of the hits
+    Fetch,
+}
+
+impl SearchStage {
+    /// Every stage, in pipeline order
+    pub const ALL: [SearchStage; 5] = [
+        SearchStage::Embed,
+        SearchStage::Bm25,
+        SearchStage::Vector,
+        SearchStage::Fusion,
+        SearchStage::Fetch,
+    ];
+
+    /// Value of the `stage` label
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SearchStage::Embed => "embed",
+            SearchStage::Bm25 => "bm25",
+            SearchStage::Vector => "vector",
+            SearchStage::Fusion => "fusion",
+            SearchStage::Fetch => "fetch",
+        }
+    }
+
+    /// Record one run of this stage
+    pub fn record(self, elapsed: Duration) {
+        metrics::histogram!(SEARCH_STAGE_SECONDS, "stage" => self.as_str())
+            .record(elapsed.as_secs_f64());
+    }
+}
+
+/// Register the metric descriptions with the installed recorder
+pub fn describe_metrics() {
+    metrics::describe_histogram!(
+        SEARCH_STAGE_SECONDS,
+        metrics::Unit::Seconds,
+        "Latency of each search stage"
+    );
+}
+
+/// Await `future` and record its duration as `stage`
+pub(crate) async fn timed<F: Future>(stage: SearchStage, future: F) -> F::Output {
+    let started = Instant::now();
+    let output = future.await;
+    stage.record(started.elapsed());
+    output
+}
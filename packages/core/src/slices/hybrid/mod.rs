@@ -8,6 +8,10 @@
 //!
 //! - **rrf.rs**: Reciprocal Rank Fusion implementation for result fusion
 //! - **orchestrator.rs**: High-level orchestration of multiple retrieval methods
+//! - **consistency.rs**: Cross-store drift detection (orphans, duplicates, mismatches)
+//! - **journal.rs**: Write-ahead journal that makes cross-store writes recoverable
+//! - **metrics.rs**: Per-stage search latency histograms
+//! - **progress.rs**: Intermediate results reported while a search runs
 //!
 //! ## Usage Pattern
 //!
@@ -25,9 +29,17 @@
 //!
 //! Ref: `openspec/changes/refactor-pragmatic-slice-architecture/design.md`
 
+pub mod consistency;
+pub mod journal;
+pub mod metrics;
 pub mod orchestrator;
+pub mod progress;
 pub mod rrf;
 
 // Re-export main types at the module level
-pub use orchestrator::{DeleteResult, HybridOrchestrator};
+pub use consistency::{ConsistencyReport, RepairReport, RepairStrategy};
+pub use journal::{OperationJournal, RecoveryReport};
+pub use metrics::{describe_metrics, SearchStage, SEARCH_STAGE_SECONDS};
+pub use orchestrator::{BackendScore, DeleteResult, ExplainedHit, FusionMode, HybridOrchestrator};
+pub use progress::{ProgressSender, SearchBackend, SearchProgress};
 pub use rrf::{RrfOrchestrator, RrfResult};
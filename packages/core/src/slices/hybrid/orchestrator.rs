@@ -11,14 +11,21 @@
 //!
 //! Ref: `openspec/changes/refactor-pragmatic-slice-architecture/design.md`
 
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Instant;
 use tracing::{error, info, warn};
 
 use crate::kernel::errors::{AppError, DomainError};
-use crate::kernel::types::{AstChunk, Hit, Query};
+use crate::kernel::types::{AstChunk, ChunkFilter, Hit, Query, Score};
 
 use super::super::bm25::Bm25StoreTrait;
 use super::super::vector::VectorStoreTrait;
+use super::consistency::{group_by_id, ConsistencyReport, RepairReport, RepairStrategy};
+use super::journal::{content_hash, OperationJournal, OperationKind, RecoveryReport};
+use super::metrics::SearchStage;
+use super::progress::{Progress, ProgressSender, SearchBackend};
 use super::rrf::RrfOrchestrator;
 
 /// Result of a hybrid delete operation
@@ -29,9 +36,28 @@ pub struct DeleteResult {
     pub vector_deleted: Result<bool, AppError>,
     /// Result of BM25 store deletion
     pub bm25_deleted: Result<bool, AppError>,
+    /// Number of rows removed from the vector store
+    pub vector_count: usize,
+    /// Number of rows removed from the BM25 store
+    pub bm25_count: usize,
 }
 
 impl DeleteResult {
+    /// Build a result from per-backend row counts
+    ///
+    /// A backend counts as deleted when it removed at least one row.
+    pub fn from_counts(
+        vector_count: Result<usize, AppError>,
+        bm25_count: Result<usize, AppError>,
+    ) -> Self {
+        Self {
+            vector_count: *vector_count.as_ref().unwrap_or(&0),
+            bm25_count: *bm25_count.as_ref().unwrap_or(&0),
+            vector_deleted: vector_count.map(|count| count > 0),
+            bm25_deleted: bm25_count.map(|count| count > 0),
+        }
+    }
+
     /// Check if at least one backend succeeded in deletion
     pub fn any_success(&self) -> bool {
         matches!(
@@ -57,6 +83,74 @@ impl DeleteResult {
     }
 }
 
+/// How search results from the two backends are combined
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum FusionMode {
+    /// Reciprocal Rank Fusion of BM25 and vector results (default)
+    #[default]
+    Rrf,
+    /// BM25 full-text results only
+    Bm25,
+    /// Vector (semantic) results only
+    Vector,
+}
+
+impl FusionMode {
+    /// Get the mode name for display and serialization
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Rrf => "rrf",
+            Self::Bm25 => "bm25",
+            Self::Vector => "vector",
+        }
+    }
+}
+
+/// Position and raw score of a hit in one backend's result list
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BackendScore {
+    /// 1-based rank in the backend's results
+    pub rank: usize,
+    /// Score reported by the backend (before fusion)
+    pub score: f64,
+}
+
+/// Search hit annotated with the per-backend scores that produced it
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExplainedHit {
+    /// Document ID
+    pub id: String,
+    /// Final score (RRF score, or the backend score in single-backend modes)
+    pub score: Score,
+    /// Rank and score in the BM25 results (None if BM25 did not return it)
+    pub bm25: Option<BackendScore>,
+    /// Rank and score in the vector results (None if vector search did not return it)
+    pub vector: Option<BackendScore>,
+}
+
+impl ExplainedHit {
+    /// Convert to kernel Hit type
+    pub fn into_hit(self) -> Hit {
+        Hit::new(self.id, self.score)
+    }
+}
+
+/// Index a backend's result list by ID
+fn backend_scores(hits: &[Hit]) -> HashMap<String, BackendScore> {
+    hits.iter()
+        .enumerate()
+        .map(|(index, hit)| {
+            let score = BackendScore {
+                rank: index + 1,
+                score: hit.score.value(),
+            };
+            (hit.id.clone(), score)
+        })
+        .collect()
+}
+
 /// Hybrid search orchestrator
 ///
 /// Combines results from BM25 and vector search using RRF fusion.
@@ -68,6 +162,8 @@ pub struct HybridOrchestrator {
     bm25_store: Arc<dyn Bm25StoreTrait>,
     /// RRF fusion orchestrator
     rrf: RrfOrchestrator,
+    /// Optional write-ahead journal for cross-store writes
+    journal: Option<Arc<OperationJournal>>,
 }
 
 impl HybridOrchestrator {
@@ -91,6 +187,7 @@ impl HybridOrchestrator {
             vector_store,
             bm25_store,
             rrf: RrfOrchestrator::new(k),
+            journal: None,
         }
     }
 
@@ -102,6 +199,15 @@ impl HybridOrchestrator {
         Self::new(vector_store, bm25_store, 60)
     }
 
+    /// Record every cross-store write in a write-ahead journal
+    ///
+    /// Call `recover_journal` once after attaching the journal to resolve
+    /// operations left open by a previous process.
+    pub fn with_journal(mut self, journal: Arc<OperationJournal>) -> Self {
+        self.journal = Some(journal);
+        self
+    }
+
     /// Perform hybrid search
     ///
     /// Executes both BM25 and vector searches, then fuses results using RRF.
@@ -120,6 +226,46 @@ impl HybridOrchestrator {
     /// - Query validation fails
     /// - Both searches fail
     pub async fn search(&self, query: &Query) -> Result<Vec<Hit>, AppError> {
+        let hits = self.search_explained(query, FusionMode::Rrf).await?;
+        Ok(hits.into_iter().map(ExplainedHit::into_hit).collect())
+    }
+
+    /// Perform a search that reports each backend's contribution
+    ///
+    /// `FusionMode::Rrf` behaves exactly like [`search`](Self::search); the
+    /// single-backend modes skip the other store entirely. Every hit carries
+    /// its rank and raw score in each backend that returned it.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the query is empty, or if every queried backend fails
+    /// (a failing backend is tolerated when the other one returns results).
+    pub async fn search_explained(
+        &self,
+        query: &Query,
+        mode: FusionMode,
+    ) -> Result<Vec<ExplainedHit>, AppError> {
+        self.search_with_progress(query, mode, None).await
+    }
+
+    /// Perform an explained search, reporting intermediate results on `progress`
+    ///
+    /// Each backend's hits are sent as soon as that backend returns, followed
+    /// by the latency of every stage (`embed`, `bm25`, `vector`, `fusion`).
+    /// The fused list is the return value, identical to
+    /// [`search_explained`](Self::search_explained).
+    ///
+    /// # Errors
+    ///
+    /// Same as [`search_explained`](Self::search_explained).
+    pub async fn search_with_progress(
+        &self,
+        query: &Query,
+        mode: FusionMode,
+        progress: Option<&ProgressSender>,
+    ) -> Result<Vec<ExplainedHit>, AppError> {
+        let progress = Progress::new(progress);
+
         // Validate query
         if query.text.trim().is_empty() {
             return Err(AppError::Domain(DomainError::invalid_query(
@@ -127,91 +273,39 @@ impl HybridOrchestrator {
             )));
         }
 
-        // Execute both searches in parallel
-        let (vector_result, bm25_result) = tokio::join!(
-            self.vector_store.search(query),
-            self.bm25_store.search(query)
-        );
-
-        // Process vector search results
-        let vector_hits = match vector_result {
-            Ok(Some(hits)) if !hits.is_empty() => {
-                info!("Vector search returned {} results", hits.len());
-                Ok(hits)
-            }
-            Ok(Some(_)) | Ok(None) => {
-                info!("Vector search returned no results");
-                Ok(vec![])
-            }
-            Err(e) => {
-                warn!(error = ?e, "Vector search failed, will try BM25 only");
-                Err(e)
-            }
-        };
-
-        // Process BM25 search results
-        let bm25_hits = match bm25_result {
-            Ok(Some(results)) if !results.is_empty() => {
-                info!("BM25 search returned {} results", results.len());
-                Ok(results.into_iter().map(|r| r.to_hit()).collect())
-            }
-            Ok(Some(_)) | Ok(None) => {
-                info!("BM25 search returned no results");
-                Ok(vec![])
-            }
-            Err(e) => {
-                warn!(error = ?e, "BM25 search failed, will try vector only");
-                Err(e)
-            }
+        // Execute the selected searches (both in parallel for RRF)
+        let (vector_hits, bm25_hits) = match mode {
+            FusionMode::Rrf => tokio::join!(
+                self.vector_search(query, progress),
+                self.bm25_search(query, progress)
+            ),
+            FusionMode::Bm25 => (Ok(vec![]), self.bm25_search(query, progress).await),
+            FusionMode::Vector => (self.vector_search(query, progress).await, Ok(vec![])),
         };
 
         // Process results according to exact degradation logic:
         // 1. Both Ok → RRF fusion
         // 2. One Ok, One Err → log warning, return Ok result (degradation)
         // 3. Both Err → combine errors, return AppError (NOT empty array)
-        match (vector_hits, bm25_hits) {
-            (Ok(v), Ok(b)) => {
-                // Both searches succeeded - perform RRF fusion
-                // Fuse results using RRF (no .clone() - move ownership)
-                let fused = if !v.is_empty() && !b.is_empty() {
-                    self.rrf
-                        .fuse_two(v, b) // Move ownership, zero copy
-                        .map_err(|e| {
-                            AppError::Domain(DomainError::Other(format!(
-                                "RRF fusion failed: {}",
-                                e
-                            )))
-                        })?
-                        .into_iter()
-                        .map(|r| r.to_hit())
-                        .collect()
-                } else if !v.is_empty() {
-                    v
-                } else {
-                    b
-                };
-
-                Ok(fused)
-            }
+        let (vector_hits, bm25_hits) = match (vector_hits, bm25_hits) {
+            (Ok(v), Ok(b)) => (v, b),
             (Ok(v), Err(e)) => {
                 // Vector OK, BM25 failed - log warning and return vector results or error
                 warn!(error = ?e, "BM25 backend failed, using vector results only");
                 if v.is_empty() {
                     // Preserve root cause - don't wrap the error
-                    Err(e)
-                } else {
-                    Ok(v)
+                    return Err(e);
                 }
+                (v, vec![])
             }
             (Err(e), Ok(b)) => {
                 // BM25 OK, Vector failed - log warning and return BM25 results or error
                 warn!(error = ?e, "Vector backend failed, using BM25 results only");
                 if b.is_empty() {
                     // Preserve root cause - don't wrap the error
-                    Err(e)
-                } else {
-                    Ok(b)
+                    return Err(e);
                 }
+                (vec![], b)
             }
             (Err(vec_err), Err(bm25_err)) => {
                 // Both searches failed - log both and return first error
@@ -221,9 +315,119 @@ impl HybridOrchestrator {
                     "Both search backends failed"
                 );
                 // Return vector error, log that BM25 also failed
-                Err(vec_err)
+                return Err(vec_err);
             }
-        }
+        };
+
+        let fusion_started = Instant::now();
+        let fused: Vec<Hit> = if !vector_hits.is_empty() && !bm25_hits.is_empty() {
+            // Both searches returned results - perform RRF fusion
+            self.rrf
+                .fuse_two(vector_hits.clone(), bm25_hits.clone())
+                .map_err(|e| {
+                    AppError::Domain(DomainError::Other(format!("RRF fusion failed: {}", e)))
+                })?
+                .into_iter()
+                .map(|r| r.to_hit())
+                .collect()
+        } else if !vector_hits.is_empty() {
+            vector_hits.clone()
+        } else {
+            bm25_hits.clone()
+        };
+
+        let vector_scores = backend_scores(&vector_hits);
+        let bm25_scores = backend_scores(&bm25_hits);
+        let hits = fused
+            .into_iter()
+            .map(|hit| ExplainedHit {
+                bm25: bm25_scores.get(&hit.id).copied(),
+                vector: vector_scores.get(&hit.id).copied(),
+                id: hit.id,
+                score: hit.score,
+            })
+            .collect();
+        progress.stage(SearchStage::Fusion, fusion_started.elapsed());
+        Ok(hits)
+    }
+
+    /// RRF constant used to fuse the two backends
+    pub fn rrf_k(&self) -> i32 {
+        self.rrf.k()
+    }
+
+    /// Run the vector search, treating "no results" as an empty list
+    ///
+    /// Query embedding is timed separately when the store exposes it.
+    async fn vector_search(
+        &self,
+        query: &Query,
+        progress: Progress<'_>,
+    ) -> Result<Vec<Hit>, AppError> {
+        let embed_started = Instant::now();
+        let result = match self.vector_store.embed_query(&query.text).await {
+            Ok(Some(embedding)) => {
+                progress.stage(SearchStage::Embed, embed_started.elapsed());
+                progress
+                    .timed(
+                        SearchStage::Vector,
+                        self.vector_store.search_with_embedding(query, embedding),
+                    )
+                    .await
+            }
+            Ok(None) => {
+                progress
+                    .timed(SearchStage::Vector, self.vector_store.search(query))
+                    .await
+            }
+            Err(e) => Err(e),
+        };
+
+        let hits = match result {
+            Ok(Some(hits)) if !hits.is_empty() => {
+                info!("Vector search returned {} results", hits.len());
+                hits
+            }
+            Ok(Some(_)) | Ok(None) => {
+                info!("Vector search returned no results");
+                vec![]
+            }
+            Err(e) => {
+                warn!(error = ?e, "Vector search failed, will try BM25 only");
+                progress.failed(SearchBackend::Vector, &e);
+                return Err(e);
+            }
+        };
+        progress.results(SearchBackend::Vector, &hits);
+        Ok(hits)
+    }
+
+    /// Run the BM25 search, treating "no results" as an empty list
+    async fn bm25_search(
+        &self,
+        query: &Query,
+        progress: Progress<'_>,
+    ) -> Result<Vec<Hit>, AppError> {
+        let hits: Vec<Hit> = match progress
+            .timed(SearchStage::Bm25, self.bm25_store.search(query))
+            .await
+        {
+            Ok(Some(results)) if !results.is_empty() => {
+                info!("BM25 search returned {} results", results.len());
+                results.into_iter().map(|r| r.to_hit()).collect()
+            }
+            Ok(Some(_)) | Ok(None) => {
+                info!("BM25 search returned no results");
+                vec![]
+            }
+            Err(e) => {
+                warn!(error = ?e, "BM25 search failed, will try vector only");
+                progress.failed(SearchBackend::Bm25, &e);
+                return Err(e);
+            }
+        };
+        progress.results(SearchBackend::Bm25, &hits);
+        Ok(hits)
     }
 
     /// Add a document to both stores
@@ -256,6 +460,9 @@ impl HybridOrchestrator {
             "keywords": keywords.unwrap_or("")
         });
 
+        // Record intent before touching either store
+        let txn = self.journal_begin(OperationKind::Add, vec![id.to_string()], &[content])?;
+
         // Add to both stores in parallel
         let (vector_result, bm25_result) = tokio::join!(
             self.vector_store.add(id, content, Some(&metadata)),
@@ -267,6 +474,7 @@ impl HybridOrchestrator {
         match (vector_result, bm25_result) {
             (Ok(()), Ok(())) => {
                 info!(id = %id, "Document added to both stores successfully");
+                self.journal_commit(txn);
                 Ok(())
             }
             (Ok(()), Err(bm25_err)) => {
@@ -283,8 +491,10 @@ impl HybridOrchestrator {
                         rollback_error = ?rollback_err,
                         "Failed to rollback vector store - orphan document may exist"
                     );
+                    self.journal_abandon(txn);
                 } else {
                     info!(id = %id, "Vector store rolled back successfully");
+                    self.journal_commit(txn);
                 }
                 Err(bm25_err)
             }
@@ -302,8 +512,10 @@ impl HybridOrchestrator {
                         rollback_error = ?rollback_err,
                         "Failed to rollback BM25 store - orphan document may exist"
                     );
+                    self.journal_abandon(txn);
                 } else {
                     info!(id = %id, "BM25 store rolled back successfully");
+                    self.journal_commit(txn);
                 }
                 Err(vector_err)
             }
@@ -315,6 +527,7 @@ impl HybridOrchestrator {
                     bm25_error = ?bm25_err,
                     "Both stores failed to add document"
                 );
+                self.journal_commit(txn);
                 Err(vector_err)
             }
         }
@@ -348,6 +561,10 @@ impl HybridOrchestrator {
         // Extract all IDs before concurrent execution for potential rollback
         let ids: Vec<String> = chunks.iter().map(|c| c.id.clone()).collect();
 
+        // Record intent before touching either store
+        let contents: Vec<&str> = chunks.iter().map(|c| c.content.as_str()).collect();
+        let txn = self.journal_begin(OperationKind::Add, ids.clone(), &contents)?;
+
         // Execute batch add on both stores concurrently (use join! instead of try_join!)
         // Clone only once for bm25_store to avoid memory bloat
         let bm25_chunks = chunks.clone();
@@ -363,6 +580,7 @@ impl HybridOrchestrator {
                     "Batch add completed successfully for {} chunks",
                     chunk_count
                 );
+                self.journal_commit(txn);
                 Ok(())
             }
             (Ok(()), Err(bm25_err)) => {
@@ -370,8 +588,10 @@ impl HybridOrchestrator {
                 warn!(
                     "BM25 store failed after vector store succeeded, rolling back vector store",
                 );
+                let mut rolled_back = true;
                 for id in &ids {
                     if let Err(del_err) = self.vector_store.delete(id).await {
+                        rolled_back = false;
                         error!(
                             id = %id,
                             error = ?del_err,
@@ -379,6 +599,11 @@ impl HybridOrchestrator {
                         );
                     }
                 }
+                if rolled_back {
+                    self.journal_commit(txn);
+                } else {
+                    self.journal_abandon(txn);
+                }
                 error!(error = ?bm25_err, "BM25 store failed to add batch");
                 Err(bm25_err)
             }
@@ -387,8 +612,10 @@ impl HybridOrchestrator {
                 warn!(
                     "Vector store failed after BM25 store succeeded, rolling back BM25 store",
                 );
+                let mut rolled_back = true;
                 for id in &ids {
                     if let Err(del_err) = self.bm25_store.delete(id).await {
+                        rolled_back = false;
                         error!(
                             id = %id,
                             error = ?del_err,
@@ -396,6 +623,11 @@ impl HybridOrchestrator {
                         );
                     }
                 }
+                if rolled_back {
+                    self.journal_commit(txn);
+                } else {
+                    self.journal_abandon(txn);
+                }
                 error!(error = ?vector_err, "Vector store failed to add batch");
                 Err(vector_err)
             }
@@ -406,12 +638,108 @@ impl HybridOrchestrator {
                     bm25_error = ?bm25_err,
                     "Both stores failed to add batch"
                 );
+                self.journal_commit(txn);
                 // Return vector error as primary (could also create a combined error)
                 Err(vector_err)
             }
         }
     }
 
+    /// Insert or replace AST chunks in both stores by ID
+    ///
+    /// Re-running with the same chunks leaves exactly one copy of each ID in
+    /// each store, which makes rebuilds and re-migrations idempotent.
+    ///
+    /// # Failure Handling
+    ///
+    /// An upsert may overwrite an existing document, so the previous version
+    /// cannot be restored by deleting from the store that succeeded. Instead,
+    /// when only one store succeeds the affected IDs are removed from *both*
+    /// stores, leaving them consistently absent until the next upsert. The
+    /// chunks are journaled and the entry is only committed once that cleanup
+    /// succeeds, so after a crash or a failed cleanup `recover_journal`
+    /// re-applies the upsert on the next start.
+    ///
+    /// # Parameters
+    ///
+    /// * `chunks` - AST chunk list
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - Both backends succeeded
+    /// * `Err(AppError)` - At least one backend failed
+    pub async fn upsert_batch(&self, chunks: Vec<AstChunk>) -> Result<(), AppError> {
+        if chunks.is_empty() {
+            return Ok(());
+        }
+
+        let chunk_count = chunks.len();
+        info!("Upserting {} chunks into hybrid stores", chunk_count);
+
+        let ids: Vec<String> = chunks.iter().map(|c| c.id.clone()).collect();
+        let txn = self.journal_begin_upsert(&chunks)?;
+
+        let bm25_chunks = chunks.clone();
+        let (vector_result, bm25_result) = tokio::join!(
+            self.vector_store.upsert_batch(chunks),
+            self.bm25_store.upsert_batch(bm25_chunks),
+        );
+
+        match (vector_result, bm25_result) {
+            (Ok(()), Ok(())) => {
+                info!("Upsert completed successfully for {} chunks", chunk_count);
+                self.journal_commit(txn);
+                Ok(())
+            }
+            (Ok(()), Err(err)) | (Err(err), Ok(())) => {
+                warn!("Only one store accepted the upsert, removing affected IDs from both stores");
+                if self.purge_ids(&ids).await {
+                    self.journal_commit(txn);
+                } else {
+                    self.journal_abandon(txn);
+                }
+                error!(error = ?err, "Upsert failed on one store");
+                Err(err)
+            }
+            (Err(vector_err), Err(bm25_err)) => {
+                // Each store writes its batch atomically, so both still hold
+                // their previous versions
+                error!(
+                    vector_error = ?vector_err,
+                    bm25_error = ?bm25_err,
+                    "Both stores failed to upsert batch"
+                );
+                self.journal_commit(txn);
+                Err(vector_err)
+            }
+        }
+    }
+
+    /// Insert or replace a single AST chunk in both stores by ID
+    ///
+    /// See [`HybridOrchestrator::upsert_batch`] for failure handling.
+    pub async fn upsert(&self, chunk: AstChunk) -> Result<(), AppError> {
+        self.upsert_batch(vec![chunk]).await
+    }
+
+    /// Delete IDs from both stores, returning `true` if every delete succeeded
+    async fn purge_ids(&self, ids: &[String]) -> bool {
+        let mut purged = true;
+        for id in ids {
+            let (vector_result, bm25_result) =
+                tokio::join!(self.vector_store.delete(id), self.bm25_store.delete(id));
+            if let Err(e) = vector_result {
+                purged = false;
+                error!(id = %id, error = ?e, "Failed to delete from vector store during cleanup");
+            }
+            if let Err(e) = bm25_result {
+                purged = false;
+                error!(id = %id, error = ?e, "Failed to delete from BM25 store during cleanup");
+            }
+        }
+        purged
+    }
+
     /// Delete a document from both stores
     ///
     /// This is a convenience method for deleting documents from both backends.
@@ -437,6 +765,15 @@ impl HybridOrchestrator {
     /// }
     /// ```
     pub async fn delete(&self, id: &str) -> DeleteResult {
+        // Record intent; a journal failure must not block deletes
+        let txn = match self.journal_begin(OperationKind::Delete, vec![id.to_string()], &[]) {
+            Ok(txn) => txn,
+            Err(e) => {
+                warn!(error = ?e, id = %id, "Failed to journal delete, continuing without journal");
+                None
+            }
+        };
+
         // Delete from both stores in parallel
         let (vector_result, bm25_result) =
             tokio::join!(self.vector_store.delete(id), self.bm25_store.delete(id));
@@ -458,13 +795,53 @@ impl HybridOrchestrator {
             }
         };
 
+        // A failed side is replayed on the next recovery
+        if vector_deleted.is_ok() && bm25_deleted.is_ok() {
+            self.journal_commit(txn);
+        } else {
+            self.journal_abandon(txn);
+        }
+
         // Return detailed results
         DeleteResult {
+            vector_count: usize::from(matches!(vector_deleted, Ok(true))),
+            bm25_count: usize::from(matches!(bm25_deleted, Ok(true))),
             vector_deleted,
             bm25_deleted,
         }
     }
 
+    /// Delete every document matching a filter from both stores
+    ///
+    /// Used to clean up all sections of a removed or renamed file without
+    /// knowing their IDs. Bulk deletes are not journaled because the affected
+    /// IDs are only known to each store; if one side fails, re-run the delete
+    /// or use `repair` to remove the remaining orphans.
+    ///
+    /// # Parameters
+    ///
+    /// * `filter` - Path prefix, pack and/or node type criteria (AND-ed)
+    ///
+    /// # Returns
+    ///
+    /// Returns `DeleteResult` with the number of rows removed from each backend.
+    /// An empty filter is rejected by both stores with `DomainError::NotAllowed`.
+    pub async fn delete_where(&self, filter: &ChunkFilter) -> DeleteResult {
+        let (vector_result, bm25_result) = tokio::join!(
+            self.vector_store.delete_where(filter),
+            self.bm25_store.delete_where(filter)
+        );
+
+        if let Err(e) = &vector_result {
+            warn!(error = ?e, filter = ?filter, "Vector bulk delete failed");
+        }
+        if let Err(e) = &bm25_result {
+            warn!(error = ?e, filter = ?filter, "BM25 bulk delete failed");
+        }
+
+        DeleteResult::from_counts(vector_result, bm25_result)
+    }
+
     /// Check health of both stores
     ///
     /// Returns true if both stores are healthy.
@@ -494,6 +871,273 @@ impl HybridOrchestrator {
         Ok(vector_healthy && bm25_healthy)
     }
 
+    /// Map every chunk ID held by either store to its file path
+    ///
+    /// Rows present in only one store are included, so callers can clean up
+    /// chunks that a rebuild no longer produces even after partial writes.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if either store cannot be enumerated.
+    pub async fn chunk_paths(&self) -> Result<HashMap<String, String>, AppError> {
+        let (bm25_chunks, vector_chunks) = tokio::try_join!(
+            self.bm25_store.list_chunks(),
+            self.vector_store.list_chunks()
+        )?;
+
+        Ok(bm25_chunks
+            .into_iter()
+            .chain(vector_chunks)
+            .map(|chunk| (chunk.id, chunk.file_path))
+            .collect())
+    }
+
+    /// Compare the contents of both stores
+    ///
+    /// Lists every row of both backends and reports orphans, duplicates and
+    /// content mismatches. Nothing is modified.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if either store cannot be enumerated.
+    pub async fn check_consistency(&self) -> Result<ConsistencyReport, AppError> {
+        let (bm25_chunks, vector_chunks) = tokio::try_join!(
+            self.bm25_store.list_chunks(),
+            self.vector_store.list_chunks()
+        )?;
+
+        let report = ConsistencyReport::from_chunks(&bm25_chunks, &vector_chunks);
+        info!(
+            bm25_count = report.bm25_count,
+            vector_count = report.vector_count,
+            issues = report.issue_count(),
+            "Consistency check completed"
+        );
+        Ok(report)
+    }
+
+    /// Repair drift between the two stores
+    ///
+    /// # Strategies
+    ///
+    /// - `Resync`: orphans are copied to the store that misses them. Duplicated
+    ///   or mismatched IDs are rewritten from the BM25 copy, which is treated as
+    ///   the source of truth because it stores every field verbatim.
+    /// - `DeleteOrphans`: orphans are deleted from the store that holds them.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first backend error. Changes made before the error are kept;
+    /// running the check again shows what is left.
+    pub async fn repair(&self, strategy: RepairStrategy) -> Result<RepairReport, AppError> {
+        let (bm25_chunks, vector_chunks) = tokio::try_join!(
+            self.bm25_store.list_chunks(),
+            self.vector_store.list_chunks()
+        )?;
+        let report = ConsistencyReport::from_chunks(&bm25_chunks, &vector_chunks);
+        let mut repair = RepairReport::default();
+
+        if report.is_consistent() {
+            return Ok(repair);
+        }
+
+        match strategy {
+            RepairStrategy::Resync => {
+                let bm25 = group_by_id(&bm25_chunks);
+                let vector = group_by_id(&vector_chunks);
+
+                // Prefer the BM25 copy, fall back to the vector copy for BM25 orphans
+                let source = |id: &str| -> Option<AstChunk> {
+                    bm25.get(id)
+                        .or_else(|| vector.get(id))
+                        .and_then(|rows| rows.first())
+                        .map(|chunk| (*chunk).clone())
+                };
+
+                let vector_writes: Vec<AstChunk> = report
+                    .missing_in_vector
+                    .iter()
+                    .chain(&report.duplicates_vector)
+                    .chain(&report.content_mismatches)
+                    .collect::<std::collections::BTreeSet<_>>()
+                    .into_iter()
+                    .filter_map(|id| source(id))
+                    .collect();
+
+                let bm25_writes: Vec<AstChunk> = report
+                    .missing_in_bm25
+                    .iter()
+                    .chain(&report.duplicates_bm25)
+                    .collect::<std::collections::BTreeSet<_>>()
+                    .into_iter()
+                    .filter_map(|id| source(id))
+                    .collect();
+
+                repair.vector_written = vector_writes.len();
+                repair.bm25_written = bm25_writes.len();
+
+                // Both stores deduplicate IDs on batch add, so rewriting is enough
+                self.vector_store.add_batch(vector_writes).await?;
+                self.bm25_store.add_batch(bm25_writes).await?;
+            }
+            RepairStrategy::DeleteOrphans => {
+                for id in &report.missing_in_vector {
+                    if self.bm25_store.delete(id).await? {
+                        repair.bm25_deleted += 1;
+                    }
+                }
+                for id in &report.missing_in_bm25 {
+                    if self.vector_store.delete(id).await? {
+                        repair.vector_deleted += 1;
+                    }
+                }
+            }
+        }
+
+        info!(
+            strategy = ?strategy,
+            changes = repair.total_changes(),
+            "Repair completed"
+        );
+        Ok(repair)
+    }
+
+    /// Resolve operations left open in the journal by a previous process
+    ///
+    /// - **Add**: kept when both stores hold the journaled content hash for an
+    ///   ID, otherwise the ID is deleted from both stores (the content itself is
+    ///   not journaled, so a half-written add cannot be replayed).
+    /// - **Upsert**: the journaled chunks are upserted into both stores again,
+    ///   so an ID that existed before keeps a version in both stores.
+    /// - **Delete**: replayed on both stores.
+    ///
+    /// The journal is cleared once every pending operation is resolved. Without
+    /// a journal this is a no-op.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the journal cannot be read or a store operation fails;
+    /// the journal is left intact so recovery can be retried.
+    pub async fn recover_journal(&self) -> Result<RecoveryReport, AppError> {
+        let mut report = RecoveryReport::default();
+        let Some(journal) = &self.journal else {
+            return Ok(report);
+        };
+
+        for op in journal.pending()? {
+            warn!(txn = op.txn, kind = ?op.kind, ids = op.ids.len(), "Recovering unfinished operation");
+
+            match op.kind {
+                OperationKind::Add => {
+                    let (bm25_docs, vector_docs) = tokio::try_join!(
+                        self.bm25_store.get_by_ids(&op.ids),
+                        self.vector_store.get_by_ids(&op.ids)
+                    )?;
+
+                    for (i, id) in op.ids.iter().enumerate() {
+                        let expected = op.content_hashes.get(i);
+                        let bm25_hash = bm25_docs
+                            .get(i)
+                            .and_then(|doc| doc.as_ref())
+                            .and_then(|doc| doc.content.as_deref())
+                            .map(content_hash);
+                        let vector_hash = vector_docs
+                            .get(i)
+                            .and_then(|chunk| chunk.as_ref())
+                            .map(|chunk| content_hash(&chunk.content));
+
+                        if expected.is_some()
+                            && bm25_hash.as_ref() == expected
+                            && vector_hash.as_ref() == expected
+                        {
+                            report.adds_completed += 1;
+                        } else {
+                            self.vector_store.delete(id).await?;
+                            self.bm25_store.delete(id).await?;
+                            report.adds_rolled_back += 1;
+                        }
+                    }
+                }
+                OperationKind::Upsert => {
+                    let count = op.chunks.len();
+                    tokio::try_join!(
+                        self.vector_store.upsert_batch(op.chunks.clone()),
+                        self.bm25_store.upsert_batch(op.chunks)
+                    )?;
+                    report.upserts_replayed += count;
+                }
+                OperationKind::Delete => {
+                    for id in &op.ids {
+                        self.vector_store.delete(id).await?;
+                        self.bm25_store.delete(id).await?;
+                        report.deletes_replayed += 1;
+                    }
+                }
+            }
+        }
+
+        journal.clear()?;
+
+        if report.total() > 0 {
+            info!(
+                adds_completed = report.adds_completed,
+                adds_rolled_back = report.adds_rolled_back,
+                upserts_replayed = report.upserts_replayed,
+                deletes_replayed = report.deletes_replayed,
+                "Journal recovery completed"
+            );
+        }
+        Ok(report)
+    }
+
+    /// Record an operation in the journal, if one is attached
+    fn journal_begin(
+        &self,
+        kind: OperationKind,
+        ids: Vec<String>,
+        contents: &[&str],
+    ) -> Result<Option<u64>, AppError> {
+        self.journal
+            .as_ref()
+            .map(|journal| journal.begin(kind, ids, contents))
+            .transpose()
+    }
+
+    /// Record an upsert, with its chunks, in the journal, if one is attached
+    fn journal_begin_upsert(&self, chunks: &[AstChunk]) -> Result<Option<u64>, AppError> {
+        self.journal
+            .as_ref()
+            .map(|journal| journal.begin_upsert(chunks))
+            .transpose()
+    }
+
+    /// Mark a journaled operation as complete
+    ///
+    /// Failures are only logged: recovery re-checks the stores anyway.
+    fn journal_commit(&self, txn: Option<u64>) {
+        if let (Some(journal), Some(txn)) = (&self.journal, txn) {
+            if let Err(e) = journal.commit(txn) {
+                warn!(error = ?e, txn = txn, "Failed to commit journal entry");
+            }
+        }
+    }
+
+    /// Stop tracking a journaled operation that left the stores diverged
+    ///
+    /// Its begin entry stays on disk so the next `recover_journal` resolves it.
+    fn journal_abandon(&self, txn: Option<u64>) {
+        if let (Some(journal), Some(txn)) = (&self.journal, txn) {
+            if let Err(e) = journal.abandon(txn) {
+                warn!(error = ?e, txn = txn, "Failed to abandon journal entry");
+            }
+        }
+    }
+
+    /// Get the attached journal, if any
+    pub fn journal(&self) -> Option<&Arc<OperationJournal>> {
+        self.journal.as_ref()
+    }
+
     /// Get reference to BM25 store (for advanced usage)
     pub fn bm25_store(&self) -> &Arc<dyn Bm25StoreTrait> {
         &self.bm25_store
@@ -569,6 +1213,17 @@ mod tests {
             }
         }
 
+        async fn upsert_batch(&self, _chunks: Vec<AstChunk>) -> Result<(), AppError> {
+            if self.add_should_fail {
+                Err(AppError::Infra(InfraError::database(
+                    "mock vector upsert_batch failed",
+                    None::<std::io::Error>,
+                )))
+            } else {
+                Ok(())
+            }
+        }
+
         async fn delete(&self, _id: &str) -> Result<bool, AppError> {
             if self.delete_should_fail {
                 Err(AppError::Infra(InfraError::database(
@@ -583,6 +1238,25 @@ mod tests {
         async fn health_check(&self) -> Result<bool, AppError> {
             Ok(true)
         }
+
+        async fn get_by_id(&self, _id: &str) -> Result<Option<AstChunk>, AppError> {
+            Ok(None)
+        }
+
+        async fn list_chunks(&self) -> Result<Vec<AstChunk>, AppError> {
+            Ok(vec![])
+        }
+
+        async fn delete_where(&self, _filter: &ChunkFilter) -> Result<usize, AppError> {
+            if self.delete_should_fail {
+                Err(AppError::Infra(InfraError::database(
+                    "mock vector delete_where failed",
+                    None::<std::io::Error>,
+                )))
+            } else {
+                Ok(2)
+            }
+        }
     }
 
     struct MockBm25Store {
@@ -652,6 +1326,17 @@ mod tests {
             }
         }
 
+        async fn upsert_batch(&self, _chunks: Vec<AstChunk>) -> Result<(), AppError> {
+            if self.add_should_fail {
+                Err(AppError::Infra(InfraError::database(
+                    "mock BM25 upsert_batch failed",
+                    None::<std::io::Error>,
+                )))
+            } else {
+                Ok(())
+            }
+        }
+
         async fn delete(&self, _id: &str) -> Result<bool, AppError> {
             if self.delete_should_fail {
                 Err(AppError::Infra(InfraError::database(
@@ -674,6 +1359,21 @@ mod tests {
         async fn get_by_ids(&self, _ids: &[String]) -> Result<Vec<Option<Bm25Result>>, AppError> {
             Ok(vec![])
         }
+
+        async fn list_chunks(&self) -> Result<Vec<AstChunk>, AppError> {
+            Ok(vec![])
+        }
+
+        async fn delete_where(&self, _filter: &ChunkFilter) -> Result<usize, AppError> {
+            if self.delete_should_fail {
+                Err(AppError::Infra(InfraError::database(
+                    "mock BM25 delete_where failed",
+                    None::<std::io::Error>,
+                )))
+            } else {
+                Ok(2)
+            }
+        }
     }
 
     /// Helper to create a test orchestrator
@@ -714,6 +1414,167 @@ mod tests {
         assert!(!hits.is_empty());
     }
 
+    #[test]
+    fn test_hybrid_search_records_stage_latencies() {
+        use super::super::metrics::SEARCH_STAGE_SECONDS;
+        use metrics_util::debugging::DebuggingRecorder;
+
+        let recorder = DebuggingRecorder::new();
+        let snapshotter = recorder.snapshotter();
+        metrics::with_local_recorder(&recorder, || {
+            tokio::runtime::Builder::new_current_thread()
+                .build()
+                .unwrap()
+                .block_on(async {
+                    let orchestrator = create_test_orchestrator().await;
+                    orchestrator.search(&Query::new("test query", 10)).await.unwrap();
+                })
+        });
+
+        let stages: std::collections::BTreeSet<String> = snapshotter
+            .snapshot()
+            .into_vec()
+            .into_iter()
+            .filter(|(key, ..)| key.key().name() == SEARCH_STAGE_SECONDS)
+            .flat_map(|(key, ..)| {
+                key.key()
+                    .labels()
+                    .filter(|label| label.key() == "stage")
+                    .map(|label| label.value().to_string())
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        // The mock vector store embeds inside `search`, so there is no embed stage
+        assert_eq!(
+            stages.iter().map(String::as_str).collect::<Vec<_>>(),
+            vec!["bm25", "fusion", "vector"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_hybrid_search_explained_reports_backend_scores() {
+        let orchestrator = create_test_orchestrator().await;
+        let query = Query::new("test query", 10);
+
+        let hits = orchestrator
+            .search_explained(&query, FusionMode::Rrf)
+            .await
+            .unwrap();
+        let plain = orchestrator.search(&query).await.unwrap();
+        assert_eq!(
+            hits.iter().map(|h| h.id.as_str()).collect::<Vec<_>>(),
+            plain.iter().map(|h| h.id.as_str()).collect::<Vec<_>>()
+        );
+
+        let bm25_doc = hits.iter().find(|h| h.id == "bm25-doc2").unwrap();
+        assert_eq!(bm25_doc.bm25, Some(BackendScore { rank: 2, score: 0.8 }));
+        assert_eq!(bm25_doc.vector, None);
+        let expected = 1.0 / (orchestrator.rrf_k() as f64 + 2.0);
+        assert!((bm25_doc.score.value() - expected).abs() < 1e-9);
+
+        let vector_doc = hits.iter().find(|h| h.id == "vec-doc1").unwrap();
+        assert_eq!(vector_doc.vector, Some(BackendScore { rank: 1, score: 0.95 }));
+    }
+
+    #[tokio::test]
+    async fn test_search_with_progress_reports_backends_and_stages() {
+        use super::super::progress::SearchProgress;
+
+        let orchestrator = create_test_orchestrator().await;
+        let query = Query::new("test query", 10);
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+
+        let hits = orchestrator
+            .search_with_progress(&query, FusionMode::Rrf, Some(&sender))
+            .await
+            .unwrap();
+        drop(sender);
+        let explained = orchestrator
+            .search_explained(&query, FusionMode::Rrf)
+            .await
+            .unwrap();
+        assert_eq!(hits, explained);
+
+        let mut results = HashMap::new();
+        let mut stages = Vec::new();
+        while let Some(progress) = receiver.recv().await {
+            match progress {
+                SearchProgress::Results { backend, hits } => {
+                    results.insert(backend, hits);
+                }
+                SearchProgress::Stage { stage, .. } => stages.push(stage),
+                SearchProgress::Failed { backend, error } => {
+                    panic!("{} failed: {}", backend.as_str(), error)
+                }
+            }
+        }
+        assert_eq!(results[&SearchBackend::Bm25][1].id, "bm25-doc2");
+        assert_eq!(results[&SearchBackend::Vector][0].id, "vec-doc1");
+        // Fusion needs both result lists, so it is always reported last
+        assert_eq!(stages.last(), Some(&SearchStage::Fusion));
+        assert!(stages.contains(&SearchStage::Bm25) && stages.contains(&SearchStage::Vector));
+    }
+
+    #[tokio::test]
+    async fn test_search_with_progress_reports_failed_backend() {
+        use super::super::progress::SearchProgress;
+
+        let vector_store = Arc::new(MockVectorStore {
+            should_fail: true,
+            empty_results: false,
+            delete_should_fail: false,
+            add_should_fail: false,
+        });
+        let bm25_store = Arc::new(MockBm25Store {
+            should_fail: false,
+            empty_results: false,
+            delete_should_fail: false,
+            add_should_fail: false,
+        });
+        let orchestrator = HybridOrchestrator::default_with_stores(vector_store, bm25_store);
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+
+        let hits = orchestrator
+            .search_with_progress(&Query::new("test query", 10), FusionMode::Rrf, Some(&sender))
+            .await
+            .unwrap();
+        assert!(!hits.is_empty());
+        drop(sender);
+
+        let mut failed = Vec::new();
+        while let Some(progress) = receiver.recv().await {
+            if let SearchProgress::Failed { backend, .. } = progress {
+                failed.push(backend);
+            }
+        }
+        assert_eq!(failed, vec![SearchBackend::Vector]);
+    }
+
+    #[tokio::test]
+    async fn test_hybrid_search_single_backend_modes() {
+        let orchestrator = create_test_orchestrator().await;
+        let query = Query::new("test query", 10);
+
+        let bm25 = orchestrator
+            .search_explained(&query, FusionMode::Bm25)
+            .await
+            .unwrap();
+        assert_eq!(bm25.len(), 2);
+        assert!(bm25.iter().all(|h| h.vector.is_none()));
+        assert_eq!(bm25[0].score.value(), 0.9, "BM25 mode keeps raw scores");
+
+        let vector = orchestrator
+            .search_explained(&query, FusionMode::Vector)
+            .await
+            .unwrap();
+        assert!(vector.iter().all(|h| h.id.starts_with("vec-doc")));
+
+        assert_eq!(
+            serde_json::from_str::<FusionMode>("\"bm25\"").unwrap(),
+            FusionMode::Bm25
+        );
+    }
+
     #[tokio::test]
     async fn test_hybrid_search_empty_query() {
         let orchestrator = create_test_orchestrator().await;
@@ -741,6 +1602,8 @@ mod tests {
         assert!(result.first_error().is_none());
         assert!(matches!(result.vector_deleted, Ok(true)));
         assert!(matches!(result.bm25_deleted, Ok(true)));
+        assert_eq!(result.vector_count, 1);
+        assert_eq!(result.bm25_count, 1);
     }
 
     #[tokio::test]
@@ -871,7 +1734,7 @@ mod tests {
         assert!(result.first_error().is_some(), "Should have vector error");
 
         assert!(
-            matches!(result.vector_deleted, Err(_)),
+            result.vector_deleted.is_err(),
             "Vector delete should fail"
         );
         assert!(
@@ -912,7 +1775,7 @@ mod tests {
             "Vector delete should succeed"
         );
         assert!(
-            matches!(result.bm25_deleted, Err(_)),
+            result.bm25_deleted.is_err(),
             "BM25 delete should fail"
         );
     }
@@ -986,4 +1849,111 @@ mod tests {
         // Note: We can't directly verify the rollback happened without more sophisticated mocking,
         // but the test ensures the error path is exercised and rollback code is executed.
     }
+
+    #[tokio::test]
+    async fn test_hybrid_upsert() {
+        let orchestrator = create_test_orchestrator().await;
+        let chunk = AstChunk::without_dependencies("id", "doc.md", "Title", "file", "content");
+
+        assert!(orchestrator.upsert(chunk.clone()).await.is_ok());
+        assert!(orchestrator.upsert_batch(vec![chunk.clone(), chunk]).await.is_ok());
+        assert!(orchestrator.upsert_batch(vec![]).await.is_ok());
+    }
+
+    /// Build an orchestrator with a journal where only the BM25 upsert fails
+    fn create_partial_upsert_orchestrator(
+        dir: &tempfile::TempDir,
+        delete_should_fail: bool,
+    ) -> (HybridOrchestrator, Arc<OperationJournal>) {
+        let vector_store = Arc::new(MockVectorStore {
+            should_fail: false,
+            empty_results: false,
+            delete_should_fail,
+            add_should_fail: false,
+        });
+
+        let bm25_store = Arc::new(MockBm25Store {
+            should_fail: false,
+            empty_results: false,
+            delete_should_fail: false,
+            add_should_fail: true,
+        });
+
+        let journal = Arc::new(OperationJournal::open(dir.path().join("journal.jsonl")).unwrap());
+        let orchestrator = HybridOrchestrator::default_with_stores(vector_store, bm25_store)
+            .with_journal(Arc::clone(&journal));
+        (orchestrator, journal)
+    }
+
+    #[tokio::test]
+    async fn test_hybrid_upsert_partial_failure_cleans_up_and_commits_journal() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let (orchestrator, journal) = create_partial_upsert_orchestrator(&dir, false);
+
+        let result = orchestrator
+            .upsert(AstChunk::without_dependencies("id", "doc.md", "Title", "file", "content"))
+            .await;
+
+        assert!(result.is_err(), "Should return the BM25 upsert error");
+        assert!(journal.pending().unwrap().is_empty(), "Cleanup succeeded, journal is committed");
+    }
+
+    #[tokio::test]
+    async fn test_hybrid_upsert_failed_cleanup_leaves_journal_pending() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let (orchestrator, journal) = create_partial_upsert_orchestrator(&dir, true);
+
+        let result = orchestrator
+            .upsert(AstChunk::without_dependencies("id", "doc.md", "Title", "file", "content"))
+            .await;
+
+        assert!(result.is_err());
+        assert_eq!(journal.pending().unwrap().len(), 1, "Recovery must finish the cleanup");
+
+        // The failed upsert no longer blocks truncation, but its entry is kept
+        let txn = journal.begin(OperationKind::Delete, vec!["other".to_string()], &[]).unwrap();
+        journal.commit(txn).unwrap();
+        let contents = std::fs::read_to_string(journal.path()).unwrap();
+        assert_eq!(contents.lines().count(), 1);
+        assert_eq!(journal.pending().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_hybrid_delete_where_reports_counts() {
+        let orchestrator = create_test_orchestrator().await;
+        let result = orchestrator
+            .delete_where(&ChunkFilter::new().with_path_prefix("docs/"))
+            .await;
+
+        assert!(result.both_success());
+        assert_eq!(result.vector_count, 2);
+        assert_eq!(result.bm25_count, 2);
+    }
+
+    #[tokio::test]
+    async fn test_hybrid_delete_where_partial_failure() {
+        let vector_store = Arc::new(MockVectorStore {
+            should_fail: false,
+            empty_results: false,
+            delete_should_fail: true,
+            add_should_fail: false,
+        });
+
+        let bm25_store = Arc::new(MockBm25Store {
+            should_fail: false,
+            empty_results: false,
+            delete_should_fail: false,
+            add_should_fail: false,
+        });
+
+        let orchestrator = HybridOrchestrator::default_with_stores(vector_store, bm25_store);
+        let result = orchestrator
+            .delete_where(&ChunkFilter::new().with_pack("guide"))
+            .await;
+
+        assert!(result.any_success());
+        assert!(result.vector_deleted.is_err());
+        assert_eq!(result.vector_count, 0);
+        assert_eq!(result.bm25_count, 2);
+    }
 }
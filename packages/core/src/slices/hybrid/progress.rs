@@ -0,0 +1,103 @@
+//! Intermediate search results
+//!
+//! [`HybridOrchestrator::search_with_progress`](super::HybridOrchestrator::search_with_progress)
+//! reports each backend's results and each stage's latency on a channel as
+//! soon as they are available, so callers can stream them before fusion is
+//! done (the server's `/api/search/stream`).
+
+use std::future::Future;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::kernel::types::Hit;
+
+use super::metrics::SearchStage;
+
+/// A search backend queried by the orchestrator
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SearchBackend {
+    /// BM25 full-text search
+    Bm25,
+    /// Vector similarity search
+    Vector,
+}
+
+impl SearchBackend {
+    /// Backend name for display and serialization
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SearchBackend::Bm25 => "bm25",
+            SearchBackend::Vector => "vector",
+        }
+    }
+}
+
+/// One step of a search in progress
+#[derive(Debug, Clone, PartialEq)]
+pub enum SearchProgress {
+    /// A backend returned its results, in that backend's rank order
+    Results {
+        backend: SearchBackend,
+        hits: Vec<Hit>,
+    },
+    /// A backend failed; the search goes on with the other one
+    Failed {
+        backend: SearchBackend,
+        error: String,
+    },
+    /// A stage finished
+    Stage {
+        stage: SearchStage,
+        elapsed: Duration,
+    },
+}
+
+/// Channel receiving the progress of a search
+pub type ProgressSender = UnboundedSender<SearchProgress>;
+
+/// Records stage metrics and forwards progress to an optional listener
+///
+/// Send errors are ignored: a listener that went away (a disconnected client)
+/// must not fail the search.
+#[derive(Clone, Copy)]
+pub(crate) struct Progress<'a>(Option<&'a ProgressSender>);
+
+impl<'a> Progress<'a> {
+    pub(crate) fn new(sender: Option<&'a ProgressSender>) -> Self {
+        Self(sender)
+    }
+
+    fn send(&self, progress: SearchProgress) {
+        if let Some(sender) = self.0 {
+            let _ = sender.send(progress);
+        }
+    }
+
+    /// Record one run of `stage`
+    pub(crate) fn stage(&self, stage: SearchStage, elapsed: Duration) {
+        stage.record(elapsed);
+        self.send(SearchProgress::Stage { stage, elapsed });
+    }
+
+    /// Await `future` and record its duration as `stage`
+    pub(crate) async fn timed<F: Future>(&self, stage: SearchStage, future: F) -> F::Output {
+        let started = Instant::now();
+        let output = future.await;
+        self.stage(stage, started.elapsed());
+        output
+    }
+
+    pub(crate) fn results(&self, backend: SearchBackend, hits: &[Hit]) {
+        self.send(SearchProgress::Results {
+            backend,
+            hits: hits.to_vec(),
+        });
+    }
+
+    pub(crate) fn failed(&self, backend: SearchBackend, error: &impl std::fmt::Display) {
+        self.send(SearchProgress::Failed {
+            backend,
+            error: error.to_string(),
+        });
+    }
+}
@@ -80,6 +80,11 @@ impl RrfOrchestrator {
         Self::new(60)
     }
 
+    /// RRF constant k
+    pub fn k(&self) -> i32 {
+        self.k
+    }
+
     /// Fuse results from multiple rankers using RRF
     ///
     /// # Parameters
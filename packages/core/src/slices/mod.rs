@@ -9,9 +9,11 @@
 //! - **vector/**: Vector storage abstraction with LanceDB backend
 //! - **bm25/**: BM25 full-text search with Tantivy backend
 //! - **hybrid/**: Hybrid retrieval orchestration (RRF fusion)
+//! - **graph/**: Document link graph (outbound links, backlinks, orphans)
 //!
 //! Ref: `openspec/changes/refactor-pragmatic-slice-architecture/design.md`
 
 pub mod bm25;
+pub mod graph;
 pub mod hybrid;
 pub mod vector;
@@ -265,7 +265,7 @@ mod tests {
         let schema = table.schema().await.expect("Failed to get schema");
 
         // Verify field count
-        assert_eq!(schema.fields().len(), 7);
+        assert_eq!(schema.fields().len(), 15);
 
         // Verify vector field
         let vector_field = schema
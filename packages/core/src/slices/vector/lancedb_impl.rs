@@ -7,7 +7,7 @@ use anyhow::{Context, Result as AnyhowResult};
 ///
 /// Ref: `openspec/changes/refactor-pragmatic-slice-architecture/design.md` - Rule 2
 use async_trait::async_trait;
-use arrow::array::{Float32Array, RecordBatch, StringArray};
+use arrow::array::{Float32Array, RecordBatch, StringArray, UInt32Array};
 use arrow::record_batch::RecordBatchIterator;
 use lancedb::connection::Connection as LanceConnection;
 use lancedb::query::{ExecutableQuery, QueryBase};
@@ -16,8 +16,10 @@ use std::sync::Arc;
 use futures::StreamExt;
 
 use crate::embeddings::EmbeddingModel;
-use crate::kernel::errors::{AppError, InfraError};
-use crate::kernel::types::{AstChunk, Hit, Query, Score};
+use crate::kernel::errors::{AppError, DomainError, InfraError};
+use crate::kernel::types::{
+    dedupe_last_wins_by, AstChunk, ChunkFilter, Hit, LineSpan, PageRange, Query, Score,
+};
 
 use super::trait_::VectorStoreTrait;
 
@@ -91,6 +93,427 @@ impl LanceDbStore {
             .with_context(|| format!("Failed to open table: {}", self.table_name))
     }
 
+    /// Build an `id IN (...)` filter with every ID escaped as a SQL string literal
+    ///
+    /// Single quotes are doubled (`'` → `''`), which is the literal escaping
+    /// understood by LanceDB's SQL filter parser. This keeps caller-provided IDs
+    /// from breaking out of the literal.
+    fn id_filter<S: AsRef<str>>(ids: &[S]) -> String {
+        format!(
+            "id IN ({})",
+            ids.iter()
+                .map(|id| format!("'{}'", id.as_ref().replace('\'', "''")))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+    }
+
+    /// Build a SQL predicate for a chunk filter
+    ///
+    /// Criteria are AND-ed. The path prefix and tag become `LIKE` patterns with
+    /// `\`, `%` and `_` escaped; all values have single quotes doubled. Tags are
+    /// stored comma-separated, so a tag matches as a whole `,tag,` element.
+    /// Returns `None` for an empty filter.
+    fn filter_predicate(filter: &ChunkFilter) -> Option<String> {
+        fn quote(value: &str) -> String {
+            format!("'{}'", value.replace('\'', "''"))
+        }
+
+        fn escape_like(value: &str) -> String {
+            value
+                .replace('\\', "\\\\")
+                .replace('%', "\\%")
+                .replace('_', "\\_")
+        }
+
+        let mut clauses = Vec::new();
+        if let Some(prefix) = &filter.path_prefix {
+            clauses.push(format!("file_path LIKE {}", quote(&format!("{}%", escape_like(prefix)))));
+        }
+        if let Some(pack) = &filter.pack {
+            clauses.push(format!("pack = {}", quote(pack)));
+        }
+        if !filter.packs.is_empty() {
+            let packs: Vec<String> = filter.packs.iter().map(|pack| quote(pack)).collect();
+            clauses.push(format!("pack IN ({})", packs.join(", ")));
+        }
+        if let Some(node_type) = &filter.node_type {
+            // `code` also matches language-tagged types such as `code:rust`
+            clauses.push(format!(
+                "(node_type = {} OR node_type LIKE {})",
+                quote(node_type),
+                quote(&format!("{}:%", escape_like(node_type)))
+            ));
+        }
+        if let Some(tag) = &filter.tag {
+            clauses.push(format!(
+                "(',' || tags || ',') LIKE {}",
+                quote(&format!("%,{},%", escape_like(&encode_tag(tag))))
+            ));
+        }
+        if let Some(version) = &filter.version {
+            clauses.push(format!("version = {}", quote(version)));
+        }
+
+        (!clauses.is_empty()).then(|| clauses.join(" AND "))
+    }
+
+    /// Number of nearest neighbours to fetch for a query
+    ///
+    /// A node type boost reorders hits, so extra candidates are fetched and the list
+    /// is truncated to `query.limit` after rescoring.
+    fn fetch_limit(query: &Query) -> usize {
+        if query.boost.is_some() {
+            query.limit.saturating_mul(BOOST_OVERFETCH_FACTOR)
+        } else {
+            query.limit
+        }
+    }
+
+    /// Fetch stored rows by ID with a filtered scan on the `id` column
+    ///
+    /// If an ID has several rows (legacy appends), the first row wins.
+    async fn fetch_by_ids(
+        &self,
+        ids: &[String],
+    ) -> Result<std::collections::HashMap<String, AstChunk>, AppError> {
+        let mut found = std::collections::HashMap::with_capacity(ids.len());
+        if ids.is_empty() {
+            return Ok(found);
+        }
+
+        for chunk in self.scan_chunks(Some(Self::id_filter(ids))).await? {
+            found.entry(chunk.id.clone()).or_insert(chunk);
+        }
+
+        Ok(found)
+    }
+
+    /// Scan stored rows, optionally restricted by a SQL filter
+    ///
+    /// The vector column is not selected, so returned chunks never carry embeddings.
+    /// Every matching row is returned, including rows that share an ID.
+    async fn scan_chunks(&self, filter: Option<String>) -> Result<Vec<AstChunk>, AppError> {
+        use lancedb::query::Select;
+
+        let table = self
+            .get_table()
+            .await
+            .map_err(|e| AppError::Infra(InfraError::database(
+                "Failed to open table for scan",
+                Some(e),
+            )))?;
+
+        let mut query = table.query().select(Select::columns(&[
+            "id",
+            "file_path",
+            "symbol_name",
+            "node_type",
+            "content",
+            "dependencies",
+            "pack",
+            "heading_path",
+            "tags",
+            "version",
+            "start_line",
+            "end_line",
+            "start_page",
+            "end_page",
+        ]));
+        if let Some(filter) = filter {
+            query = query.only_if(filter);
+        }
+
+        let mut results_stream = query
+            .execute()
+            .await
+            .map_err(|e| AppError::Infra(InfraError::database(
+                "Table scan failed",
+                Some(e),
+            )))?;
+
+        let mut chunks = Vec::new();
+        while let Some(batch_result) = results_stream.next().await {
+            let batch = batch_result.map_err(|e| {
+                AppError::Infra(InfraError::database(
+                    "Failed to read result batch",
+                    Some(e),
+                ))
+            })?;
+
+            chunks.extend(Self::batch_to_chunks(&batch)?);
+        }
+
+        Ok(chunks)
+    }
+
+    /// Convert a LanceDB result batch into kernel `AstChunk`s (without vectors)
+    fn batch_to_chunks(batch: &RecordBatch) -> Result<Vec<AstChunk>, AppError> {
+        use arrow::array::Array;
+
+        fn string_column<'a>(batch: &'a RecordBatch, name: &str) -> Result<&'a StringArray, AppError> {
+            batch
+                .column_by_name(name)
+                .ok_or_else(|| {
+                    AppError::Infra(InfraError::Other(format!(
+                        "Missing {} column in scan results",
+                        name
+                    )))
+                })?
+                .as_any()
+                .downcast_ref::<StringArray>()
+                .ok_or_else(|| {
+                    AppError::Infra(InfraError::Other(format!(
+                        "Failed to cast {} column to StringArray",
+                        name
+                    )))
+                })
+        }
+
+        fn line_column<'a>(batch: &'a RecordBatch, name: &str) -> Result<&'a UInt32Array, AppError> {
+            batch
+                .column_by_name(name)
+                .ok_or_else(|| {
+                    AppError::Infra(InfraError::Other(format!(
+                        "Missing {} column in scan results",
+                        name
+                    )))
+                })?
+                .as_any()
+                .downcast_ref::<UInt32Array>()
+                .ok_or_else(|| {
+                    AppError::Infra(InfraError::Other(format!(
+                        "Failed to cast {} column to UInt32Array",
+                        name
+                    )))
+                })
+        }
+
+        let ids = string_column(batch, "id")?;
+        let file_paths = string_column(batch, "file_path")?;
+        let symbol_names = string_column(batch, "symbol_name")?;
+        let node_types = string_column(batch, "node_type")?;
+        let contents = string_column(batch, "content")?;
+        let dependencies = string_column(batch, "dependencies")?;
+        let packs = string_column(batch, "pack")?;
+        let heading_paths = string_column(batch, "heading_path")?;
+        let tags = string_column(batch, "tags")?;
+        let versions = string_column(batch, "version")?;
+        let start_lines = line_column(batch, "start_line")?;
+        let end_lines = line_column(batch, "end_line")?;
+        let start_pages = line_column(batch, "start_page")?;
+        let end_pages = line_column(batch, "end_page")?;
+
+        let chunks = (0..batch.num_rows())
+            .map(|row| {
+                // Dependencies are stored as a nullable comma-separated string
+                let deps = if dependencies.is_null(row) {
+                    Vec::new()
+                } else {
+                    dependencies
+                        .value(row)
+                        .split(',')
+                        .filter(|d| !d.is_empty())
+                        .map(str::to_string)
+                        .collect()
+                };
+
+                let mut chunk = AstChunk::new(
+                    ids.value(row),
+                    file_paths.value(row),
+                    symbol_names.value(row),
+                    node_types.value(row),
+                    contents.value(row),
+                    deps,
+                );
+                if !packs.is_null(row) {
+                    chunk.pack = Some(packs.value(row).to_string());
+                }
+                if !heading_paths.is_null(row) {
+                    chunk.heading_path = heading_paths
+                        .value(row)
+                        .split(HEADING_PATH_SEPARATOR)
+                        .map(str::to_string)
+                        .collect();
+                }
+                if !tags.is_null(row) {
+                    chunk.tags = tags
+                        .value(row)
+                        .split(TAG_SEPARATOR)
+                        .filter(|t| !t.is_empty())
+                        .map(decode_tag)
+                        .collect();
+                }
+                if !versions.is_null(row) {
+                    chunk.version = Some(versions.value(row).to_string());
+                }
+                if !start_lines.is_null(row) && !end_lines.is_null(row) {
+                    chunk.span = Some(LineSpan {
+                        start: start_lines.value(row),
+                        end: end_lines.value(row),
+                    });
+                }
+                if !start_pages.is_null(row) && !end_pages.is_null(row) {
+                    chunk.pages = Some(PageRange {
+                        start: start_pages.value(row),
+                        end: end_pages.value(row),
+                    });
+                }
+                chunk
+            })
+            .collect();
+
+        Ok(chunks)
+    }
+
+    /// Embed chunks and build a single Arrow `RecordBatch` in the AST chunk schema
+    ///
+    /// # Performance Requirements
+    ///
+    /// - **Defense Line 1**: Use `embed_batch()` for all chunks at once (NEVER in a loop)
+    /// - **Defense Line 2**: Build a single `RecordBatch` for all chunks
+    fn build_chunk_batch(&self, chunks: &[AstChunk]) -> Result<RecordBatch, AppError> {
+        use crate::slices::vector::schema::{ast_chunk_schema, VECTOR_DIM};
+        use arrow::array::FixedSizeListArray;
+
+        // **Defense Line 1**: Batch vector generation - NEVER in a loop
+        // Chunks sliced from headed sections embed their breadcrumb along with the content
+        let texts: Vec<String> = chunks.iter().map(embedding_text).collect();
+        let contents: Vec<&str> = texts.iter().map(String::as_str).collect();
+        let embeddings = self
+            .embedding_model
+            .embed_batch(&contents)
+            .map_err(|e| AppError::Infra(InfraError::Other(format!(
+                "Failed to generate batch embeddings: {}",
+                e
+            ))))?;
+
+        // Verify embedding count matches chunk count
+        if embeddings.len() != chunks.len() {
+            return Err(AppError::Infra(InfraError::Other(format!(
+                "Embedding count mismatch: expected {}, got {}",
+                chunks.len(),
+                embeddings.len()
+            ))));
+        }
+
+        let schema = Arc::new(ast_chunk_schema());
+
+        // **Defense Line 2**: Build Arrow RecordBatch
+
+        // Build arrays by field
+        let id_array = StringArray::from(chunks.iter().map(|c| c.id.as_str()).collect::<Vec<_>>());
+        let file_path_array = StringArray::from(chunks.iter().map(|c| c.file_path.as_str()).collect::<Vec<_>>());
+        let symbol_name_array = StringArray::from(chunks.iter().map(|c| c.symbol_name.as_str()).collect::<Vec<_>>());
+        let node_type_array = StringArray::from(chunks.iter().map(|c| c.node_type.as_str()).collect::<Vec<_>>());
+        let content_array = StringArray::from(chunks.iter().map(|c| c.content.as_str()).collect::<Vec<_>>());
+
+        // Dependencies: Serialize as comma-separated string
+        let dependencies_array = StringArray::from(
+            chunks.iter()
+                .map(|c| {
+                    if c.dependencies.is_empty() {
+                        None
+                    } else {
+                        Some(c.dependencies.join(","))
+                    }
+                })
+                .collect::<Vec<Option<String>>>()
+        );
+
+        // Vector: FixedSizeListArray (flatten all embeddings into one Float32Array)
+        let vector_item_field = arrow::datatypes::Field::new("item", arrow::datatypes::DataType::Float32, true);
+        let all_vector_values = Float32Array::from(
+            embeddings
+                .into_iter()
+                .flat_map(|vec| vec.into_iter())
+                .collect::<Vec<f32>>()
+        );
+        let vector_array = FixedSizeListArray::new(
+            Arc::new(vector_item_field),
+            VECTOR_DIM,
+            Arc::new(all_vector_values),
+            None,
+        );
+
+        let pack_array = StringArray::from(
+            chunks.iter().map(|c| c.pack.as_deref()).collect::<Vec<Option<&str>>>()
+        );
+
+        // Heading path: Serialize as " > "-joined breadcrumb
+        let heading_path_array = StringArray::from(
+            chunks.iter()
+                .map(|c| {
+                    if c.heading_path.is_empty() {
+                        None
+                    } else {
+                        Some(c.heading_path.join(HEADING_PATH_SEPARATOR))
+                    }
+                })
+                .collect::<Vec<Option<String>>>()
+        );
+
+        // Tags: Serialize as comma-separated string, with commas inside tags encoded
+        let tags_array = StringArray::from(
+            chunks.iter()
+                .map(|c| {
+                    (!c.tags.is_empty()).then(|| {
+                        let tags: Vec<String> = c.tags.iter().map(|t| encode_tag(t)).collect();
+                        tags.join(&TAG_SEPARATOR.to_string())
+                    })
+                })
+                .collect::<Vec<Option<String>>>()
+        );
+        let version_array = StringArray::from(
+            chunks.iter().map(|c| c.version.as_deref()).collect::<Vec<Option<&str>>>()
+        );
+        let start_line_array = UInt32Array::from(
+            chunks.iter().map(|c| c.span.map(|s| s.start)).collect::<Vec<Option<u32>>>()
+        );
+        let end_line_array = UInt32Array::from(
+            chunks.iter().map(|c| c.span.map(|s| s.end)).collect::<Vec<Option<u32>>>()
+        );
+        let start_page_array = UInt32Array::from(
+            chunks.iter().map(|c| c.pages.map(|p| p.start)).collect::<Vec<Option<u32>>>()
+        );
+        let end_page_array = UInt32Array::from(
+            chunks.iter().map(|c| c.pages.map(|p| p.end)).collect::<Vec<Option<u32>>>()
+        );
+
+        RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(id_array),
+                Arc::new(file_path_array),
+                Arc::new(symbol_name_array),
+                Arc::new(node_type_array),
+                Arc::new(content_array),
+                Arc::new(dependencies_array),
+                Arc::new(vector_array),
+                Arc::new(pack_array),
+                Arc::new(heading_path_array),
+                Arc::new(tags_array),
+                Arc::new(version_array),
+                Arc::new(start_line_array),
+                Arc::new(end_line_array),
+                Arc::new(start_page_array),
+                Arc::new(end_page_array),
+            ],
+        ).map_err(|e| {
+            AppError::Infra(InfraError::Other(format!("Failed to create RecordBatch: {}", e)))
+        })
+    }
+
+    /// Embed query text with the store's embedding model
+    fn embed_text(&self, text: &str) -> Result<Vec<f32>, AppError> {
+        self.embedding_model
+            .embed_text(text)
+            .map_err(|e| AppError::Infra(InfraError::Other(format!(
+                "Failed to generate query embedding: {}",
+                e
+            ))))
+    }
+
     /// Normalize a raw distance score to [0.0, 1.0] range
     ///
     /// LanceDB returns different distance metrics depending on the index type.
@@ -144,14 +567,19 @@ impl VectorStoreTrait for LanceDbStore {
     /// - Converts LanceDB results to Hit types
     async fn search(&self, query: &Query) -> Result<Option<Vec<Hit>>, AppError> {
         // Step 1: Generate embedding vector for the query text
-        let query_vector = self
-            .embedding_model
-            .embed_text(&query.text)
-            .map_err(|e| AppError::Infra(InfraError::Other(format!(
-                "Failed to generate query embedding: {}",
-                e
-            ))))?;
+        let query_vector = self.embed_text(&query.text)?;
+        self.search_with_embedding(query, query_vector).await
+    }
+
+    async fn embed_query(&self, text: &str) -> Result<Option<Vec<f32>>, AppError> {
+        self.embed_text(text).map(Some)
+    }
 
+    async fn search_with_embedding(
+        &self,
+        query: &Query,
+        query_vector: Vec<f32>,
+    ) -> Result<Option<Vec<Hit>>, AppError> {
         // Step 2: Get the LanceDB table
         let table = self
             .get_table()
@@ -171,7 +599,11 @@ impl VectorStoreTrait for LanceDbStore {
                 "Failed to create vector query",
                 Some(e),
             )))?
-            .limit(query.limit);
+            .limit(Self::fetch_limit(query));
+        let vector_query = match query.filter.as_ref().and_then(Self::filter_predicate) {
+            Some(predicate) => vector_query.only_if(predicate),
+            None => vector_query,
+        };
 
         // Execute the query
         let mut results_stream: lancedb::arrow::SendableRecordBatchStream = vector_query
@@ -183,7 +615,7 @@ impl VectorStoreTrait for LanceDbStore {
             )))?;
 
         // Step 4: Collect results from the stream and convert to Hit types
-        let mut hits = Vec::new();
+        let mut ranked: Vec<(f64, String)> = Vec::new();
 
         while let Some(batch_result) = results_stream.next().await {
             let batch = batch_result.map_err(|e| {
@@ -224,6 +656,10 @@ impl VectorStoreTrait for LanceDbStore {
                 ))
             })?;
 
+            let node_types = batch
+                .column_by_name("node_type")
+                .and_then(|col| col.as_any().downcast_ref::<StringArray>());
+
             // Convert each row to a Hit
             for row in 0..batch.num_rows() {
                 let distance = distances.value(row);
@@ -233,11 +669,25 @@ impl VectorStoreTrait for LanceDbStore {
                 // LanceDB uses L2 distance by default, which ranges [0, +infinity)
                 // We convert to [0, 1] where 1.0 is best match
                 let score = Self::normalize_score(distance, DistanceMetric::L2);
+                let factor = match (&query.boost, node_types) {
+                    (Some(boost), Some(node_types)) => boost.factor_for(node_types.value(row)),
+                    _ => 1.0,
+                };
 
-                hits.push(Hit { id, score });
+                ranked.push((score.value() * factor, id));
             }
         }
 
+        // Boosted scores are ranked before clamping back into [0.0, 1.0]
+        if query.boost.is_some() {
+            ranked.sort_by(|a, b| b.0.total_cmp(&a.0));
+            ranked.truncate(query.limit);
+        }
+        let hits: Vec<Hit> = ranked
+            .into_iter()
+            .map(|(score, id)| Hit::with_raw_score(id, score))
+            .collect();
+
         if hits.is_empty() {
             Ok(None)
         } else {
@@ -342,6 +792,14 @@ impl VectorStoreTrait for LanceDbStore {
             Arc::new(vector_values),
             None,
         );
+        let pack_array = StringArray::from(vec![None::<&str>]);
+        let heading_path_array = StringArray::from(vec![None::<&str>]);
+        let tags_array = StringArray::from(vec![None::<&str>]);
+        let version_array = StringArray::from(vec![None::<&str>]);
+        let start_line_array = UInt32Array::from(vec![None::<u32>]);
+        let end_line_array = UInt32Array::from(vec![None::<u32>]);
+        let start_page_array = UInt32Array::from(vec![None::<u32>]);
+        let end_page_array = UInt32Array::from(vec![None::<u32>]);
 
         // Create the record batch
         let batch = RecordBatch::try_new(
@@ -354,6 +812,14 @@ impl VectorStoreTrait for LanceDbStore {
                 Arc::new(content_array) as Arc<dyn arrow::array::Array>,
                 Arc::new(dependencies_array) as Arc<dyn arrow::array::Array>,
                 Arc::new(vector_array) as Arc<dyn arrow::array::Array>,
+                Arc::new(pack_array) as Arc<dyn arrow::array::Array>,
+                Arc::new(heading_path_array) as Arc<dyn arrow::array::Array>,
+                Arc::new(tags_array) as Arc<dyn arrow::array::Array>,
+                Arc::new(version_array) as Arc<dyn arrow::array::Array>,
+                Arc::new(start_line_array) as Arc<dyn arrow::array::Array>,
+                Arc::new(end_line_array) as Arc<dyn arrow::array::Array>,
+                Arc::new(start_page_array) as Arc<dyn arrow::array::Array>,
+                Arc::new(end_page_array) as Arc<dyn arrow::array::Array>,
             ],
         )
         .map_err(|e| {
@@ -383,51 +849,87 @@ impl VectorStoreTrait for LanceDbStore {
     ///
     /// # Implementation Notes
     ///
-    /// Uses LanceDB's delete operation to remove document by id.
-    ///
-    /// # Phase 1 Limitation
-    ///
-    /// This is a placeholder implementation.
+    /// Counts matching rows first (LanceDB's delete does not report how many rows
+    /// it removed), then deletes with an escaped `id IN (...)` predicate. Every
+    /// row with the ID is removed, including duplicates left by appends.
     async fn delete(&self, id: &str) -> Result<bool, AppError> {
-        let _ = id; // Suppress unused warning in Phase 1
-
-        // Phase 1: Placeholder - return true
-        // Phase 2: Implement actual deletion:
-        // let table = self.get_table().await
-        //     .map_err(|e| InfraError::database("delete failed", Some(e)))?;
-        //
-        // SECURITY WARNING: The following line is vulnerable to SQL injection!
-        // table.delete(&format!("id == '{}'", id))
-        //
-        // DO NOT USE string formatting for query parameters. An attacker could inject
-        // malicious SQL by crafting an id like: "doc1' OR '1'='1"
-        //
-        // Instead, use parameter binding or proper escaping. LanceDB supports safer
-        // alternatives such as:
-        //
-        // Option 1: Use LanceDB's builder API with proper parameter binding (preferred):
-        // table.delete()
-        //     .only_if("id == $1")
-        //     .execute(&[id])  // Parameter binding prevents injection
-        //
-        // Option 2: If using raw filter strings, properly escape the input:
-        // let escaped_id = id.replace('\\', "\\\\").replace('\'', "\\'");
-        // table.delete(&format!("id == '{}'", escaped_id))
-        //
-        // Option 3: Use LanceDB's expression builder if available:
-        // use lancedb::dsl::col;
-        // table.delete(col("id").eq(lancedb::Literal::new(id)))
-        //
-        // Reference: https://lancedb.github.io/lancedb/security/
-        //
-        // table.delete(&format!("id == '{}'", id))
-        //     .execute()
-        //     .await
-        //     .map_err(|e| InfraError::database("delete failed", Some(e)))?;
+        let table = self
+            .get_table()
+            .await
+            .map_err(|e| AppError::Infra(InfraError::database(
+                "Failed to open table for delete",
+                Some(e),
+            )))?;
+
+        let filter = Self::id_filter(&[id]);
+
+        let matching = table
+            .count_rows(Some(filter.clone()))
+            .await
+            .map_err(|e| AppError::Infra(InfraError::database(
+                "Failed to count rows for delete",
+                Some(e),
+            )))?;
+
+        if matching == 0 {
+            return Ok(false);
+        }
+
+        table
+            .delete(&filter)
+            .await
+            .map_err(|e| AppError::Infra(InfraError::database(
+                "Failed to delete record from LanceDB",
+                Some(e),
+            )))?;
 
         Ok(true)
     }
 
+    /// Delete every row matching a filter
+    ///
+    /// # Implementation Notes
+    ///
+    /// Translates the filter into a single SQL predicate (see `filter_predicate`),
+    /// counts matching rows, then deletes them in one table version.
+    async fn delete_where(&self, filter: &ChunkFilter) -> Result<usize, AppError> {
+        let Some(predicate) = Self::filter_predicate(filter) else {
+            return Err(AppError::Domain(DomainError::not_allowed(
+                "delete_where requires at least one filter criterion",
+            )));
+        };
+
+        let table = self
+            .get_table()
+            .await
+            .map_err(|e| AppError::Infra(InfraError::database(
+                "Failed to open table for bulk delete",
+                Some(e),
+            )))?;
+
+        let matching = table
+            .count_rows(Some(predicate.clone()))
+            .await
+            .map_err(|e| AppError::Infra(InfraError::database(
+                "Failed to count rows for bulk delete",
+                Some(e),
+            )))?;
+
+        if matching == 0 {
+            return Ok(0);
+        }
+
+        table
+            .delete(&predicate)
+            .await
+            .map_err(|e| AppError::Infra(InfraError::database(
+                "Failed to bulk delete from LanceDB",
+                Some(e),
+            )))?;
+
+        Ok(matching)
+    }
+
     /// Check if the store is healthy and accessible
     ///
     /// # Implementation Notes
@@ -463,24 +965,8 @@ impl VectorStoreTrait for LanceDbStore {
             return Ok(());
         }
 
-        // **Defense Line 1**: Batch vector generation - NEVER in a loop
-        let contents: Vec<&str> = chunks.iter().map(|c| c.content.as_str()).collect();
-        let embeddings = self
-            .embedding_model
-            .embed_batch(&contents)
-            .map_err(|e| AppError::Infra(InfraError::Other(format!(
-                "Failed to generate batch embeddings: {}",
-                e
-            ))))?;
-
-        // Verify embedding count matches chunk count
-        if embeddings.len() != chunks.len() {
-            return Err(AppError::Infra(InfraError::Other(format!(
-                "Embedding count mismatch: expected {}, got {}",
-                chunks.len(),
-                embeddings.len()
-            ))));
-        }
+        // **Defense Lines 1 & 2**: Batch embedding + single RecordBatch
+        let batch = self.build_chunk_batch(&chunks)?;
 
         // **Defense Line 0**: Prevent duplicate data by deleting existing records with same IDs
         // This prevents duplicate entries when rebuilding indexes
@@ -497,18 +983,7 @@ impl VectorStoreTrait for LanceDbStore {
         // Build a filter condition: id IN ('id1', 'id2', 'id3', ...)
         if !ids.is_empty() {
             // Properly escape each ID to prevent SQL injection
-            let escaped_ids: Vec<String> = ids.iter()
-                .map(|id| id.replace('\\', "\\\\").replace('\'', "\\'"))
-                .collect();
-
-            let filter_condition = format!(
-                "id IN ({})",
-                escaped_ids
-                    .iter()
-                    .map(|id| format!("'{}'", id))
-                    .collect::<Vec<_>>()
-                    .join(", ")
-            );
+            let filter_condition = Self::id_filter(&ids);
 
             // Execute deletion (ignore errors if table is empty or records don't exist)
             let _ = table.delete(&filter_condition).await;
@@ -518,71 +993,11 @@ impl VectorStoreTrait for LanceDbStore {
             // 3. The insertion error will be more informative than a deletion error
         }
 
-        // **Defense Line 1**: Batch vector generation - extract contents for embedding
-        // (Already done above before deletion)
-        use crate::slices::vector::schema::{ast_chunk_schema, VECTOR_DIM};
-        use arrow::array::{FixedSizeListArray, Float32Array, StringArray};
-
-        let schema = Arc::new(ast_chunk_schema());
-
-        // **Defense Line 2**: Build Arrow RecordBatch
-
-        // Build arrays by field
-        let id_array = StringArray::from(chunks.iter().map(|c| c.id.as_str()).collect::<Vec<_>>());
-        let file_path_array = StringArray::from(chunks.iter().map(|c| c.file_path.as_str()).collect::<Vec<_>>());
-        let symbol_name_array = StringArray::from(chunks.iter().map(|c| c.symbol_name.as_str()).collect::<Vec<_>>());
-        let node_type_array = StringArray::from(chunks.iter().map(|c| c.node_type.as_str()).collect::<Vec<_>>());
-        let content_array = StringArray::from(chunks.iter().map(|c| c.content.as_str()).collect::<Vec<_>>());
-
-        // Dependencies: Serialize as comma-separated string
-        let dependencies_array = StringArray::from(
-            chunks.iter()
-                .map(|c| {
-                    if c.dependencies.is_empty() {
-                        None
-                    } else {
-                        Some(c.dependencies.join(","))
-                    }
-                })
-                .collect::<Vec<Option<String>>>()
-        );
-
-        // Vector: FixedSizeListArray (flatten all embeddings into one Float32Array)
-        let vector_item_field = arrow::datatypes::Field::new("item", arrow::datatypes::DataType::Float32, true);
-        let all_vector_values = Float32Array::from(
-            embeddings
-                .into_iter()
-                .flat_map(|vec| vec.into_iter())
-                .collect::<Vec<f32>>()
-        );
-        let vector_array = FixedSizeListArray::new(
-            Arc::new(vector_item_field),
-            VECTOR_DIM,
-            Arc::new(all_vector_values),
-            None,
-        );
-
-        // Create RecordBatch
-        let batch = RecordBatch::try_new(
-            schema.clone(),
-            vec![
-                Arc::new(id_array),
-                Arc::new(file_path_array),
-                Arc::new(symbol_name_array),
-                Arc::new(node_type_array),
-                Arc::new(content_array),
-                Arc::new(dependencies_array),
-                Arc::new(vector_array),
-            ],
-        ).map_err(|e| {
-            AppError::Infra(InfraError::Other(format!("Failed to create RecordBatch: {}", e)))
-        })?;
-
         // **Defense Line 3**: Single LanceDB write - NEVER in a loop
         // Note: table is already opened above for deletion, reuse it
-        let batches = vec![batch];
+        let schema = batch.schema();
         let reader = RecordBatchIterator::new(
-            batches.into_iter().map(Ok),
+            vec![batch].into_iter().map(Ok),
             schema,
         );
 
@@ -594,6 +1009,143 @@ impl VectorStoreTrait for LanceDbStore {
 
         Ok(())
     }
+
+    /// Insert or replace AST chunks by ID
+    ///
+    /// # Implementation Notes
+    ///
+    /// Uses `merge_insert` on the `id` column: matched rows are updated in place,
+    /// unmatched rows are inserted, all in a single table version. If the input
+    /// repeats an ID, the last chunk wins (merge_insert rejects ambiguous sources).
+    async fn upsert_batch(&self, chunks: Vec<AstChunk>) -> Result<(), AppError> {
+        let chunks = dedupe_last_wins_by(chunks, |chunk| chunk.id.clone());
+        if chunks.is_empty() {
+            return Ok(());
+        }
+
+        let batch = self.build_chunk_batch(&chunks)?;
+
+        let table = self
+            .get_table()
+            .await
+            .map_err(|e| AppError::Infra(InfraError::database(
+                "Failed to open table for upsert",
+                Some(e),
+            )))?;
+
+        let schema = batch.schema();
+        let reader: Box<dyn arrow::array::RecordBatchReader + Send> = Box::new(
+            RecordBatchIterator::new(vec![batch].into_iter().map(Ok), schema),
+        );
+
+        let mut merge = table.merge_insert(&["id"]);
+        merge.when_matched_update_all(None).when_not_matched_insert_all();
+        merge
+            .execute(reader)
+            .await
+            .map_err(|e| AppError::Infra(InfraError::database(
+                "Failed to upsert batch into LanceDB",
+                Some(e),
+            )))?;
+
+        Ok(())
+    }
+
+    /// Get a stored document by ID
+    ///
+    /// # Implementation Notes
+    ///
+    /// Runs a filtered scan (`id IN ('...')`) without touching the vector column.
+    async fn get_by_id(&self, id: &str) -> Result<Option<AstChunk>, AppError> {
+        let mut found = self.fetch_by_ids(&[id.to_string()]).await?;
+        Ok(found.remove(id))
+    }
+
+    /// Get multiple stored documents by IDs (batch version)
+    ///
+    /// Uses a single filtered scan for all IDs and restores the input order.
+    async fn get_by_ids(&self, ids: &[String]) -> Result<Vec<Option<AstChunk>>, AppError> {
+        let mut found = self.fetch_by_ids(ids).await?;
+        Ok(ids.iter().map(|id| found.remove(id)).collect())
+    }
+
+    /// List every stored document as an AST chunk
+    ///
+    /// Full table scan without the vector column.
+    async fn list_chunks(&self) -> Result<Vec<AstChunk>, AppError> {
+        self.scan_chunks(None).await
+    }
+
+    /// Count rows without scanning them
+    async fn count(&self) -> Result<usize, AppError> {
+        let table = self
+            .get_table()
+            .await
+            .map_err(|e| AppError::Infra(InfraError::database(
+                "Failed to open table for count",
+                Some(e),
+            )))?;
+
+        table
+            .count_rows(None)
+            .await
+            .map_err(|e| AppError::Infra(InfraError::database(
+                "Failed to count rows",
+                Some(e),
+            )))
+    }
+
+    /// Version of the table's latest manifest (opened fresh, so external writes show)
+    async fn version(&self) -> Result<Option<u64>, AppError> {
+        let table = self
+            .get_table()
+            .await
+            .map_err(|e| AppError::Infra(InfraError::database(
+                "Failed to open table for version",
+                Some(e),
+            )))?;
+
+        table
+            .version()
+            .await
+            .map(Some)
+            .map_err(|e| AppError::Infra(InfraError::database(
+                "Failed to read table version",
+                Some::<anyhow::Error>(e.into()),
+            )))
+    }
+}
+
+/// Candidate multiplier used when a node type boost reorders vector hits
+const BOOST_OVERFETCH_FACTOR: usize = 3;
+
+/// Separator used to store `AstChunk::heading_path` in a single Utf8 column
+const HEADING_PATH_SEPARATOR: &str = " > ";
+
+/// Separator of the tags stored in the `tags` column
+const TAG_SEPARATOR: char = ',';
+
+/// Encode a tag for the `tags` column
+///
+/// `%` and `,` are percent-encoded so that a stored tag never contains the
+/// separator and `LIKE '%,tag,%'` only matches whole tags. Other tags are
+/// stored unchanged, so rows written before the encoding read back the same.
+fn encode_tag(tag: &str) -> String {
+    tag.replace('%', "%25").replace(TAG_SEPARATOR, "%2C")
+}
+
+/// Decode a tag stored by [`encode_tag`]
+fn decode_tag(tag: &str) -> String {
+    tag.replace("%2C", ",").replace("%25", "%")
+}
+
+/// Text sent to the embedding model: breadcrumb line followed by the content
+fn embedding_text(chunk: &AstChunk) -> String {
+    if chunk.heading_path.is_empty() {
+        chunk.content.clone()
+    } else {
+        format!("{}\n\n{}", chunk.heading_path.join(HEADING_PATH_SEPARATOR), chunk.content)
+    }
 }
 
 #[cfg(test)]
@@ -650,7 +1202,7 @@ mod tests {
         assert!(hits.is_some(), "Should return Some(hits), not None");
 
         let hits = hits.unwrap();
-        assert!(hits.len() > 0, "Should find at least one result");
+        assert!(!hits.is_empty(), "Should find at least one result");
         assert_eq!(hits[0].id, "doc1", "First hit should be doc1");
     }
 
@@ -669,13 +1221,38 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_delete_placeholder() {
+    async fn test_delete() {
         let (store, _temp_dir) = create_test_store().await;
 
+        // Deleting a missing document is not an error
         let result = store.delete("doc1").await;
-
         assert!(result.is_ok());
-        assert_eq!(result.unwrap(), true);
+        assert!(!result.unwrap(), "Missing document should report false");
+
+        // Legacy `add` appends, so the same ID can exist twice
+        store.add("doc1", "first", None).await.expect("Add should succeed");
+        store.add("doc1", "second", None).await.expect("Add should succeed");
+        store.add("doc2", "other", None).await.expect("Add should succeed");
+
+        assert!(store.delete("doc1").await.expect("Delete should succeed"));
+
+        let remaining = store.list_chunks().await.expect("list_chunks should succeed");
+        assert_eq!(remaining.len(), 1, "All rows for doc1 should be removed");
+        assert_eq!(remaining[0].id, "doc2");
+    }
+
+    #[tokio::test]
+    async fn test_list_chunks_includes_duplicates() {
+        let (store, _temp_dir) = create_test_store().await;
+
+        assert!(store.list_chunks().await.unwrap().is_empty());
+
+        store.add("dup", "content", None).await.expect("Add should succeed");
+        store.add("dup", "content", None).await.expect("Add should succeed");
+
+        let chunks = store.list_chunks().await.expect("list_chunks should succeed");
+        assert_eq!(chunks.len(), 2);
+        assert!(chunks.iter().all(|c| c.id == "dup" && c.vector.is_none()));
     }
 
     #[tokio::test]
@@ -685,7 +1262,7 @@ mod tests {
         let result = store.health_check().await;
 
         assert!(result.is_ok());
-        assert_eq!(result.unwrap(), true);
+        assert!(result.unwrap());
     }
 
     #[tokio::test]
@@ -710,6 +1287,344 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_get_by_id_and_get_by_ids() {
+        let (store, _temp_dir) = create_test_store().await;
+
+        let chunks = vec![
+            AstChunk::new(
+                "chunk-1",
+                "src/auth.rs",
+                "AuthManager",
+                "class",
+                "struct AuthManager;",
+                vec!["User".to_string(), "Session".to_string()],
+            ),
+            AstChunk::without_dependencies("chunk-2", "src/user.rs", "User", "class", "struct User;"),
+        ];
+        store.add_batch(chunks).await.expect("Batch add should succeed");
+
+        let chunk = store
+            .get_by_id("chunk-1")
+            .await
+            .expect("get_by_id should succeed")
+            .expect("chunk-1 should exist");
+        assert_eq!(chunk.symbol_name, "AuthManager");
+        assert_eq!(chunk.file_path, "src/auth.rs");
+        assert_eq!(chunk.content, "struct AuthManager;");
+        assert_eq!(chunk.dependencies, vec!["User", "Session"]);
+        assert!(chunk.vector.is_none(), "Vectors should not be returned");
+
+        assert!(store.get_by_id("missing").await.unwrap().is_none());
+
+        // Order follows the input IDs, missing IDs map to None
+        let results = store
+            .get_by_ids(&["chunk-2".to_string(), "missing".to_string(), "chunk-1".to_string()])
+            .await
+            .expect("get_by_ids should succeed");
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].as_ref().unwrap().id, "chunk-2");
+        assert!(results[1].is_none());
+        assert_eq!(results[2].as_ref().unwrap().id, "chunk-1");
+    }
+
+    #[tokio::test]
+    async fn test_get_by_id_escapes_quotes() {
+        let (store, _temp_dir) = create_test_store().await;
+
+        store
+            .add_batch(vec![AstChunk::without_dependencies(
+                "it's-a-doc",
+                "doc.md",
+                "Quoted",
+                "file",
+                "content",
+            )])
+            .await
+            .expect("Batch add should succeed");
+
+        let found = store.get_by_id("it's-a-doc").await.expect("Quoted id should not break the filter");
+        assert!(found.is_some());
+
+        // An injection attempt must not match every row
+        let injected = store.get_by_id("x' OR '1'='1").await.unwrap();
+        assert!(injected.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_version_tracks_writes_from_other_handles() {
+        let (store, temp_dir) = create_test_store().await;
+        let before = store
+            .version()
+            .await
+            .unwrap()
+            .expect("LanceDB tracks versions");
+
+        // Write through a separate connection, as another process would
+        let conn = connect(temp_dir.path().to_str().unwrap()).await.unwrap();
+        let other = LanceDbStore::new(
+            conn,
+            "test_knowledge",
+            Arc::new(EmbeddingModel::test_stub()),
+        );
+        other
+            .add_batch(vec![AstChunk::without_dependencies(
+                "v-1", "a.md", "A", "file", "alpha",
+            )])
+            .await
+            .expect("Batch add should succeed");
+
+        let after = store.version().await.unwrap().unwrap();
+        assert!(after > before, "{} should be newer than {}", after, before);
+    }
+
+    #[tokio::test]
+    async fn test_upsert_batch_replaces_existing_rows() {
+        let (store, _temp_dir) = create_test_store().await;
+
+        store
+            .upsert_batch(vec![
+                AstChunk::without_dependencies("up-1", "a.md", "First", "file", "v1"),
+                AstChunk::without_dependencies("up-2", "b.md", "Second", "file", "v1"),
+            ])
+            .await
+            .expect("Initial upsert should insert");
+
+        // Re-running with the same and new IDs must not duplicate rows
+        store
+            .upsert_batch(vec![
+                AstChunk::without_dependencies("up-1", "a.md", "First", "file", "v2"),
+                AstChunk::without_dependencies("up-3", "c.md", "Third", "file", "v1"),
+            ])
+            .await
+            .expect("Second upsert should merge");
+
+        let mut chunks = store.list_chunks().await.unwrap();
+        chunks.sort_by(|a, b| a.id.cmp(&b.id));
+        let summary: Vec<(&str, &str)> = chunks
+            .iter()
+            .map(|c| (c.id.as_str(), c.content.as_str()))
+            .collect();
+        assert_eq!(summary, vec![("up-1", "v2"), ("up-2", "v1"), ("up-3", "v1")]);
+    }
+
+    #[tokio::test]
+    async fn test_heading_path_round_trip() {
+        let (store, _temp_dir) = create_test_store().await;
+
+        store
+            .add_batch(vec![
+                AstChunk::without_dependencies("hp-1", "a.md", "Linux", "file", "apt")
+                    .with_heading_path(vec!["Guide".into(), "Install".into(), "Linux".into()]),
+                AstChunk::without_dependencies("hp-2", "b.md", "Other", "file", "text"),
+            ])
+            .await
+            .expect("Batch add should succeed");
+
+        let mut chunks = store.list_chunks().await.unwrap();
+        chunks.sort_by(|a, b| a.id.cmp(&b.id));
+        assert_eq!(chunks[0].heading_path, vec!["Guide", "Install", "Linux"]);
+        assert!(chunks[1].heading_path.is_empty());
+    }
+
+    #[test]
+    fn test_embedding_text_prefixes_breadcrumb() {
+        let plain = AstChunk::without_dependencies("a", "a.md", "A", "file", "body");
+        assert_eq!(embedding_text(&plain), "body");
+
+        let headed = plain.with_heading_path(vec!["Doc".into(), "Section".into()]);
+        assert_eq!(embedding_text(&headed), "Doc > Section\n\nbody");
+    }
+
+    #[tokio::test]
+    async fn test_upsert_is_idempotent_with_repeated_ids() {
+        let (store, _temp_dir) = create_test_store().await;
+
+        let chunks = vec![
+            AstChunk::without_dependencies("same", "a.md", "Same", "file", "old"),
+            AstChunk::without_dependencies("same", "a.md", "Same", "file", "new"),
+        ];
+        store.upsert_batch(chunks.clone()).await.expect("Upsert should succeed");
+        store.upsert_batch(chunks).await.expect("Repeated upsert should succeed");
+        store
+            .upsert(AstChunk::without_dependencies("same", "a.md", "Same", "file", "new"))
+            .await
+            .expect("Single upsert should succeed");
+
+        let chunks = store.list_chunks().await.unwrap();
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].content, "new", "Last chunk for an ID wins");
+    }
+
+    #[tokio::test]
+    async fn test_delete_where_matches_literal_path_prefix() {
+        let (store, _temp_dir) = create_test_store().await;
+
+        store
+            .upsert_batch(vec![
+                AstChunk::without_dependencies("a", "docs/my_file.md", "A", "file", "a").with_pack("guide"),
+                AstChunk::without_dependencies("b", "docs/myXfile.md", "B", "file", "b").with_pack("guide"),
+                AstChunk::without_dependencies("c", "docs/100%.md", "C", "class", "c"),
+            ])
+            .await
+            .unwrap();
+
+        // `_` and `%` must match literally, not as LIKE wildcards
+        let deleted = store
+            .delete_where(&ChunkFilter::new().with_path_prefix("docs/my_"))
+            .await
+            .expect("Prefix delete should succeed");
+        assert_eq!(deleted, 1);
+
+        let deleted = store
+            .delete_where(&ChunkFilter::new().with_path_prefix("docs/100%"))
+            .await
+            .unwrap();
+        assert_eq!(deleted, 1);
+
+        let remaining = store.list_chunks().await.unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].id, "b");
+        assert_eq!(remaining[0].pack.as_deref(), Some("guide"));
+    }
+
+    #[tokio::test]
+    async fn test_tags_with_separator_round_trip_and_match_whole() {
+        let (store, _temp_dir) = create_test_store().await;
+
+        store
+            .upsert_batch(vec![
+                AstChunk::without_dependencies("a", "a.md", "A", "file", "a")
+                    .with_tags(vec!["a,b".into(), "100%".into()]),
+                AstChunk::without_dependencies("b", "b.md", "B", "file", "b")
+                    .with_tags(vec!["b".into()]),
+            ])
+            .await
+            .unwrap();
+
+        let mut chunks = store.list_chunks().await.unwrap();
+        chunks.sort_by(|a, b| a.id.cmp(&b.id));
+        assert_eq!(chunks[0].tags, vec!["a,b", "100%"]);
+
+        // "b" must not match the second half of "a,b"
+        let deleted = store.delete_where(&ChunkFilter::new().with_tag("b")).await.unwrap();
+        assert_eq!(deleted, 1);
+        let deleted = store.delete_where(&ChunkFilter::new().with_tag("a,b")).await.unwrap();
+        assert_eq!(deleted, 1);
+        assert!(store.list_chunks().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_search_and_delete_by_tag_and_version() {
+        let (store, _temp_dir) = create_test_store().await;
+
+        store
+            .upsert_batch(vec![
+                AstChunk::without_dependencies("a", "a.md", "A", "file", "a")
+                    .with_tags(vec!["blocks".into(), "events".into()])
+                    .with_version("1.20"),
+                AstChunk::without_dependencies("b", "b.md", "B", "file", "b")
+                    .with_tags(vec!["block".into()])
+                    .with_version("1.21"),
+                AstChunk::without_dependencies("c", "c.md", "C", "file", "c"),
+            ])
+            .await
+            .unwrap();
+
+        let mut chunks = store.list_chunks().await.unwrap();
+        chunks.sort_by(|a, b| a.id.cmp(&b.id));
+        assert_eq!(chunks[0].tags, vec!["blocks", "events"]);
+        assert_eq!(chunks[0].version.as_deref(), Some("1.20"));
+        assert!(chunks[2].tags.is_empty() && chunks[2].version.is_none());
+
+        // "block" must not match the "blocks" tag
+        let hits = store
+            .search(&Query::new("a", 10).with_filter(ChunkFilter::new().with_tag("block")))
+            .await
+            .unwrap()
+            .unwrap_or_default();
+        let ids: Vec<&str> = hits.iter().map(|h| h.id.as_str()).collect();
+        assert_eq!(ids, vec!["b"]);
+
+        let hits = store
+            .search(&Query::new("a", 10).with_filter(ChunkFilter::new().with_version("1.20")))
+            .await
+            .unwrap()
+            .unwrap_or_default();
+        let ids: Vec<&str> = hits.iter().map(|h| h.id.as_str()).collect();
+        assert_eq!(ids, vec!["a"]);
+
+        let deleted = store
+            .delete_where(&ChunkFilter::new().with_tag("events"))
+            .await
+            .unwrap();
+        assert_eq!(deleted, 1);
+        assert_eq!(store.list_chunks().await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_delete_where_by_pack_and_node_type() {
+        let (store, _temp_dir) = create_test_store().await;
+
+        store
+            .upsert_batch(vec![
+                AstChunk::without_dependencies("a", "a.md", "A", "file", "a").with_pack("it's"),
+                AstChunk::without_dependencies("b", "b.md", "B", "class", "b").with_pack("it's"),
+                AstChunk::without_dependencies("c", "c.md", "C", "class", "c"),
+            ])
+            .await
+            .unwrap();
+
+        let deleted = store
+            .delete_where(&ChunkFilter::new().with_pack("it's").with_node_type("class"))
+            .await
+            .unwrap();
+        assert_eq!(deleted, 1);
+
+        let deleted = store
+            .delete_where(&ChunkFilter::new().with_pack("missing"))
+            .await
+            .unwrap();
+        assert_eq!(deleted, 0);
+
+        assert!(store.delete_where(&ChunkFilter::new()).await.is_err());
+        assert_eq!(store.list_chunks().await.unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_filter_predicate() {
+        assert_eq!(LanceDbStore::filter_predicate(&ChunkFilter::new()), None);
+        assert_eq!(
+            LanceDbStore::filter_predicate(
+                &ChunkFilter::new()
+                    .with_path_prefix("docs/a_b")
+                    .with_pack("o'neil")
+                    .with_node_type("file")
+            )
+            .unwrap(),
+            "file_path LIKE 'docs/a\\_b%' AND pack = 'o''neil' \
+             AND (node_type = 'file' OR node_type LIKE 'file:%')"
+        );
+        assert_eq!(
+            LanceDbStore::filter_predicate(&ChunkFilter::new().with_tag("1_x").with_version("1.20"))
+                .unwrap(),
+            "(',' || tags || ',') LIKE '%,1\\_x,%' AND version = '1.20'"
+        );
+        assert_eq!(
+            LanceDbStore::filter_predicate(&ChunkFilter::new().with_packs(["guide", "it's"])).unwrap(),
+            "pack IN ('guide', 'it''s')"
+        );
+    }
+
+    #[test]
+    fn test_fetch_limit_overfetches_with_boost() {
+        assert_eq!(LanceDbStore::fetch_limit(&Query::new("q", 5)), 5);
+        assert_eq!(
+            LanceDbStore::fetch_limit(&Query::new("q", 5).with_boost("code", 2.0)),
+            5 * BOOST_OVERFETCH_FACTOR
+        );
+    }
+
     #[test]
     fn test_normalize_score_cosine() {
         // Test cosine distance normalization
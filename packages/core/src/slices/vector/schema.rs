@@ -22,6 +22,12 @@ use std::sync::Arc;
 #[allow(dead_code)]
 pub(crate) const VECTOR_DIM: i32 = 384;
 
+/// Version of the `ast_chunk_schema` layout, reported by health checks
+///
+/// Bump whenever a column is added, removed or changes type. Tables created
+/// with another layout are rejected by `validate_ast_chunk_schema` when opened.
+pub(crate) const VECTOR_SCHEMA_VERSION: u32 = 2;
+
 /// AST Chunk Arrow schema for LanceDB
 ///
 /// This schema defines the structure of AST chunks stored in LanceDB.
@@ -35,12 +41,15 @@ pub(crate) const VECTOR_DIM: i32 = 384;
 /// - `content`: Full code block/AST content (Utf8, non-null)
 /// - `dependencies`: Dependencies as comma-separated string (Utf8, nullable) - avoids Arrow ListArray
 /// - `vector`: Vector embedding (384-dim FixedSizeList(Float32), non-null)
+/// - `pack`: Owning pack name (Utf8, nullable) - used for filtering and bulk deletes
+/// - `start_line` / `end_line`: Source line span (UInt32, nullable) - set for source code chunks
+/// - `start_page` / `end_page`: Page range (UInt32, nullable) - set for chunks of paginated documents
 ///
 /// # Invariants
 ///
 /// - Vector dimension must match `VECTOR_DIM` constant
 /// - Vector elements must be Float32
-/// - Only `dependencies` field is nullable
+/// - Only `dependencies` and `pack` fields are nullable
 /// - Dependencies are serialized as comma-separated strings to avoid Arrow ListArray complexity
 #[allow(dead_code)]
 pub(crate) fn ast_chunk_schema() -> Schema {
@@ -61,6 +70,20 @@ pub(crate) fn ast_chunk_schema() -> Schema {
             ),
             false,
         ),
+        // pack: owning pack name (nullable), appended last to keep existing column order
+        Field::new("pack", DataType::Utf8, true),
+        // heading_path: " > "-joined heading breadcrumb (nullable)
+        Field::new("heading_path", DataType::Utf8, true),
+        // tags: comma-separated front-matter tags, `%` and `,` percent-encoded (nullable)
+        Field::new("tags", DataType::Utf8, true),
+        // version: front-matter document version (nullable)
+        Field::new("version", DataType::Utf8, true),
+        // start_line / end_line: 1-based source line span (nullable)
+        Field::new("start_line", DataType::UInt32, true),
+        Field::new("end_line", DataType::UInt32, true),
+        // start_page / end_page: 1-based page range of paginated documents (nullable)
+        Field::new("start_page", DataType::UInt32, true),
+        Field::new("end_page", DataType::UInt32, true),
     ])
 }
 
@@ -230,12 +253,23 @@ pub fn validate_knowledge_schema(schema: &Schema) -> Result<(), String> {
 mod tests {
     use super::*;
 
+    /// The AST chunk schema with the field at `index` replaced
+    fn schema_with_field(index: usize, field: Field) -> Schema {
+        let mut fields: Vec<Field> = ast_chunk_schema()
+            .fields()
+            .iter()
+            .map(|f| f.as_ref().clone())
+            .collect();
+        fields[index] = field;
+        Schema::new(fields)
+    }
+
     #[test]
     fn test_ast_chunk_schema() {
         let schema = ast_chunk_schema();
 
-        // Verify 7 fields
-        assert_eq!(schema.fields().len(), 7);
+        // Verify 15 fields
+        assert_eq!(schema.fields().len(), 15);
 
         // Verify field names
         let field_names: Vec<_> = schema.fields().iter().map(|f| f.name().as_str()).collect();
@@ -243,12 +277,20 @@ mod tests {
             field_names,
             vec![
                 "id",
-                "title",
-                "summary",
+                "file_path",
+                "symbol_name",
+                "node_type",
                 "content",
+                "dependencies",
                 "vector",
-                "keywords",
-                "source_path"
+                "pack",
+                "heading_path",
+                "tags",
+                "version",
+                "start_line",
+                "end_line",
+                "start_page",
+                "end_page"
             ]
         );
 
@@ -258,7 +300,7 @@ mod tests {
         assert!(!id_field.is_nullable());
 
         // Verify vector field type and dimension
-        let vector_field = schema.field(4);
+        let vector_field = schema.field(6);
         assert!(!vector_field.is_nullable());
         match vector_field.data_type() {
             DataType::FixedSizeList(field, size) => {
@@ -268,9 +310,9 @@ mod tests {
             _ => panic!("vector field should be FixedSizeList"),
         }
 
-        // Verify keywords field is nullable
-        let keywords_field = schema.field(5);
-        assert!(keywords_field.is_nullable());
+        // Verify dependencies field is nullable
+        let dependencies_field = schema.field(5);
+        assert!(dependencies_field.is_nullable());
     }
 
     #[test]
@@ -292,11 +334,8 @@ mod tests {
     #[test]
     fn test_validate_knowledge_schema_wrong_vector_dimension() {
         // Create a schema with wrong vector dimension
-        let wrong_schema = Schema::new(vec![
-            Field::new("id", DataType::Utf8, false),
-            Field::new("title", DataType::Utf8, false),
-            Field::new("summary", DataType::Utf8, false),
-            Field::new("content", DataType::Utf8, false),
+        let wrong_schema = schema_with_field(
+            6,
             Field::new(
                 "vector",
                 DataType::FixedSizeList(
@@ -305,9 +344,7 @@ mod tests {
                 ),
                 false,
             ),
-            Field::new("keywords", DataType::Utf8, true),
-            Field::new("source_path", DataType::Utf8, false),
-        ]);
+        );
 
         let result = validate_ast_chunk_schema(&wrong_schema);
         assert!(result.is_err());
@@ -319,11 +356,8 @@ mod tests {
     #[test]
     fn test_validate_ast_chunk_schema_wrong_vector_type() {
         // Create a schema with wrong vector element type
-        let wrong_schema = Schema::new(vec![
-            Field::new("id", DataType::Utf8, false),
-            Field::new("title", DataType::Utf8, false),
-            Field::new("summary", DataType::Utf8, false),
-            Field::new("content", DataType::Utf8, false),
+        let wrong_schema = schema_with_field(
+            6,
             Field::new(
                 "vector",
                 DataType::FixedSizeList(
@@ -332,9 +366,7 @@ mod tests {
                 ),
                 false,
             ),
-            Field::new("keywords", DataType::Utf8, true),
-            Field::new("source_path", DataType::Utf8, false),
-        ]);
+        );
 
         let result = validate_ast_chunk_schema(&wrong_schema);
         assert!(result.is_err());
@@ -346,51 +378,27 @@ mod tests {
     #[test]
     fn test_validate_ast_chunk_schema_wrong_field_name() {
         // Create a schema with wrong field name at index 1
-        let wrong_schema = Schema::new(vec![
-            Field::new("id", DataType::Utf8, false),
-            Field::new("subject", DataType::Utf8, false), // Wrong name, should be "title"
-            Field::new("summary", DataType::Utf8, false),
-            Field::new("content", DataType::Utf8, false),
-            Field::new(
-                "vector",
-                DataType::FixedSizeList(
-                    Arc::new(Field::new("item", DataType::Float32, true)),
-                    VECTOR_DIM,
-                ),
-                false,
-            ),
-            Field::new("keywords", DataType::Utf8, true),
-            Field::new("source_path", DataType::Utf8, false),
-        ]);
+        let wrong_schema = schema_with_field(
+            1,
+            Field::new("path", DataType::Utf8, false), // Wrong name, should be "file_path"
+        );
 
         let result = validate_ast_chunk_schema(&wrong_schema);
         assert!(result.is_err());
         let err = result.unwrap_err();
         assert!(err.contains("Field name mismatch"));
         assert!(err.contains("index 1"));
-        assert!(err.contains("expected 'title'"));
-        assert!(err.contains("got 'subject'"));
+        assert!(err.contains("expected 'file_path'"));
+        assert!(err.contains("got 'path'"));
     }
 
     #[test]
     fn test_validate_ast_chunk_schema_wrong_nullable_flag() {
         // Create a schema with wrong nullable flag for id field
-        let wrong_schema = Schema::new(vec![
+        let wrong_schema = schema_with_field(
+            0,
             Field::new("id", DataType::Utf8, true), // Should be non-null
-            Field::new("title", DataType::Utf8, false),
-            Field::new("summary", DataType::Utf8, false),
-            Field::new("content", DataType::Utf8, false),
-            Field::new(
-                "vector",
-                DataType::FixedSizeList(
-                    Arc::new(Field::new("item", DataType::Float32, true)),
-                    VECTOR_DIM,
-                ),
-                false,
-            ),
-            Field::new("keywords", DataType::Utf8, true),
-            Field::new("source_path", DataType::Utf8, false),
-        ]);
+        );
 
         let result = validate_ast_chunk_schema(&wrong_schema);
         assert!(result.is_err());
@@ -401,29 +409,17 @@ mod tests {
 
     #[test]
     fn test_validate_ast_chunk_schema_wrong_data_type() {
-        // Create a schema with wrong data type for title field
-        let wrong_schema = Schema::new(vec![
-            Field::new("id", DataType::Utf8, false),
-            Field::new("title", DataType::Int64, false), // Wrong type, should be Utf8
-            Field::new("summary", DataType::Utf8, false),
-            Field::new("content", DataType::Utf8, false),
-            Field::new(
-                "vector",
-                DataType::FixedSizeList(
-                    Arc::new(Field::new("item", DataType::Float32, true)),
-                    VECTOR_DIM,
-                ),
-                false,
-            ),
-            Field::new("keywords", DataType::Utf8, true),
-            Field::new("source_path", DataType::Utf8, false),
-        ]);
+        // Create a schema with wrong data type for symbol_name field
+        let wrong_schema = schema_with_field(
+            2,
+            Field::new("symbol_name", DataType::Int64, false), // Wrong type, should be Utf8
+        );
 
         let result = validate_ast_chunk_schema(&wrong_schema);
         assert!(result.is_err());
         let err = result.unwrap_err();
         assert!(err.contains("Data type mismatch"));
-        assert!(err.contains("title"));
+        assert!(err.contains("symbol_name"));
     }
 
     #[test]
@@ -431,9 +427,11 @@ mod tests {
         // Create a schema that exactly matches the expected schema
         let correct_schema = Schema::new(vec![
             Field::new("id", DataType::Utf8, false),
-            Field::new("title", DataType::Utf8, false),
-            Field::new("summary", DataType::Utf8, false),
+            Field::new("file_path", DataType::Utf8, false),
+            Field::new("symbol_name", DataType::Utf8, false),
+            Field::new("node_type", DataType::Utf8, false),
             Field::new("content", DataType::Utf8, false),
+            Field::new("dependencies", DataType::Utf8, true),
             Field::new(
                 "vector",
                 DataType::FixedSizeList(
@@ -442,8 +440,14 @@ mod tests {
                 ),
                 false,
             ),
-            Field::new("keywords", DataType::Utf8, true),
-            Field::new("source_path", DataType::Utf8, false),
+            Field::new("pack", DataType::Utf8, true),
+            Field::new("heading_path", DataType::Utf8, true),
+            Field::new("tags", DataType::Utf8, true),
+            Field::new("version", DataType::Utf8, true),
+            Field::new("start_line", DataType::UInt32, true),
+            Field::new("end_line", DataType::UInt32, true),
+            Field::new("start_page", DataType::UInt32, true),
+            Field::new("end_page", DataType::UInt32, true),
         ]);
 
         let result = validate_ast_chunk_schema(&correct_schema);
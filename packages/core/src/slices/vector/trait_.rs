@@ -9,7 +9,7 @@
 //! Ref: `openspec/changes/refactor-pragmatic-slice-architecture/design.md` - Rule 2
 
 use crate::kernel::errors::AppError;
-use crate::kernel::types::{AstChunk, Hit, Query};
+use crate::kernel::types::{AstChunk, ChunkFilter, Hit, Query};
 use async_trait::async_trait;
 
 /// Vector store trait
@@ -104,6 +104,32 @@ pub trait VectorStoreTrait: Send + Sync {
     /// ```
     async fn search(&self, query: &Query) -> Result<Option<Vec<Hit>>, AppError>;
 
+    /// Embed query text ahead of a search
+    ///
+    /// Lets callers time embedding separately from the vector search. Stores
+    /// that embed inside `search` keep the default, which returns `Ok(None)`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(vector))` - Query embedding to pass to `search_with_embedding`
+    /// * `Ok(None)` - The store does not expose query embedding
+    /// * `Err(AppError)` - Embedding failed
+    async fn embed_query(&self, _text: &str) -> Result<Option<Vec<f32>>, AppError> {
+        Ok(None)
+    }
+
+    /// Search with a query embedding produced by `embed_query`
+    ///
+    /// Same contract as `search`. The default ignores the embedding and calls
+    /// `search`.
+    async fn search_with_embedding(
+        &self,
+        query: &Query,
+        _embedding: Vec<f32>,
+    ) -> Result<Option<Vec<Hit>>, AppError> {
+        self.search(query).await
+    }
+
     /// Add a document to the vector store
     ///
     /// # Parameters
@@ -162,6 +188,106 @@ pub trait VectorStoreTrait: Send + Sync {
     /// * `Ok(())` - Batch add successful
     /// * `Err(AppError)` - Batch add failed
     async fn add_batch(&self, chunks: Vec<AstChunk>) -> Result<(), AppError>;
+
+    /// Insert or replace AST chunks by ID
+    ///
+    /// Unlike `add_batch`, re-running `upsert_batch` with the same chunks leaves
+    /// exactly one row per ID, so rebuilding an index is idempotent. If the input
+    /// repeats an ID, the last chunk wins.
+    ///
+    /// # Parameters
+    ///
+    /// * `chunks` - AST chunk list
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - Upsert successful
+    /// * `Err(AppError)` - Upsert failed
+    async fn upsert_batch(&self, chunks: Vec<AstChunk>) -> Result<(), AppError>;
+
+    /// Insert or replace a single AST chunk by ID
+    ///
+    /// # Default Implementation
+    ///
+    /// Delegates to `upsert_batch` with a single chunk.
+    async fn upsert(&self, chunk: AstChunk) -> Result<(), AppError> {
+        self.upsert_batch(vec![chunk]).await
+    }
+
+    /// Get a stored document by ID
+    ///
+    /// # Parameters
+    ///
+    /// * `id` - Document ID to retrieve
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(chunk))` - Document found; `chunk.vector` is always `None`
+    /// * `Ok(None)` - Document not found
+    /// * `Err(AppError)` - Retrieval failed
+    async fn get_by_id(&self, id: &str) -> Result<Option<AstChunk>, AppError>;
+
+    /// Get multiple stored documents by IDs
+    ///
+    /// # Parameters
+    ///
+    /// * `ids` - Slice of document IDs to retrieve
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(vec)` - Vector of options in the same order as input IDs.
+    ///   Each element is `Some(chunk)` if found, `None` if not found.
+    /// * `Err(AppError)` - Retrieval failed
+    ///
+    /// # Default Implementation
+    ///
+    /// The default implementation calls `get_by_id` for each ID concurrently.
+    /// Implementations may override this to provide a single filtered scan.
+    async fn get_by_ids(&self, ids: &[String]) -> Result<Vec<Option<AstChunk>>, AppError> {
+        use futures::future::try_join_all;
+        let futures = ids.iter().map(|id| self.get_by_id(id));
+        try_join_all(futures).await
+    }
+
+    /// List every stored document as an AST chunk
+    ///
+    /// Used by consistency checks to enumerate the full table. Rows that share
+    /// an ID are all returned, so callers can detect duplicates.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(chunks)` - All stored documents (`chunk.vector` is always `None`)
+    /// * `Err(AppError)` - Enumeration failed
+    async fn list_chunks(&self) -> Result<Vec<AstChunk>, AppError>;
+
+    /// Number of stored rows (duplicate rows are all counted)
+    ///
+    /// The default implementation enumerates every row with `list_chunks`;
+    /// implementations should override it with a cheaper count.
+    async fn count(&self) -> Result<usize, AppError> {
+        Ok(self.list_chunks().await?.len())
+    }
+
+    /// Version of the stored table
+    ///
+    /// Increases with every write, including writes made by another process,
+    /// so callers can notice external rebuilds. The default implementation
+    /// returns `None` (not tracked).
+    async fn version(&self) -> Result<Option<u64>, AppError> {
+        Ok(None)
+    }
+
+    /// Delete every stored document matching a filter
+    ///
+    /// # Parameters
+    ///
+    /// * `filter` - Path prefix, pack and/or node type criteria (AND-ed)
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(count)` - Number of rows removed (duplicate rows are all counted)
+    /// * `Err(AppError)` - Filter was empty or the delete failed
+    async fn delete_where(&self, filter: &ChunkFilter) -> Result<usize, AppError>;
 }
 
 #[cfg(test)]
@@ -246,6 +372,42 @@ mod tests {
             }
             Ok(())
         }
+
+        async fn upsert_batch(&self, _chunks: Vec<AstChunk>) -> Result<(), AppError> {
+            if self.should_fail {
+                return Err(AppError::Infra(InfraError::database(
+                    "mock upsert failed",
+                    None::<std::io::Error>,
+                )));
+            }
+            Ok(())
+        }
+
+        async fn get_by_id(&self, id: &str) -> Result<Option<AstChunk>, AppError> {
+            if self.should_fail {
+                return Err(AppError::Infra(InfraError::database(
+                    "mock get failed",
+                    None::<std::io::Error>,
+                )));
+            }
+
+            // Mock: only doc1 exists
+            if id == "doc1" {
+                Ok(Some(AstChunk::without_dependencies(
+                    "doc1", "doc1.md", "Doc One", "file", "content",
+                )))
+            } else {
+                Ok(None)
+            }
+        }
+
+        async fn list_chunks(&self) -> Result<Vec<AstChunk>, AppError> {
+            Ok(vec![])
+        }
+
+        async fn delete_where(&self, _filter: &ChunkFilter) -> Result<usize, AppError> {
+            Ok(0)
+        }
     }
 
     #[tokio::test]
@@ -331,4 +493,21 @@ mod tests {
         assert!(result.is_ok());
         assert!(result.unwrap());
     }
+
+    #[tokio::test]
+    async fn test_vector_store_trait_get_by_ids_default_preserves_order() {
+        let store = MockVectorStore {
+            should_fail: false,
+            empty_results: false,
+        };
+
+        let results = store
+            .get_by_ids(&["missing".to_string(), "doc1".to_string()])
+            .await
+            .expect("get_by_ids should succeed");
+
+        assert_eq!(results.len(), 2);
+        assert!(results[0].is_none(), "Unknown id should map to None");
+        assert_eq!(results[1].as_ref().unwrap().id, "doc1");
+    }
 }
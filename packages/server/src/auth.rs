@@ -0,0 +1,429 @@
+//! Bearer token authentication
+//!
+//! API keys are listed in a TOML file (`.contextfy/api_keys.toml`, or the path
+//! in `CONTEXTFY_API_KEYS`). Only the SHA-256 of each token is stored:
+//!
+//! ```toml
+//! [[keys]]
+//! name = "docs-team"
+//! sha256 = "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08"
+//! scopes = ["read", "write"]
+//! packs = ["guide"]  # optional; omit to allow every pack
+//! ```
+//!
+//! Each documented route declares the scope it needs (see `api_routes`).
+//! Requests without a valid `Authorization: Bearer <token>` header get 401,
+//! keys lacking the scope or touching another pack get 403. The server refuses
+//! to start when the keys file cannot be read; running without authentication
+//! takes an explicit `server.auth.disabled` in `contextfy.json`.
+
+use axum::{
+    async_trait,
+    extract::{FromRequestParts, Request},
+    http::{header, request::Parts, HeaderMap},
+    middleware::Next,
+    response::Response,
+};
+use contextfy_core::ChunkFilter;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::ApiError;
+
+/// Environment variable overriding the keys file location
+pub(crate) const KEYS_FILE_ENV: &str = "CONTEXTFY_API_KEYS";
+
+/// Keys file used when `CONTEXTFY_API_KEYS` is not set
+const DEFAULT_KEYS_FILE: &str = ".contextfy/api_keys.toml";
+
+/// Permission a route requires
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Scope {
+    /// Search and read documents
+    Read,
+    /// Create, replace and delete documents
+    Write,
+    /// Administrative operations; implies every other scope
+    Admin,
+}
+
+impl Scope {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            Self::Read => "read",
+            Self::Write => "write",
+            Self::Admin => "admin",
+        }
+    }
+}
+
+/// An entry of the keys file
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct ApiKey {
+    /// Name used in logs and error messages
+    pub(crate) name: String,
+    /// Hex-encoded SHA-256 of the bearer token
+    sha256: String,
+    scopes: Vec<Scope>,
+    /// Packs this key may access (empty = all packs)
+    #[serde(default)]
+    packs: Vec<String>,
+}
+
+impl ApiKey {
+    fn has_scope(&self, scope: Scope) -> bool {
+        self.scopes.contains(&scope) || self.scopes.contains(&Scope::Admin)
+    }
+
+    fn allows_pack(&self, pack: Option<&str>) -> bool {
+        self.packs.is_empty() || pack.is_some_and(|pack| self.packs.iter().any(|p| p == pack))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct KeysFile {
+    #[serde(default)]
+    keys: Vec<ApiKey>,
+}
+
+/// API keys indexed by token hash
+#[derive(Debug)]
+pub(crate) struct ApiKeys {
+    by_hash: HashMap<String, Arc<ApiKey>>,
+}
+
+impl ApiKeys {
+    /// Load keys from `CONTEXTFY_API_KEYS` or the default keys file
+    ///
+    /// The file must exist: a missing keys file is an error, never a silent
+    /// fallback to unauthenticated access.
+    pub(crate) fn from_env() -> anyhow::Result<Self> {
+        let path = std::env::var_os(KEYS_FILE_ENV)
+            .map(PathBuf::from)
+            .unwrap_or_else(|| PathBuf::from(DEFAULT_KEYS_FILE));
+        Self::load(&path)
+    }
+
+    /// Load keys from a TOML keys file
+    pub(crate) fn load(path: &Path) -> anyhow::Result<Self> {
+        let raw = std::fs::read_to_string(path).map_err(|e| {
+            anyhow::anyhow!("Failed to read API keys file {}: {}", path.display(), e)
+        })?;
+        Self::from_toml(&raw)
+            .map_err(|e| anyhow::anyhow!("Invalid API keys file {}: {}", path.display(), e))
+    }
+
+    pub(crate) fn from_toml(raw: &str) -> anyhow::Result<Self> {
+        let file: KeysFile = toml::from_str(raw)?;
+        let mut by_hash = HashMap::new();
+        for mut key in file.keys {
+            key.sha256 = key.sha256.trim().to_ascii_lowercase();
+            if key.sha256.len() != 64 || !key.sha256.chars().all(|c| c.is_ascii_hexdigit()) {
+                anyhow::bail!("key '{}': sha256 must be 64 hex characters", key.name);
+            }
+            if key.scopes.is_empty() {
+                anyhow::bail!("key '{}' has no scopes", key.name);
+            }
+            let name = key.name.clone();
+            if by_hash.insert(key.sha256.clone(), Arc::new(key)).is_some() {
+                anyhow::bail!("key '{}' reuses the token of another key", name);
+            }
+        }
+        Ok(Self { by_hash })
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.by_hash.len()
+    }
+
+    fn authenticate(&self, token: &str) -> Option<Arc<ApiKey>> {
+        self.by_hash.get(&hash_token(token)).cloned()
+    }
+}
+
+/// Hex-encoded SHA-256 of a token, as stored in the keys file
+pub(crate) fn hash_token(token: &str) -> String {
+    format!("{:x}", Sha256::digest(token.as_bytes()))
+}
+
+pub(crate) fn bearer_token(headers: &HeaderMap) -> Option<&str> {
+    let value = headers.get(header::AUTHORIZATION)?.to_str().ok()?;
+    let (scheme, token) = value.split_once(' ')?;
+    scheme
+        .eq_ignore_ascii_case("bearer")
+        .then(|| token.trim())
+        .filter(|token| !token.is_empty())
+}
+
+/// Hash of the request's bearer token when it belongs to a configured key
+///
+/// `None` when authentication is disabled, no token is sent or the token is
+/// unknown, so made-up tokens never identify a client.
+pub(crate) fn verified_token_hash(request: &Request) -> Option<String> {
+    let keys = request.extensions().get::<Arc<ApiKeys>>()?;
+    let hash = hash_token(bearer_token(request.headers())?);
+    keys.by_hash.contains_key(&hash).then_some(hash)
+}
+
+/// Route middleware admitting requests whose key has `scope`
+///
+/// Reads the key store from the request extensions (installed by `app`) and
+/// passes the authenticated key on to handlers as a [`Caller`].
+pub(crate) async fn require_scope(
+    scope: Scope,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    let Some(keys) = request.extensions().get::<Arc<ApiKeys>>().cloned() else {
+        return Ok(next.run(request).await);
+    };
+
+    let token = bearer_token(request.headers())
+        .ok_or_else(|| ApiError::unauthorized("Missing bearer token"))?;
+    let key = keys.authenticate(token).ok_or_else(|| {
+        tracing::warn!(path = %request.uri().path(), "Rejected invalid API key");
+        ApiError::unauthorized("Invalid API key")
+    })?;
+
+    if !key.has_scope(scope) {
+        tracing::warn!(key = %key.name, scope = scope.as_str(), "API key lacks scope");
+        return Err(ApiError::forbidden(format!(
+            "API key '{}' lacks the '{}' scope",
+            key.name,
+            scope.as_str()
+        )));
+    }
+
+    request.extensions_mut().insert(Caller(Some(key)));
+    Ok(next.run(request).await)
+}
+
+/// The API key a request was authenticated with (`None` when auth is disabled)
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Caller(Option<Arc<ApiKey>>);
+
+impl Caller {
+    /// Reject access to a document outside the key's packs
+    pub(crate) fn check_pack(&self, pack: Option<&str>) -> Result<(), ApiError> {
+        match &self.0 {
+            Some(key) if !key.allows_pack(pack) => Err(ApiError::forbidden(format!(
+                "API key '{}' may not access pack '{}'",
+                key.name,
+                pack.unwrap_or("(none)")
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    /// Restrict a filter to the key's packs
+    ///
+    /// An explicitly requested pack must be one of them.
+    pub(crate) fn scope_filter(&self, mut filter: ChunkFilter) -> Result<ChunkFilter, ApiError> {
+        if let Some(key) = self.0.as_ref().filter(|key| !key.packs.is_empty()) {
+            if filter.pack.is_some() {
+                self.check_pack(filter.pack.as_deref())?;
+            }
+            filter.packs = key.packs.clone();
+        }
+        Ok(filter)
+    }
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for Caller {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(parts
+            .extensions
+            .get::<Caller>()
+            .cloned()
+            .unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{
+        body::Body,
+        http::{HeaderValue, StatusCode},
+        middleware::from_fn,
+        routing::get,
+        Extension, Router,
+    };
+    use tower::ServiceExt;
+
+    fn keys() -> ApiKeys {
+        ApiKeys::from_toml(&format!(
+            r#"
+            [[keys]]
+            name = "reader"
+            sha256 = "{}"
+            scopes = ["read"]
+            packs = ["guide"]
+
+            [[keys]]
+            name = "ops"
+            sha256 = "{}"
+            scopes = ["admin"]
+            "#,
+            hash_token("reader-token"),
+            hash_token("ops-token").to_uppercase()
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_keys_file_scopes_and_packs() {
+        let keys = keys();
+        assert_eq!(keys.len(), 2);
+        assert!(keys.authenticate("wrong").is_none());
+
+        let reader = keys.authenticate("reader-token").unwrap();
+        assert!(reader.has_scope(Scope::Read));
+        assert!(!reader.has_scope(Scope::Write));
+        assert!(reader.allows_pack(Some("guide")));
+        assert!(!reader.allows_pack(Some("api")));
+        assert!(!reader.allows_pack(None));
+
+        let ops = keys.authenticate("ops-token").unwrap();
+        assert!(ops.has_scope(Scope::Write) && ops.has_scope(Scope::Admin));
+        assert!(ops.allows_pack(None));
+    }
+
+    #[test]
+    fn test_keys_file_validation() {
+        assert!(ApiKeys::load(Path::new("/nonexistent/api_keys.toml")).is_err());
+        assert!(ApiKeys::from_toml(
+            "[[keys]]\nname = \"a\"\nsha256 = \"abc\"\nscopes = [\"read\"]"
+        )
+        .is_err());
+        let hash = hash_token("t");
+        let no_scopes = format!("[[keys]]\nname = \"a\"\nsha256 = \"{}\"\nscopes = []", hash);
+        assert!(ApiKeys::from_toml(&no_scopes).is_err());
+        let duplicate = format!(
+            "[[keys]]\nname = \"a\"\nsha256 = \"{h}\"\nscopes = [\"read\"]\n\
+             [[keys]]\nname = \"b\"\nsha256 = \"{h}\"\nscopes = [\"read\"]",
+            h = hash
+        );
+        assert!(ApiKeys::from_toml(&duplicate).is_err());
+        assert_eq!(ApiKeys::from_toml("").unwrap().len(), 0);
+    }
+
+    #[test]
+    fn test_bearer_token_parsing() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(bearer_token(&headers), None);
+        headers.insert(
+            header::AUTHORIZATION,
+            HeaderValue::from_static("Bearer abc"),
+        );
+        assert_eq!(bearer_token(&headers), Some("abc"));
+        headers.insert(
+            header::AUTHORIZATION,
+            HeaderValue::from_static("bearer  abc "),
+        );
+        assert_eq!(bearer_token(&headers), Some("abc"));
+        headers.insert(header::AUTHORIZATION, HeaderValue::from_static("Basic abc"));
+        assert_eq!(bearer_token(&headers), None);
+    }
+
+    #[test]
+    fn test_caller_scopes_filter_to_packs() {
+        let keys = keys();
+        let reader = Caller(keys.authenticate("reader-token"));
+
+        let filter = reader.scope_filter(ChunkFilter::new()).unwrap();
+        assert_eq!(filter.packs, vec!["guide"]);
+        assert!(reader
+            .scope_filter(ChunkFilter::new().with_pack("guide"))
+            .is_ok());
+        assert!(reader
+            .scope_filter(ChunkFilter::new().with_pack("api"))
+            .is_err());
+        assert!(reader.check_pack(Some("api")).is_err());
+
+        let anonymous = Caller::default();
+        assert!(anonymous
+            .scope_filter(ChunkFilter::new())
+            .unwrap()
+            .is_empty());
+        assert!(anonymous.check_pack(None).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_require_scope_middleware() {
+        let route = |scope: Scope| {
+            get(|caller: Caller| async move {
+                caller.0.map(|key| key.name.clone()).unwrap_or_default()
+            })
+            .route_layer(from_fn(move |request, next| {
+                require_scope(scope, request, next)
+            }))
+        };
+        let app = Router::new()
+            .route("/read", route(Scope::Read))
+            .route("/write", route(Scope::Write))
+            .layer(Extension(Arc::new(keys())));
+
+        let status = |path: &'static str, token: Option<&'static str>| {
+            let app = app.clone();
+            async move {
+                let mut request = axum::http::Request::builder().uri(path);
+                if let Some(token) = token {
+                    request = request.header(header::AUTHORIZATION, format!("Bearer {}", token));
+                }
+                let response = app
+                    .oneshot(request.body(Body::empty()).unwrap())
+                    .await
+                    .unwrap();
+                (
+                    response.status(),
+                    response.headers().get(header::WWW_AUTHENTICATE).cloned(),
+                )
+            }
+        };
+
+        assert_eq!(
+            status("/read", None).await,
+            (
+                StatusCode::UNAUTHORIZED,
+                Some(HeaderValue::from_static("Bearer"))
+            )
+        );
+        assert_eq!(
+            status("/read", Some("wrong")).await.0,
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(
+            status("/read", Some("reader-token")).await.0,
+            StatusCode::OK
+        );
+        assert_eq!(
+            status("/write", Some("reader-token")).await.0,
+            StatusCode::FORBIDDEN
+        );
+        assert_eq!(status("/write", Some("ops-token")).await.0, StatusCode::OK);
+
+        // Without a key store every request is allowed
+        let open = Router::new().route("/write", route(Scope::Write));
+        let response = open
+            .oneshot(
+                axum::http::Request::builder()
+                    .uri("/write")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
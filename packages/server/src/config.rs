@@ -0,0 +1,210 @@
+//! Server configuration
+//!
+//! Read from the `server` section of `contextfy.json`; every field is optional:
+//!
+//! ```json
+//! {
+//!   "server": {
+//!     "limits": {
+//!       "rate_per_second": 10,
+//!       "burst": 20,
+//!       "max_concurrent": 4,
+//!       "max_queued": 32,
+//!       "queue_timeout_ms": 10000,
+//!       "max_body_bytes": 2097152
+//!     },
+//!     "reload": {
+//!       "watch": true,
+//!       "poll_interval_ms": 2000
+//!     },
+//!     "auth": {
+//!       "disabled": false
+//!     }
+//!   }
+//! }
+//! ```
+
+use serde::Deserialize;
+use std::path::Path;
+use std::time::Duration;
+
+/// Project configuration file holding the `server` section
+pub(crate) const CONFIG_FILE: &str = "contextfy.json";
+
+#[derive(Debug, Default, Deserialize)]
+struct ProjectConfig {
+    #[serde(default)]
+    server: ServerConfig,
+}
+
+/// The `server` section of `contextfy.json`
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct ServerConfig {
+    pub(crate) limits: LimitsConfig,
+    pub(crate) reload: ReloadConfig,
+    pub(crate) auth: AuthConfig,
+}
+
+impl ServerConfig {
+    /// Load the `server` section, using defaults when the file does not exist
+    pub(crate) fn load(path: &Path) -> anyhow::Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let raw = std::fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("Failed to read {}: {}", path.display(), e))?;
+        Self::from_json(&raw).map_err(|e| anyhow::anyhow!("Invalid {}: {}", path.display(), e))
+    }
+
+    fn from_json(raw: &str) -> anyhow::Result<Self> {
+        let config: ProjectConfig = serde_json::from_str(raw)?;
+        config.server.limits.validate()?;
+        config.server.reload.validate()?;
+        Ok(config.server)
+    }
+}
+
+/// Request rate, concurrency and size limits
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct LimitsConfig {
+    /// Sustained requests per second per client (0 disables rate limiting)
+    pub(crate) rate_per_second: f64,
+    /// Requests a client may send in a burst above the sustained rate
+    pub(crate) burst: u32,
+    /// Embedding-bound requests (search, document writes) running at once
+    pub(crate) max_concurrent: usize,
+    /// Requests allowed to wait for a slot; further requests get 503
+    pub(crate) max_queued: usize,
+    /// How long a queued request waits for a slot before getting 503
+    pub(crate) queue_timeout_ms: u64,
+    /// Largest accepted request body in bytes
+    pub(crate) max_body_bytes: usize,
+}
+
+impl Default for LimitsConfig {
+    fn default() -> Self {
+        Self {
+            rate_per_second: 10.0,
+            burst: 20,
+            max_concurrent: 4,
+            max_queued: 32,
+            queue_timeout_ms: 10_000,
+            max_body_bytes: 2 * 1024 * 1024,
+        }
+    }
+}
+
+impl LimitsConfig {
+    pub(crate) fn queue_timeout(&self) -> Duration {
+        Duration::from_millis(self.queue_timeout_ms)
+    }
+
+    fn validate(&self) -> anyhow::Result<()> {
+        if !self.rate_per_second.is_finite() || self.rate_per_second < 0.0 {
+            anyhow::bail!("limits.rate_per_second must be a non-negative number");
+        }
+        if self.rate_per_second > 0.0 && self.burst == 0 {
+            anyhow::bail!("limits.burst must be at least 1 when rate limiting is enabled");
+        }
+        if self.max_concurrent == 0 {
+            anyhow::bail!("limits.max_concurrent must be at least 1");
+        }
+        if self.max_body_bytes == 0 {
+            anyhow::bail!("limits.max_body_bytes must be at least 1");
+        }
+        Ok(())
+    }
+}
+
+/// Reloading the indexes after they are rebuilt by another process
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct ReloadConfig {
+    /// Poll the index versions and reload when they change
+    pub(crate) watch: bool,
+    /// How often the index versions are read
+    pub(crate) poll_interval_ms: u64,
+}
+
+impl Default for ReloadConfig {
+    fn default() -> Self {
+        Self {
+            watch: true,
+            poll_interval_ms: 2_000,
+        }
+    }
+}
+
+impl ReloadConfig {
+    pub(crate) fn poll_interval(&self) -> Duration {
+        Duration::from_millis(self.poll_interval_ms)
+    }
+
+    fn validate(&self) -> anyhow::Result<()> {
+        if self.poll_interval_ms == 0 {
+            anyhow::bail!("reload.poll_interval_ms must be at least 1");
+        }
+        Ok(())
+    }
+}
+
+/// API key authentication
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct AuthConfig {
+    /// Serve every request without checking API keys
+    ///
+    /// Authentication is otherwise mandatory: the server refuses to start
+    /// when the keys file cannot be read.
+    pub(crate) disabled: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_server_section_defaults_and_overrides() {
+        let config = ServerConfig::from_json(r#"{"name": "guide"}"#).unwrap();
+        assert_eq!(config.limits.max_concurrent, 4);
+        assert_eq!(config.limits.queue_timeout(), Duration::from_secs(10));
+
+        let config = ServerConfig::from_json(
+            r#"{"server": {"limits": {"rate_per_second": 0.5, "max_body_bytes": 1024}}}"#,
+        )
+        .unwrap();
+        assert_eq!(config.limits.rate_per_second, 0.5);
+        assert_eq!(config.limits.max_body_bytes, 1024);
+        assert_eq!(config.limits.burst, 20);
+        assert!(config.reload.watch);
+        assert_eq!(config.reload.poll_interval(), Duration::from_secs(2));
+        assert!(!config.auth.disabled);
+
+        let config =
+            ServerConfig::from_json(r#"{"server": {"reload": {"watch": false}}}"#).unwrap();
+        assert!(!config.reload.watch);
+
+        let config =
+            ServerConfig::from_json(r#"{"server": {"auth": {"disabled": true}}}"#).unwrap();
+        assert!(config.auth.disabled);
+    }
+
+    #[test]
+    fn test_invalid_limits_rejected() {
+        for raw in [
+            r#"{"server": {"limits": {"max_concurrent": 0}}}"#,
+            r#"{"server": {"limits": {"rate_per_second": -1}}}"#,
+            r#"{"server": {"limits": {"burst": 0}}}"#,
+            r#"{"server": {"limits": {"max_conncurrent": 2}}}"#,
+            r#"{"server": {"reload": {"poll_interval_ms": 0}}}"#,
+        ] {
+            assert!(ServerConfig::from_json(raw).is_err(), "{}", raw);
+        }
+        assert!(ServerConfig::from_json(
+            r#"{"server": {"limits": {"rate_per_second": 0, "burst": 0}}}"#
+        )
+        .is_ok());
+    }
+}
@@ -0,0 +1,484 @@
+//! Document write endpoints
+//!
+//! - `POST /api/documents` - add one document or a batch (rejects IDs that already exist)
+//! - `PUT /api/documents/:id` - insert or replace a single document
+//! - `DELETE /api/documents/:id` - delete a document, reporting each backend's result
+//! - `DELETE /api/documents?path_prefix=` - delete every document under a path prefix
+//!
+//! Documents are accepted either as full [`AstChunk`]s or in a simple
+//! title/summary/content/keywords form that is mapped onto a chunk (see
+//! [`SimpleDocument`]). All writes hold the engine's write lock so searches
+//! never observe a half-applied batch.
+
+use axum::{
+    extract::{rejection::JsonRejection, Path as ApiPath, Query, State},
+    http::StatusCode,
+    response::Json,
+};
+use contextfy_core::{AstChunk, ChunkFilter, DeleteResult, SearchEngine};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use tokio::sync::RwLockMappedWriteGuard;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::{auth::Caller, ApiError, AppState};
+
+/// Simple document form, stored as a `file` chunk
+///
+/// Title becomes the symbol name and summary the file path. Keywords are split
+/// on whitespace into the chunk's dependencies, which both stores index: the
+/// BM25 `dependencies` field (boosted 2x) and the LanceDB `dependencies` column.
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub(crate) struct SimpleDocument {
+    /// Document ID (optional for `PUT`, where the path provides it)
+    #[serde(default)]
+    id: String,
+    title: String,
+    #[serde(default)]
+    summary: String,
+    content: String,
+    /// Space-separated keywords, stored as the chunk's dependencies
+    #[serde(default)]
+    keywords: Option<String>,
+}
+
+/// A document in a write request
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+#[serde(untagged)]
+pub(crate) enum DocumentInput {
+    /// Full chunk (requires `file_path`, `symbol_name` and `node_type`)
+    Chunk(Box<AstChunk>),
+    /// Title/summary/content/keywords form
+    Simple(SimpleDocument),
+}
+
+impl DocumentInput {
+    fn into_chunk(self) -> AstChunk {
+        match self {
+            Self::Chunk(chunk) => *chunk,
+            Self::Simple(doc) => AstChunk::new(
+                doc.id,
+                doc.summary,
+                doc.title,
+                "file",
+                doc.content,
+                doc.keywords
+                    .unwrap_or_default()
+                    .split_whitespace()
+                    .map(str::to_string)
+                    .collect(),
+            ),
+        }
+    }
+}
+
+/// Body of `POST /api/documents`: a single document or an array of documents
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(untagged)]
+pub(crate) enum DocumentsPayload {
+    Batch(Vec<DocumentInput>),
+    Single(DocumentInput),
+}
+
+impl DocumentsPayload {
+    fn into_chunks(self) -> Vec<AstChunk> {
+        match self {
+            Self::Batch(docs) => docs.into_iter().map(DocumentInput::into_chunk).collect(),
+            Self::Single(doc) => vec![doc.into_chunk()],
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub(crate) struct WriteResponse {
+    ids: Vec<String>,
+    count: usize,
+}
+
+impl WriteResponse {
+    fn new(ids: Vec<String>) -> Self {
+        Self {
+            count: ids.len(),
+            ids,
+        }
+    }
+}
+
+/// Outcome of a delete in one backend
+#[derive(Debug, Serialize, ToSchema)]
+pub(crate) struct BackendDelete {
+    deleted: bool,
+    count: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// Serializable form of [`DeleteResult`]
+#[derive(Debug, Serialize, ToSchema)]
+pub(crate) struct DeleteResponse {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    path_prefix: Option<String>,
+    vector: BackendDelete,
+    bm25: BackendDelete,
+}
+
+impl DeleteResponse {
+    fn new(result: &DeleteResult) -> Self {
+        let backend =
+            |deleted: &Result<bool, contextfy_core::AppError>, count: usize| BackendDelete {
+                deleted: *deleted.as_ref().unwrap_or(&false),
+                count,
+                error: deleted.as_ref().err().map(ToString::to_string),
+            };
+        Self {
+            id: None,
+            path_prefix: None,
+            vector: backend(&result.vector_deleted, result.vector_count),
+            bm25: backend(&result.bm25_deleted, result.bm25_count),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub(crate) struct DeleteQuery {
+    /// Delete every document whose file path starts with this prefix (required)
+    path_prefix: Option<String>,
+}
+
+/// Turn axum's JSON rejection into the API error format
+fn json_body<T>(body: Result<Json<T>, JsonRejection>) -> Result<T, ApiError> {
+    body.map(|Json(value)| value).map_err(|rejection| {
+        tracing::warn!(error = %rejection, "Rejected document payload");
+        if rejection.status() == StatusCode::PAYLOAD_TOO_LARGE {
+            return ApiError::payload_too_large("Request body exceeds the configured size limit");
+        }
+        ApiError::bad_request(format!(
+            "Invalid document payload: {}",
+            rejection.body_text()
+        ))
+    })
+}
+
+/// Reject empty IDs and IDs repeated within one request
+fn validate_chunks(chunks: &[AstChunk]) -> Result<(), ApiError> {
+    if chunks.is_empty() {
+        return Err(ApiError::bad_request("At least one document is required"));
+    }
+    let mut seen = HashSet::new();
+    for chunk in chunks {
+        if chunk.id.trim().is_empty() {
+            return Err(ApiError::bad_request("Document ID cannot be empty"));
+        }
+        if !seen.insert(chunk.id.as_str()) {
+            return Err(ApiError::bad_request(format!(
+                "Document ID '{}' appears more than once",
+                chunk.id
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Reject documents outside the caller's packs
+fn check_packs(caller: &Caller, chunks: &[AstChunk]) -> Result<(), ApiError> {
+    chunks
+        .iter()
+        .try_for_each(|chunk| caller.check_pack(chunk.pack.as_deref()))
+}
+
+/// Map a delete with no successful backend to 404 (nothing matched) or 500 (backend error)
+fn delete_error(result: &DeleteResult, target: &str) -> Option<ApiError> {
+    if result.any_success() {
+        return None;
+    }
+    Some(match result.first_error() {
+        Some(e) => {
+            tracing::error!(error = ?e, target = %target, "Delete failed");
+            ApiError::internal("Failed to delete documents due to an internal error")
+        }
+        None => ApiError::not_found(format!("No documents matched {}", target)),
+    })
+}
+
+/// Release the write lock and record the index version the write left behind
+///
+/// Keeps the reload watcher from reopening the engine for this server's own
+/// writes. Called after every write attempt, since a failed write may still
+/// have committed to one store.
+async fn finish_write(engine: &AppState, engine_guard: RwLockMappedWriteGuard<'_, SearchEngine>) {
+    let version = engine_guard.index_version().await;
+    drop(engine_guard);
+    match version {
+        Ok(version) => engine.record_local_write(version).await,
+        Err(e) => tracing::warn!(error = ?e, "Failed to read index version after write"),
+    }
+}
+
+/// Reject replacing or deleting a stored document outside the caller's packs
+async fn check_existing_pack(
+    engine: &SearchEngine,
+    caller: &Caller,
+    doc_id: &str,
+) -> Result<(), ApiError> {
+    let existing = engine.get_document(doc_id).await.map_err(|e| {
+        tracing::error!(error = ?e, doc_id = %doc_id, "Failed to load document");
+        ApiError::internal("Failed to load document due to an internal error")
+    })?;
+    match existing {
+        Some(doc) => caller.check_pack(doc.pack.as_deref()),
+        None => Ok(()),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/documents",
+    tag = "documents",
+    request_body = DocumentsPayload,
+    responses(
+        (status = 201, description = "Documents added", body = WriteResponse),
+        (status = 400, description = "Invalid payload", body = ApiError),
+        (status = 409, description = "A document ID already exists", body = ApiError),
+        (status = 500, description = "Storage error", body = ApiError)
+    ),
+    security(("bearer" = []))
+)]
+pub(crate) async fn create_documents_handler(
+    State(engine): State<AppState>,
+    caller: Caller,
+    body: Result<Json<DocumentsPayload>, JsonRejection>,
+) -> Result<(StatusCode, Json<WriteResponse>), ApiError> {
+    let chunks = json_body(body)?.into_chunks();
+    validate_chunks(&chunks)?;
+    check_packs(&caller, &chunks)?;
+    let ids: Vec<String> = chunks.iter().map(|chunk| chunk.id.clone()).collect();
+
+    tracing::info!(count = chunks.len(), "Document create request received");
+
+    let engine_guard = engine.write().await?;
+
+    let existing = engine_guard.get_documents(&ids).await.map_err(|e| {
+        tracing::error!(error = ?e, "Failed to check existing documents");
+        ApiError::internal("Failed to add documents due to an internal error")
+    })?;
+    if let Some(doc) = existing.into_iter().flatten().next() {
+        tracing::warn!(doc_id = %doc.id, "Document already exists");
+        return Err(ApiError::conflict(format!(
+            "Document with ID '{}' already exists; use PUT /api/documents/{} to replace it",
+            doc.id, doc.id
+        )));
+    }
+
+    let result = engine_guard.add_batch(chunks).await;
+    finish_write(&engine, engine_guard).await;
+    result.map_err(|e| {
+        tracing::error!(error = ?e, "Failed to add documents");
+        ApiError::internal("Failed to add documents due to an internal error")
+    })?;
+
+    tracing::info!(count = ids.len(), "Documents added successfully");
+    Ok((StatusCode::CREATED, Json(WriteResponse::new(ids))))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/documents/{id}",
+    tag = "documents",
+    params(("id" = String, Path, description = "Document ID")),
+    request_body = DocumentInput,
+    responses(
+        (status = 200, description = "Document inserted or replaced", body = WriteResponse),
+        (status = 400, description = "Invalid payload or mismatched ID", body = ApiError),
+        (status = 500, description = "Storage error", body = ApiError)
+    ),
+    security(("bearer" = []))
+)]
+pub(crate) async fn upsert_document_handler(
+    State(engine): State<AppState>,
+    caller: Caller,
+    ApiPath(doc_id): ApiPath<String>,
+    body: Result<Json<DocumentInput>, JsonRejection>,
+) -> Result<Json<WriteResponse>, ApiError> {
+    let mut chunk = json_body(body)?.into_chunk();
+    if chunk.id.is_empty() {
+        chunk.id = doc_id.clone();
+    } else if chunk.id != doc_id {
+        return Err(ApiError::bad_request(format!(
+            "Document ID '{}' in the body does not match '{}' in the path",
+            chunk.id, doc_id
+        )));
+    }
+    validate_chunks(std::slice::from_ref(&chunk))?;
+    check_packs(&caller, std::slice::from_ref(&chunk))?;
+
+    tracing::info!(doc_id = %doc_id, "Document upsert request received");
+
+    let engine_guard = engine.write().await?;
+    check_existing_pack(&engine_guard, &caller, &doc_id).await?;
+
+    let result = engine_guard.upsert(chunk).await;
+    finish_write(&engine, engine_guard).await;
+    result.map_err(|e| {
+        tracing::error!(error = ?e, doc_id = %doc_id, "Failed to upsert document");
+        ApiError::internal("Failed to upsert document due to an internal error")
+    })?;
+
+    tracing::info!(doc_id = %doc_id, "Document upserted successfully");
+    Ok(Json(WriteResponse::new(vec![doc_id])))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/documents/{id}",
+    tag = "documents",
+    params(("id" = String, Path, description = "Document ID")),
+    responses(
+        (status = 200, description = "Document deleted from at least one store", body = DeleteResponse),
+        (status = 404, description = "No document with this ID", body = ApiError),
+        (status = 500, description = "Storage error", body = ApiError)
+    ),
+    security(("bearer" = []))
+)]
+pub(crate) async fn delete_document_handler(
+    State(engine): State<AppState>,
+    caller: Caller,
+    ApiPath(doc_id): ApiPath<String>,
+) -> Result<Json<DeleteResponse>, ApiError> {
+    tracing::info!(doc_id = %doc_id, "Document delete request received");
+
+    let engine_guard = engine.write().await?;
+    check_existing_pack(&engine_guard, &caller, &doc_id).await?;
+    let result = engine_guard.delete(&doc_id).await;
+    finish_write(&engine, engine_guard).await;
+
+    if let Some(error) = delete_error(&result, &format!("ID '{}'", doc_id)) {
+        return Err(error);
+    }
+
+    tracing::info!(doc_id = %doc_id, both = result.both_success(), "Document deleted");
+    Ok(Json(DeleteResponse {
+        id: Some(doc_id),
+        ..DeleteResponse::new(&result)
+    }))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/documents",
+    tag = "documents",
+    params(DeleteQuery),
+    responses(
+        (status = 200, description = "Documents deleted from at least one store", body = DeleteResponse),
+        (status = 400, description = "Missing path_prefix", body = ApiError),
+        (status = 404, description = "No documents under this prefix", body = ApiError),
+        (status = 500, description = "Storage error", body = ApiError)
+    ),
+    security(("bearer" = []))
+)]
+pub(crate) async fn delete_by_prefix_handler(
+    State(engine): State<AppState>,
+    caller: Caller,
+    Query(params): Query<DeleteQuery>,
+) -> Result<Json<DeleteResponse>, ApiError> {
+    let prefix = params
+        .path_prefix
+        .filter(|prefix| !prefix.trim().is_empty())
+        .ok_or_else(|| ApiError::bad_request("The path_prefix query parameter is required"))?;
+
+    tracing::info!(path_prefix = %prefix, "Bulk delete request received");
+
+    let filter = caller.scope_filter(ChunkFilter::new().with_path_prefix(prefix.clone()))?;
+    let engine_guard = engine.write().await?;
+    let result = engine_guard.delete_where(&filter).await;
+    finish_write(&engine, engine_guard).await;
+
+    if let Some(error) = delete_error(&result, &format!("path prefix '{}'", prefix)) {
+        return Err(error);
+    }
+
+    tracing::info!(
+        path_prefix = %prefix,
+        vector_count = result.vector_count,
+        bm25_count = result.bm25_count,
+        "Documents deleted by path prefix"
+    );
+    Ok(Json(DeleteResponse {
+        path_prefix: Some(prefix),
+        ..DeleteResponse::new(&result)
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_payload_accepts_single_and_batch() {
+        let single: DocumentsPayload = serde_json::from_value(json!({
+            "id": "doc-1",
+            "title": "Spawning",
+            "summary": "How to spawn entities",
+            "content": "Call spawn().",
+            "keywords": "spawn entity"
+        }))
+        .unwrap();
+        let chunks = single.into_chunks();
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].symbol_name, "Spawning");
+        assert_eq!(chunks[0].file_path, "How to spawn entities");
+        assert_eq!(chunks[0].dependencies, vec!["spawn", "entity"]);
+
+        let batch: DocumentsPayload = serde_json::from_value(json!([
+            {"id": "doc-1", "title": "A", "content": "a"},
+            {
+                "id": "src/lib.rs::run",
+                "file_path": "src/lib.rs",
+                "symbol_name": "run",
+                "node_type": "function",
+                "content": "fn run() {}",
+                "pack": "core"
+            }
+        ]))
+        .unwrap();
+        let chunks = batch.into_chunks();
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[0].node_type, "file");
+        assert_eq!(chunks[1].node_type, "function");
+        assert_eq!(chunks[1].pack.as_deref(), Some("core"));
+    }
+
+    #[test]
+    fn test_validate_chunks() {
+        let chunk = |id: &str| AstChunk::without_dependencies(id, "a.md", "A", "file", "a");
+        assert!(validate_chunks(&[]).is_err());
+        assert!(validate_chunks(&[chunk(" ")]).is_err());
+        assert!(validate_chunks(&[chunk("a"), chunk("a")]).is_err());
+        assert!(validate_chunks(&[chunk("a"), chunk("b")]).is_ok());
+    }
+
+    #[test]
+    fn test_delete_response_reports_each_backend() {
+        let result = DeleteResult::from_counts(
+            Ok(3),
+            Err(contextfy_core::AppError::Infra(
+                contextfy_core::InfraError::database("index locked", None::<anyhow::Error>),
+            )),
+        );
+        let body = serde_json::to_value(DeleteResponse::new(&result)).unwrap();
+        assert_eq!(body["vector"], json!({"deleted": true, "count": 3}));
+        assert_eq!(body["bm25"]["deleted"], json!(false));
+        assert!(body["bm25"]["error"]
+            .as_str()
+            .unwrap()
+            .contains("index locked"));
+        assert!(delete_error(&result, "ID 'a'").is_none());
+
+        let missing = DeleteResult::from_counts(Ok(0), Ok(0));
+        let error = delete_error(&missing, "ID 'a'").unwrap();
+        assert_eq!(error.error_type.status_code(), StatusCode::NOT_FOUND);
+    }
+}
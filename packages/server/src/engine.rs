@@ -0,0 +1,158 @@
+//! Shared search engine
+//!
+//! The server starts listening before the search engine is ready, because
+//! loading the embedding model can take minutes on a cold start. Until the
+//! engine is set, API handlers answer 503 and `/health/ready` fails.
+//!
+//! Once loaded, the engine can be reopened and swapped in (see `reload.rs`)
+//! to pick up indexes rebuilt by another process.
+
+use contextfy_core::{IndexVersion, SearchEngine};
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::sync::{RwLock, RwLockMappedWriteGuard, RwLockReadGuard, RwLockWriteGuard};
+
+use crate::ApiError;
+
+/// How long clients are asked to wait while the engine is loading
+const LOADING_RETRY_AFTER: Duration = Duration::from_secs(5);
+
+/// The search engine, once it has been loaded
+pub(crate) struct Engine {
+    engine: RwLock<Option<SearchEngine>>,
+    load_error: Mutex<Option<String>>,
+    /// Index version the current engine was opened at (`None` until first
+    /// read); the lock also serializes reloads
+    version: tokio::sync::Mutex<Option<IndexVersion>>,
+}
+
+impl Engine {
+    /// An engine that is still loading
+    pub(crate) fn loading() -> Self {
+        Self {
+            engine: RwLock::new(None),
+            load_error: Mutex::new(None),
+            version: tokio::sync::Mutex::new(None),
+        }
+    }
+
+    /// Make the loaded engine available to handlers
+    pub(crate) async fn set(&self, engine: SearchEngine) {
+        *self.engine.write().await = Some(engine);
+    }
+
+    /// Record that loading failed; handlers keep answering 503
+    pub(crate) fn fail(&self, error: impl std::fmt::Display) {
+        *self.load_error.lock().unwrap_or_else(|e| e.into_inner()) = Some(error.to_string());
+    }
+
+    /// Why loading failed, if it did
+    pub(crate) fn load_error(&self) -> Option<String> {
+        self.load_error
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Shared access to the engine, or `None` while it is not loaded
+    pub(crate) async fn get(&self) -> Option<RwLockReadGuard<'_, SearchEngine>> {
+        RwLockReadGuard::try_map(self.engine.read().await, Option::as_ref).ok()
+    }
+
+    /// Shared access to the engine, or 503 while it is not loaded
+    pub(crate) async fn read(&self) -> Result<RwLockReadGuard<'_, SearchEngine>, ApiError> {
+        match self.get().await {
+            Some(engine) => Ok(engine),
+            None => Err(self.not_ready()),
+        }
+    }
+
+    /// Exclusive access to the engine, or 503 while it is not loaded
+    pub(crate) async fn write(&self) -> Result<RwLockMappedWriteGuard<'_, SearchEngine>, ApiError> {
+        RwLockWriteGuard::try_map(self.engine.write().await, Option::as_mut)
+            .map_err(|_| self.not_ready())
+    }
+
+    /// Index version the current engine was opened at
+    ///
+    /// Read from the engine on first use, so a rebuild that finished before
+    /// the first call goes unnoticed until the next one.
+    pub(crate) async fn loaded_version(&self) -> Result<IndexVersion, ApiError> {
+        let mut loaded = self.version.lock().await;
+        if let Some(version) = loaded.as_ref() {
+            return Ok(version.clone());
+        }
+        let version = self.read().await?.index_version().await.map_err(|e| {
+            tracing::warn!(error = ?e, "Failed to read index version");
+            ApiError::internal("Failed to read index version")
+        })?;
+        *loaded = Some(version.clone());
+        Ok(version)
+    }
+
+    /// Take `version` as the loaded version after a write through this server
+    ///
+    /// The engine already sees its own writes, so the watcher must not mistake
+    /// the version change for an external rebuild. Call after releasing the
+    /// engine lock: `reload` holds the version lock while it waits for it.
+    pub(crate) async fn record_local_write(&self, version: IndexVersion) {
+        *self.version.lock().await = Some(version);
+    }
+
+    /// Reopen the search engine on the same stores and swap it in
+    ///
+    /// The new engine is opened while requests keep using the old one and is
+    /// then swapped in under the write lock, so requests see either engine but
+    /// never a missing one. Requests in flight finish on the old engine.
+    pub(crate) async fn reload(&self) -> Result<IndexVersion, ApiError> {
+        let mut loaded = self.version.lock().await;
+        let (engine, version) = {
+            let current = self.read().await?;
+            // Read the version first: a commit landing while the engine opens
+            // then shows up as a newer version and triggers another reload
+            let version = current.index_version().await;
+            let engine = current.reopen().await;
+            match (engine, version) {
+                (Ok(engine), Ok(version)) => (engine, version),
+                (Err(e), _) | (_, Err(e)) => {
+                    tracing::error!(error = ?e, "Failed to reload search engine");
+                    return Err(ApiError::internal("Failed to reload search engine"));
+                }
+            }
+        };
+
+        *self.engine.write().await = Some(engine);
+        *loaded = Some(version.clone());
+        Ok(version)
+    }
+
+    fn not_ready(&self) -> ApiError {
+        match self.load_error() {
+            Some(_) => ApiError::unavailable("Search engine failed to load"),
+            None => ApiError::unavailable("Search engine is still loading")
+                .with_retry_after(LOADING_RETRY_AFTER),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_unloaded_engine_is_unavailable() {
+        let engine = Engine::loading();
+        assert!(engine.get().await.is_none());
+
+        let error = engine.read().await.err().unwrap();
+        assert_eq!(error.retry_after, Some(LOADING_RETRY_AFTER));
+        assert!(engine.write().await.is_err());
+
+        engine.fail("model download failed");
+        assert_eq!(
+            engine.load_error().as_deref(),
+            Some("model download failed")
+        );
+        assert_eq!(engine.read().await.err().unwrap().retry_after, None);
+    }
+}
@@ -0,0 +1,253 @@
+//! Liveness and readiness endpoints
+//!
+//! - `GET /health/live` answers 200 as long as the process serves requests.
+//! - `GET /health/ready` answers 200 only when the embedding model is loaded
+//!   and both stores pass their health check, and 503 otherwise, so
+//!   orchestrators hold traffic back while the server is starting.
+//!
+//! Both return JSON; readiness also reports document counts, schema versions
+//! and the time of the last `contextfy build`.
+
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Json},
+};
+use chrono::{DateTime, SecondsFormat, Utc};
+use contextfy_core::{HealthReport, StoreHealth};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::AppState;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum ReadyState {
+    /// Model loaded and both stores healthy
+    Ready,
+    /// The search engine or embedding model is still loading
+    Loading,
+    /// Loading the search engine failed
+    Failed,
+    /// Loaded, but a store failed its health check
+    Unhealthy,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub(crate) struct LiveResponse {
+    /// Always `ok`
+    status: &'static str,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub(crate) struct EmbeddingModelStatus {
+    loaded: bool,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub(crate) struct StoreStatus {
+    healthy: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    /// Stored documents (absent when counting failed)
+    documents: Option<usize>,
+    schema_version: u32,
+}
+
+impl From<StoreHealth> for StoreStatus {
+    fn from(health: StoreHealth) -> Self {
+        Self {
+            healthy: health.healthy,
+            error: health.error,
+            documents: health.documents,
+            schema_version: health.schema_version,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub(crate) struct Stores {
+    tantivy: StoreStatus,
+    lancedb: StoreStatus,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub(crate) struct ReadyResponse {
+    status: ReadyState,
+    embedding_model: EmbeddingModelStatus,
+    /// Store health (absent until the search engine is loaded)
+    stores: Option<Stores>,
+    /// Time of the last `contextfy build` (RFC 3339)
+    last_build: Option<String>,
+    /// Why loading failed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl ReadyResponse {
+    fn new(model_loaded: bool, report: Option<HealthReport>, load_error: Option<String>) -> Self {
+        let status = match (&report, &load_error) {
+            (_, Some(_)) => ReadyState::Failed,
+            (None, None) => ReadyState::Loading,
+            (Some(_), None) if !model_loaded => ReadyState::Loading,
+            (Some(report), None) if report.is_healthy() => ReadyState::Ready,
+            (Some(_), None) => ReadyState::Unhealthy,
+        };
+        let last_build = report
+            .as_ref()
+            .and_then(|report| report.last_build)
+            .map(|time| DateTime::<Utc>::from(time).to_rfc3339_opts(SecondsFormat::Secs, true));
+
+        Self {
+            status,
+            embedding_model: EmbeddingModelStatus {
+                loaded: model_loaded,
+            },
+            stores: report.map(|report| Stores {
+                tantivy: report.bm25.into(),
+                lancedb: report.vector.into(),
+            }),
+            last_build,
+            error: load_error,
+        }
+    }
+
+    fn status_code(&self) -> StatusCode {
+        match self.status {
+            ReadyState::Ready => StatusCode::OK,
+            _ => StatusCode::SERVICE_UNAVAILABLE,
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/health/live",
+    tag = "system",
+    responses((status = 200, description = "The server is running", body = LiveResponse))
+)]
+pub(crate) async fn live_handler() -> Json<LiveResponse> {
+    Json(LiveResponse { status: "ok" })
+}
+
+#[utoipa::path(
+    get,
+    path = "/health/ready",
+    tag = "system",
+    responses(
+        (status = 200, description = "Ready to serve search traffic", body = ReadyResponse),
+        (status = 503, description = "Still loading, or a store is unhealthy", body = ReadyResponse)
+    )
+)]
+pub(crate) async fn ready_handler(State(engine): State<AppState>) -> impl IntoResponse {
+    let report = match engine.get().await {
+        Some(engine) => Some(engine.health_report().await),
+        None => None,
+    };
+    let response = ReadyResponse::new(
+        contextfy_core::embedding_model_loaded(),
+        report,
+        engine.load_error(),
+    );
+    if response.status == ReadyState::Unhealthy {
+        tracing::warn!(stores = ?response.stores, "Readiness check failed");
+    }
+    (response.status_code(), Json(response))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ServerConfig;
+    use crate::engine::Engine;
+    use axum::body::{to_bytes, Body};
+    use axum::http::{header, Request};
+    use std::sync::Arc;
+    use std::time::{Duration, SystemTime};
+    use tower::ServiceExt;
+
+    fn store(healthy: bool) -> StoreHealth {
+        StoreHealth {
+            healthy,
+            error: (!healthy).then(|| "index reload failed".to_string()),
+            documents: Some(3),
+            schema_version: 1,
+        }
+    }
+
+    fn report(bm25_healthy: bool) -> HealthReport {
+        HealthReport {
+            bm25: store(bm25_healthy),
+            vector: store(true),
+            last_build: Some(SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000)),
+        }
+    }
+
+    #[test]
+    fn test_ready_state() {
+        let ready = ReadyResponse::new(true, Some(report(true)), None);
+        assert_eq!(ready.status, ReadyState::Ready);
+        assert_eq!(ready.status_code(), StatusCode::OK);
+
+        let cases = [
+            (ReadyResponse::new(false, None, None), ReadyState::Loading),
+            (
+                ReadyResponse::new(false, Some(report(true)), None),
+                ReadyState::Loading,
+            ),
+            (
+                ReadyResponse::new(true, Some(report(false)), None),
+                ReadyState::Unhealthy,
+            ),
+            (
+                ReadyResponse::new(false, None, Some("download failed".to_string())),
+                ReadyState::Failed,
+            ),
+        ];
+        for (response, expected) in cases {
+            assert_eq!(response.status, expected);
+            assert_eq!(response.status_code(), StatusCode::SERVICE_UNAVAILABLE);
+        }
+    }
+
+    #[test]
+    fn test_ready_body() {
+        let body =
+            serde_json::to_value(ReadyResponse::new(true, Some(report(false)), None)).unwrap();
+        assert_eq!(body["status"], "unhealthy");
+        assert_eq!(body["embedding_model"]["loaded"], true);
+        assert_eq!(body["stores"]["tantivy"]["healthy"], false);
+        assert_eq!(body["stores"]["tantivy"]["error"], "index reload failed");
+        assert_eq!(body["stores"]["lancedb"]["documents"], 3);
+        assert_eq!(body["stores"]["lancedb"]["schema_version"], 1);
+        assert!(body["stores"]["lancedb"].get("error").is_none());
+        assert_eq!(body["last_build"], "2023-11-14T22:13:20Z");
+
+        let loading = serde_json::to_value(ReadyResponse::new(false, None, None)).unwrap();
+        assert_eq!(loading["status"], "loading");
+        assert!(loading["stores"].is_null());
+        assert!(loading["last_build"].is_null());
+    }
+
+    #[tokio::test]
+    async fn test_probes_while_engine_is_loading() {
+        let app = crate::app(Arc::new(Engine::loading()), None, &ServerConfig::default());
+        let get = |uri: &str| {
+            app.clone()
+                .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+        };
+
+        let live = get("/health/live").await.unwrap();
+        assert_eq!(live.status(), StatusCode::OK);
+
+        let ready = get("/health/ready").await.unwrap();
+        assert_eq!(ready.status(), StatusCode::SERVICE_UNAVAILABLE);
+        let body = to_bytes(ready.into_body(), usize::MAX).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["status"], "loading");
+
+        let search = get("/api/search?q=spawn").await.unwrap();
+        assert_eq!(search.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(search.headers()[header::RETRY_AFTER], "5");
+    }
+}
@@ -0,0 +1,388 @@
+//! Request rate and concurrency limits
+//!
+//! - Every API route is rate limited per client with a token bucket; clients
+//!   are told to back off with 429 and `Retry-After`.
+//! - Embedding-bound routes (search and document writes) share a global
+//!   concurrency cap. The embedding model serializes inference behind a mutex,
+//!   so extra requests wait in a bounded queue and get 503 when it is full or
+//!   the wait times out.
+//!
+//! A client is identified by its API key once the bearer token has been
+//! verified against the key store, and by its peer address otherwise
+//! (authentication disabled, no token or an unknown token). Made-up tokens
+//! therefore share their address's bucket instead of getting a fresh one.
+
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    middleware::Next,
+    response::Response,
+};
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::Semaphore;
+
+use crate::config::LimitsConfig;
+use crate::{auth, ApiError};
+
+/// Most buckets kept at once; idle (fully refilled) buckets are dropped
+/// first, then the least recently used one
+const MAX_TRACKED_CLIENTS: usize = 10_000;
+
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+/// Per-client token buckets
+#[derive(Debug)]
+pub(crate) struct RateLimiter {
+    rate_per_second: f64,
+    burst: f64,
+    max_clients: usize,
+    buckets: Mutex<HashMap<String, Bucket>>,
+}
+
+impl RateLimiter {
+    pub(crate) fn new(config: &LimitsConfig) -> Self {
+        Self {
+            rate_per_second: config.rate_per_second,
+            burst: f64::from(config.burst),
+            max_clients: MAX_TRACKED_CLIENTS,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn enabled(&self) -> bool {
+        self.rate_per_second > 0.0
+    }
+
+    /// Take a token for `client`, or return how long until one is available
+    fn acquire(&self, client: &str, now: Instant) -> Result<(), Duration> {
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+
+        if !buckets.contains_key(client) && buckets.len() >= self.max_clients {
+            let (rate, burst) = (self.rate_per_second, self.burst);
+            buckets.retain(|_, bucket| {
+                let idle = now.saturating_duration_since(bucket.updated).as_secs_f64();
+                bucket.tokens + idle * rate < burst
+            });
+            if buckets.len() >= self.max_clients {
+                let oldest = buckets
+                    .iter()
+                    .min_by_key(|(_, bucket)| bucket.updated)
+                    .map(|(key, _)| key.clone());
+                if let Some(oldest) = oldest {
+                    buckets.remove(&oldest);
+                }
+            }
+        }
+
+        let bucket = buckets.entry(client.to_string()).or_insert(Bucket {
+            tokens: self.burst,
+            updated: now,
+        });
+        let elapsed = now.saturating_duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.rate_per_second).min(self.burst);
+        bucket.updated = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            let wait = (1.0 - bucket.tokens) / self.rate_per_second;
+            Err(Duration::from_secs_f64(wait))
+        }
+    }
+}
+
+/// Global cap on embedding-bound requests with a bounded wait queue
+#[derive(Debug)]
+pub(crate) struct ConcurrencyLimiter {
+    permits: Arc<Semaphore>,
+    queued: AtomicUsize,
+    max_queued: usize,
+    queue_timeout: Duration,
+}
+
+impl ConcurrencyLimiter {
+    pub(crate) fn new(config: &LimitsConfig) -> Self {
+        Self {
+            permits: Arc::new(Semaphore::new(config.max_concurrent)),
+            queued: AtomicUsize::new(0),
+            max_queued: config.max_queued,
+            queue_timeout: config.queue_timeout(),
+        }
+    }
+
+    /// Wait for a slot, failing with 503 when the queue is full or the wait times out
+    async fn acquire(&self) -> Result<tokio::sync::OwnedSemaphorePermit, ApiError> {
+        if let Ok(permit) = Arc::clone(&self.permits).try_acquire_owned() {
+            return Ok(permit);
+        }
+
+        let queued = self.queued.fetch_add(1, Ordering::SeqCst);
+        let _dequeue = QueueSlot(&self.queued);
+        if queued >= self.max_queued {
+            return Err(busy(self.queue_timeout));
+        }
+
+        match tokio::time::timeout(
+            self.queue_timeout,
+            Arc::clone(&self.permits).acquire_owned(),
+        )
+        .await
+        {
+            Ok(Ok(permit)) => Ok(permit),
+            _ => Err(busy(self.queue_timeout)),
+        }
+    }
+}
+
+/// Decrements the queue length when a waiting request leaves the queue
+struct QueueSlot<'a>(&'a AtomicUsize);
+
+impl Drop for QueueSlot<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+fn busy(retry_after: Duration) -> ApiError {
+    tracing::warn!("Rejected request: embedding queue is full");
+    ApiError::unavailable("Server is busy; retry later").with_retry_after(retry_after)
+}
+
+/// Identify the client for rate limiting
+fn client_key(request: &Request) -> String {
+    if let Some(hash) = auth::verified_token_hash(request) {
+        return format!("key:{}", hash);
+    }
+    match request.extensions().get::<ConnectInfo<SocketAddr>>() {
+        Some(ConnectInfo(addr)) => format!("ip:{}", addr.ip()),
+        None => "ip:unknown".to_string(),
+    }
+}
+
+/// Route middleware enforcing the per-client rate limit
+pub(crate) async fn rate_limit(
+    State(limiter): State<Arc<RateLimiter>>,
+    request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    if limiter.enabled() {
+        let client = client_key(&request);
+        if let Err(wait) = limiter.acquire(&client, Instant::now()) {
+            tracing::warn!(client = %client, path = %request.uri().path(), "Rate limit exceeded");
+            return Err(ApiError::too_many_requests("Rate limit exceeded").with_retry_after(wait));
+        }
+    }
+    Ok(next.run(request).await)
+}
+
+/// A taken concurrency slot, released when the last clone is dropped
+///
+/// Streaming handlers keep working after their response headers are sent;
+/// they move a clone into the task producing the body so the slot stays
+/// taken until the stream ends.
+#[derive(Clone)]
+pub(crate) struct ConcurrencySlot(#[allow(dead_code)] Arc<tokio::sync::OwnedSemaphorePermit>);
+
+/// Route middleware holding a concurrency slot for the duration of the request
+///
+/// The slot is also available to handlers as an `Extension<ConcurrencySlot>`.
+pub(crate) async fn limit_concurrency(
+    State(limiter): State<Arc<ConcurrencyLimiter>>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    let slot = ConcurrencySlot(Arc::new(limiter.acquire().await?));
+    request.extensions_mut().insert(slot.clone());
+    Ok(next.run(request).await)
+}
+
+/// Seconds for a `Retry-After` header (rounded up, at least 1)
+pub(crate) fn retry_after_secs(wait: Duration) -> u64 {
+    (wait.as_secs_f64().ceil() as u64).max(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{
+        body::Body,
+        http::{header, StatusCode},
+        middleware::from_fn_with_state,
+        routing::get,
+        Router,
+    };
+    use tower::ServiceExt;
+
+    fn limits(rate_per_second: f64, burst: u32) -> LimitsConfig {
+        LimitsConfig {
+            rate_per_second,
+            burst,
+            ..LimitsConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_token_bucket_burst_and_refill() {
+        let limiter = RateLimiter::new(&limits(2.0, 3));
+        let start = Instant::now();
+
+        for _ in 0..3 {
+            assert!(limiter.acquire("a", start).is_ok());
+        }
+        let wait = limiter.acquire("a", start).unwrap_err();
+        assert_eq!(wait, Duration::from_millis(500));
+        // Other clients have their own bucket
+        assert!(limiter.acquire("b", start).is_ok());
+
+        // Half a second refills one token
+        assert!(limiter
+            .acquire("a", start + Duration::from_millis(500))
+            .is_ok());
+        assert!(limiter
+            .acquire("a", start + Duration::from_millis(500))
+            .is_err());
+        // Refill is capped at the burst size
+        let later = start + Duration::from_secs(60);
+        for _ in 0..3 {
+            assert!(limiter.acquire("a", later).is_ok());
+        }
+        assert!(limiter.acquire("a", later).is_err());
+    }
+
+    #[test]
+    fn test_retry_after_rounds_up() {
+        assert_eq!(retry_after_secs(Duration::from_millis(500)), 1);
+        assert_eq!(retry_after_secs(Duration::from_secs(2)), 2);
+        assert_eq!(retry_after_secs(Duration::from_millis(2001)), 3);
+        assert_eq!(retry_after_secs(Duration::ZERO), 1);
+    }
+
+    #[tokio::test]
+    async fn test_concurrency_queue_and_timeout() {
+        let limiter = ConcurrencyLimiter::new(&LimitsConfig {
+            max_concurrent: 1,
+            max_queued: 1,
+            queue_timeout_ms: 50,
+            ..LimitsConfig::default()
+        });
+
+        let held = limiter.acquire().await.unwrap();
+
+        // One request may queue; it times out while the slot is held
+        let started = Instant::now();
+        assert!(limiter.acquire().await.is_err());
+        assert!(started.elapsed() >= Duration::from_millis(50));
+        assert_eq!(limiter.queued.load(Ordering::SeqCst), 0);
+
+        // A queued request gets the slot once it is released
+        let waiter = limiter.acquire();
+        let release = async {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            drop(held);
+        };
+        let (permit, ()) = tokio::join!(waiter, release);
+        let permit = permit.unwrap();
+
+        // With the slot taken and the queue full, requests are rejected at once
+        let queued = limiter.acquire();
+        let overflow = async {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+            let started = Instant::now();
+            let result = limiter.acquire().await;
+            (result.is_err(), started.elapsed())
+        };
+        let (queued, (rejected, elapsed)) = tokio::join!(queued, overflow);
+        assert!(rejected);
+        assert!(elapsed < Duration::from_millis(50));
+        assert!(queued.is_err());
+        drop(permit);
+    }
+
+    #[test]
+    fn test_bucket_count_is_capped() {
+        let mut limiter = RateLimiter::new(&limits(1.0, 5));
+        limiter.max_clients = 2;
+        let start = Instant::now();
+
+        assert!(limiter.acquire("a", start).is_ok());
+        assert!(limiter
+            .acquire("b", start + Duration::from_millis(1))
+            .is_ok());
+        // Neither bucket is idle, so the least recently used one is evicted
+        assert!(limiter
+            .acquire("c", start + Duration::from_millis(2))
+            .is_ok());
+
+        let buckets = limiter.buckets.lock().unwrap();
+        assert_eq!(buckets.len(), 2);
+        assert!(buckets.contains_key("b") && buckets.contains_key("c"));
+    }
+
+    fn keys_app(limiter: Arc<RateLimiter>) -> Router {
+        let keys = auth::ApiKeys::from_toml(&format!(
+            "[[keys]]\nname = \"a\"\nsha256 = \"{}\"\nscopes = [\"read\"]\n\n\
+             [[keys]]\nname = \"b\"\nsha256 = \"{}\"\nscopes = [\"read\"]",
+            auth::hash_token("a"),
+            auth::hash_token("b")
+        ))
+        .unwrap();
+        Router::new()
+            .route("/", get(|| async { "ok" }))
+            .route_layer(from_fn_with_state(limiter, rate_limit))
+            .layer(axum::Extension(Arc::new(keys)))
+    }
+
+    async fn send(app: &Router, token: &str) -> axum::response::Response {
+        app.clone()
+            .oneshot(
+                axum::http::Request::builder()
+                    .uri("/")
+                    .header(header::AUTHORIZATION, format!("Bearer {}", token))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_middleware_returns_429() {
+        let app = keys_app(Arc::new(RateLimiter::new(&limits(1.0, 1))));
+
+        assert_eq!(send(&app, "a").await.status(), StatusCode::OK);
+        let limited = send(&app, "a").await;
+        assert_eq!(limited.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(limited.headers()[header::RETRY_AFTER], "1");
+        // Verified keys have their own bucket
+        assert_eq!(send(&app, "b").await.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_unknown_tokens_share_the_address_bucket() {
+        let app = keys_app(Arc::new(RateLimiter::new(&limits(1.0, 1))));
+        assert_eq!(send(&app, "made-up-1").await.status(), StatusCode::OK);
+        assert_eq!(
+            send(&app, "made-up-2").await.status(),
+            StatusCode::TOO_MANY_REQUESTS
+        );
+
+        // Without a key store every token falls back to the address
+        let limiter = Arc::new(RateLimiter::new(&limits(1.0, 1)));
+        let app = Router::new()
+            .route("/", get(|| async { "ok" }))
+            .route_layer(from_fn_with_state(limiter, rate_limit));
+        assert_eq!(send(&app, "a").await.status(), StatusCode::OK);
+        assert_eq!(
+            send(&app, "b").await.status(),
+            StatusCode::TOO_MANY_REQUESTS
+        );
+    }
+}
@@ -1,39 +1,57 @@
+use auth::{ApiKeys, Caller, Scope};
 use axum::{
-    extract::{Path as ApiPath, Query, State},
-    http::StatusCode,
+    extract::{DefaultBodyLimit, Path as ApiPath, State},
+    handler::Handler,
+    http::{header, HeaderValue, Method, StatusCode},
+    middleware,
     response::{IntoResponse, Json, Response},
-    routing::{get, Router},
+    routing::{get, on, MethodFilter, MethodRouter, Router},
+    Extension,
 };
-use contextfy_core::SearchEngine;
-use serde::{Deserialize, Serialize};
+use contextfy_core::{PageRange, SearchEngine};
+use engine::Engine;
+use serde::Serialize;
+use config::ServerConfig;
+use limits::{ConcurrencyLimiter, RateLimiter};
+use std::net::SocketAddr;
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use std::time::Duration;
 use tower_http::services::ServeDir;
-
-#[derive(Debug, Deserialize)]
-struct SearchQuery {
-    q: String,
-}
-
-#[derive(Debug, Serialize)]
-struct SearchResult {
-    id: String,
-    score: f64,
-}
-
-#[derive(Debug, Serialize)]
-struct SearchResponse {
-    results: Vec<SearchResult>,
-}
-
-#[derive(Debug, Serialize)]
+use utoipa::ToSchema;
+
+mod auth;
+mod config;
+mod documents;
+mod engine;
+mod health;
+mod limits;
+mod openapi;
+mod reload;
+mod search;
+mod telemetry;
+
+#[derive(Debug, Serialize, ToSchema)]
 struct DocumentResponse {
     id: String,
     title: String,
     content: String,
+    /// Page range of the section within a paginated source (PDF)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pages: Option<PageRange>,
+    /// Source file name plus page range, e.g. `manual.pdf pp. 3-4`
+    citation: String,
+    /// Store the document was read from (`vector` or `bm25`)
+    #[schema(value_type = String)]
+    source: &'static str,
 }
 
-type AppState = Arc<RwLock<SearchEngine>>;
+type AppState = Arc<Engine>;
+
+/// Tantivy BM25 index directory
+const BM25_INDEX_DIR: &str = ".contextfy/data/bm25_index";
+
+/// LanceDB database directory
+const LANCEDB_DIR: &str = ".contextfy/data/lancedb";
 
 /// API Error type enumeration
 ///
@@ -42,8 +60,14 @@ type AppState = Arc<RwLock<SearchEngine>>;
 #[derive(Debug, Clone, Copy)]
 enum ApiErrorType {
     BadRequest,
+    Unauthorized,
+    Forbidden,
     NotFound,
+    Conflict,
+    PayloadTooLarge,
+    TooManyRequests,
     InternalServerError,
+    ServiceUnavailable,
 }
 
 impl ApiErrorType {
@@ -51,8 +75,14 @@ impl ApiErrorType {
     fn status_code(&self) -> StatusCode {
         match self {
             Self::BadRequest => StatusCode::BAD_REQUEST,
+            Self::Unauthorized => StatusCode::UNAUTHORIZED,
+            Self::Forbidden => StatusCode::FORBIDDEN,
             Self::NotFound => StatusCode::NOT_FOUND,
+            Self::Conflict => StatusCode::CONFLICT,
+            Self::PayloadTooLarge => StatusCode::PAYLOAD_TOO_LARGE,
+            Self::TooManyRequests => StatusCode::TOO_MANY_REQUESTS,
             Self::InternalServerError => StatusCode::INTERNAL_SERVER_ERROR,
+            Self::ServiceUnavailable => StatusCode::SERVICE_UNAVAILABLE,
         }
     }
 
@@ -60,8 +90,14 @@ impl ApiErrorType {
     fn as_str(&self) -> &'static str {
         match self {
             Self::BadRequest => "Bad Request",
+            Self::Unauthorized => "Unauthorized",
+            Self::Forbidden => "Forbidden",
             Self::NotFound => "Not Found",
+            Self::Conflict => "Conflict",
+            Self::PayloadTooLarge => "Payload Too Large",
+            Self::TooManyRequests => "Too Many Requests",
             Self::InternalServerError => "Internal Server Error",
+            Self::ServiceUnavailable => "Service Unavailable",
         }
     }
 }
@@ -71,12 +107,15 @@ impl ApiErrorType {
 /// Uses enum-driven error typing for robust status code mapping.
 /// The `error_type` field ensures correct HTTP status codes regardless
 /// of message content changes.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 struct ApiError {
     #[serde(skip)]
     error_type: ApiErrorType,
     error: String,
     message: String,
+    /// Delay sent as `Retry-After` on 429/503 responses
+    #[serde(skip)]
+    retry_after: Option<Duration>,
 }
 
 impl ApiError {
@@ -86,19 +125,56 @@ impl ApiError {
             error_type,
             error: error_type.as_str().to_string(),
             message: message.into(),
+            retry_after: None,
         }
     }
 
+    /// Tell the client how long to wait before retrying
+    fn with_retry_after(mut self, wait: Duration) -> Self {
+        self.retry_after = Some(wait);
+        self
+    }
+
     /// Convenience method for bad request errors
     fn bad_request(message: impl Into<String>) -> Self {
         Self::new(ApiErrorType::BadRequest, message)
     }
 
+    /// Convenience method for missing or invalid credentials
+    fn unauthorized(message: impl Into<String>) -> Self {
+        Self::new(ApiErrorType::Unauthorized, message)
+    }
+
+    /// Convenience method for valid credentials without permission
+    fn forbidden(message: impl Into<String>) -> Self {
+        Self::new(ApiErrorType::Forbidden, message)
+    }
+
     /// Convenience method for not found errors
     fn not_found(message: impl Into<String>) -> Self {
         Self::new(ApiErrorType::NotFound, message)
     }
 
+    /// Convenience method for conflict errors
+    fn conflict(message: impl Into<String>) -> Self {
+        Self::new(ApiErrorType::Conflict, message)
+    }
+
+    /// Convenience method for request bodies over the size limit
+    fn payload_too_large(message: impl Into<String>) -> Self {
+        Self::new(ApiErrorType::PayloadTooLarge, message)
+    }
+
+    /// Convenience method for clients over their rate limit
+    fn too_many_requests(message: impl Into<String>) -> Self {
+        Self::new(ApiErrorType::TooManyRequests, message)
+    }
+
+    /// Convenience method for requests shed under load or sent before the engine is loaded
+    fn unavailable(message: impl Into<String>) -> Self {
+        Self::new(ApiErrorType::ServiceUnavailable, message)
+    }
+
     /// Convenience method for internal server errors
     fn internal(message: impl Into<String>) -> Self {
         Self::new(ApiErrorType::InternalServerError, message)
@@ -108,7 +184,19 @@ impl ApiError {
 impl IntoResponse for ApiError {
     fn into_response(self) -> Response {
         let status = self.error_type.status_code();
-        (status, Json(self)).into_response()
+        let retry_after = self.retry_after;
+        let mut response = (status, Json(self)).into_response();
+        if status == StatusCode::UNAUTHORIZED {
+            response
+                .headers_mut()
+                .insert(header::WWW_AUTHENTICATE, HeaderValue::from_static("Bearer"));
+        }
+        if let Some(wait) = retry_after {
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, limits::retry_after_secs(wait).into());
+        }
+        response
     }
 }
 
@@ -117,26 +205,58 @@ async fn main() -> anyhow::Result<()> {
     // Initialize tracing
     tracing_init()?;
 
-    // Initialize SearchEngine with default paths
-    let engine = SearchEngine::new(
-        Some(std::path::Path::new(".contextfy/data/bm25_index")),
-        ".contextfy/data/lancedb",
-        "knowledge",
-    )
-    .await
-    .map_err(|e| {
-        tracing::error!(error = ?e, "Failed to initialize search engine");
-        anyhow::anyhow!("SearchEngine initialization failed: {}", e)
-    })?;
-
-    let app_state = Arc::new(RwLock::new(engine));
+    // Install the Prometheus recorder before anything records metrics
+    telemetry::recorder();
+
+    // Load the search engine in the background so health probes answer while
+    // the embedding model is loading
+    let app_state = Arc::new(Engine::loading());
+    let loader_state = Arc::clone(&app_state);
+    let runtime = tokio::runtime::Handle::current();
+    tokio::task::spawn_blocking(move || runtime.block_on(load_engine(loader_state)));
+
+    let server_config = ServerConfig::load(std::path::Path::new(config::CONFIG_FILE))?;
+
+    let api_keys = if server_config.auth.disabled {
+        tracing::warn!(
+            "AUTHENTICATION IS DISABLED (server.auth.disabled in {}): any client that can \
+             reach the server can read, modify and delete documents",
+            config::CONFIG_FILE
+        );
+        None
+    } else {
+        let keys = ApiKeys::from_env().map_err(|e| {
+            anyhow::anyhow!(
+                "{} (create the keys file, set {} to its path, or set server.auth.disabled \
+                 in {} to run without authentication)",
+                e,
+                auth::KEYS_FILE_ENV,
+                config::CONFIG_FILE
+            )
+        })?;
+        tracing::info!(keys = keys.len(), "API key authentication enabled");
+        Some(keys)
+    };
+    let limits = &server_config.limits;
+    tracing::info!(
+        rate_per_second = limits.rate_per_second,
+        burst = limits.burst,
+        max_concurrent = limits.max_concurrent,
+        max_queued = limits.max_queued,
+        max_body_bytes = limits.max_body_bytes,
+        "Request limits configured"
+    );
+
+    if server_config.reload.watch {
+        let interval = server_config.reload.poll_interval();
+        tracing::info!(
+            poll_interval_ms = interval.as_millis() as u64,
+            "Watching indexes for external rebuilds"
+        );
+        tokio::spawn(reload::watch_indexes(Arc::clone(&app_state), interval));
+    }
 
-    let app = Router::new()
-        .route("/api/search", get(search_handler))
-        .route("/api/document/:id", get(document_handler))
-        .nest_service("/", ServeDir::new("packages/web/static"))
-        .route("/health", get(health_handler))
-        .with_state(app_state);
+    let app = app(app_state, api_keys, &server_config);
 
     let listener = tokio::net::TcpListener::bind("127.0.0.1:3000")
         .await
@@ -148,7 +268,12 @@ async fn main() -> anyhow::Result<()> {
     tracing::info!("Server listening on http://127.0.0.1:3000");
     tracing::info!("Web UI available at http://127.0.0.1:3000/");
 
-    axum::serve(listener, app).await.map_err(|e| {
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await
+    .map_err(|e| {
         tracing::error!(error = ?e, "Server error");
         anyhow::anyhow!("Server error: {}", e)
     })?;
@@ -156,6 +281,184 @@ async fn main() -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Initialize the search engine with default paths and hand it to the handlers
+async fn load_engine(state: AppState) {
+    tracing::info!("Loading search engine");
+    let engine = SearchEngine::new(
+        Some(std::path::Path::new(BM25_INDEX_DIR)),
+        LANCEDB_DIR,
+        "knowledge",
+    )
+    .await;
+    match engine {
+        Ok(engine) => {
+            state.set(engine).await;
+            tracing::info!("Search engine ready");
+        }
+        Err(e) => {
+            tracing::error!(error = ?e, "Failed to initialize search engine");
+            state.fail(format!("{:#}", e));
+        }
+    }
+}
+
+/// A documented API route
+///
+/// The router is built from [`api_routes`], and the OpenAPI tests check the same
+/// table against the generated spec so routes and documentation cannot drift apart.
+struct ApiRoute {
+    method: Method,
+    path: &'static str,
+    /// Scope required when authentication is enabled (`None` = public and
+    /// exempt from rate limiting)
+    scope: Option<Scope>,
+    /// Whether the handler runs the embedding model (concurrency limited)
+    embedding_bound: bool,
+    handler: MethodRouter<AppState>,
+}
+
+impl ApiRoute {
+    fn new<H, T>(method: Method, path: &'static str, scope: Scope, handler: H) -> Self
+    where
+        H: Handler<T, AppState>,
+        T: 'static,
+    {
+        Self {
+            scope: Some(scope),
+            ..Self::public(method, path, handler)
+        }
+    }
+
+    fn public<H, T>(method: Method, path: &'static str, handler: H) -> Self
+    where
+        H: Handler<T, AppState>,
+        T: 'static,
+    {
+        let filter = MethodFilter::try_from(method.clone()).expect("standard HTTP method");
+        Self {
+            method,
+            path,
+            scope: None,
+            embedding_bound: false,
+            handler: on(filter, handler),
+        }
+    }
+
+    /// Mark the route as running the embedding model
+    fn embedding_bound(mut self) -> Self {
+        self.embedding_bound = true;
+        self
+    }
+}
+
+fn api_routes() -> Vec<ApiRoute> {
+    vec![
+        ApiRoute::new(
+            Method::GET,
+            "/api/search",
+            Scope::Read,
+            search::search_handler,
+        )
+        .embedding_bound(),
+        ApiRoute::new(
+            Method::GET,
+            "/api/search/stream",
+            Scope::Read,
+            search::search_stream_handler,
+        )
+        .embedding_bound(),
+        ApiRoute::new(
+            Method::GET,
+            "/api/document/:id",
+            Scope::Read,
+            document_handler,
+        ),
+        ApiRoute::new(
+            Method::POST,
+            "/api/documents",
+            Scope::Write,
+            documents::create_documents_handler,
+        )
+        .embedding_bound(),
+        ApiRoute::new(
+            Method::DELETE,
+            "/api/documents",
+            Scope::Write,
+            documents::delete_by_prefix_handler,
+        ),
+        ApiRoute::new(
+            Method::PUT,
+            "/api/documents/:id",
+            Scope::Write,
+            documents::upsert_document_handler,
+        )
+        .embedding_bound(),
+        ApiRoute::new(
+            Method::DELETE,
+            "/api/documents/:id",
+            Scope::Write,
+            documents::delete_document_handler,
+        ),
+        ApiRoute::new(
+            Method::POST,
+            "/api/admin/reload",
+            Scope::Admin,
+            reload::reload_handler,
+        ),
+        ApiRoute::public(Method::GET, "/health", health_handler),
+        ApiRoute::public(Method::GET, "/health/live", health::live_handler),
+        ApiRoute::public(Method::GET, "/health/ready", health::ready_handler),
+        ApiRoute::new(
+            Method::GET,
+            "/metrics",
+            Scope::Admin,
+            telemetry::metrics_handler,
+        ),
+    ]
+}
+
+fn app(state: AppState, api_keys: Option<ApiKeys>, config: &ServerConfig) -> Router {
+    let rate_limiter = Arc::new(RateLimiter::new(&config.limits));
+    let concurrency_limiter = Arc::new(ConcurrencyLimiter::new(&config.limits));
+
+    // Layers run outermost-last: rate limit, then auth, then the concurrency slot
+    let router = api_routes()
+        .into_iter()
+        .fold(Router::new(), |router, route| {
+            tracing::debug!(method = %route.method, path = route.path, "Registering route");
+            let mut handler = route.handler;
+            if route.embedding_bound {
+                handler = handler.route_layer(middleware::from_fn_with_state(
+                    Arc::clone(&concurrency_limiter),
+                    limits::limit_concurrency,
+                ));
+            }
+            if let Some(scope) = route.scope {
+                handler = handler
+                    .route_layer(middleware::from_fn(move |request, next| {
+                        auth::require_scope(scope, request, next)
+                    }))
+                    .route_layer(middleware::from_fn_with_state(
+                        Arc::clone(&rate_limiter),
+                        limits::rate_limit,
+                    ));
+            }
+            router.route(route.path, handler)
+        })
+        .route("/api/openapi.json", get(openapi::openapi_handler))
+        .route("/api/docs", get(openapi::docs_handler))
+        // Counts every API request, including those rejected by the layers above
+        .route_layer(middleware::from_fn(telemetry::track_requests))
+        .nest_service("/", ServeDir::new("packages/web/static"))
+        .layer(DefaultBodyLimit::max(config.limits.max_body_bytes));
+
+    let router = match api_keys {
+        Some(keys) => router.layer(Extension(Arc::new(keys))),
+        None => router,
+    };
+    router.with_state(state)
+}
+
 fn tracing_init() -> anyhow::Result<()> {
     use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
@@ -171,64 +474,48 @@ fn tracing_init() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[utoipa::path(
+    get,
+    path = "/health",
+    tag = "system",
+    responses((status = 200, description = "Server is running", body = String, content_type = "text/plain"))
+)]
 async fn health_handler() -> &'static str {
     "Contextfy Server - OK"
 }
 
-async fn search_handler(
-    State(engine): State<AppState>,
-    Query(params): Query<SearchQuery>,
-) -> Result<Json<SearchResponse>, ApiError> {
-    // Validate query parameter
-    let query_text = params.q.trim();
-    if query_text.is_empty() {
-        tracing::warn!("Received empty search query");
-        return Err(ApiError::bad_request("Search query cannot be empty"));
-    }
-
-    tracing::info!(query_length = query_text.len(), "Search request received");
-
-    let engine_guard = engine.read().await;
-
-    match engine_guard.search(query_text, 10).await {
-        Ok(hits) => {
-            tracing::info!(results_count = hits.len(), "Search completed successfully");
-            let search_results = hits
-                .into_iter()
-                .map(|hit| SearchResult {
-                    id: hit.id,
-                    score: hit.score.value(),
-                })
-                .collect();
-
-            Ok(Json(SearchResponse {
-                results: search_results,
-            }))
-        }
-        Err(e) => {
-            tracing::error!(error = ?e, query_length = query_text.len(), "Search failed");
-            Err(ApiError::internal(
-                "Failed to process search request due to an internal error",
-            ))
-        }
-    }
-}
-
+#[utoipa::path(
+    get,
+    path = "/api/document/{id}",
+    tag = "documents",
+    params(("id" = String, Path, description = "Document ID")),
+    responses(
+        (status = 200, description = "Document found", body = DocumentResponse),
+        (status = 404, description = "No document with this ID", body = ApiError),
+        (status = 500, description = "Storage error", body = ApiError)
+    ),
+    security(("bearer" = []))
+)]
 async fn document_handler(
     State(engine): State<AppState>,
+    caller: Caller,
     ApiPath(doc_id): ApiPath<String>,
 ) -> Result<Json<DocumentResponse>, ApiError> {
     tracing::info!(doc_id = %doc_id, "Document request received");
 
-    let engine_guard = engine.read().await;
+    let engine_guard = engine.read().await?;
 
     match engine_guard.get_document(&doc_id).await {
         Ok(Some(doc)) => {
+            caller.check_pack(doc.pack.as_deref())?;
             tracing::info!(doc_id = %doc_id, "Document retrieved successfully");
             Ok(Json(DocumentResponse {
+                citation: doc.citation(),
+                pages: doc.pages,
                 id: doc.id,
                 title: doc.symbol_name,
                 content: doc.content.unwrap_or_default(),
+                source: doc.source.as_str(),
             }))
         }
         Ok(None) => {
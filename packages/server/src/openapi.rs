@@ -0,0 +1,337 @@
+//! OpenAPI document
+//!
+//! The OpenAPI 3 spec is generated from the handler annotations and the
+//! request/response types, served at `GET /api/openapi.json` and rendered with
+//! Redoc at `GET /api/docs`. Client types should be generated from the spec
+//! instead of written by hand.
+
+use axum::http::Method;
+use axum::response::{Html, Json};
+use utoipa::openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme};
+use utoipa::openapi::path::{Operation, PathItemType};
+use utoipa::openapi::{ContentBuilder, Ref, ResponseBuilder};
+use utoipa::{Modify, OpenApi};
+
+use crate::{documents, health, reload, search, telemetry};
+
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "Contextfy API",
+        description = "Hybrid (BM25 + vector) search over the Contextfy knowledge base"
+    ),
+    paths(
+        search::search_handler,
+        search::search_stream_handler,
+        crate::document_handler,
+        documents::create_documents_handler,
+        documents::delete_by_prefix_handler,
+        documents::upsert_document_handler,
+        documents::delete_document_handler,
+        crate::health_handler,
+        health::live_handler,
+        health::ready_handler,
+        telemetry::metrics_handler,
+        reload::reload_handler,
+    ),
+    components(schemas(
+        crate::ApiError,
+        crate::DocumentResponse,
+        search::SearchResponse,
+        search::SearchResult,
+        search::BackendScores,
+        search::Explanation,
+        search::BackendRank,
+        search::PartialResults,
+        search::PartialHit,
+        search::StageTimings,
+        contextfy_core::FusionMode,
+        documents::DocumentsPayload,
+        documents::DocumentInput,
+        documents::SimpleDocument,
+        contextfy_core::AstChunk,
+        contextfy_core::kernel::types::LineSpan,
+        contextfy_core::PageRange,
+        documents::WriteResponse,
+        documents::DeleteResponse,
+        documents::BackendDelete,
+        health::LiveResponse,
+        health::ReadyResponse,
+        health::ReadyState,
+        health::EmbeddingModelStatus,
+        health::Stores,
+        health::StoreStatus,
+        reload::ReloadResponse,
+        reload::IndexVersionBody,
+    )),
+    modifiers(&BearerAuth, &LimitResponses),
+    tags(
+        (name = "search", description = "Search the knowledge base"),
+        (name = "documents", description = "Read and write documents"),
+        (name = "system", description = "Server status")
+    )
+)]
+pub(crate) struct ApiDoc;
+
+/// Registers the `bearer` security scheme and the 401/403 responses shared by
+/// every operation that requires it
+struct BearerAuth;
+
+impl Modify for BearerAuth {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        if let Some(components) = openapi.components.as_mut() {
+            components.add_security_scheme(
+                "bearer",
+                SecurityScheme::Http(HttpBuilder::new().scheme(HttpAuthScheme::Bearer).build()),
+            );
+        }
+
+        for item in openapi.paths.paths.values_mut() {
+            for operation in item.operations.values_mut() {
+                if operation.security.is_some() {
+                    add_error(operation, "401", "Missing or invalid bearer token");
+                    add_error(operation, "403", "API key lacks the scope or pack");
+                }
+            }
+        }
+    }
+}
+
+/// Adds the 413/429/503 responses produced by the limit middleware and by
+/// handlers called before the search engine is loaded, following the flags of
+/// the route table
+struct LimitResponses;
+
+impl Modify for LimitResponses {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        for route in crate::api_routes() {
+            let Some(operation) = operation_mut(openapi, &route) else {
+                continue;
+            };
+            if route.scope.is_some() {
+                add_error(operation, "429", "Rate limit exceeded (see `Retry-After`)");
+            }
+            if route.embedding_bound {
+                add_error(
+                    operation,
+                    "503",
+                    "Server busy or search engine still loading (see `Retry-After`)",
+                );
+            } else if route.scope.is_some() {
+                add_error(
+                    operation,
+                    "503",
+                    "Search engine still loading (see `Retry-After`)",
+                );
+            }
+            if operation.request_body.is_some() {
+                add_error(operation, "413", "Request body over the size limit");
+            }
+        }
+    }
+}
+
+/// Convert an axum route (`/api/documents/:id`) to OpenAPI form (`/api/documents/{id}`)
+fn openapi_path(route: &str) -> String {
+    route
+        .split('/')
+        .map(|segment| match segment.strip_prefix(':') {
+            Some(param) => format!("{{{}}}", param),
+            None => segment.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn operation_mut<'a>(
+    openapi: &'a mut utoipa::openapi::OpenApi,
+    route: &crate::ApiRoute,
+) -> Option<&'a mut Operation> {
+    let method = match route.method {
+        Method::GET => PathItemType::Get,
+        Method::POST => PathItemType::Post,
+        Method::PUT => PathItemType::Put,
+        Method::DELETE => PathItemType::Delete,
+        Method::PATCH => PathItemType::Patch,
+        _ => return None,
+    };
+    openapi
+        .paths
+        .paths
+        .get_mut(&openapi_path(route.path))?
+        .operations
+        .get_mut(&method)
+}
+
+fn add_error(operation: &mut Operation, status: &str, description: &str) {
+    let response = ResponseBuilder::new()
+        .description(description)
+        .content(
+            "application/json",
+            ContentBuilder::new()
+                .schema(Ref::from_schema_name("ApiError"))
+                .build(),
+        )
+        .build();
+    operation
+        .responses
+        .responses
+        .insert(status.to_string(), response.into());
+}
+
+/// Page rendering the spec with Redoc (loaded from its CDN)
+const DOCS_PAGE: &str = r#"<!DOCTYPE html>
+<html>
+<head>
+    <title>Contextfy API</title>
+    <meta charset="utf-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1">
+</head>
+<body>
+    <redoc spec-url="/api/openapi.json"></redoc>
+    <script src="https://cdn.redoc.ly/redoc/latest/bundles/redoc.standalone.js"></script>
+</body>
+</html>
+"#;
+
+pub(crate) async fn openapi_handler() -> Json<utoipa::openapi::OpenApi> {
+    Json(ApiDoc::openapi())
+}
+
+pub(crate) async fn docs_handler() -> Html<&'static str> {
+    Html(DOCS_PAGE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+    use std::collections::BTreeSet;
+
+    fn spec() -> Value {
+        serde_json::to_value(ApiDoc::openapi()).unwrap()
+    }
+
+    fn collect_refs(value: &Value, refs: &mut BTreeSet<String>) {
+        match value {
+            Value::Object(map) => {
+                for (key, value) in map {
+                    match (key.as_str(), value) {
+                        ("$ref", Value::String(reference)) => {
+                            refs.insert(reference.clone());
+                        }
+                        _ => collect_refs(value, refs),
+                    }
+                }
+            }
+            Value::Array(items) => items.iter().for_each(|item| collect_refs(item, refs)),
+            _ => {}
+        }
+    }
+
+    #[test]
+    fn test_spec_matches_router() {
+        let routed: BTreeSet<(String, String)> = crate::api_routes()
+            .iter()
+            .map(|route| {
+                (
+                    route.method.as_str().to_lowercase(),
+                    openapi_path(route.path),
+                )
+            })
+            .collect();
+
+        let spec = spec();
+        let documented: BTreeSet<(String, String)> = spec["paths"]
+            .as_object()
+            .unwrap()
+            .iter()
+            .flat_map(|(path, item)| {
+                item.as_object()
+                    .unwrap()
+                    .keys()
+                    .filter(|key| *key != "parameters")
+                    .map(move |method| (method.clone(), path.clone()))
+            })
+            .collect();
+
+        let undocumented: Vec<_> = routed.difference(&documented).collect();
+        let unrouted: Vec<_> = documented.difference(&routed).collect();
+        assert!(
+            undocumented.is_empty(),
+            "routes missing from the OpenAPI spec: {:?}",
+            undocumented
+        );
+        assert!(
+            unrouted.is_empty(),
+            "OpenAPI operations without a route: {:?}",
+            unrouted
+        );
+    }
+
+    #[test]
+    fn test_security_matches_route_scopes() {
+        let spec = spec();
+        assert_eq!(
+            spec["components"]["securitySchemes"]["bearer"]["scheme"],
+            "bearer"
+        );
+        for route in crate::api_routes() {
+            let operation = &spec["paths"][openapi_path(route.path)]
+                [route.method.as_str().to_lowercase()];
+            assert_eq!(
+                operation.get("security").is_some(),
+                route.scope.is_some(),
+                "security of {} {} does not match its route scope",
+                route.method,
+                route.path
+            );
+            if route.scope.is_some() {
+                assert!(operation["responses"].get("401").is_some());
+                assert!(operation["responses"].get("403").is_some());
+                assert!(operation["responses"].get("429").is_some());
+                assert!(operation["responses"].get("503").is_some());
+            }
+            if route.embedding_bound {
+                assert!(operation["responses"]["503"]["description"]
+                    .as_str()
+                    .unwrap()
+                    .contains("busy"));
+            }
+        }
+    }
+
+    #[test]
+    fn test_spec_references_resolve() {
+        let spec = spec();
+        let mut refs = BTreeSet::new();
+        collect_refs(&spec, &mut refs);
+        assert!(refs.contains("#/components/schemas/SearchResponse"));
+
+        for reference in refs {
+            let name = reference
+                .strip_prefix("#/components/schemas/")
+                .unwrap_or_else(|| panic!("unexpected reference {}", reference));
+            assert!(
+                spec["components"]["schemas"].get(name).is_some(),
+                "schema {} is referenced but not registered in ApiDoc",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn test_search_parameters_documented() {
+        let spec = spec();
+        let params: BTreeSet<&str> = spec["paths"]["/api/search"]["get"]["parameters"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|param| param["name"].as_str().unwrap())
+            .collect();
+        for name in ["q", "limit", "offset", "fusion", "explain", "pack", "tag"] {
+            assert!(params.contains(name), "missing search parameter {}", name);
+        }
+        assert_eq!(spec["openapi"].as_str().map(|v| &v[..2]), Some("3."));
+    }
+}
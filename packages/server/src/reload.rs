@@ -0,0 +1,250 @@
+//! Hot reload of the indexes
+//!
+//! `contextfy build` writes the indexes from another process. The server
+//! notices by polling the index versions (a digest of Tantivy's `meta.json`
+//! and the LanceDB table version) and reopens the search engine once they
+//! change, then swaps it in behind the engine lock. A build commits once per
+//! file, so a reload waits until the version has stayed the same for one poll.
+//!
+//! Writes through the document API update the loaded version themselves (see
+//! `Engine::record_local_write`), so they never trigger a reload.
+//!
+//! `POST /api/admin/reload` triggers the same swap by hand.
+
+use axum::{extract::State, response::Json};
+use contextfy_core::IndexVersion;
+use serde::Serialize;
+use std::time::{Duration, Instant};
+use tokio::time::MissedTickBehavior;
+use utoipa::ToSchema;
+
+use crate::engine::Engine;
+use crate::{ApiError, AppState};
+
+/// What a poll of the index versions should lead to
+#[derive(Debug, PartialEq, Eq)]
+enum Decision {
+    /// The indexes match the loaded engine
+    Unchanged,
+    /// The indexes changed and may still be changing
+    Wait,
+    /// The indexes changed and stayed the same for a whole poll
+    Reload,
+}
+
+/// Tracks index versions across polls
+#[derive(Debug, Default)]
+struct Debouncer {
+    /// Changed version seen on the previous poll
+    pending: Option<IndexVersion>,
+}
+
+impl Debouncer {
+    fn observe(&mut self, loaded: &IndexVersion, current: IndexVersion) -> Decision {
+        if &current == loaded {
+            self.pending = None;
+            Decision::Unchanged
+        } else if self.pending.as_ref() == Some(&current) {
+            self.pending = None;
+            Decision::Reload
+        } else {
+            self.pending = Some(current);
+            Decision::Wait
+        }
+    }
+}
+
+/// Compare the current index versions with those the engine was opened at
+async fn check(
+    engine: &Engine,
+    debouncer: &mut Debouncer,
+    current: IndexVersion,
+) -> Result<Decision, ApiError> {
+    let loaded = engine.loaded_version().await?;
+    Ok(debouncer.observe(&loaded, current))
+}
+
+/// Poll the index versions every `interval` and reload when they change
+///
+/// Runs for the lifetime of the server; polls are skipped while the engine is
+/// still loading.
+pub(crate) async fn watch_indexes(engine: AppState, interval: Duration) {
+    let mut ticker = tokio::time::interval(interval);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    let mut debouncer = Debouncer::default();
+
+    loop {
+        ticker.tick().await;
+        let Some(current) = engine.get().await else {
+            continue;
+        };
+        let version = current.index_version().await;
+        drop(current);
+
+        let version = match version {
+            Ok(version) => version,
+            Err(e) => {
+                tracing::warn!(error = ?e, "Failed to read index version");
+                continue;
+            }
+        };
+        let Ok(decision) = check(&engine, &mut debouncer, version).await else {
+            continue;
+        };
+
+        match decision {
+            Decision::Unchanged => {}
+            Decision::Wait => tracing::debug!("Index change detected, waiting for it to settle"),
+            Decision::Reload => {
+                let started = Instant::now();
+                if let Ok(version) = engine.reload().await {
+                    tracing::info!(
+                        bm25 = ?version.bm25,
+                        vector = ?version.vector,
+                        elapsed_ms = started.elapsed().as_millis() as u64,
+                        "Reloaded search engine after index change"
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Index versions the engine was opened at
+#[derive(Debug, Serialize, ToSchema)]
+pub(crate) struct IndexVersionBody {
+    /// Digest of the Tantivy index metadata
+    bm25: Option<String>,
+    /// LanceDB table version
+    vector: Option<u64>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub(crate) struct ReloadResponse {
+    version: IndexVersionBody,
+    elapsed_ms: u64,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/admin/reload",
+    tag = "system",
+    responses(
+        (status = 200, description = "Search engine reopened and swapped in", body = ReloadResponse),
+        (status = 500, description = "Reopening the stores failed; the old engine stays in use", body = ApiError)
+    ),
+    security(("bearer" = []))
+)]
+pub(crate) async fn reload_handler(
+    State(engine): State<AppState>,
+) -> Result<Json<ReloadResponse>, ApiError> {
+    let started = Instant::now();
+    let version = engine.reload().await?;
+    tracing::info!(bm25 = ?version.bm25, vector = ?version.vector, "Search engine reloaded on request");
+
+    Ok(Json(ReloadResponse {
+        version: IndexVersionBody {
+            bm25: version.bm25,
+            vector: version.vector,
+        },
+        elapsed_ms: started.elapsed().as_millis() as u64,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn version(vector: u64) -> IndexVersion {
+        IndexVersion {
+            bm25: Some(format!("meta-{}", vector)),
+            vector: Some(vector),
+        }
+    }
+
+    #[test]
+    fn test_reload_waits_for_version_to_settle() {
+        let mut debouncer = Debouncer::default();
+        let loaded = version(1);
+
+        assert_eq!(debouncer.observe(&loaded, version(1)), Decision::Unchanged);
+        // A build in progress keeps moving the version
+        assert_eq!(debouncer.observe(&loaded, version(2)), Decision::Wait);
+        assert_eq!(debouncer.observe(&loaded, version(3)), Decision::Wait);
+        assert_eq!(debouncer.observe(&loaded, version(3)), Decision::Reload);
+
+        // Changes that revert before settling do not reload
+        assert_eq!(debouncer.observe(&loaded, version(4)), Decision::Wait);
+        assert_eq!(debouncer.observe(&loaded, version(1)), Decision::Unchanged);
+        assert_eq!(debouncer.observe(&loaded, version(4)), Decision::Wait);
+    }
+
+    #[tokio::test]
+    async fn test_local_write_does_not_trigger_reload() {
+        let engine = Engine::loading();
+        let mut debouncer = Debouncer::default();
+        engine.record_local_write(version(1)).await;
+
+        // A write through the document API moves the indexes and records the new version
+        engine.record_local_write(version(2)).await;
+        for _ in 0..3 {
+            let decision = check(&engine, &mut debouncer, version(2)).await.unwrap();
+            assert_eq!(decision, Decision::Unchanged);
+        }
+
+        // A rebuild by another process still reloads once it settles
+        assert_eq!(
+            check(&engine, &mut debouncer, version(3)).await.unwrap(),
+            Decision::Wait
+        );
+        assert_eq!(
+            check(&engine, &mut debouncer, version(3)).await.unwrap(),
+            Decision::Reload
+        );
+    }
+
+    #[tokio::test]
+    async fn test_reload_requires_admin_scope_and_loaded_engine() {
+        use crate::auth::ApiKeys;
+        use crate::config::ServerConfig;
+        use axum::body::Body;
+        use axum::http::{header, Request, StatusCode};
+        use std::sync::Arc;
+        use tower::ServiceExt;
+
+        let keys = ApiKeys::from_toml(&format!(
+            r#"
+            [[keys]]
+            name = "reader"
+            sha256 = "{}"
+            scopes = ["read", "write"]
+
+            [[keys]]
+            name = "ops"
+            sha256 = "{}"
+            scopes = ["admin"]
+            "#,
+            crate::auth::hash_token("reader-token"),
+            crate::auth::hash_token("ops-token"),
+        ))
+        .unwrap();
+        let app = crate::app(
+            Arc::new(Engine::loading()),
+            Some(keys),
+            &ServerConfig::default(),
+        );
+        let reload = |token: &str| {
+            app.clone().oneshot(
+                Request::post("/api/admin/reload")
+                    .header(header::AUTHORIZATION, format!("Bearer {}", token))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+        };
+
+        let forbidden = reload("reader-token").await.unwrap();
+        assert_eq!(forbidden.status(), StatusCode::FORBIDDEN);
+        let loading = reload("ops-token").await.unwrap();
+        assert_eq!(loading.status(), StatusCode::SERVICE_UNAVAILABLE);
+    }
+}
@@ -0,0 +1,774 @@
+//! Search endpoint
+//!
+//! `GET /api/search?q=` returns enriched results (title, summary, file path,
+//! node type, snippet and per-backend scores) so clients can render a result
+//! list without fetching every document. Supported parameters:
+//!
+//! - `limit` (default 10, at most 100) and `offset` for pagination
+//!   (`offset + limit` at most 1000)
+//! - `path_prefix`, `pack`, `node_type`, `tag`, `version` filters (AND-ed)
+//! - `fusion`: `rrf` (default), `bm25` or `vector`
+//! - `explain`: include each backend's rank and the RRF constant per result
+//!
+//! `GET /api/search/stream` takes the same parameters and answers with
+//! server-sent events, for debugging ranking: `bm25` and `vector` carry each
+//! backend's hits as soon as it returns, then `fused` carries the same page
+//! `/api/search` would return and `timings` the latency of every stage.
+
+use axum::{
+    extract::{Query, State},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        Json,
+    },
+    Extension,
+};
+use contextfy_core::parser::extract_summary;
+use contextfy_core::{
+    BackendScore, ChunkFilter, DocumentDetails, ExplainedHit, FusionMode, Hit, PageRange,
+    SearchEngine, SearchProgress, SearchStage,
+};
+use futures::Stream;
+use serde::{Deserialize, Serialize};
+use std::convert::Infallible;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedSender};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::limits::ConcurrencySlot;
+use crate::{auth::Caller, ApiError, AppState};
+
+/// Results per page when `limit` is not given
+const DEFAULT_LIMIT: usize = 10;
+
+/// Largest accepted `limit`
+const MAX_LIMIT: usize = 100;
+
+/// Largest accepted `offset + limit`; every backend fetches this many candidates
+const MAX_RESULT_WINDOW: usize = 1000;
+
+/// Maximum snippet length in characters
+const SNIPPET_CHARS: usize = 200;
+
+/// Characters of context kept before the first matching term
+const SNIPPET_LEAD_CHARS: usize = 60;
+
+fn default_limit() -> usize {
+    DEFAULT_LIMIT
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub(crate) struct SearchQuery {
+    /// Query text
+    q: String,
+    /// Results per page
+    #[serde(default = "default_limit")]
+    #[param(default = 10, minimum = 1, maximum = 100)]
+    limit: usize,
+    /// Number of results to skip (`offset + limit` at most 1000)
+    #[serde(default)]
+    offset: usize,
+    /// How the BM25 and vector results are combined
+    #[serde(default)]
+    #[param(inline)]
+    fusion: FusionMode,
+    /// Include each backend's rank in the results
+    #[serde(default)]
+    explain: bool,
+    /// Only match documents whose file path starts with this prefix
+    path_prefix: Option<String>,
+    /// Only match documents in this pack
+    pack: Option<String>,
+    /// Only match this node type or its subtypes (`code` matches `code:rust`)
+    node_type: Option<String>,
+    /// Only match documents carrying this tag
+    tag: Option<String>,
+    /// Only match this document version
+    version: Option<String>,
+}
+
+impl SearchQuery {
+    /// Metadata filter built from the filter parameters (blank values are ignored)
+    fn filter(&self) -> ChunkFilter {
+        let value = |v: &Option<String>| {
+            v.as_deref()
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+                .map(str::to_string)
+        };
+        ChunkFilter {
+            path_prefix: value(&self.path_prefix),
+            pack: value(&self.pack),
+            node_type: value(&self.node_type),
+            tag: value(&self.tag),
+            version: value(&self.version),
+            ..ChunkFilter::default()
+        }
+    }
+}
+
+/// Raw score of a result in each backend (absent when the backend did not return it)
+#[derive(Debug, Serialize, ToSchema)]
+pub(crate) struct BackendScores {
+    bm25: Option<f64>,
+    vector: Option<f64>,
+}
+
+/// Rank of a result in one backend's list
+#[derive(Debug, Serialize, ToSchema)]
+pub(crate) struct BackendRank {
+    rank: usize,
+    score: f64,
+}
+
+impl From<BackendScore> for BackendRank {
+    fn from(score: BackendScore) -> Self {
+        Self {
+            rank: score.rank,
+            score: score.score,
+        }
+    }
+}
+
+/// How a result's score was produced (only with `explain=true`)
+#[derive(Debug, Serialize, ToSchema)]
+pub(crate) struct Explanation {
+    fusion: FusionMode,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rrf_k: Option<i32>,
+    bm25: Option<BackendRank>,
+    vector: Option<BackendRank>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub(crate) struct SearchResult {
+    id: String,
+    score: f64,
+    title: String,
+    summary: String,
+    file_path: String,
+    node_type: Option<String>,
+    /// Page range of the section within a paginated source (PDF)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pages: Option<PageRange>,
+    /// Source file name plus page range, e.g. `manual.pdf pp. 3-4`
+    citation: String,
+    snippet: String,
+    scores: BackendScores,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    explanation: Option<Explanation>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub(crate) struct SearchResponse {
+    results: Vec<SearchResult>,
+    /// Number of fused candidates retrieved to serve this page
+    ///
+    /// Each backend returns at most `offset + limit` hits, so this is not a
+    /// count of all matching documents.
+    candidates: usize,
+    /// Whether requesting the next page (`offset + limit`) can return more results
+    has_more: bool,
+    offset: usize,
+    limit: usize,
+    fusion: FusionMode,
+    elapsed_ms: u64,
+}
+
+/// Build a result entry from a hit and its stored document
+fn to_result(
+    hit: ExplainedHit,
+    doc: Option<DocumentDetails>,
+    query_text: &str,
+    explanation: Option<Explanation>,
+) -> SearchResult {
+    let citation = doc
+        .as_ref()
+        .map(DocumentDetails::citation)
+        .unwrap_or_default();
+    let (title, file_path, node_type, pages, content) = match doc {
+        Some(doc) => (
+            doc.symbol_name,
+            doc.file_path,
+            doc.node_type,
+            doc.pages,
+            doc.content.unwrap_or_default(),
+        ),
+        None => {
+            tracing::warn!(doc_id = %hit.id, "Search hit missing from both stores");
+            Default::default()
+        }
+    };
+
+    SearchResult {
+        score: hit.score.value(),
+        title,
+        summary: extract_summary(&content),
+        file_path,
+        node_type,
+        pages,
+        citation,
+        snippet: snippet(&content, query_text),
+        scores: BackendScores {
+            bm25: hit.bm25.map(|s| s.score),
+            vector: hit.vector.map(|s| s.score),
+        },
+        explanation,
+        id: hit.id,
+    }
+}
+
+/// Extract a short excerpt around the first query term found in `content`
+///
+/// Falls back to the beginning of the content when no term matches. Whitespace
+/// is collapsed and `…` marks truncated ends.
+pub(crate) fn snippet(content: &str, query_text: &str) -> String {
+    let match_start = query_text
+        .split_whitespace()
+        .filter(|term| term.chars().count() >= 2)
+        .filter_map(|term| find_ignore_case(content, term))
+        .min()
+        .unwrap_or(0);
+
+    let lead = content[..match_start]
+        .char_indices()
+        .rev()
+        .nth(SNIPPET_LEAD_CHARS - 1)
+        .map_or(0, |(i, _)| i);
+    let excerpt: String = content[lead..].chars().take(SNIPPET_CHARS).collect();
+    let truncated_end = lead + excerpt.len() < content.len();
+
+    let mut snippet = excerpt.split_whitespace().collect::<Vec<_>>().join(" ");
+    if lead > 0 {
+        snippet.insert(0, '…');
+    }
+    if truncated_end {
+        snippet.push('…');
+    }
+    snippet
+}
+
+/// Byte offset in `content` of the first case-insensitive occurrence of `term`
+///
+/// Compares lowercased chars starting at each char boundary of `content`, so
+/// the offset is always a valid slice index even when lowercasing changes the
+/// byte length of some chars.
+fn find_ignore_case(content: &str, term: &str) -> Option<usize> {
+    let term: Vec<char> = term.chars().flat_map(char::to_lowercase).collect();
+    content.char_indices().map(|(i, _)| i).find(|&i| {
+        let mut rest = content[i..].chars().flat_map(char::to_lowercase);
+        term.iter().all(|&c| rest.next() == Some(c))
+    })
+}
+
+/// Validate the parameters and build the backend query
+fn build_query(params: &SearchQuery, caller: &Caller) -> Result<contextfy_core::Query, ApiError> {
+    let query_text = params.q.trim();
+    if query_text.is_empty() {
+        tracing::warn!("Received empty search query");
+        return Err(ApiError::bad_request("Search query cannot be empty"));
+    }
+    if params.limit == 0 || params.limit > MAX_LIMIT {
+        return Err(ApiError::bad_request(format!(
+            "limit must be between 1 and {}",
+            MAX_LIMIT
+        )));
+    }
+    let window = params
+        .offset
+        .checked_add(params.limit)
+        .filter(|window| *window <= MAX_RESULT_WINDOW)
+        .ok_or_else(|| {
+            ApiError::bad_request(format!(
+                "offset + limit must be at most {}",
+                MAX_RESULT_WINDOW
+            ))
+        })?;
+
+    tracing::info!(
+        query_length = query_text.len(),
+        limit = params.limit,
+        offset = params.offset,
+        fusion = params.fusion.as_str(),
+        "Search request received"
+    );
+
+    // Fetch enough candidates from each backend to cover the requested page
+    Ok(contextfy_core::Query::new(query_text, window)
+        .with_filter(caller.scope_filter(params.filter())?))
+}
+
+fn search_failed(error: anyhow::Error, query_text: &str) -> ApiError {
+    tracing::error!(error = ?error, query_length = query_text.len(), "Search failed");
+    ApiError::internal("Failed to process search request due to an internal error")
+}
+
+/// Whether matches beyond the requested window may exist
+///
+/// Every backend is asked for `window` hits: more remain when the fused list
+/// already runs past the window or when either backend filled its quota.
+fn has_more(hits: &[ExplainedHit], window: usize) -> bool {
+    let bm25 = hits.iter().filter(|hit| hit.bm25.is_some()).count();
+    let vector = hits.iter().filter(|hit| hit.vector.is_some()).count();
+    window < MAX_RESULT_WINDOW && (hits.len() > window || bm25 >= window || vector >= window)
+}
+
+/// Cut the requested page out of the fused hits and load its documents
+///
+/// Returns the page, the number of fused candidates and whether a next page
+/// can return more results.
+async fn results_page(
+    engine: &SearchEngine,
+    hits: Vec<ExplainedHit>,
+    params: &SearchQuery,
+) -> Result<(Vec<SearchResult>, usize, bool), ApiError> {
+    let candidates = hits.len();
+    let has_more = has_more(&hits, params.offset + params.limit);
+    let page: Vec<ExplainedHit> = hits
+        .into_iter()
+        .skip(params.offset)
+        .take(params.limit)
+        .collect();
+
+    let ids: Vec<String> = page.iter().map(|hit| hit.id.clone()).collect();
+    let docs = engine.get_documents(&ids).await.map_err(|e| {
+        tracing::error!(error = ?e, "Failed to load search result documents");
+        ApiError::internal("Failed to process search request due to an internal error")
+    })?;
+    let rrf_k = engine.orchestrator().rrf_k();
+
+    let results: Vec<SearchResult> = page
+        .into_iter()
+        .zip(docs)
+        .map(|(hit, doc)| {
+            let explanation = params.explain.then(|| Explanation {
+                fusion: params.fusion,
+                rrf_k: (params.fusion == FusionMode::Rrf).then_some(rrf_k),
+                bm25: hit.bm25.map(Into::into),
+                vector: hit.vector.map(Into::into),
+            });
+            to_result(hit, doc, params.q.trim(), explanation)
+        })
+        .collect();
+
+    tracing::info!(
+        results_count = results.len(),
+        candidates,
+        has_more,
+        "Search completed successfully"
+    );
+    Ok((results, candidates, has_more))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/search",
+    tag = "search",
+    params(SearchQuery),
+    responses(
+        (status = 200, description = "One page of search results", body = SearchResponse),
+        (status = 400, description = "Empty query or invalid parameters", body = ApiError),
+        (status = 500, description = "Search backend error", body = ApiError)
+    ),
+    security(("bearer" = []))
+)]
+pub(crate) async fn search_handler(
+    State(engine): State<AppState>,
+    caller: Caller,
+    Query(params): Query<SearchQuery>,
+) -> Result<Json<SearchResponse>, ApiError> {
+    let started = Instant::now();
+    let query = build_query(&params, &caller)?;
+
+    let engine = engine.read().await?;
+    let hits = engine
+        .search_explained(&query, params.fusion)
+        .await
+        .map_err(|e| search_failed(e, &query.text))?;
+    let (results, candidates, has_more) = results_page(&engine, hits, &params).await?;
+    drop(engine);
+
+    Ok(Json(SearchResponse {
+        results,
+        candidates,
+        has_more,
+        offset: params.offset,
+        limit: params.limit,
+        fusion: params.fusion,
+        elapsed_ms: started.elapsed().as_millis() as u64,
+    }))
+}
+
+/// A hit in one backend's result list
+#[derive(Debug, Serialize, ToSchema)]
+pub(crate) struct PartialHit {
+    id: String,
+    /// 1-based rank in the backend's results
+    rank: usize,
+    /// Score reported by the backend
+    score: f64,
+}
+
+/// Payload of the `bm25` and `vector` events
+#[derive(Debug, Serialize, ToSchema)]
+pub(crate) struct PartialResults {
+    /// The backend's hits before fusion (empty when it failed)
+    hits: Vec<PartialHit>,
+    /// Set when the backend failed; the fused list uses the other backend only
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl PartialResults {
+    fn new(hits: Vec<Hit>) -> Self {
+        let hits = hits
+            .into_iter()
+            .enumerate()
+            .map(|(index, hit)| PartialHit {
+                rank: index + 1,
+                score: hit.score.value(),
+                id: hit.id,
+            })
+            .collect();
+        Self { hits, error: None }
+    }
+
+    fn failed() -> Self {
+        Self {
+            hits: Vec::new(),
+            error: Some("Search backend failed".to_string()),
+        }
+    }
+}
+
+/// Payload of the `timings` event, in milliseconds
+///
+/// A stage is absent when it did not run (e.g. `vector` with `fusion=bm25`).
+#[derive(Debug, Default, Serialize, ToSchema)]
+pub(crate) struct StageTimings {
+    embed_ms: Option<f64>,
+    bm25_ms: Option<f64>,
+    vector_ms: Option<f64>,
+    fusion_ms: Option<f64>,
+    fetch_ms: Option<f64>,
+    total_ms: f64,
+}
+
+impl StageTimings {
+    fn record(&mut self, stage: SearchStage, elapsed: Duration) {
+        let ms = Some(elapsed.as_secs_f64() * 1000.0);
+        match stage {
+            SearchStage::Embed => self.embed_ms = ms,
+            SearchStage::Bm25 => self.bm25_ms = ms,
+            SearchStage::Vector => self.vector_ms = ms,
+            SearchStage::Fusion => self.fusion_ms = ms,
+            SearchStage::Fetch => self.fetch_ms = ms,
+        }
+    }
+}
+
+/// Serialize `data` as an SSE event named `name`
+fn sse_event(name: &str, data: &impl Serialize) -> Event {
+    Event::default()
+        .event(name)
+        .json_data(data)
+        .unwrap_or_else(|e| {
+            tracing::error!(error = ?e, event = name, "Failed to serialize search event");
+            Event::default().event("error").data("{}")
+        })
+}
+
+/// Run a streamed search, sending its events to `events`
+async fn stream_search(
+    engine: AppState,
+    query: contextfy_core::Query,
+    params: SearchQuery,
+    events: UnboundedSender<Event>,
+) -> Result<(), ApiError> {
+    let started = Instant::now();
+    let engine = engine.read().await?;
+    let mut timings = StageTimings::default();
+
+    let (progress, mut progress_events) = unbounded_channel();
+    let search = async {
+        let hits = engine
+            .search_with_progress(&query, params.fusion, &progress)
+            .await;
+        // Closing the channel ends the forwarding loop below
+        drop(progress);
+        hits
+    };
+    let forward = async {
+        while let Some(progress) = progress_events.recv().await {
+            match progress {
+                SearchProgress::Results { backend, hits } => {
+                    let _ = events.send(sse_event(backend.as_str(), &PartialResults::new(hits)));
+                }
+                SearchProgress::Failed { backend, error } => {
+                    tracing::warn!(backend = backend.as_str(), error = %error, "Search backend failed");
+                    let _ = events.send(sse_event(backend.as_str(), &PartialResults::failed()));
+                }
+                SearchProgress::Stage { stage, elapsed } => timings.record(stage, elapsed),
+            }
+        }
+    };
+    let (hits, ()) = tokio::join!(search, forward);
+    let hits = hits.map_err(|e| search_failed(e, &query.text))?;
+
+    let fetch_started = Instant::now();
+    let (results, candidates, has_more) = results_page(&engine, hits, &params).await?;
+    timings.record(SearchStage::Fetch, fetch_started.elapsed());
+    drop(engine);
+
+    let fused = SearchResponse {
+        results,
+        candidates,
+        has_more,
+        offset: params.offset,
+        limit: params.limit,
+        fusion: params.fusion,
+        elapsed_ms: started.elapsed().as_millis() as u64,
+    };
+    let _ = events.send(sse_event("fused", &fused));
+    timings.total_ms = started.elapsed().as_secs_f64() * 1000.0;
+    let _ = events.send(sse_event("timings", &timings));
+    Ok(())
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/search/stream",
+    tag = "search",
+    params(SearchQuery),
+    responses(
+        (
+            status = 200,
+            description = "Server-sent events: `bm25` and `vector` (PartialResults) as each backend \
+                returns, then `fused` (SearchResponse) and `timings` (StageTimings). \
+                A failure after the stream started is sent as an `error` event (ApiError).",
+            content_type = "text/event-stream",
+            body = String
+        ),
+        (status = 400, description = "Empty query or invalid parameters", body = ApiError)
+    ),
+    security(("bearer" = []))
+)]
+pub(crate) async fn search_stream_handler(
+    State(engine): State<AppState>,
+    caller: Caller,
+    slot: Option<Extension<ConcurrencySlot>>,
+    Query(params): Query<SearchQuery>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, ApiError> {
+    let query = build_query(&params, &caller)?;
+    // Fail with a plain 503 rather than an event stream while loading
+    drop(engine.read().await?);
+
+    let (events, receiver) = unbounded_channel();
+    tokio::spawn(async move {
+        // Keep the concurrency slot until the search is done
+        let _slot = slot;
+        if let Err(error) = stream_search(engine, query, params, events.clone()).await {
+            let _ = events.send(sse_event("error", &error));
+        }
+    });
+
+    let stream = futures::stream::unfold(receiver, |mut receiver| async move {
+        let event = receiver.recv().await?;
+        Some((Ok(event), receiver))
+    });
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::StatusCode;
+    use contextfy_core::Score;
+
+    fn parse(uri: &str) -> Query<SearchQuery> {
+        Query::try_from_uri(&uri.parse().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_snippet_centers_on_first_term() {
+        let content = format!("{} The spawn() function creates entities.", "intro ".repeat(30));
+        let snippet = snippet(&content, "Spawn entity");
+        assert!(snippet.starts_with('…'));
+        assert!(snippet.contains("The spawn() function creates entities."));
+        assert!(!snippet.ends_with('…'));
+
+        let short = super::snippet("Line one\n\nline   two", "missing");
+        assert_eq!(short, "Line one line two");
+    }
+
+    #[test]
+    fn test_snippet_handles_multibyte_text() {
+        let content = "方块事件".repeat(100);
+        let snippet = snippet(&content, "事件");
+        assert!(snippet.ends_with('…'));
+        assert_eq!(snippet.chars().count(), SNIPPET_CHARS + 1);
+    }
+
+    #[test]
+    fn test_snippet_with_mixed_width_case_mappings() {
+        // 'Ⱥ' grows and 'ẞ' shrinks when lowercased: the total length is unchanged
+        // but byte offsets in the lowercased text fall inside chars of the original
+        assert_eq!(snippet("Ⱥẞx", "ßx"), "Ⱥẞx");
+        assert_eq!(find_ignore_case("Ⱥẞx", "ßX"), Some(2));
+
+        // 'İ' lowercases to two chars; the match is still found on a char boundary
+        let content = format!("{} Ⱥẞx marks the spot", "İ".repeat(100));
+        let snippet = snippet(&content, "ẞX");
+        assert!(snippet.starts_with('…'));
+        assert!(snippet.contains("Ⱥẞx marks the spot"));
+    }
+
+    #[test]
+    fn test_query_parameters() {
+        let Query(params) = parse(
+            "/api/search?q=spawn&limit=5&offset=10&fusion=bm25&explain=true&pack=core&tag=%20",
+        );
+        assert_eq!(params.limit, 5);
+        assert_eq!(params.offset, 10);
+        assert_eq!(params.fusion, FusionMode::Bm25);
+        assert!(params.explain);
+        let filter = params.filter();
+        assert_eq!(filter.pack.as_deref(), Some("core"));
+        assert_eq!(filter.tag, None);
+
+        let Query(defaults) = parse("/api/search?q=spawn");
+        assert_eq!(defaults.limit, DEFAULT_LIMIT);
+        assert_eq!(defaults.fusion, FusionMode::Rrf);
+        assert!(defaults.filter().is_empty());
+    }
+
+    #[test]
+    fn test_result_window_is_bounded() {
+        let caller = Caller::default();
+        let Query(params) = parse("/api/search?q=spawn&offset=990&limit=10");
+        assert_eq!(build_query(&params, &caller).unwrap().limit, MAX_RESULT_WINDOW);
+
+        for uri in [
+            "/api/search?q=spawn&offset=991&limit=10",
+            "/api/search?q=spawn&offset=100000000000",
+            "/api/search?q=spawn&offset=18446744073709551615&limit=100",
+        ] {
+            let Query(params) = parse(uri);
+            let error = build_query(&params, &caller).unwrap_err();
+            assert_eq!(error.error_type.status_code(), StatusCode::BAD_REQUEST, "{}", uri);
+        }
+    }
+
+    #[test]
+    fn test_result_carries_backend_scores() {
+        let hit = ExplainedHit {
+            id: "doc-1".to_string(),
+            score: Score::new(0.03),
+            bm25: Some(BackendScore {
+                rank: 1,
+                score: 7.5,
+            }),
+            vector: None,
+        };
+        let result = to_result(hit, None, "spawn", None);
+        let body = serde_json::to_value(&result).unwrap();
+        assert_eq!(body["scores"]["bm25"], 7.5);
+        assert!(body["scores"]["vector"].is_null());
+        assert!(body.get("explanation").is_none());
+    }
+
+    #[test]
+    fn test_has_more_follows_backend_quotas() {
+        let hit = |rank: usize, bm25: bool, vector: bool| ExplainedHit {
+            id: format!("doc-{}", rank),
+            score: Score::new(0.01),
+            bm25: bm25.then_some(BackendScore { rank, score: 1.0 }),
+            vector: vector.then_some(BackendScore { rank, score: 0.5 }),
+        };
+
+        // Both backends returned fewer hits than asked for: nothing left
+        let short = vec![hit(1, true, true), hit(2, true, false)];
+        assert!(!has_more(&short, 3));
+        // BM25 filled its window of 2, so it may hold more matches
+        assert!(has_more(&short, 2));
+        // The fused union already runs past the window
+        let wide = vec![
+            hit(1, true, false),
+            hit(2, false, true),
+            hit(3, true, false),
+        ];
+        assert!(has_more(&wide, 2));
+        // The next page would exceed the result window
+        assert!(!has_more(&short, MAX_RESULT_WINDOW));
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_result_cites_page_range() {
+        let hit = ExplainedHit {
+            id: "manual-3".to_string(),
+            score: Score::new(0.03),
+            bm25: None,
+            vector: None,
+        };
+        let doc = DocumentDetails {
+            id: "manual-3".to_string(),
+            symbol_name: "Setup".to_string(),
+            file_path: "docs/manual.pdf".to_string(),
+            content: Some("Install the package".to_string()),
+            node_type: Some("prose".to_string()),
+            pack: None,
+            pages: Some(PageRange { start: 3, end: 4 }),
+            source: contextfy_core::DocumentSource::Bm25,
+            title: "Setup".to_string(),
+            summary: "docs/manual.pdf".to_string(),
+        };
+        let body = serde_json::to_value(to_result(hit, Some(doc), "install", None)).unwrap();
+        assert_eq!(body["pages"]["start"], 3);
+        assert_eq!(body["pages"]["end"], 4);
+        assert_eq!(body["citation"], "manual.pdf pp. 3-4");
+    }
+
+    #[test]
+    fn test_stream_event_payloads() {
+        let partial = PartialResults::new(vec![
+            Hit::new("doc-a", Score::new(0.9)),
+            Hit::new("doc-b", Score::new(0.4)),
+        ]);
+        let body = serde_json::to_value(&partial).unwrap();
+        assert_eq!(body["hits"][1]["id"], "doc-b");
+        assert_eq!(body["hits"][1]["rank"], 2);
+        assert!(body.get("error").is_none());
+        let failed = serde_json::to_value(PartialResults::failed()).unwrap();
+        assert_eq!(failed["hits"].as_array().unwrap().len(), 0);
+        assert!(failed["error"].is_string());
+
+        let mut timings = StageTimings::default();
+        timings.record(SearchStage::Bm25, Duration::from_micros(1500));
+        timings.record(SearchStage::Fetch, Duration::from_millis(2));
+        let body = serde_json::to_value(&timings).unwrap();
+        assert_eq!(body["bm25_ms"], 1.5);
+        assert_eq!(body["fetch_ms"], 2.0);
+        assert!(body["vector_ms"].is_null());
+    }
+
+    #[tokio::test]
+    async fn test_stream_rejects_bad_requests_before_streaming() {
+        use crate::{config::ServerConfig, engine::Engine};
+        use axum::{body::Body, http::Request, http::StatusCode};
+        use std::sync::Arc;
+        use tower::ServiceExt;
+
+        let app = crate::app(Arc::new(Engine::loading()), None, &ServerConfig::default());
+        let get = |uri: &str| {
+            app.clone()
+                .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+        };
+
+        let empty = get("/api/search/stream?q=%20").await.unwrap();
+        assert_eq!(empty.status(), StatusCode::BAD_REQUEST);
+        let loading = get("/api/search/stream?q=spawn").await.unwrap();
+        assert_eq!(loading.status(), StatusCode::SERVICE_UNAVAILABLE);
+    }
+}
@@ -0,0 +1,224 @@
+//! Prometheus metrics
+//!
+//! `GET /metrics` (admin scope) exposes, in Prometheus text format:
+//!
+//! - `contextfy_http_requests_total{method,route,status}` and
+//!   `contextfy_http_request_duration_seconds{method,route}` for every API route
+//! - `contextfy_search_stage_seconds{stage}`, recorded by the core search
+//!   pipeline (`embed`, `bm25`, `vector`, `fusion`, `fetch`)
+//! - `contextfy_documents{store}` and `contextfy_index_size_bytes{store}`,
+//!   refreshed on every scrape
+
+use axum::{
+    extract::{MatchedPath, Request, State},
+    http::header,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use metrics_exporter_prometheus::{PrometheusBuilder, PrometheusHandle};
+use std::path::Path;
+use std::sync::OnceLock;
+use std::time::Instant;
+
+use crate::AppState;
+
+const HTTP_REQUESTS_TOTAL: &str = "contextfy_http_requests_total";
+const HTTP_REQUEST_DURATION_SECONDS: &str = "contextfy_http_request_duration_seconds";
+const DOCUMENTS: &str = "contextfy_documents";
+const INDEX_SIZE_BYTES: &str = "contextfy_index_size_bytes";
+
+/// Histogram buckets in seconds, from a cached BM25 lookup to a cold model call
+const LATENCY_BUCKETS: &[f64] = &[
+    0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+/// Content type of the Prometheus text exposition format
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4";
+
+/// Handle to the global Prometheus recorder, installed on first use
+pub(crate) fn recorder() -> &'static PrometheusHandle {
+    static HANDLE: OnceLock<PrometheusHandle> = OnceLock::new();
+    HANDLE.get_or_init(|| {
+        let handle = PrometheusBuilder::new()
+            .set_buckets(LATENCY_BUCKETS)
+            .expect("latency buckets are not empty")
+            .install_recorder()
+            .expect("no other metrics recorder is installed");
+        describe_metrics();
+        handle
+    })
+}
+
+fn describe_metrics() {
+    contextfy_core::describe_metrics();
+    metrics::describe_counter!(HTTP_REQUESTS_TOTAL, "API requests by route and status");
+    metrics::describe_histogram!(
+        HTTP_REQUEST_DURATION_SECONDS,
+        metrics::Unit::Seconds,
+        "API request latency by route"
+    );
+    metrics::describe_gauge!(DOCUMENTS, "Documents stored in each backend");
+    metrics::describe_gauge!(
+        INDEX_SIZE_BYTES,
+        metrics::Unit::Bytes,
+        "On-disk size of each backend's index"
+    );
+}
+
+/// Route middleware counting requests and their latency
+///
+/// Labelled with the route template (`/api/documents/:id`) rather than the
+/// request path, so document IDs do not create new series.
+pub(crate) async fn track_requests(request: Request, next: Next) -> Response {
+    let method = request.method().to_string();
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_else(|| "unmatched".to_string());
+
+    let started = Instant::now();
+    let response = next.run(request).await;
+
+    metrics::histogram!(
+        HTTP_REQUEST_DURATION_SECONDS,
+        "method" => method.clone(),
+        "route" => route.clone()
+    )
+    .record(started.elapsed().as_secs_f64());
+    metrics::counter!(
+        HTTP_REQUESTS_TOTAL,
+        "method" => method,
+        "route" => route,
+        "status" => response.status().as_u16().to_string()
+    )
+    .increment(1);
+
+    response
+}
+
+/// Total size in bytes of the files under `path` (0 when it does not exist)
+fn dir_size(path: &Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return std::fs::metadata(path).map_or(0, |meta| meta.len());
+    };
+    entries
+        .flatten()
+        .map(|entry| match entry.file_type() {
+            Ok(kind) if kind.is_dir() => dir_size(&entry.path()),
+            _ => entry.metadata().map_or(0, |meta| meta.len()),
+        })
+        .sum()
+}
+
+/// Refresh the document count and index size gauges
+async fn update_store_gauges(engine: &AppState) {
+    if let Some(engine) = engine.get().await {
+        let counts = engine.document_counts().await;
+        for (store, count) in [("bm25", counts.bm25), ("vector", counts.vector)] {
+            if let Some(count) = count {
+                metrics::gauge!(DOCUMENTS, "store" => store).set(count as f64);
+            }
+        }
+    }
+
+    let sizes = tokio::task::spawn_blocking(|| {
+        [
+            ("bm25", dir_size(Path::new(crate::BM25_INDEX_DIR))),
+            ("vector", dir_size(Path::new(crate::LANCEDB_DIR))),
+        ]
+    })
+    .await;
+    match sizes {
+        Ok(sizes) => {
+            for (store, bytes) in sizes {
+                metrics::gauge!(INDEX_SIZE_BYTES, "store" => store).set(bytes as f64);
+            }
+        }
+        Err(e) => tracing::warn!(error = ?e, "Failed to measure index size"),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/metrics",
+    tag = "system",
+    responses((
+        status = 200,
+        description = "Metrics in Prometheus text format",
+        body = String,
+        content_type = "text/plain; version=0.0.4"
+    )),
+    security(("bearer" = []))
+)]
+pub(crate) async fn metrics_handler(State(engine): State<AppState>) -> impl IntoResponse {
+    update_store_gauges(&engine).await;
+
+    let handle = recorder();
+    handle.run_upkeep();
+    ([(header::CONTENT_TYPE, CONTENT_TYPE)], handle.render())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{
+        body::{to_bytes, Body},
+        http::StatusCode,
+        middleware::from_fn,
+        routing::get,
+        Router,
+    };
+    use contextfy_core::SearchStage;
+    use std::time::Duration;
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn test_requests_and_stages_are_exported() {
+        let handle = recorder();
+        let app = Router::new()
+            .route(
+                "/items/:id",
+                get(|| async { (StatusCode::NOT_FOUND, "missing") }),
+            )
+            .route_layer(from_fn(track_requests));
+
+        for id in ["a", "b"] {
+            let response = app
+                .clone()
+                .oneshot(
+                    axum::http::Request::builder()
+                        .uri(format!("/items/{}", id))
+                        .body(Body::empty())
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::NOT_FOUND);
+            to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        }
+        SearchStage::Fetch.record(Duration::from_millis(3));
+
+        let rendered = handle.render();
+        assert!(
+            rendered.contains(
+                r#"contextfy_http_requests_total{method="GET",route="/items/:id",status="404"} 2"#
+            ),
+            "{}",
+            rendered
+        );
+        assert!(rendered.contains(
+            r#"contextfy_http_request_duration_seconds_bucket{method="GET",route="/items/:id",le="0.001"}"#
+        ));
+        assert!(rendered.contains(r#"contextfy_search_stage_seconds_count{stage="fetch"}"#));
+        assert!(rendered.contains("# TYPE contextfy_search_stage_seconds histogram"));
+    }
+
+    #[test]
+    fn test_dir_size() {
+        assert_eq!(dir_size(Path::new("/nonexistent/contextfy/index")), 0);
+        let src = Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+        let this_file = std::fs::metadata(src.join("telemetry.rs")).unwrap().len();
+        assert!(dir_size(&src) > this_file);
+    }
+}